@@ -0,0 +1,49 @@
+-- Add migration script here
+-- Remisi (pengurangan masa pidana) per riwayat hukum dewasa
+
+CREATE TYPE jenis_remisi_enum AS ENUM ('Umum', 'Khusus', 'Dasawarsa');
+
+CREATE TABLE remisi_dewasa (
+    id SERIAL PRIMARY KEY,
+    klien_id INTEGER NOT NULL REFERENCES klien(id) ON DELETE RESTRICT,
+    riwayat_hukum_dewasa_id INTEGER NOT NULL REFERENCES riwayat_hukum_dewasa(id) ON DELETE RESTRICT,
+    nomor_sk_remisi_dewasa VARCHAR(255),
+    jenis_remisi_dewasa jenis_remisi_enum NOT NULL,
+    tanggal_sk_remisi_dewasa DATE,
+    jumlah_hari_remisi_dewasa INTEGER NOT NULL CHECK (jumlah_hari_remisi_dewasa >= 0),
+    keterangan TEXT,
+    catatan TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TRIGGER set_timestamp BEFORE UPDATE ON remisi_dewasa FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX idx_remisi_dewasa_klien_id ON remisi_dewasa(klien_id);
+CREATE INDEX idx_remisi_dewasa_riwayat_hukum_id ON remisi_dewasa(riwayat_hukum_dewasa_id);
+
+-- Sertakan remisi dalam cascade soft delete dari klien
+CREATE OR REPLACE FUNCTION cascade_soft_delete_from_klien()
+RETURNS TRIGGER AS $$
+BEGIN
+    -- Update tabel workflow Dewasa
+    UPDATE penerimaan_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE riwayat_hukum_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE layanan_integrasi_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE wajib_lapor_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE proses_hukum_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE remisi_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+
+    -- Update tabel workflow Anak
+    UPDATE penerimaan_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE riwayat_hukum_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE layanan_integrasi_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE wajib_lapor_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE proses_hukum_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
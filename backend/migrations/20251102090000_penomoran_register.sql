@@ -0,0 +1,100 @@
+-- Add migration script here
+-- Penomoran otomatis register litmas, integrasi, dan proses hukum per Bapas per tahun
+
+CREATE TYPE jenis_register_enum AS ENUM ('Litmas', 'Integrasi', 'Proses Hukum');
+
+-- Kode singkat Bapas untuk dipakai di nomor register (misalnya 'BPS.JKT')
+ALTER TABLE bapas ADD COLUMN kode_bapas VARCHAR(50) UNIQUE;
+
+-- Template format per Bapas. Jika tidak ada baris untuk sebuah Bapas,
+-- aplikasi memakai template bawaan.
+CREATE TABLE format_nomor_register (
+    id SERIAL PRIMARY KEY,
+    bapas_id INTEGER NOT NULL REFERENCES bapas(id) ON DELETE CASCADE,
+    jenis_register jenis_register_enum NOT NULL,
+    template TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    UNIQUE (bapas_id, jenis_register)
+);
+
+-- Penghitung urutan. Baris dikunci (UPDATE) selama transaksi pembuatan data
+-- berjalan, sehingga nomor tidak lompat walaupun ada permintaan bersamaan
+-- dan tidak terpakai jika transaksi dibatalkan.
+CREATE TABLE urutan_nomor_register (
+    bapas_id INTEGER NOT NULL REFERENCES bapas(id) ON DELETE CASCADE,
+    jenis_register jenis_register_enum NOT NULL,
+    tahun INTEGER NOT NULL,
+    nomor_terakhir INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (bapas_id, jenis_register, tahun)
+);
+
+CREATE TRIGGER set_timestamp BEFORE UPDATE ON format_nomor_register FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp();
+CREATE TRIGGER set_timestamp BEFORE UPDATE ON urutan_nomor_register FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp();
+
+-- Nomor register yang sudah ganda sebelum indeks unik dibuat. Baris tertua
+-- mempertahankan nomornya; baris lain dicatat di sini lalu nomornya dikosongkan
+-- supaya bisa diberi nomor ulang oleh petugas.
+CREATE TABLE nomor_register_ganda (
+    id SERIAL PRIMARY KEY,
+    tabel VARCHAR(64) NOT NULL,
+    baris_id INTEGER NOT NULL,
+    nomor_register TEXT NOT NULL,
+    dicatat_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+WITH urut AS (
+    SELECT id, no_register_litmas_dewasa AS nomor,
+        ROW_NUMBER() OVER (PARTITION BY no_register_litmas_dewasa ORDER BY created_at, id) AS ke
+    FROM penerimaan_dewasa WHERE deleted_at IS NULL AND no_register_litmas_dewasa <> ''
+), dicatat AS (
+    INSERT INTO nomor_register_ganda (tabel, baris_id, nomor_register)
+    SELECT 'penerimaan_dewasa', id, nomor FROM urut WHERE ke > 1
+    RETURNING baris_id
+)
+UPDATE penerimaan_dewasa SET no_register_litmas_dewasa = NULL WHERE id IN (SELECT baris_id FROM dicatat);
+
+WITH urut AS (
+    SELECT id, nomor_register_integrasi_dewasa AS nomor,
+        ROW_NUMBER() OVER (PARTITION BY nomor_register_integrasi_dewasa ORDER BY created_at, id) AS ke
+    FROM layanan_integrasi_dewasa WHERE deleted_at IS NULL AND nomor_register_integrasi_dewasa <> ''
+), dicatat AS (
+    INSERT INTO nomor_register_ganda (tabel, baris_id, nomor_register)
+    SELECT 'layanan_integrasi_dewasa', id, nomor FROM urut WHERE ke > 1
+    RETURNING baris_id
+)
+UPDATE layanan_integrasi_dewasa SET nomor_register_integrasi_dewasa = NULL WHERE id IN (SELECT baris_id FROM dicatat);
+
+WITH urut AS (
+    SELECT id, nomor_register_proses_hukum_dewasa AS nomor,
+        ROW_NUMBER() OVER (PARTITION BY nomor_register_proses_hukum_dewasa ORDER BY created_at, id) AS ke
+    FROM proses_hukum_dewasa WHERE deleted_at IS NULL AND nomor_register_proses_hukum_dewasa <> ''
+), dicatat AS (
+    INSERT INTO nomor_register_ganda (tabel, baris_id, nomor_register)
+    SELECT 'proses_hukum_dewasa', id, nomor FROM urut WHERE ke > 1
+    RETURNING baris_id
+)
+UPDATE proses_hukum_dewasa SET nomor_register_proses_hukum_dewasa = NULL WHERE id IN (SELECT baris_id FROM dicatat);
+
+DO $$
+DECLARE
+    jumlah INTEGER;
+BEGIN
+    SELECT COUNT(*) INTO jumlah FROM nomor_register_ganda;
+    IF jumlah > 0 THEN
+        RAISE WARNING '% nomor register ganda dikosongkan; lihat tabel nomor_register_ganda', jumlah;
+    END IF;
+END;
+$$;
+
+-- Nomor register (otomatis maupun diisi manual) unik di antara data aktif,
+-- sehingga tabrakan nomor langsung ditolak alih-alih tersimpan diam-diam
+CREATE UNIQUE INDEX idx_penerimaan_dewasa_no_register_litmas ON penerimaan_dewasa(no_register_litmas_dewasa)
+    WHERE deleted_at IS NULL AND no_register_litmas_dewasa <> '';
+CREATE UNIQUE INDEX idx_layanan_integrasi_dewasa_nomor_register ON layanan_integrasi_dewasa(nomor_register_integrasi_dewasa)
+    WHERE deleted_at IS NULL AND nomor_register_integrasi_dewasa <> '';
+CREATE UNIQUE INDEX idx_proses_hukum_dewasa_nomor_register ON proses_hukum_dewasa(nomor_register_proses_hukum_dewasa)
+    WHERE deleted_at IS NULL AND nomor_register_proses_hukum_dewasa <> '';
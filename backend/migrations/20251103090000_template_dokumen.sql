@@ -0,0 +1,22 @@
+-- Add migration script here
+-- Template dokumen (Litmas, surat tugas, surat pengakhiran) untuk generator dokumen
+
+CREATE TYPE jenis_dokumen_enum AS ENUM ('Litmas', 'Surat Tugas', 'Surat Pengakhiran');
+
+-- bapas_id NULL berarti template nasional yang berlaku untuk semua Bapas
+CREATE TABLE template_dokumen (
+    id SERIAL PRIMARY KEY,
+    bapas_id INTEGER REFERENCES bapas(id) ON DELETE CASCADE,
+    jenis_dokumen jenis_dokumen_enum NOT NULL,
+    nama_template VARCHAR(255) NOT NULL,
+    isi_template TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TRIGGER set_timestamp BEFORE UPDATE ON template_dokumen FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX idx_template_dokumen_bapas_jenis ON template_dokumen(bapas_id, jenis_dokumen);
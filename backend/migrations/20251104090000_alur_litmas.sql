@@ -0,0 +1,57 @@
+-- Add migration script here
+-- Alur kerja Litmas: penugasan, draft, review oleh Kasubsi, persetujuan, pengiriman
+
+CREATE TYPE status_litmas_enum AS ENUM ('Diterima', 'Ditugaskan', 'Draft', 'Direview', 'Disetujui', 'Dikirim');
+
+-- Reviewer Litmas harus Kasubsi; ditandai admin secara eksplisit, bukan ditebak dari teks jabatan
+ALTER TABLE users ADD COLUMN kasubsi_user BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Satu baris per penerimaan; tanggal_jatuh_tempo adalah batas tahap yang sedang berjalan
+CREATE TABLE alur_litmas_dewasa (
+    id SERIAL PRIMARY KEY,
+    penerimaan_dewasa_id INTEGER NOT NULL UNIQUE REFERENCES penerimaan_dewasa(id) ON DELETE CASCADE,
+    klien_id INTEGER NOT NULL REFERENCES klien(id) ON DELETE CASCADE,
+    status_litmas status_litmas_enum NOT NULL DEFAULT 'Diterima',
+    petugas_litmas_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    reviewer_litmas_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    tanggal_jatuh_tempo DATE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- Jejak setiap perpindahan status beserta komentar revisi
+CREATE TABLE riwayat_alur_litmas_dewasa (
+    id SERIAL PRIMARY KEY,
+    alur_litmas_dewasa_id INTEGER NOT NULL REFERENCES alur_litmas_dewasa(id) ON DELETE CASCADE,
+    dari_status status_litmas_enum NOT NULL,
+    ke_status status_litmas_enum NOT NULL,
+    komentar TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE TRIGGER set_timestamp BEFORE UPDATE ON alur_litmas_dewasa FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX idx_alur_litmas_dewasa_klien_id ON alur_litmas_dewasa(klien_id);
+CREATE INDEX idx_alur_litmas_dewasa_jatuh_tempo ON alur_litmas_dewasa(tanggal_jatuh_tempo) WHERE status_litmas <> 'Dikirim';
+CREATE INDEX idx_riwayat_alur_litmas_dewasa_alur_id ON riwayat_alur_litmas_dewasa(alur_litmas_dewasa_id);
+
+-- Penerimaan yang sudah ada sebelum alur ini. Yang litmasnya sudah dipakai (ada
+-- proses hukum untuk penerimaan itu, atau layanan integrasi klien sesudahnya)
+-- dianggap selesai. Sisanya mulai dari Diterima dengan batas penugasan 2 hari
+-- sejak migrasi, supaya data lama tidak langsung tampil sebagai terlambat.
+INSERT INTO alur_litmas_dewasa (penerimaan_dewasa_id, klien_id, status_litmas, tanggal_jatuh_tempo, created_by, updated_by)
+SELECT p.id, p.klien_id,
+    CASE WHEN s.selesai THEN 'Dikirim'::status_litmas_enum ELSE 'Diterima'::status_litmas_enum END,
+    CASE WHEN s.selesai THEN NULL ELSE (NOW() AT TIME ZONE 'Asia/Jakarta')::DATE + 2 END,
+    p.created_by, p.created_by
+FROM penerimaan_dewasa p
+CROSS JOIN LATERAL (
+    SELECT EXISTS (SELECT 1 FROM proses_hukum_dewasa ph WHERE ph.penerimaan_dewasa_id = p.id)
+        OR EXISTS (
+            SELECT 1 FROM layanan_integrasi_dewasa l
+            WHERE l.klien_id = p.klien_id AND l.created_at >= p.created_at
+        ) AS selesai
+) s;
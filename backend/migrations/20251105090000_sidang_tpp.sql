@@ -0,0 +1,79 @@
+-- Add migration script here
+-- Sidang Tim Pengamat Pemasyarakatan (TPP): anggota, agenda per klien, suara, keputusan
+
+CREATE TYPE peran_tpp_enum AS ENUM ('Ketua', 'Sekretaris', 'Anggota');
+CREATE TYPE suara_tpp_enum AS ENUM ('Setuju', 'Tidak Setuju', 'Abstain');
+CREATE TYPE keputusan_tpp_enum AS ENUM ('Disetujui', 'Ditolak', 'Ditunda');
+
+CREATE TABLE sidang_tpp (
+    id SERIAL PRIMARY KEY,
+    bapas_id INTEGER NOT NULL REFERENCES bapas(id) ON DELETE CASCADE,
+    tanggal_sidang DATE NOT NULL,
+    tempat_sidang VARCHAR(255),
+    keterangan TEXT,
+    -- Notulen yang dikunci membekukan anggota, agenda, suara, dan keputusan.
+    -- Setiap penguncian ulang setelah dibuka menghasilkan versi notulen baru.
+    notulen_versi INTEGER NOT NULL DEFAULT 0,
+    notulen_dikunci_at TIMESTAMPTZ,
+    notulen_dikunci_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TABLE anggota_sidang_tpp (
+    sidang_tpp_id INTEGER NOT NULL REFERENCES sidang_tpp(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    peran_tpp peran_tpp_enum NOT NULL DEFAULT 'Anggota',
+    PRIMARY KEY (sidang_tpp_id, user_id)
+);
+
+-- Satu agenda = satu klien yang dibahas; layanan_integrasi_dewasa_id diisi
+-- setelah rekomendasi yang disetujui menghasilkan layanan integrasi
+CREATE TABLE agenda_sidang_tpp (
+    id SERIAL PRIMARY KEY,
+    sidang_tpp_id INTEGER NOT NULL REFERENCES sidang_tpp(id) ON DELETE CASCADE,
+    klien_id INTEGER NOT NULL REFERENCES klien(id) ON DELETE CASCADE,
+    penerimaan_dewasa_id INTEGER REFERENCES penerimaan_dewasa(id) ON DELETE SET NULL,
+    urutan INTEGER NOT NULL DEFAULT 1,
+    uraian TEXT,
+    rekomendasi TEXT,
+    keputusan_tpp keputusan_tpp_enum,
+    layanan_integrasi_dewasa_id INTEGER REFERENCES layanan_integrasi_dewasa(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE TABLE suara_agenda_tpp (
+    agenda_sidang_tpp_id INTEGER NOT NULL REFERENCES agenda_sidang_tpp(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    suara_tpp suara_tpp_enum NOT NULL,
+    catatan TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (agenda_sidang_tpp_id, user_id)
+);
+
+-- Isi notulen pada setiap penguncian. Versi lama tetap tersimpan walaupun
+-- notulen dibuka dan dikunci ulang.
+CREATE TABLE versi_notulen_sidang_tpp (
+    id SERIAL PRIMARY KEY,
+    sidang_tpp_id INTEGER NOT NULL REFERENCES sidang_tpp(id) ON DELETE CASCADE,
+    versi INTEGER NOT NULL,
+    isi JSONB NOT NULL,
+    dikunci_at TIMESTAMPTZ NOT NULL,
+    dikunci_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    UNIQUE (sidang_tpp_id, versi)
+);
+
+CREATE TRIGGER set_timestamp BEFORE UPDATE ON sidang_tpp FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp();
+CREATE TRIGGER set_timestamp BEFORE UPDATE ON agenda_sidang_tpp FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp();
+CREATE TRIGGER set_timestamp BEFORE UPDATE ON suara_agenda_tpp FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX idx_sidang_tpp_bapas_tanggal ON sidang_tpp(bapas_id, tanggal_sidang);
+CREATE INDEX idx_agenda_sidang_tpp_sidang_id ON agenda_sidang_tpp(sidang_tpp_id);
+CREATE INDEX idx_agenda_sidang_tpp_klien_id ON agenda_sidang_tpp(klien_id);
@@ -0,0 +1,40 @@
+-- Add migration script here
+-- Sesi bimbingan kepribadian/kemandirian (individu maupun kelompok) dan kehadiran klien
+
+CREATE TYPE program_bimbingan_enum AS ENUM ('Kepribadian', 'Kemandirian');
+CREATE TYPE bentuk_bimbingan_enum AS ENUM ('Individu', 'Kelompok');
+CREATE TYPE status_kehadiran_enum AS ENUM ('Hadir', 'Izin', 'Tidak Hadir');
+
+CREATE TABLE sesi_bimbingan (
+    id SERIAL PRIMARY KEY,
+    bapas_id INTEGER NOT NULL REFERENCES bapas(id) ON DELETE CASCADE,
+    pembimbing_id INTEGER NOT NULL REFERENCES users(id) ON DELETE RESTRICT,
+    program_bimbingan program_bimbingan_enum NOT NULL,
+    bentuk_bimbingan bentuk_bimbingan_enum NOT NULL,
+    topik VARCHAR(255) NOT NULL,
+    waktu_mulai TIMESTAMPTZ NOT NULL,
+    durasi_menit INTEGER NOT NULL CHECK (durasi_menit > 0),
+    tempat VARCHAR(255),
+    catatan TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    deleted_at TIMESTAMPTZ
+);
+
+-- Setiap peserta terhubung ke layanan integrasi yang sedang dijalaninya
+CREATE TABLE peserta_sesi_bimbingan (
+    sesi_bimbingan_id INTEGER NOT NULL REFERENCES sesi_bimbingan(id) ON DELETE CASCADE,
+    klien_id INTEGER NOT NULL REFERENCES klien(id) ON DELETE CASCADE,
+    layanan_integrasi_dewasa_id INTEGER REFERENCES layanan_integrasi_dewasa(id) ON DELETE SET NULL,
+    status_kehadiran status_kehadiran_enum NOT NULL DEFAULT 'Hadir',
+    catatan TEXT,
+    PRIMARY KEY (sesi_bimbingan_id, klien_id)
+);
+
+CREATE TRIGGER set_timestamp BEFORE UPDATE ON sesi_bimbingan FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX idx_sesi_bimbingan_bapas_waktu ON sesi_bimbingan(bapas_id, waktu_mulai);
+CREATE INDEX idx_sesi_bimbingan_pembimbing_id ON sesi_bimbingan(pembimbing_id);
+CREATE INDEX idx_peserta_sesi_bimbingan_klien_id ON peserta_sesi_bimbingan(klien_id);
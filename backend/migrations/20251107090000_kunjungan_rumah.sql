@@ -0,0 +1,83 @@
+-- Add migration script here
+-- Kunjungan rumah klien/penjamin oleh PK: lokasi, foto, temuan, dan tindak lanjut
+
+CREATE TYPE tujuan_kunjungan_enum AS ENUM (
+    'Penelitian Kemasyarakatan', 'Pengawasan', 'Pembimbingan', 'Verifikasi Penjamin', 'Lainnya'
+);
+
+CREATE TABLE kunjungan_rumah (
+    id SERIAL PRIMARY KEY,
+    klien_id INTEGER NOT NULL REFERENCES klien(id) ON DELETE CASCADE,
+    penerimaan_dewasa_id INTEGER REFERENCES penerimaan_dewasa(id) ON DELETE SET NULL,
+    petugas_id INTEGER NOT NULL REFERENCES users(id) ON DELETE RESTRICT,
+    waktu_kunjungan TIMESTAMPTZ NOT NULL,
+    tujuan_kunjungan tujuan_kunjungan_enum NOT NULL,
+    alamat_kunjungan TEXT,
+    -- Geotag dan foto disimpan seperti pada wajib_lapor_dewasa
+    latitude DECIMAL(10, 8),
+    longitude DECIMAL(11, 8),
+    photo_paths TEXT[] NOT NULL DEFAULT '{}',
+    -- Temuan terstruktur
+    temuan_kondisi_rumah TEXT,
+    temuan_hubungan_keluarga TEXT,
+    temuan_lingkungan TEXT,
+    temuan_penjamin TEXT,
+    temuan_pekerjaan TEXT,
+    kesimpulan TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TABLE orang_ditemui_kunjungan (
+    id SERIAL PRIMARY KEY,
+    kunjungan_rumah_id INTEGER NOT NULL REFERENCES kunjungan_rumah(id) ON DELETE CASCADE,
+    nama VARCHAR(255) NOT NULL,
+    hubungan_dengan_klien VARCHAR(100)
+);
+
+CREATE TABLE tindak_lanjut_kunjungan (
+    id SERIAL PRIMARY KEY,
+    kunjungan_rumah_id INTEGER NOT NULL REFERENCES kunjungan_rumah(id) ON DELETE CASCADE,
+    uraian TEXT NOT NULL,
+    batas_waktu DATE,
+    selesai BOOLEAN NOT NULL DEFAULT FALSE,
+    tanggal_selesai DATE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE TRIGGER set_timestamp BEFORE UPDATE ON kunjungan_rumah FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp();
+CREATE TRIGGER set_timestamp BEFORE UPDATE ON tindak_lanjut_kunjungan FOR EACH ROW EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX idx_kunjungan_rumah_klien_id ON kunjungan_rumah(klien_id);
+CREATE INDEX idx_kunjungan_rumah_petugas_waktu ON kunjungan_rumah(petugas_id, waktu_kunjungan);
+CREATE INDEX idx_orang_ditemui_kunjungan_id ON orang_ditemui_kunjungan(kunjungan_rumah_id);
+CREATE INDEX idx_tindak_lanjut_kunjungan_id ON tindak_lanjut_kunjungan(kunjungan_rumah_id);
+
+-- Kunjungan ikut terhapus (soft) bersama klien
+CREATE OR REPLACE FUNCTION cascade_soft_delete_from_klien()
+RETURNS TRIGGER AS $$
+BEGIN
+    -- Update tabel workflow Dewasa
+    UPDATE penerimaan_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE riwayat_hukum_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE layanan_integrasi_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE wajib_lapor_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE proses_hukum_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE remisi_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE kunjungan_rumah SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+
+    -- Update tabel workflow Anak
+    UPDATE penerimaan_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE riwayat_hukum_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE layanan_integrasi_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE wajib_lapor_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE proses_hukum_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,65 @@
+-- Add migration script here
+-- Sinkronisasi offline untuk petugas lapangan: log operasi idempoten dan catatan lapangan
+
+-- Waktu lapor menurut perangkat, diisi jika laporan dikirim belakangan (offline)
+ALTER TABLE wajib_lapor_dewasa ADD COLUMN waktu_lapor_klien TIMESTAMPTZ;
+
+CREATE TABLE catatan_lapangan (
+    id SERIAL PRIMARY KEY,
+    klien_id INTEGER NOT NULL REFERENCES klien(id) ON DELETE CASCADE,
+    kunjungan_rumah_id INTEGER REFERENCES kunjungan_rumah(id) ON DELETE SET NULL,
+    isi TEXT NOT NULL,
+    waktu_catat TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON catatan_lapangan
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX idx_catatan_lapangan_klien_id ON catatan_lapangan(klien_id);
+
+CREATE TYPE jenis_operasi_sinkron_enum AS ENUM ('Wajib Lapor', 'Kunjungan Rumah', 'Catatan Lapangan');
+
+-- Satu baris per operasi yang sudah diterapkan; client_uuid dibuat oleh perangkat
+-- sehingga pengiriman ulang batch yang sama tidak menggandakan data.
+CREATE TABLE operasi_sinkronisasi (
+    client_uuid VARCHAR(36) PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    jenis_operasi jenis_operasi_sinkron_enum NOT NULL,
+    entitas_id BIGINT NOT NULL,
+    waktu_klien TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_operasi_sinkronisasi_user_id ON operasi_sinkronisasi(user_id);
+
+-- Catatan lapangan ikut terhapus (soft) bersama klien
+CREATE OR REPLACE FUNCTION cascade_soft_delete_from_klien()
+RETURNS TRIGGER AS $$
+BEGIN
+    -- Update tabel workflow Dewasa
+    UPDATE penerimaan_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE riwayat_hukum_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE layanan_integrasi_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE wajib_lapor_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE proses_hukum_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE remisi_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE kunjungan_rumah SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE catatan_lapangan SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+
+    -- Update tabel workflow Anak
+    UPDATE penerimaan_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE riwayat_hukum_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE layanan_integrasi_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE wajib_lapor_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE proses_hukum_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,20 @@
+-- Add migration script here
+-- Kartu klien ber-QR untuk check-in kiosk. Token di QR ditandatangani server
+-- dan merujuk baris di tabel ini, sehingga kartu bisa dicabut.
+
+CREATE TABLE kartu_klien (
+    id SERIAL PRIMARY KEY,
+    klien_id INTEGER NOT NULL REFERENCES klien(id) ON DELETE CASCADE,
+    -- Bapas penerbit; kiosk Bapas lain menolak kartu ini
+    bapas_id INTEGER NOT NULL REFERENCES bapas(id) ON DELETE RESTRICT,
+    diterbitkan_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    dicabut_at TIMESTAMPTZ,
+    dicabut_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    alasan_pencabutan TEXT
+);
+
+CREATE INDEX idx_kartu_klien_klien_id ON kartu_klien(klien_id);
+
+-- Hanya satu kartu aktif per klien
+CREATE UNIQUE INDEX idx_kartu_klien_aktif ON kartu_klien(klien_id) WHERE dicabut_at IS NULL;
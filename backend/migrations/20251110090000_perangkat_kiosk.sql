@@ -0,0 +1,32 @@
+-- Add migration script here
+-- Identitas perangkat kiosk per Bapas. Kiosk tidak lagi login sebagai user;
+-- setiap perangkat punya API key sendiri (hanya hash yang disimpan).
+
+CREATE TABLE perangkat_kiosk (
+    id SERIAL PRIMARY KEY,
+    bapas_id INTEGER NOT NULL REFERENCES bapas(id) ON DELETE RESTRICT,
+    nama_perangkat VARCHAR(100) NOT NULL,
+    lokasi TEXT,
+    api_key_hash TEXT NOT NULL UNIQUE,
+    aktif BOOLEAN NOT NULL DEFAULT TRUE,
+    terakhir_terlihat_at TIMESTAMPTZ,
+    versi_aplikasi VARCHAR(50),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON perangkat_kiosk
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX idx_perangkat_kiosk_bapas_id ON perangkat_kiosk(bapas_id);
+
+-- Check-in kiosk mencatat perangkatnya; created_by dibiarkan NULL
+ALTER TABLE wajib_lapor_dewasa
+    ADD COLUMN perangkat_kiosk_id INTEGER REFERENCES perangkat_kiosk(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_wajib_lapor_dewasa_perangkat_kiosk_id ON wajib_lapor_dewasa(perangkat_kiosk_id);
@@ -0,0 +1,58 @@
+-- Add migration script here
+-- Portal mandiri klien: akun login (kode klien + PIN), jadwal wajib lapor,
+-- dan pengumuman Bapas
+
+-- PIN tetap memakai klien.pin_klien_hash; tabel ini menyimpan kode login
+-- dan penguncian setelah beberapa kali gagal
+CREATE TABLE akun_portal_klien (
+    klien_id INTEGER PRIMARY KEY REFERENCES klien(id) ON DELETE CASCADE,
+    kode_klien VARCHAR(12) NOT NULL UNIQUE,
+    gagal_login INTEGER NOT NULL DEFAULT 0,
+    terkunci_sampai TIMESTAMPTZ,
+    login_terakhir_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON akun_portal_klien
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+-- Frekuensi wajib lapor per layanan integrasi; tanpa baris berarti 30 hari
+CREATE TABLE jadwal_wajib_lapor (
+    layanan_integrasi_dewasa_id INTEGER PRIMARY KEY REFERENCES layanan_integrasi_dewasa(id) ON DELETE CASCADE,
+    interval_hari INTEGER NOT NULL CHECK (interval_hari > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON jadwal_wajib_lapor
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE TABLE pengumuman_bapas (
+    id SERIAL PRIMARY KEY,
+    bapas_id INTEGER NOT NULL REFERENCES bapas(id) ON DELETE CASCADE,
+    judul VARCHAR(255) NOT NULL,
+    isi TEXT NOT NULL,
+    berlaku_mulai DATE NOT NULL DEFAULT CURRENT_DATE,
+    berlaku_sampai DATE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON pengumuman_bapas
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX idx_pengumuman_bapas_bapas_id ON pengumuman_bapas(bapas_id, berlaku_mulai DESC);
@@ -0,0 +1,68 @@
+-- Add migration script here
+-- Pengikatan perangkat untuk wajib lapor mandiri (online). Klien mendaftarkan
+-- HP-nya dengan kode sekali pakai dari PK; check-in mandiri wajib menyertakan
+-- token perangkat tersebut.
+
+CREATE TABLE perangkat_klien (
+    id SERIAL PRIMARY KEY,
+    klien_id INTEGER NOT NULL REFERENCES klien(id) ON DELETE RESTRICT,
+    nama_perangkat VARCHAR(100) NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    terakhir_dipakai_at TIMESTAMPTZ,
+    dicabut_at TIMESTAMPTZ,
+    dicabut_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    alasan_pencabutan TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON perangkat_klien
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX idx_perangkat_klien_klien_id ON perangkat_klien(klien_id);
+
+-- Kode pendaftaran sekali pakai; hanya hash yang disimpan
+CREATE TABLE kode_pendaftaran_perangkat (
+    id SERIAL PRIMARY KEY,
+    klien_id INTEGER NOT NULL REFERENCES klien(id) ON DELETE CASCADE,
+    kode_hash TEXT NOT NULL,
+    kedaluwarsa_at TIMESTAMPTZ NOT NULL,
+    dipakai_at TIMESTAMPTZ,
+    perangkat_klien_id INTEGER REFERENCES perangkat_klien(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_kode_pendaftaran_perangkat_klien_id ON kode_pendaftaran_perangkat(klien_id);
+
+-- Percobaan pendaftaran per klien (kode atau PIN salah). Setelah beberapa kali
+-- gagal, pendaftaran perangkat untuk klien itu dikunci sementara
+CREATE TABLE percobaan_pendaftaran_perangkat (
+    klien_id INTEGER PRIMARY KEY REFERENCES klien(id) ON DELETE CASCADE,
+    gagal_daftar INTEGER NOT NULL DEFAULT 0,
+    terkunci_sampai TIMESTAMPTZ
+);
+
+ALTER TABLE wajib_lapor_dewasa
+    ADD COLUMN perangkat_klien_id INTEGER REFERENCES perangkat_klien(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_wajib_lapor_dewasa_perangkat_klien_id ON wajib_lapor_dewasa(perangkat_klien_id);
+
+-- Peringatan untuk PK (mis. check-in dari perangkat baru)
+CREATE TYPE jenis_peringatan_enum AS ENUM ('Perangkat Baru');
+
+CREATE TABLE peringatan (
+    id BIGSERIAL PRIMARY KEY,
+    penerima_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    klien_id INTEGER REFERENCES klien(id) ON DELETE CASCADE,
+    jenis_peringatan jenis_peringatan_enum NOT NULL,
+    pesan TEXT NOT NULL,
+    -- ID baris terkait sesuai jenis (mis. perangkat_klien.id)
+    entitas_id BIGINT,
+    dibaca_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_peringatan_penerima_id ON peringatan(penerima_id, dibaca_at);
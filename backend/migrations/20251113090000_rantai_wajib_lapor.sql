@@ -0,0 +1,194 @@
+-- Add migration script here
+-- Rantai hash wajib lapor dewasa: setiap baris menyimpan hash yang mencakup
+-- hash baris sebelumnya milik klien yang sama, sehingga perubahan atau
+-- penghapusan langsung di database dapat dideteksi lewat endpoint verifikasi.
+-- Status penghapusan (deleted_at, deleted_by, alasan) disegel terpisah di
+-- hash_penghapusan dan disegel ulang setiap kali berubah, karena hash_rantai
+-- baris lama tidak bisa diganti tanpa memutus rantai baris sesudahnya.
+--
+-- Hash adalah HMAC-SHA256 dengan kunci RANTAI_LAPOR_SECRET yang hanya dipegang
+-- aplikasi. Aplikasi memasang kunci di setiap koneksi sebagai setting sesi
+-- `aksara.kunci_rantai_lapor`; tanpa kunci, pihak yang hanya punya akses tulis ke
+-- database tidak dapat menyegel ulang baris yang diubah. Jika tabel sudah berisi
+-- data, jalankan migrasi ini dengan kunci yang sama, misalnya
+-- PGOPTIONS='-c aksara.kunci_rantai_lapor=<kunci>' sqlx migrate run
+
+CREATE EXTENSION IF NOT EXISTS pgcrypto;
+
+ALTER TABLE wajib_lapor_dewasa
+    ADD COLUMN photo_hash_dewasa VARCHAR(64),
+    ADD COLUMN nomor_urut BIGINT,
+    ADD COLUMN hash_sebelumnya VARCHAR(64),
+    ADD COLUMN hash_rantai VARCHAR(64),
+    ADD COLUMN deleted_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN alasan_penghapusan TEXT,
+    -- Terhapus karena kliennya dihapus; hanya baris ini yang dipulihkan bersama klien
+    ADD COLUMN dihapus_oleh_kaskade BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN hash_penghapusan VARCHAR(64);
+
+-- Ujung rantai per klien; juga mendeteksi baris terakhir yang dihapus permanen
+CREATE TABLE rantai_wajib_lapor_dewasa (
+    klien_id INTEGER PRIMARY KEY REFERENCES klien(id) ON DELETE RESTRICT,
+    jumlah BIGINT NOT NULL DEFAULT 0,
+    hash_terakhir VARCHAR(64),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE OR REPLACE FUNCTION hmac_rantai_lapor(isi TEXT)
+RETURNS VARCHAR AS $$
+DECLARE
+    kunci TEXT := current_setting('aksara.kunci_rantai_lapor', true);
+BEGIN
+    IF kunci IS NULL OR kunci = '' THEN
+        RAISE EXCEPTION 'kunci rantai wajib lapor (aksara.kunci_rantai_lapor) belum dipasang pada sesi ini';
+    END IF;
+    RETURN encode(hmac(convert_to(isi, 'UTF8'), convert_to(kunci, 'UTF8'), 'sha256'), 'hex');
+END;
+$$ LANGUAGE plpgsql STABLE;
+
+-- Satu-satunya definisi isi yang di-hash; dipakai trigger dan verifikasi.
+-- Waktu ditulis dalam UTC dengan presisi mikrodetik agar hasilnya stabil.
+CREATE OR REPLACE FUNCTION hash_wajib_lapor_dewasa(w wajib_lapor_dewasa)
+RETURNS VARCHAR AS $$
+BEGIN
+    RETURN hmac_rantai_lapor(concat_ws('|',
+        w.klien_id::TEXT,
+        w.nomor_urut::TEXT,
+        COALESCE(w.hash_sebelumnya, ''),
+        COALESCE(w.photo_path_dewasa, ''),
+        COALESCE(w.photo_hash_dewasa, ''),
+        COALESCE(w.latitude_dewasa::TEXT, ''),
+        COALESCE(w.longitude_dewasa::TEXT, ''),
+        w.metode_lapor_dewasa::TEXT,
+        to_char(w.created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US'),
+        COALESCE(to_char(w.waktu_lapor_klien AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US'), '')
+    ));
+END;
+$$ LANGUAGE plpgsql STABLE;
+
+-- Segel status penghapusan, terikat ke hash_rantai barisnya
+CREATE OR REPLACE FUNCTION hash_penghapusan_wajib_lapor_dewasa(w wajib_lapor_dewasa)
+RETURNS VARCHAR AS $$
+BEGIN
+    RETURN hmac_rantai_lapor(concat_ws('|',
+        w.hash_rantai,
+        COALESCE(to_char(w.deleted_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US'), ''),
+        COALESCE(w.deleted_by::TEXT, ''),
+        COALESCE(w.alasan_penghapusan, ''),
+        w.dihapus_oleh_kaskade::TEXT
+    ));
+END;
+$$ LANGUAGE plpgsql STABLE;
+
+-- Mengisi hash untuk data yang sudah ada, urut per klien
+DO $$
+DECLARE
+    baris RECORD;
+    klien_sebelumnya INTEGER := NULL;
+    urut BIGINT := 0;
+    hash_lalu VARCHAR(64) := NULL;
+BEGIN
+    FOR baris IN SELECT id, klien_id FROM wajib_lapor_dewasa ORDER BY klien_id, created_at, id LOOP
+        IF klien_sebelumnya IS DISTINCT FROM baris.klien_id THEN
+            urut := 0;
+            hash_lalu := NULL;
+            klien_sebelumnya := baris.klien_id;
+        END IF;
+        urut := urut + 1;
+        UPDATE wajib_lapor_dewasa SET nomor_urut = urut, hash_sebelumnya = hash_lalu WHERE id = baris.id;
+        UPDATE wajib_lapor_dewasa w SET hash_rantai = hash_wajib_lapor_dewasa(w) WHERE w.id = baris.id
+            RETURNING hash_rantai INTO hash_lalu;
+        UPDATE wajib_lapor_dewasa w SET hash_penghapusan = hash_penghapusan_wajib_lapor_dewasa(w) WHERE w.id = baris.id;
+        INSERT INTO rantai_wajib_lapor_dewasa (klien_id, jumlah, hash_terakhir)
+        VALUES (baris.klien_id, urut, hash_lalu)
+        ON CONFLICT (klien_id) DO UPDATE SET jumlah = EXCLUDED.jumlah, hash_terakhir = EXCLUDED.hash_terakhir;
+    END LOOP;
+END;
+$$;
+
+ALTER TABLE wajib_lapor_dewasa
+    ALTER COLUMN nomor_urut SET NOT NULL,
+    ALTER COLUMN hash_rantai SET NOT NULL,
+    ALTER COLUMN hash_penghapusan SET NOT NULL;
+
+CREATE UNIQUE INDEX idx_wajib_lapor_dewasa_klien_nomor_urut ON wajib_lapor_dewasa(klien_id, nomor_urut);
+
+-- Semua jalur insert (petugas, kiosk, mandiri, sinkronisasi) otomatis tersegel.
+-- Baris ujung rantai dikunci supaya insert bersamaan untuk klien yang sama berurutan.
+CREATE OR REPLACE FUNCTION segel_wajib_lapor_dewasa()
+RETURNS TRIGGER AS $$
+DECLARE
+    ujung rantai_wajib_lapor_dewasa%ROWTYPE;
+BEGIN
+    INSERT INTO rantai_wajib_lapor_dewasa (klien_id) VALUES (NEW.klien_id)
+    ON CONFLICT (klien_id) DO NOTHING;
+    SELECT * INTO ujung FROM rantai_wajib_lapor_dewasa WHERE klien_id = NEW.klien_id FOR UPDATE;
+
+    NEW.nomor_urut := ujung.jumlah + 1;
+    NEW.hash_sebelumnya := ujung.hash_terakhir;
+    NEW.hash_rantai := hash_wajib_lapor_dewasa(NEW);
+    NEW.hash_penghapusan := hash_penghapusan_wajib_lapor_dewasa(NEW);
+
+    UPDATE rantai_wajib_lapor_dewasa
+    SET jumlah = NEW.nomor_urut, hash_terakhir = NEW.hash_rantai, updated_at = NOW()
+    WHERE klien_id = NEW.klien_id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER segel_rantai
+BEFORE INSERT ON wajib_lapor_dewasa
+FOR EACH ROW
+EXECUTE PROCEDURE segel_wajib_lapor_dewasa();
+
+-- Hapus dan pulihkan hanya bisa disegel ulang dari sesi yang memegang kunci,
+-- jadi perubahan langsung tanpa kunci gagal, dan perubahan dengan trigger
+-- dimatikan tertangkap oleh verifikasi
+CREATE OR REPLACE FUNCTION segel_penghapusan_wajib_lapor_dewasa()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.hash_penghapusan := hash_penghapusan_wajib_lapor_dewasa(NEW);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER segel_penghapusan
+BEFORE UPDATE OF deleted_at, deleted_by, alasan_penghapusan, dihapus_oleh_kaskade ON wajib_lapor_dewasa
+FOR EACH ROW
+EXECUTE PROCEDURE segel_penghapusan_wajib_lapor_dewasa();
+
+-- Penghapusan berantai dari klien ikut mencatat alasan dan penghapusnya.
+-- Laporan yang sudah dihapus sendiri (dengan alasannya) tidak disentuh, dan
+-- pemulihan klien hanya mengembalikan laporan yang terhapus karena klien.
+CREATE OR REPLACE FUNCTION cascade_soft_delete_from_klien()
+RETURNS TRIGGER AS $$
+BEGIN
+    -- Update tabel workflow Dewasa
+    UPDATE penerimaan_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE riwayat_hukum_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE layanan_integrasi_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    IF NEW.deleted_at IS NOT NULL THEN
+        UPDATE wajib_lapor_dewasa
+        SET deleted_at = NEW.deleted_at, deleted_by = NEW.updated_by, alasan_penghapusan = 'Klien dihapus',
+            dihapus_oleh_kaskade = TRUE
+        WHERE klien_id = NEW.id AND deleted_at IS NULL;
+    ELSE
+        UPDATE wajib_lapor_dewasa
+        SET deleted_at = NULL, deleted_by = NULL, alasan_penghapusan = NULL, dihapus_oleh_kaskade = FALSE
+        WHERE klien_id = NEW.id AND dihapus_oleh_kaskade;
+    END IF;
+    UPDATE proses_hukum_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE remisi_dewasa SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE kunjungan_rumah SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE catatan_lapangan SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+
+    -- Update tabel workflow Anak
+    UPDATE penerimaan_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE riwayat_hukum_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE layanan_integrasi_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE wajib_lapor_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+    UPDATE proses_hukum_anak SET deleted_at = NEW.deleted_at WHERE klien_id = NEW.id;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
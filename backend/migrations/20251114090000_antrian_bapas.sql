@@ -0,0 +1,80 @@
+-- Add migration script here
+-- Janji temu dan antrian layanan di kantor Bapas. Slot layanan per Bapas punya
+-- kapasitas; klien dipesankan oleh PK atau memesan sendiri lewat portal, lalu
+-- mengambil nomor antrian di kiosk pada hari kunjungan.
+
+CREATE TYPE status_antrian_enum AS ENUM (
+    'Dipesan', 'Menunggu', 'Dipanggil', 'Dilayani', 'Tidak Hadir', 'Dibatalkan'
+);
+
+CREATE TABLE slot_layanan_bapas (
+    id SERIAL PRIMARY KEY,
+    bapas_id INTEGER NOT NULL REFERENCES bapas(id) ON DELETE RESTRICT,
+    tanggal DATE NOT NULL,
+    jam_mulai TIME NOT NULL,
+    jam_selesai TIME NOT NULL,
+    kapasitas INTEGER NOT NULL CHECK (kapasitas > 0),
+    keterangan TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    deleted_at TIMESTAMPTZ,
+    CHECK (jam_selesai > jam_mulai)
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON slot_layanan_bapas
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE UNIQUE INDEX idx_slot_layanan_bapas_unik ON slot_layanan_bapas(bapas_id, tanggal, jam_mulai)
+    WHERE deleted_at IS NULL;
+
+-- Satu baris per kunjungan: janji temu (ada slot) atau datang langsung (tanpa slot).
+-- Data foto/lokasi dari kiosk disimpan sampai klien dilayani, lalu menjadi wajib lapor.
+CREATE TABLE antrian_bapas (
+    id SERIAL PRIMARY KEY,
+    bapas_id INTEGER NOT NULL REFERENCES bapas(id) ON DELETE RESTRICT,
+    tanggal DATE NOT NULL,
+    slot_layanan_id INTEGER REFERENCES slot_layanan_bapas(id) ON DELETE SET NULL,
+    klien_id INTEGER NOT NULL REFERENCES klien(id) ON DELETE RESTRICT,
+    nomor_antrian INTEGER,
+    status_antrian status_antrian_enum NOT NULL DEFAULT 'Dipesan',
+    dipesan_klien BOOLEAN NOT NULL DEFAULT FALSE,
+    perangkat_kiosk_id INTEGER REFERENCES perangkat_kiosk(id) ON DELETE SET NULL,
+    -- Foto dan lokasi check-in; untuk klien anak ikut disalin ke wajib_lapor_anak
+    photo_path_dewasa TEXT,
+    photo_hash_dewasa VARCHAR(64),
+    latitude_dewasa NUMERIC(9,6),
+    longitude_dewasa NUMERIC(9,6),
+    check_in_at TIMESTAMPTZ,
+    dipanggil_at TIMESTAMPTZ,
+    selesai_at TIMESTAMPTZ,
+    wajib_lapor_dewasa_id BIGINT REFERENCES wajib_lapor_dewasa(id) ON DELETE SET NULL,
+    wajib_lapor_anak_id BIGINT REFERENCES wajib_lapor_anak(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON antrian_bapas
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX idx_antrian_bapas_bapas_tanggal ON antrian_bapas(bapas_id, tanggal);
+CREATE INDEX idx_antrian_bapas_slot_layanan_id ON antrian_bapas(slot_layanan_id);
+CREATE UNIQUE INDEX idx_antrian_bapas_nomor ON antrian_bapas(bapas_id, tanggal, nomor_antrian);
+-- Satu kunjungan aktif per klien per hari
+CREATE UNIQUE INDEX idx_antrian_bapas_klien_aktif ON antrian_bapas(klien_id, tanggal)
+    WHERE status_antrian NOT IN ('Tidak Hadir', 'Dibatalkan');
+
+-- Penghitung nomor antrian harian per Bapas
+CREATE TABLE urutan_antrian_bapas (
+    bapas_id INTEGER NOT NULL REFERENCES bapas(id) ON DELETE CASCADE,
+    tanggal DATE NOT NULL,
+    nomor_terakhir INTEGER NOT NULL,
+    PRIMARY KEY (bapas_id, tanggal)
+);
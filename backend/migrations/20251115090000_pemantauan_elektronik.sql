@@ -0,0 +1,122 @@
+-- Add migration script here
+-- Pemantauan elektronik (gelang GPS) atas perintah pengadilan. Vendor pemantauan
+-- mengirim titik lacak secara berkelompok dengan API key masing-masing; zona
+-- inklusi/eksklusi per klien diperiksa saat titik diterima.
+
+CREATE TYPE jenis_zona_enum AS ENUM ('Inklusi', 'Eksklusi');
+
+ALTER TYPE jenis_peringatan_enum ADD VALUE 'Pelanggaran Zona';
+
+CREATE TABLE vendor_pemantauan (
+    id SERIAL PRIMARY KEY,
+    nama_vendor VARCHAR(255) NOT NULL,
+    api_key_hash VARCHAR(64) NOT NULL UNIQUE,
+    aktif BOOLEAN NOT NULL DEFAULT TRUE,
+    terakhir_kirim_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON vendor_pemantauan
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+-- Penugasan perangkat vendor ke klien. Selama aktif (selesai_at NULL), satu
+-- perangkat hanya untuk satu klien dan satu klien hanya memakai satu perangkat.
+CREATE TABLE pemantauan_elektronik (
+    id SERIAL PRIMARY KEY,
+    klien_id INTEGER NOT NULL REFERENCES klien(id) ON DELETE RESTRICT,
+    vendor_id INTEGER NOT NULL REFERENCES vendor_pemantauan(id) ON DELETE RESTRICT,
+    kode_perangkat VARCHAR(100) NOT NULL,
+    dasar_penetapan TEXT,
+    mulai_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    selesai_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON pemantauan_elektronik
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE UNIQUE INDEX idx_pemantauan_elektronik_perangkat_aktif ON pemantauan_elektronik(vendor_id, kode_perangkat)
+    WHERE selesai_at IS NULL;
+CREATE UNIQUE INDEX idx_pemantauan_elektronik_klien_aktif ON pemantauan_elektronik(klien_id)
+    WHERE selesai_at IS NULL;
+
+-- Titik lacak: baris ramping tanpa kolom audit. Indeks unik (klien_id, waktu_rekam)
+-- sekaligus menjadi indeks utama pembacaan jejak per klien dan menolak kiriman ulang.
+CREATE TABLE titik_lacak (
+    id BIGSERIAL PRIMARY KEY,
+    pemantauan_id INTEGER NOT NULL REFERENCES pemantauan_elektronik(id) ON DELETE CASCADE,
+    klien_id INTEGER NOT NULL REFERENCES klien(id) ON DELETE CASCADE,
+    waktu_rekam TIMESTAMPTZ NOT NULL,
+    latitude DOUBLE PRECISION NOT NULL,
+    longitude DOUBLE PRECISION NOT NULL,
+    akurasi_meter REAL,
+    diterima_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_titik_lacak_klien_waktu ON titik_lacak(klien_id, waktu_rekam);
+
+-- Zona berbentuk lingkaran (pusat + radius) atau poligon ([[lat, lon], ...]).
+-- Jam berlaku (WIB) opsional, mis. jam malam 21:00-06:00 di rumah.
+CREATE TABLE zona_pemantauan (
+    id SERIAL PRIMARY KEY,
+    klien_id INTEGER NOT NULL REFERENCES klien(id) ON DELETE RESTRICT,
+    nama_zona VARCHAR(255) NOT NULL,
+    jenis_zona jenis_zona_enum NOT NULL,
+    latitude_pusat DOUBLE PRECISION,
+    longitude_pusat DOUBLE PRECISION,
+    radius_meter DOUBLE PRECISION CHECK (radius_meter > 0),
+    poligon JSONB,
+    jam_mulai TIME,
+    jam_selesai TIME,
+    aktif BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    deleted_at TIMESTAMPTZ,
+    CHECK (
+        (poligon IS NULL AND latitude_pusat IS NOT NULL AND longitude_pusat IS NOT NULL AND radius_meter IS NOT NULL)
+        OR (poligon IS NOT NULL AND latitude_pusat IS NULL AND longitude_pusat IS NULL AND radius_meter IS NULL)
+    ),
+    CHECK ((jam_mulai IS NULL) = (jam_selesai IS NULL))
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON zona_pemantauan
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX idx_zona_pemantauan_klien_id ON zona_pemantauan(klien_id);
+
+-- Satu baris per episode pelanggaran: dibuka oleh titik pertama yang melanggar,
+-- ditutup oleh titik pertama yang kembali patuh. Peringatan PK dikirim saat dibuka.
+CREATE TABLE pelanggaran_zona (
+    id BIGSERIAL PRIMARY KEY,
+    zona_id INTEGER NOT NULL REFERENCES zona_pemantauan(id) ON DELETE CASCADE,
+    klien_id INTEGER NOT NULL REFERENCES klien(id) ON DELETE CASCADE,
+    mulai_at TIMESTAMPTZ NOT NULL,
+    selesai_at TIMESTAMPTZ,
+    titik_pertama_id BIGINT REFERENCES titik_lacak(id) ON DELETE SET NULL,
+    jumlah_titik INTEGER NOT NULL DEFAULT 1,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON pelanggaran_zona
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX idx_pelanggaran_zona_klien_id ON pelanggaran_zona(klien_id, mulai_at);
+CREATE UNIQUE INDEX idx_pelanggaran_zona_terbuka ON pelanggaran_zona(zona_id) WHERE selesai_at IS NULL;
@@ -0,0 +1,19 @@
+-- Add migration script here
+-- Lokasi kantor Bapas dan wilayah layanannya (geometri GeoJSON Polygon/MultiPolygon,
+-- urutan koordinat [lon, lat]) untuk peta sebaran klien dan check-in.
+
+CREATE TABLE lokasi_bapas (
+    bapas_id INTEGER PRIMARY KEY REFERENCES bapas(id) ON DELETE CASCADE,
+    latitude DOUBLE PRECISION NOT NULL CHECK (latitude BETWEEN -90 AND 90),
+    longitude DOUBLE PRECISION NOT NULL CHECK (longitude BETWEEN -180 AND 180),
+    wilayah_layanan JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON lokasi_bapas
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
@@ -0,0 +1,20 @@
+-- Add migration script here
+-- Laporan bulanan Bapas/Kanwil. Angka dihitung dari tabel operasional lalu
+-- dibekukan sebagai snapshot JSON saat laporan dikirim, sehingga perubahan data
+-- sesudahnya tidak mengubah angka yang sudah dilaporkan. Baris tidak pernah diubah.
+
+CREATE TABLE laporan_bulanan (
+    id SERIAL PRIMARY KEY,
+    bapas_id INTEGER REFERENCES bapas(id) ON DELETE RESTRICT,
+    kanwil_id INTEGER REFERENCES kanwil(id) ON DELETE RESTRICT,
+    -- Tanggal 1 bulan yang dilaporkan
+    periode DATE NOT NULL CHECK (EXTRACT(DAY FROM periode) = 1),
+    isi_laporan JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    -- Tepat satu cakupan: Bapas atau Kanwil
+    CHECK ((bapas_id IS NULL) <> (kanwil_id IS NULL))
+);
+
+CREATE UNIQUE INDEX idx_laporan_bulanan_bapas_periode ON laporan_bulanan(bapas_id, periode) WHERE bapas_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_laporan_bulanan_kanwil_periode ON laporan_bulanan(kanwil_id, periode) WHERE kanwil_id IS NOT NULL;
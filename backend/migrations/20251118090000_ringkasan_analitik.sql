@@ -0,0 +1,87 @@
+-- Add migration script here
+-- Ringkasan analitik untuk dashboard. Materialized view disegarkan berkala oleh
+-- penjadwal di aplikasi (REFRESH ... CONCURRENTLY, sehingga pembaca tidak terblokir);
+-- setiap view wajib punya unique index atas kolom pengelompokannya.
+-- Kolom pk_id/bapas_id/kanwil_id mengikuti klien agar filter role tetap berlaku.
+
+-- Wajib lapor per hari (WIB) per klien-grup dan metode
+CREATE MATERIALIZED VIEW mv_wajib_lapor_harian AS
+SELECT k.pk_id, k.bapas_id, k.kanwil_id, k.tipe_klien,
+    (w.waktu_lapor AT TIME ZONE 'Asia/Jakarta')::DATE AS tanggal,
+    w.metode_lapor,
+    COUNT(*) AS jumlah
+FROM (
+    SELECT klien_id, metode_lapor_dewasa AS metode_lapor, COALESCE(waktu_lapor_klien, created_at) AS waktu_lapor
+    FROM wajib_lapor_dewasa WHERE deleted_at IS NULL
+    UNION ALL
+    SELECT klien_id, metode_lapor_anak, created_at
+    FROM wajib_lapor_anak WHERE deleted_at IS NULL
+) w
+JOIN klien k ON k.id = w.klien_id
+WHERE k.deleted_at IS NULL
+GROUP BY 1, 2, 3, 4, 5, 6;
+
+CREATE UNIQUE INDEX idx_mv_wajib_lapor_harian_kunci
+    ON mv_wajib_lapor_harian(pk_id, bapas_id, kanwil_id, tipe_klien, tanggal, metode_lapor);
+CREATE INDEX idx_mv_wajib_lapor_harian_tanggal ON mv_wajib_lapor_harian(tanggal);
+
+-- Status layanan integrasi per hari penyegaran: Diakhiri (dicabut), Selesai (masa
+-- bimbingan lewat), atau Aktif
+CREATE MATERIALIZED VIEW mv_layanan_per_status AS
+SELECT k.pk_id, k.bapas_id, k.kanwil_id, k.tipe_klien, l.jenis_bimbingan,
+    CASE
+        WHEN COALESCE(l.pengakhiran, FALSE) THEN 'Diakhiri'
+        WHEN l.masa_akhir < (NOW() AT TIME ZONE 'Asia/Jakarta')::DATE THEN 'Selesai'
+        ELSE 'Aktif'
+    END AS status_layanan,
+    COUNT(*) AS jumlah
+FROM (
+    SELECT klien_id, jenis_bimbingan_dewasa AS jenis_bimbingan, pengakhiran_dewasa AS pengakhiran,
+        masa_bimbingan_akhir_dewasa AS masa_akhir
+    FROM layanan_integrasi_dewasa WHERE deleted_at IS NULL
+    UNION ALL
+    SELECT klien_id, jenis_bimbingan_anak, pengakhiran_anak, masa_bimbingan_akhir_anak
+    FROM layanan_integrasi_anak WHERE deleted_at IS NULL
+) l
+JOIN klien k ON k.id = l.klien_id
+WHERE k.deleted_at IS NULL
+GROUP BY 1, 2, 3, 4, 5, 6;
+
+CREATE UNIQUE INDEX idx_mv_layanan_per_status_kunci
+    ON mv_layanan_per_status(pk_id, bapas_id, kanwil_id, tipe_klien, jenis_bimbingan, status_layanan);
+
+-- Klien per PK menurut demografi; jumlah_klien_aktif = klien dengan layanan Aktif
+CREATE MATERIALIZED VIEW mv_klien_per_pk AS
+SELECT k.pk_id, k.bapas_id, k.kanwil_id, k.tipe_klien,
+    k.jenis_kelamin_klien AS jenis_kelamin,
+    k.pendidikan_terakhir_klien AS pendidikan,
+    k.pekerjaan_klien AS pekerjaan,
+    k.kewarganegaraan_klien AS kewarganegaraan,
+    COUNT(*) AS jumlah_klien,
+    COUNT(*) FILTER (WHERE EXISTS (
+        SELECT 1 FROM layanan_integrasi_dewasa l
+        WHERE l.klien_id = k.id AND l.deleted_at IS NULL AND NOT COALESCE(l.pengakhiran_dewasa, FALSE)
+            AND (l.masa_bimbingan_akhir_dewasa IS NULL OR l.masa_bimbingan_akhir_dewasa >= (NOW() AT TIME ZONE 'Asia/Jakarta')::DATE)
+        UNION ALL
+        SELECT 1 FROM layanan_integrasi_anak l
+        WHERE l.klien_id = k.id AND l.deleted_at IS NULL AND NOT COALESCE(l.pengakhiran_anak, FALSE)
+            AND (l.masa_bimbingan_akhir_anak IS NULL OR l.masa_bimbingan_akhir_anak >= (NOW() AT TIME ZONE 'Asia/Jakarta')::DATE)
+    )) AS jumlah_klien_aktif
+FROM klien k
+WHERE k.deleted_at IS NULL
+GROUP BY 1, 2, 3, 4, 5, 6, 7, 8;
+
+CREATE UNIQUE INDEX idx_mv_klien_per_pk_kunci
+    ON mv_klien_per_pk(pk_id, bapas_id, kanwil_id, tipe_klien, jenis_kelamin, pendidikan, pekerjaan, kewarganegaraan);
+
+-- Waktu penyegaran terakhir per view, ditampilkan sebagai "data per" di dashboard
+CREATE TABLE penyegaran_analitik (
+    nama_view TEXT PRIMARY KEY,
+    disegarkan_at TIMESTAMPTZ NOT NULL,
+    durasi_ms INTEGER NOT NULL
+);
+
+INSERT INTO penyegaran_analitik (nama_view, disegarkan_at, durasi_ms) VALUES
+    ('mv_wajib_lapor_harian', NOW(), 0),
+    ('mv_layanan_per_status', NOW(), 0),
+    ('mv_klien_per_pk', NOW(), 0);
@@ -0,0 +1,94 @@
+-- Add migration script here
+-- Residivisme dihitung dari data, bukan hanya dari flag manual klien.pengulangan_klien.
+-- Seorang klien dihitung residivis jika memenuhi salah satu:
+--   1. punya lebih dari satu riwayat hukum,
+--   2. ada penerimaan baru setelah layanan integrasinya berakhir (pengakhiran atau
+--      masa bimbingan habis),
+-- Kecocokan identitas (nama yang dinormalkan + tanggal lahir) dengan klien lain
+-- yang terdaftar lebih dulu tidak ikut dihitung: dua orang berbeda bisa bernama
+-- sama dan lahir di hari yang sama. Kecocokan itu hanya dilaporkan sebagai selisih
+-- untuk diperiksa petugas.
+-- View biasa (bukan materialized) supaya selalu sejalan dengan data terbaru.
+
+CREATE INDEX idx_klien_identitas
+    ON klien (LOWER(REGEXP_REPLACE(BTRIM(nama_klien), '\s+', ' ', 'g')), tanggal_lahir_klien)
+    WHERE deleted_at IS NULL AND tanggal_lahir_klien IS NOT NULL;
+
+CREATE VIEW v_residivis_klien AS
+WITH riwayat AS (
+    SELECT klien_id, kategori_tindak_pidana_dewasa AS kategori,
+        COALESCE(tanggal_surat_keputusan_pengadilan_dewasa, (created_at AT TIME ZONE 'Asia/Jakarta')::DATE) AS tanggal, created_at
+    FROM riwayat_hukum_dewasa WHERE deleted_at IS NULL
+    UNION ALL
+    SELECT klien_id, kategori_tindak_pidana_anak,
+        COALESCE(tanggal_surat_keputusan_pengadilan_anak, (created_at AT TIME ZONE 'Asia/Jakarta')::DATE), created_at
+    FROM riwayat_hukum_anak WHERE deleted_at IS NULL
+),
+penerimaan AS (
+    SELECT klien_id, COALESCE(tanggal_permintaan_lapas_dewasa, (created_at AT TIME ZONE 'Asia/Jakarta')::DATE) AS tanggal
+    FROM penerimaan_dewasa WHERE deleted_at IS NULL
+    UNION ALL
+    SELECT klien_id, COALESCE(tanggal_permintaan_lapas_anak, (created_at AT TIME ZONE 'Asia/Jakarta')::DATE)
+    FROM penerimaan_anak WHERE deleted_at IS NULL
+),
+layanan AS (
+    SELECT klien_id, jenis_bimbingan_dewasa AS jenis_bimbingan, created_at,
+        CASE
+            WHEN pengakhiran_dewasa THEN COALESCE(tanggal_surat_pengakhiran_dewasa, (updated_at AT TIME ZONE 'Asia/Jakarta')::DATE)
+            ELSE masa_bimbingan_akhir_dewasa
+        END AS berakhir
+    FROM layanan_integrasi_dewasa WHERE deleted_at IS NULL
+    UNION ALL
+    SELECT klien_id, jenis_bimbingan_anak, created_at,
+        CASE
+            WHEN pengakhiran_anak THEN COALESCE(tanggal_surat_pengakhiran_anak, (updated_at AT TIME ZONE 'Asia/Jakarta')::DATE)
+            ELSE masa_bimbingan_akhir_anak
+        END
+    FROM layanan_integrasi_anak WHERE deleted_at IS NULL
+),
+dasar AS (
+    SELECT k.id AS klien_id, k.pk_id, k.bapas_id, k.kanwil_id, k.tipe_klien, k.pengulangan_klien,
+        (SELECT COUNT(*) FROM riwayat r WHERE r.klien_id = k.id) AS jumlah_riwayat_hukum,
+        EXISTS (
+            SELECT 1 FROM penerimaan p JOIN layanan l ON l.klien_id = p.klien_id
+            WHERE p.klien_id = k.id AND l.berakhir IS NOT NULL AND p.tanggal > l.berakhir
+        ) AS penerimaan_setelah_layanan_berakhir,
+        (
+            SELECT MIN(k2.id) FROM klien k2
+            WHERE k2.deleted_at IS NULL AND k2.id <> k.id
+                AND k2.tanggal_lahir_klien = k.tanggal_lahir_klien
+                AND LOWER(REGEXP_REPLACE(BTRIM(k2.nama_klien), '\s+', ' ', 'g'))
+                    = LOWER(REGEXP_REPLACE(BTRIM(k.nama_klien), '\s+', ' ', 'g'))
+                AND (k2.created_at, k2.id) < (k.created_at, k.id)
+        ) AS identitas_cocok_klien_id,
+        (SELECT r.kategori FROM riwayat r WHERE r.klien_id = k.id ORDER BY r.tanggal DESC, r.created_at DESC LIMIT 1)
+            AS kategori_tindak_pidana,
+        (SELECT l.jenis_bimbingan FROM layanan l WHERE l.klien_id = k.id ORDER BY l.created_at DESC LIMIT 1)
+            AS jenis_bimbingan,
+        CASE
+            WHEN k.tanggal_lahir_klien IS NULL THEN NULL
+            WHEN AGE(k.tanggal_lahir_klien) < INTERVAL '18 years' THEN '< 18'
+            WHEN AGE(k.tanggal_lahir_klien) < INTERVAL '26 years' THEN '18-25'
+            WHEN AGE(k.tanggal_lahir_klien) < INTERVAL '36 years' THEN '26-35'
+            WHEN AGE(k.tanggal_lahir_klien) < INTERVAL '46 years' THEN '36-45'
+            WHEN AGE(k.tanggal_lahir_klien) < INTERVAL '56 years' THEN '46-55'
+            ELSE '> 55'
+        END AS kelompok_umur
+    FROM klien k
+    WHERE k.deleted_at IS NULL
+)
+SELECT d.*,
+    (d.jumlah_riwayat_hukum > 1 OR d.penerimaan_setelah_layanan_berakhir) AS residivis_terhitung
+FROM dasar d;
+
+-- Jejak penyelarasan flag pengulangan_klien oleh admin, beserta bukti saat itu
+CREATE TABLE penyelarasan_residivis_klien (
+    id SERIAL PRIMARY KEY,
+    klien_id INTEGER NOT NULL REFERENCES klien(id) ON DELETE CASCADE,
+    jumlah_riwayat_hukum BIGINT NOT NULL,
+    penerimaan_setelah_layanan_berakhir BOOLEAN NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_penyelarasan_residivis_klien_klien_id ON penyelarasan_residivis_klien(klien_id);
@@ -0,0 +1,27 @@
+-- Add migration script here
+-- Definisi laporan ad-hoc (field, filter, pengelompokan, agregat) yang disimpan.
+-- Definisi hanya memuat kunci field dari daftar putih aplikasi; SQL disusun
+-- ulang setiap kali dijalankan dengan cakupan role pengguna yang menjalankan.
+-- Laporan dengan dibagikan = TRUE terlihat oleh semua pegawai di Bapas yang sama.
+
+CREATE TABLE laporan_kustom (
+    id SERIAL PRIMARY KEY,
+    bapas_id INTEGER REFERENCES bapas(id) ON DELETE CASCADE,
+    nama_laporan VARCHAR(255) NOT NULL,
+    deskripsi TEXT,
+    definisi JSONB NOT NULL,
+    dibagikan BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON laporan_kustom
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX idx_laporan_kustom_bapas_id ON laporan_kustom(bapas_id) WHERE deleted_at IS NULL;
+CREATE INDEX idx_laporan_kustom_created_by ON laporan_kustom(created_by) WHERE deleted_at IS NULL;
@@ -0,0 +1,128 @@
+-- Add migration script here
+-- Log perubahan klien dan tabel turunannya untuk change-feed integrasi eksternal
+-- dan sinkronisasi perangkat lapangan (kunjungan_rumah dan catatan_lapangan
+-- hanya dipakai sinkronisasi).
+-- Setiap INSERT/UPDATE/DELETE dicatat oleh trigger; soft delete (deleted_at terisi)
+-- dicatat sebagai 'delete' sehingga konsumen menerima tombstone, dan pemulihan
+-- (deleted_at dikosongkan) dicatat sebagai 'insert'.
+--
+-- Urutan feed adalah (xid, id). Pembaca hanya mengambil entri dari transaksi yang
+-- lebih tua dari xmin snapshot-nya, sehingga entri dari transaksi yang commit
+-- belakangan tidak pernah terlewat oleh kursor.
+--
+-- pk_id/bapas_id/kanwil_id adalah cakupan klien saat perubahan terjadi, dipakai
+-- untuk membatasi feed sesuai role pemilik API key. Saat klien berpindah cakupan,
+-- klien dan semua baris turunannya yang aktif dicatat sebagai 'delete' untuk
+-- cakupan lama dan 'insert' untuk cakupan baru.
+
+CREATE TABLE log_perubahan (
+    id BIGSERIAL PRIMARY KEY,
+    xid BIGINT NOT NULL DEFAULT (pg_current_xact_id()::TEXT::BIGINT),
+    tabel VARCHAR(64) NOT NULL,
+    baris_id BIGINT NOT NULL,
+    klien_id INTEGER NOT NULL,
+    operasi VARCHAR(10) NOT NULL CHECK (operasi IN ('insert', 'update', 'delete')),
+    pk_id INTEGER,
+    bapas_id INTEGER,
+    kanwil_id INTEGER,
+    dicatat_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_log_perubahan_urutan ON log_perubahan(xid, id);
+
+-- Mencatat semua baris turunan aktif milik satu klien dengan cakupan tertentu
+CREATE OR REPLACE FUNCTION catat_turunan_klien(
+    p_klien_id INTEGER, p_operasi TEXT, p_pk_id INTEGER, p_bapas_id INTEGER, p_kanwil_id INTEGER
+)
+RETURNS VOID AS $$
+DECLARE
+    v_tabel TEXT;
+BEGIN
+    FOREACH v_tabel IN ARRAY ARRAY[
+        'penerimaan_dewasa', 'penerimaan_anak', 'riwayat_hukum_dewasa', 'riwayat_hukum_anak',
+        'layanan_integrasi_dewasa', 'layanan_integrasi_anak', 'proses_hukum_dewasa', 'proses_hukum_anak',
+        'remisi_dewasa', 'wajib_lapor_dewasa', 'wajib_lapor_anak', 'kunjungan_rumah', 'catatan_lapangan'
+    ] LOOP
+        EXECUTE format(
+            'INSERT INTO log_perubahan (tabel, baris_id, klien_id, operasi, pk_id, bapas_id, kanwil_id)
+             SELECT %L, t.id, t.klien_id, $2, $3, $4, $5 FROM %I t
+             WHERE t.klien_id = $1 AND t.deleted_at IS NULL ORDER BY t.id', v_tabel, v_tabel)
+        USING p_klien_id, p_operasi, p_pk_id, p_bapas_id, p_kanwil_id;
+    END LOOP;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION catat_log_perubahan()
+RETURNS TRIGGER AS $$
+DECLARE
+    baris RECORD;
+    v_operasi TEXT;
+    v_klien_id INTEGER;
+    v_pk_id INTEGER;
+    v_bapas_id INTEGER;
+    v_kanwil_id INTEGER;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        baris := OLD;
+        v_operasi := 'delete';
+    ELSE
+        baris := NEW;
+        IF TG_OP = 'INSERT' THEN
+            v_operasi := 'insert';
+        ELSIF NEW.deleted_at IS NOT NULL AND OLD.deleted_at IS NULL THEN
+            v_operasi := 'delete';
+        ELSIF NEW.deleted_at IS NULL AND OLD.deleted_at IS NOT NULL THEN
+            v_operasi := 'insert';
+        ELSIF NEW.deleted_at IS NOT NULL THEN
+            -- Perubahan pada baris yang sudah dihapus tidak perlu dikirim
+            RETURN NULL;
+        ELSE
+            v_operasi := 'update';
+        END IF;
+    END IF;
+
+    IF TG_TABLE_NAME = 'klien' THEN
+        v_klien_id := baris.id;
+        v_pk_id := baris.pk_id;
+        v_bapas_id := baris.bapas_id;
+        v_kanwil_id := baris.kanwil_id;
+
+        -- Klien pindah cakupan: pemilik lama menerima tombstone untuk klien dan
+        -- turunannya, lalu pemilik baru menerima semuanya sebagai data baru
+        IF TG_OP = 'UPDATE' AND v_operasi = 'update'
+            AND (OLD.pk_id, OLD.bapas_id, OLD.kanwil_id) IS DISTINCT FROM (NEW.pk_id, NEW.bapas_id, NEW.kanwil_id) THEN
+            INSERT INTO log_perubahan (tabel, baris_id, klien_id, operasi, pk_id, bapas_id, kanwil_id)
+            VALUES (TG_TABLE_NAME, OLD.id, OLD.id, 'delete', OLD.pk_id, OLD.bapas_id, OLD.kanwil_id);
+            PERFORM catat_turunan_klien(NEW.id, 'delete', OLD.pk_id, OLD.bapas_id, OLD.kanwil_id);
+
+            INSERT INTO log_perubahan (tabel, baris_id, klien_id, operasi, pk_id, bapas_id, kanwil_id)
+            VALUES (TG_TABLE_NAME, NEW.id, NEW.id, 'insert', NEW.pk_id, NEW.bapas_id, NEW.kanwil_id);
+            PERFORM catat_turunan_klien(NEW.id, 'insert', NEW.pk_id, NEW.bapas_id, NEW.kanwil_id);
+            RETURN NULL;
+        END IF;
+    ELSE
+        v_klien_id := baris.klien_id;
+        SELECT k.pk_id, k.bapas_id, k.kanwil_id INTO v_pk_id, v_bapas_id, v_kanwil_id
+        FROM klien k WHERE k.id = v_klien_id;
+    END IF;
+
+    INSERT INTO log_perubahan (tabel, baris_id, klien_id, operasi, pk_id, bapas_id, kanwil_id)
+    VALUES (TG_TABLE_NAME, baris.id, v_klien_id, v_operasi, v_pk_id, v_bapas_id, v_kanwil_id);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER catat_log_perubahan AFTER INSERT OR UPDATE OR DELETE ON klien FOR EACH ROW EXECUTE PROCEDURE catat_log_perubahan();
+CREATE TRIGGER catat_log_perubahan AFTER INSERT OR UPDATE OR DELETE ON penerimaan_dewasa FOR EACH ROW EXECUTE PROCEDURE catat_log_perubahan();
+CREATE TRIGGER catat_log_perubahan AFTER INSERT OR UPDATE OR DELETE ON penerimaan_anak FOR EACH ROW EXECUTE PROCEDURE catat_log_perubahan();
+CREATE TRIGGER catat_log_perubahan AFTER INSERT OR UPDATE OR DELETE ON riwayat_hukum_dewasa FOR EACH ROW EXECUTE PROCEDURE catat_log_perubahan();
+CREATE TRIGGER catat_log_perubahan AFTER INSERT OR UPDATE OR DELETE ON riwayat_hukum_anak FOR EACH ROW EXECUTE PROCEDURE catat_log_perubahan();
+CREATE TRIGGER catat_log_perubahan AFTER INSERT OR UPDATE OR DELETE ON layanan_integrasi_dewasa FOR EACH ROW EXECUTE PROCEDURE catat_log_perubahan();
+CREATE TRIGGER catat_log_perubahan AFTER INSERT OR UPDATE OR DELETE ON layanan_integrasi_anak FOR EACH ROW EXECUTE PROCEDURE catat_log_perubahan();
+CREATE TRIGGER catat_log_perubahan AFTER INSERT OR UPDATE OR DELETE ON proses_hukum_dewasa FOR EACH ROW EXECUTE PROCEDURE catat_log_perubahan();
+CREATE TRIGGER catat_log_perubahan AFTER INSERT OR UPDATE OR DELETE ON proses_hukum_anak FOR EACH ROW EXECUTE PROCEDURE catat_log_perubahan();
+CREATE TRIGGER catat_log_perubahan AFTER INSERT OR UPDATE OR DELETE ON remisi_dewasa FOR EACH ROW EXECUTE PROCEDURE catat_log_perubahan();
+CREATE TRIGGER catat_log_perubahan AFTER INSERT OR UPDATE OR DELETE ON wajib_lapor_dewasa FOR EACH ROW EXECUTE PROCEDURE catat_log_perubahan();
+CREATE TRIGGER catat_log_perubahan AFTER INSERT OR UPDATE OR DELETE ON wajib_lapor_anak FOR EACH ROW EXECUTE PROCEDURE catat_log_perubahan();
+CREATE TRIGGER catat_log_perubahan AFTER INSERT OR UPDATE OR DELETE ON kunjungan_rumah FOR EACH ROW EXECUTE PROCEDURE catat_log_perubahan();
+CREATE TRIGGER catat_log_perubahan AFTER INSERT OR UPDATE OR DELETE ON catatan_lapangan FOR EACH ROW EXECUTE PROCEDURE catat_log_perubahan();
@@ -0,0 +1,487 @@
+// File baru: src/antrian/handlers.rs
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::StatusCode,
+    Json,
+};
+use chrono::Duration;
+use sqlx::PgPool;
+use crate::auth::authorization::ensure_bapas_access;
+use crate::auth::model::{AuthenticatedKiosk, AuthenticatedKlien, AuthenticatedUser};
+use crate::kartu::model::KioskWajibLapor;
+use crate::kartu::token as kartu_token;
+use crate::penomoran::service::hari_ini;
+use crate::types::{StatusAntrianEnum, TipeKlienEnum};
+use super::model::{
+    AntrianBapas, CreateSlotLayanan, FilterAntrianHarian, FilterSlotLayanan, PesanAntrian, SlotLayananBapas,
+    TransisiAntrian, UpdateSlotLayanan,
+};
+use super::service;
+
+// Jangkauan bawaan daftar slot
+const RENTANG_SLOT_HARI: i64 = 13;
+
+async fn cari_slot(
+    pool: &PgPool,
+    bapas_id: i32,
+    mulai: chrono::NaiveDate,
+    selesai: chrono::NaiveDate,
+    id: Option<i32>,
+) -> Result<Vec<SlotLayananBapas>, sqlx::Error> {
+    sqlx::query_as!(
+        SlotLayananBapas,
+        r#"
+        SELECT s.id, s.bapas_id, s.tanggal, s.jam_mulai, s.jam_selesai, s.kapasitas,
+            COUNT(a.id) AS "terisi!", s.keterangan,
+            s.created_at, s.updated_at, s.created_by, s.updated_by
+        FROM slot_layanan_bapas s
+        LEFT JOIN antrian_bapas a ON a.slot_layanan_id = s.id
+            AND a.status_antrian NOT IN ('Tidak Hadir', 'Dibatalkan')
+        WHERE s.bapas_id = $1 AND s.tanggal BETWEEN $2 AND $3 AND s.deleted_at IS NULL
+            AND ($4::INT IS NULL OR s.id = $4)
+        GROUP BY s.id
+        ORDER BY s.tanggal, s.jam_mulai
+        "#,
+        bapas_id,
+        mulai,
+        selesai,
+        id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+async fn get_slot(pool: &PgPool, id: i32) -> Result<SlotLayananBapas, StatusCode> {
+    let slot = sqlx::query!(
+        "SELECT bapas_id, tanggal FROM slot_layanan_bapas WHERE id = $1 AND deleted_at IS NULL",
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    cari_slot(pool, slot.bapas_id, slot.tanggal, slot.tanggal, Some(id))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .pop()
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+// === SLOT LAYANAN ===
+
+// --- CREATE ---
+// URL: POST /api/bapas/:bapas_id/slot-layanan
+#[axum::debug_handler]
+pub async fn create_slot_layanan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(bapas_id): Path<i32>,
+    Json(payload): Json<CreateSlotLayanan>,
+) -> Result<(StatusCode, Json<SlotLayananBapas>), StatusCode> {
+    ensure_bapas_access(&pool, &user, bapas_id, true).await?;
+    if payload.jam_selesai <= payload.jam_mulai || payload.kapasitas <= 0 || payload.tanggal < hari_ini() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO slot_layanan_bapas (bapas_id, tanggal, jam_mulai, jam_selesai, kapasitas, keterangan, created_by, updated_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $7)
+        RETURNING id
+        "#,
+        bapas_id,
+        payload.tanggal,
+        payload.jam_mulai,
+        payload.jam_selesai,
+        payload.kapasitas,
+        payload.keterangan,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        if e.as_database_error().is_some_and(|db_err| db_err.is_unique_violation()) {
+            return StatusCode::CONFLICT; // Sudah ada slot dengan jam mulai yang sama
+        }
+        tracing::error!("Failed to create slot layanan: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((StatusCode::CREATED, Json(get_slot(&pool, id).await?)))
+}
+
+// --- READ ALL ---
+// URL: GET /api/bapas/:bapas_id/slot-layanan?tanggal_mulai=&tanggal_selesai=
+#[axum::debug_handler]
+pub async fn get_all_slot_layanan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(bapas_id): Path<i32>,
+    Query(filter): Query<FilterSlotLayanan>,
+) -> Result<Json<Vec<SlotLayananBapas>>, StatusCode> {
+    ensure_bapas_access(&pool, &user, bapas_id, false).await?;
+    let mulai = filter.tanggal_mulai.unwrap_or_else(hari_ini);
+    let selesai = filter.tanggal_selesai.unwrap_or(mulai + Duration::days(RENTANG_SLOT_HARI));
+
+    let list = cari_slot(&pool, bapas_id, mulai, selesai, None).await.map_err(|e| {
+        tracing::error!("Failed to fetch slot layanan: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// --- UPDATE ---
+// URL: PUT /api/slot-layanan/:id
+// Kapasitas tidak boleh diturunkan di bawah jumlah pesanan yang sudah ada.
+#[axum::debug_handler]
+pub async fn update_slot_layanan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateSlotLayanan>,
+) -> Result<Json<SlotLayananBapas>, StatusCode> {
+    let lama = get_slot(&pool, id).await?;
+    ensure_bapas_access(&pool, &user, lama.bapas_id, true).await?;
+    let jam_mulai = payload.jam_mulai.unwrap_or(lama.jam_mulai);
+    let jam_selesai = payload.jam_selesai.unwrap_or(lama.jam_selesai);
+    if jam_selesai <= jam_mulai || payload.kapasitas.is_some_and(|k| k <= 0) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if payload.kapasitas.is_some_and(|k| (k as i64) < lama.terisi) {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE slot_layanan_bapas
+        SET jam_mulai = $1, jam_selesai = $2,
+            kapasitas = COALESCE($3, kapasitas),
+            keterangan = COALESCE($4, keterangan),
+            updated_by = $5
+        WHERE id = $6 AND deleted_at IS NULL
+        "#,
+        jam_mulai,
+        jam_selesai,
+        payload.kapasitas,
+        payload.keterangan,
+        user.id,
+        id
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| {
+        if e.as_database_error().is_some_and(|db_err| db_err.is_unique_violation()) {
+            return StatusCode::CONFLICT;
+        }
+        tracing::error!("Failed to update slot layanan: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(get_slot(&pool, id).await?))
+}
+
+// --- DELETE (SOFT) ---
+// URL: DELETE /api/slot-layanan/:id
+// Slot yang masih punya pesanan aktif harus dikosongkan dulu.
+#[axum::debug_handler]
+pub async fn delete_slot_layanan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, StatusCode> {
+    let slot = get_slot(&pool, id).await?;
+    ensure_bapas_access(&pool, &user, slot.bapas_id, true).await?;
+    if slot.terisi > 0 {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    sqlx::query!(
+        "UPDATE slot_layanan_bapas SET deleted_at = NOW(), updated_by = $1 WHERE id = $2 AND deleted_at IS NULL",
+        user.id,
+        id
+    )
+    .execute(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// === ANTRIAN (STAF) ===
+
+// --- CREATE (PK memesankan slot untuk klien) ---
+// URL: POST /api/klien/:klien_id/antrian
+#[axum::debug_handler]
+pub async fn create_antrian_for_klien(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(klien_id): Path<i32>,
+    Json(payload): Json<PesanAntrian>,
+) -> Result<(StatusCode, Json<AntrianBapas>), StatusCode> {
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let id = service::pesan_slot(&mut tx, klien_id, payload.slot_layanan_id, Some(user.id), false).await?;
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok((StatusCode::CREATED, Json(service::get_antrian(&pool, id).await?)))
+}
+
+// --- READ ALL (riwayat kunjungan klien) ---
+// URL: GET /api/klien/:klien_id/antrian
+#[axum::debug_handler]
+pub async fn get_all_antrian_for_klien(
+    Extension(pool): Extension<PgPool>,
+    Path(klien_id): Path<i32>,
+) -> Result<Json<Vec<AntrianBapas>>, StatusCode> {
+    let list = service::cari_antrian(&pool, None, None, None, Some(klien_id))
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch antrian klien: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(list))
+}
+
+// --- READ (antrian harian Bapas) ---
+// URL: GET /api/bapas/:bapas_id/antrian?tanggal=
+#[axum::debug_handler]
+pub async fn get_antrian_harian(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(bapas_id): Path<i32>,
+    Query(filter): Query<FilterAntrianHarian>,
+) -> Result<Json<Vec<AntrianBapas>>, StatusCode> {
+    ensure_bapas_access(&pool, &user, bapas_id, false).await?;
+    let tanggal = filter.tanggal.unwrap_or_else(hari_ini);
+
+    let list = service::cari_antrian(&pool, None, Some(bapas_id), Some(tanggal), None)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch antrian harian: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(list))
+}
+
+// --- TRANSISI STATUS ---
+// URL: POST /api/antrian/:id/transisi
+// Dilayani otomatis mencatat wajib lapor (foto & lokasi dari check-in kiosk)
+// di tabel wajib lapor sesuai tipe klien.
+#[axum::debug_handler]
+pub async fn transisi_antrian(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<TransisiAntrian>,
+) -> Result<Json<AntrianBapas>, StatusCode> {
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let lama = sqlx::query!(
+        r#"
+        SELECT a.bapas_id, a.klien_id, a.status_antrian AS "status_antrian: StatusAntrianEnum", a.perangkat_kiosk_id,
+            a.photo_path_dewasa, a.photo_hash_dewasa, a.latitude_dewasa, a.longitude_dewasa, a.check_in_at,
+            k.tipe_klien AS "tipe_klien: TipeKlienEnum"
+        FROM antrian_bapas a JOIN klien k ON k.id = a.klien_id
+        WHERE a.id = $1
+        FOR UPDATE OF a
+        "#,
+        id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    ensure_bapas_access(&pool, &user, lama.bapas_id, false).await?;
+    service::periksa_transisi(lama.status_antrian, payload.ke_status)?;
+
+    let dilayani = payload.ke_status == StatusAntrianEnum::Dilayani;
+    let wajib_lapor_id = if dilayani && lama.tipe_klien == TipeKlienEnum::Dewasa {
+        let lapor_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO wajib_lapor_dewasa
+                (klien_id, metode_lapor_dewasa, created_by, perangkat_kiosk_id, photo_path_dewasa, photo_hash_dewasa,
+                 latitude_dewasa, longitude_dewasa, waktu_lapor_klien)
+            VALUES ($1, 'Self-Service', $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id
+            "#,
+            lama.klien_id,
+            user.id,
+            lama.perangkat_kiosk_id,
+            lama.photo_path_dewasa,
+            lama.photo_hash_dewasa,
+            lama.latitude_dewasa,
+            lama.longitude_dewasa,
+            lama.check_in_at
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to create wajib lapor from antrian {}: {}", id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        Some(lapor_id)
+    } else {
+        None
+    };
+    let wajib_lapor_anak_id = if dilayani && lama.tipe_klien == TipeKlienEnum::Anak {
+        let lapor_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO wajib_lapor_anak (klien_id, metode_lapor_anak, created_by, photo_path_anak, latitude_anak, longitude_anak)
+            VALUES ($1, 'Self-Service', $2, $3, $4, $5)
+            RETURNING id
+            "#,
+            lama.klien_id,
+            user.id,
+            lama.photo_path_dewasa,
+            lama.latitude_dewasa,
+            lama.longitude_dewasa
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to create wajib lapor anak from antrian {}: {}", id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        Some(lapor_id)
+    } else {
+        None
+    };
+
+    sqlx::query!(
+        r#"
+        UPDATE antrian_bapas
+        SET status_antrian = $1,
+            dipanggil_at = CASE WHEN $1::status_antrian_enum = 'Dipanggil' THEN NOW() ELSE dipanggil_at END,
+            selesai_at = CASE WHEN $1::status_antrian_enum IN ('Dilayani', 'Tidak Hadir', 'Dibatalkan') THEN NOW() ELSE selesai_at END,
+            wajib_lapor_dewasa_id = COALESCE($2, wajib_lapor_dewasa_id),
+            wajib_lapor_anak_id = COALESCE($5, wajib_lapor_anak_id),
+            updated_by = $3
+        WHERE id = $4
+        "#,
+        payload.ke_status as _,
+        wajib_lapor_id,
+        user.id,
+        id,
+        wajib_lapor_anak_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update status antrian {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(service::get_antrian(&pool, id).await?))
+}
+
+// === PORTAL KLIEN ===
+
+// --- SLOT TERSEDIA ---
+// URL: GET /api/portal/slot-layanan
+// Slot Bapas klien untuk 14 hari ke depan, termasuk yang sudah penuh.
+#[axum::debug_handler]
+pub async fn get_slot_layanan_saya(
+    Extension(pool): Extension<PgPool>,
+    Extension(klien): Extension<AuthenticatedKlien>,
+) -> Result<Json<Vec<SlotLayananBapas>>, StatusCode> {
+    let mulai = hari_ini();
+    let list = cari_slot(&pool, klien.bapas_id, mulai, mulai + Duration::days(RENTANG_SLOT_HARI), None)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch slot layanan (portal): {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(list))
+}
+
+// --- PESAN ---
+// URL: POST /api/portal/antrian
+#[axum::debug_handler]
+pub async fn pesan_antrian_saya(
+    Extension(pool): Extension<PgPool>,
+    Extension(klien): Extension<AuthenticatedKlien>,
+    Json(payload): Json<PesanAntrian>,
+) -> Result<(StatusCode, Json<AntrianBapas>), StatusCode> {
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let id = service::pesan_slot(&mut tx, klien.klien_id, payload.slot_layanan_id, None, true).await?;
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok((StatusCode::CREATED, Json(service::get_antrian(&pool, id).await?)))
+}
+
+// --- DAFTAR PESANAN ---
+// URL: GET /api/portal/antrian
+#[axum::debug_handler]
+pub async fn get_antrian_saya(
+    Extension(pool): Extension<PgPool>,
+    Extension(klien): Extension<AuthenticatedKlien>,
+) -> Result<Json<Vec<AntrianBapas>>, StatusCode> {
+    let list = service::cari_antrian(&pool, None, None, None, Some(klien.klien_id))
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch antrian (portal): {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(list))
+}
+
+// --- BATALKAN ---
+// URL: POST /api/portal/antrian/:id/batal
+// Klien hanya bisa membatalkan pesanan miliknya yang belum dipanggil.
+#[axum::debug_handler]
+pub async fn batal_antrian_saya(
+    Extension(pool): Extension<PgPool>,
+    Extension(klien): Extension<AuthenticatedKlien>,
+    Path(id): Path<i32>,
+) -> Result<Json<AntrianBapas>, StatusCode> {
+    let antrian = service::get_antrian(&pool, id).await?;
+    if antrian.klien_id != klien.klien_id {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    service::periksa_transisi(antrian.status_antrian, StatusAntrianEnum::Dibatalkan)?;
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE antrian_bapas SET status_antrian = 'Dibatalkan', selesai_at = NOW()
+        WHERE id = $1 AND status_antrian IN ('Dipesan', 'Menunggu')
+        "#,
+        id
+    )
+    .execute(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if result.rows_affected() == 0 {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    Ok(Json(service::get_antrian(&pool, id).await?))
+}
+
+// === KIOSK ===
+
+// --- AMBIL NOMOR ANTRIAN ---
+// URL: POST /api/kiosk/antrian (autentikasi perangkat kiosk)
+// Body sama dengan check-in wajib lapor kiosk; foto & lokasi disimpan sampai
+// klien dilayani. 201 jika nomor baru diterbitkan, 200 jika klien sudah memegang nomor.
+pub async fn kiosk_ambil_antrian(
+    Extension(pool): Extension<PgPool>,
+    Extension(kiosk): Extension<AuthenticatedKiosk>,
+    Json(payload): Json<KioskWajibLapor>,
+) -> Result<(StatusCode, Json<AntrianBapas>), StatusCode> {
+    let klien_id = kartu_token::verifikasi(&pool, kiosk.bapas_id, &payload.token_kartu).await?;
+
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let (id, baru) = service::check_in_kiosk(&mut tx, &kiosk, klien_id, &payload.lapor).await?;
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let status = if baru { StatusCode::CREATED } else { StatusCode::OK };
+    Ok((status, Json(service::get_antrian(&pool, id).await?)))
+}
@@ -0,0 +1,89 @@
+// File baru: src/antrian/model.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use crate::types::StatusAntrianEnum;
+
+// Slot layanan beserta jumlah pesanan aktif
+#[derive(Debug, Serialize, FromRow)]
+pub struct SlotLayananBapas {
+    pub id: i32,
+    pub bapas_id: i32,
+    pub tanggal: NaiveDate,
+    pub jam_mulai: NaiveTime,
+    pub jam_selesai: NaiveTime,
+    pub kapasitas: i32,
+    pub terisi: i64,
+    pub keterangan: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSlotLayanan {
+    pub tanggal: NaiveDate,
+    pub jam_mulai: NaiveTime,
+    pub jam_selesai: NaiveTime,
+    pub kapasitas: i32,
+    pub keterangan: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSlotLayanan {
+    pub jam_mulai: Option<NaiveTime>,
+    pub jam_selesai: Option<NaiveTime>,
+    pub kapasitas: Option<i32>,
+    pub keterangan: Option<String>,
+}
+
+// Query string daftar slot; bawaan 14 hari mulai hari ini
+#[derive(Debug, Deserialize)]
+pub struct FilterSlotLayanan {
+    pub tanggal_mulai: Option<NaiveDate>,
+    pub tanggal_selesai: Option<NaiveDate>,
+}
+
+// Satu kunjungan: janji temu (ada slot) atau datang langsung lewat kiosk
+#[derive(Debug, Serialize, FromRow)]
+pub struct AntrianBapas {
+    pub id: i32,
+    pub bapas_id: i32,
+    pub tanggal: NaiveDate,
+    pub slot_layanan_id: Option<i32>,
+    pub jam_mulai: Option<NaiveTime>,
+    pub jam_selesai: Option<NaiveTime>,
+    pub klien_id: i32,
+    pub nama_klien: String,
+    pub nomor_antrian: Option<i32>,
+    pub status_antrian: StatusAntrianEnum,
+    pub dipesan_klien: bool,
+    pub perangkat_kiosk_id: Option<i32>,
+    pub check_in_at: Option<DateTime<Utc>>,
+    pub dipanggil_at: Option<DateTime<Utc>>,
+    pub selesai_at: Option<DateTime<Utc>>,
+    pub wajib_lapor_dewasa_id: Option<i64>,
+    pub wajib_lapor_anak_id: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PesanAntrian {
+    pub slot_layanan_id: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransisiAntrian {
+    pub ke_status: StatusAntrianEnum,
+}
+
+// Query string antrian harian; bawaan hari ini
+#[derive(Debug, Deserialize)]
+pub struct FilterAntrianHarian {
+    pub tanggal: Option<NaiveDate>,
+}
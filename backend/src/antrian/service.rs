@@ -0,0 +1,262 @@
+// File baru: src/antrian/service.rs
+//
+// Alur status antrian:
+//   Dipesan -> Menunggu (ambil nomor di kiosk) -> Dipanggil -> Dilayani
+// Dipesan/Menunggu boleh Dibatalkan; Dipesan/Menunggu/Dipanggil boleh ditandai
+// Tidak Hadir. Dipanggil -> Dipanggil berarti panggilan ulang.
+
+use axum::http::StatusCode;
+use chrono::NaiveDate;
+use sqlx::{PgConnection, PgPool};
+use crate::auth::model::AuthenticatedKiosk;
+use crate::klien::model_dewasa::CreateWajibLapor;
+use crate::penomoran::service::hari_ini;
+use crate::types::StatusAntrianEnum;
+use super::model::AntrianBapas;
+
+struct AntrianAktif {
+    id: i32,
+    bapas_id: i32,
+    status_antrian: StatusAntrianEnum,
+}
+
+async fn antrian_aktif(conn: &mut PgConnection, klien_id: i32, tanggal: NaiveDate) -> Result<Option<AntrianAktif>, StatusCode> {
+    sqlx::query_as!(
+        AntrianAktif,
+        r#"
+        SELECT id, bapas_id, status_antrian AS "status_antrian: StatusAntrianEnum"
+        FROM antrian_bapas
+        WHERE klien_id = $1 AND tanggal = $2 AND status_antrian NOT IN ('Tidak Hadir', 'Dibatalkan')
+        FOR UPDATE
+        "#,
+        klien_id,
+        tanggal
+    )
+    .fetch_optional(conn)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+pub fn periksa_transisi(dari: StatusAntrianEnum, ke: StatusAntrianEnum) -> Result<(), StatusCode> {
+    use StatusAntrianEnum::*;
+
+    match (dari, ke) {
+        (Menunggu | Dipanggil, Dipanggil)
+        | (Dipanggil, Dilayani)
+        | (Dipesan | Menunggu | Dipanggil, TidakHadir)
+        | (Dipesan | Menunggu, Dibatalkan) => Ok(()),
+        _ => Err(StatusCode::CONFLICT),
+    }
+}
+
+/// Nomor antrian berikutnya untuk (Bapas, tanggal). Harus dipanggil di dalam
+/// transaksi yang juga menyimpan nomor tersebut.
+pub async fn ambil_nomor_antrian(
+    conn: &mut PgConnection,
+    bapas_id: i32,
+    tanggal: NaiveDate,
+) -> Result<i32, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        INSERT INTO urutan_antrian_bapas (bapas_id, tanggal, nomor_terakhir)
+        VALUES ($1, $2, 1)
+        ON CONFLICT (bapas_id, tanggal)
+        DO UPDATE SET nomor_terakhir = urutan_antrian_bapas.nomor_terakhir + 1
+        RETURNING nomor_terakhir
+        "#,
+        bapas_id,
+        tanggal
+    )
+    .fetch_one(conn)
+    .await
+}
+
+/// Daftar antrian dengan filter opsional; dipakai staf, portal, dan kiosk.
+pub async fn cari_antrian(
+    pool: &PgPool,
+    id: Option<i32>,
+    bapas_id: Option<i32>,
+    tanggal: Option<NaiveDate>,
+    klien_id: Option<i32>,
+) -> Result<Vec<AntrianBapas>, sqlx::Error> {
+    sqlx::query_as!(
+        AntrianBapas,
+        r#"
+        SELECT a.id, a.bapas_id, a.tanggal, a.slot_layanan_id,
+            s.jam_mulai AS "jam_mulai?", s.jam_selesai AS "jam_selesai?",
+            a.klien_id, k.nama_klien, a.nomor_antrian,
+            a.status_antrian AS "status_antrian: _", a.dipesan_klien, a.perangkat_kiosk_id,
+            a.check_in_at, a.dipanggil_at, a.selesai_at, a.wajib_lapor_dewasa_id, a.wajib_lapor_anak_id,
+            a.created_at, a.updated_at, a.created_by, a.updated_by
+        FROM antrian_bapas a
+        JOIN klien k ON k.id = a.klien_id
+        LEFT JOIN slot_layanan_bapas s ON s.id = a.slot_layanan_id
+        WHERE ($1::INT IS NULL OR a.id = $1)
+            AND ($2::INT IS NULL OR a.bapas_id = $2)
+            AND ($3::DATE IS NULL OR a.tanggal = $3)
+            AND ($4::INT IS NULL OR a.klien_id = $4)
+        ORDER BY a.tanggal DESC, a.nomor_antrian NULLS LAST, s.jam_mulai, a.id
+        "#,
+        id,
+        bapas_id,
+        tanggal,
+        klien_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_antrian(pool: &PgPool, id: i32) -> Result<AntrianBapas, StatusCode> {
+    cari_antrian(pool, Some(id), None, None, None)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .pop()
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Memesan slot untuk klien. NOT_FOUND jika slot tidak ada, BAD_REQUEST jika
+/// slot milik Bapas lain atau sudah lewat, CONFLICT jika penuh atau klien sudah
+/// punya kunjungan aktif di tanggal yang sama.
+pub async fn pesan_slot(
+    conn: &mut PgConnection,
+    klien_id: i32,
+    slot_layanan_id: i32,
+    user_id: Option<i32>,
+    dipesan_klien: bool,
+) -> Result<i32, StatusCode> {
+    // Baris slot dikunci supaya dua pemesanan bersamaan tidak melewati kapasitas
+    let slot = sqlx::query!(
+        r#"
+        SELECT s.bapas_id, s.tanggal, s.kapasitas, k.bapas_id AS bapas_klien
+        FROM slot_layanan_bapas s
+        JOIN klien k ON k.id = $2
+        WHERE s.id = $1 AND s.deleted_at IS NULL
+        FOR UPDATE OF s
+        "#,
+        slot_layanan_id,
+        klien_id
+    )
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    if slot.bapas_id != slot.bapas_klien || slot.tanggal < hari_ini() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let terisi = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "jumlah!" FROM antrian_bapas
+        WHERE slot_layanan_id = $1 AND status_antrian NOT IN ('Tidak Hadir', 'Dibatalkan')
+        "#,
+        slot_layanan_id
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if terisi >= slot.kapasitas as i64 {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    sqlx::query_scalar!(
+        r#"
+        INSERT INTO antrian_bapas (bapas_id, tanggal, slot_layanan_id, klien_id, dipesan_klien, created_by, updated_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $6)
+        RETURNING id
+        "#,
+        slot.bapas_id,
+        slot.tanggal,
+        slot_layanan_id,
+        klien_id,
+        dipesan_klien,
+        user_id
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| {
+        if e.as_database_error().is_some_and(|db_err| db_err.is_unique_violation()) {
+            return StatusCode::CONFLICT;
+        }
+        tracing::error!("Failed to create antrian: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+/// Check-in di kiosk: janji temu hari ini diberi nomor, klien tanpa janji
+/// dibuatkan antrian langsung. Mengembalikan (id, true) jika nomor baru
+/// diterbitkan, (id, false) jika klien sudah memegang nomor hari ini.
+pub async fn check_in_kiosk(
+    conn: &mut PgConnection,
+    kiosk: &AuthenticatedKiosk,
+    klien_id: i32,
+    lapor: &CreateWajibLapor,
+) -> Result<(i32, bool), StatusCode> {
+    let tanggal = hari_ini();
+    let mut aktif = antrian_aktif(conn, klien_id, tanggal).await?;
+
+    // Klien tanpa janji dibuatkan baris Dipesan lebih dulu. Jika check-in lain
+    // untuk klien yang sama sudah membuatnya, baris itu yang dibaca ulang
+    // (INSERT menunggu transaksi tersebut selesai).
+    if aktif.is_none() {
+        let baru = sqlx::query_scalar!(
+            r#"
+            INSERT INTO antrian_bapas (bapas_id, tanggal, klien_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (klien_id, tanggal) WHERE status_antrian NOT IN ('Tidak Hadir', 'Dibatalkan') DO NOTHING
+            RETURNING id
+            "#,
+            kiosk.bapas_id,
+            tanggal,
+            klien_id
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to create antrian (kiosk): {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        aktif = match baru {
+            Some(id) => Some(AntrianAktif { id, bapas_id: kiosk.bapas_id, status_antrian: StatusAntrianEnum::Dipesan }),
+            None => antrian_aktif(conn, klien_id, tanggal).await?,
+        };
+    }
+
+    // Baris pemenang bisa sudah dibatalkan lagi sebelum terbaca; cukup diulang
+    let aktif = aktif.ok_or(StatusCode::CONFLICT)?;
+    if aktif.bapas_id != kiosk.bapas_id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    match aktif.status_antrian {
+        StatusAntrianEnum::Dipesan => {}
+        StatusAntrianEnum::Dilayani => return Err(StatusCode::CONFLICT),
+        _ => return Ok((aktif.id, false)),
+    }
+    let id = aktif.id;
+
+    let nomor = ambil_nomor_antrian(conn, kiosk.bapas_id, tanggal)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    sqlx::query!(
+        r#"
+        UPDATE antrian_bapas
+        SET status_antrian = 'Menunggu', nomor_antrian = $2, perangkat_kiosk_id = $3,
+            photo_path_dewasa = $4, photo_hash_dewasa = $5,
+            latitude_dewasa = $6, longitude_dewasa = $7, check_in_at = NOW()
+        WHERE id = $1
+        "#,
+        id,
+        nomor,
+        kiosk.id,
+        lapor.photo_path_dewasa,
+        lapor.photo_hash_dewasa,
+        lapor.latitude_dewasa,
+        lapor.longitude_dewasa
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok((id, true))
+}
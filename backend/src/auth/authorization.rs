@@ -1,5 +1,6 @@
 // File baru: src/auth/authorization.rs
 
+use axum::http::StatusCode;
 use sqlx::PgPool;
 use crate::auth::model::AuthenticatedUser;
 use crate::types::UserRoleEnum;
@@ -39,6 +40,24 @@ pub fn check_permission(
     }
 }
 
+/// Aturan lapor petugas: admin mengikuti aturan standar, sedangkan Pegawai
+/// boleh melaporkan semua klien di Bapasnya (tidak hanya klien binaannya).
+pub fn check_petugas_lapor_permission(
+    user: &AuthenticatedUser,
+    resource: &ResourceOwnership,
+) -> bool {
+    match user.role {
+        UserRoleEnum::SuperAdmin | UserRoleEnum::AdminKanwil | UserRoleEnum::AdminBapas => {
+            check_permission(user, resource)
+        }
+        UserRoleEnum::Pegawai => {
+            user.bapas_id.is_some()
+                && resource.bapas_id.is_some()
+                && user.bapas_id == resource.bapas_id
+        }
+    }
+}
+
 /// Helper untuk mengambil data kepemilikan Klien dari database.
 pub async fn get_klien_ownership(pool: &PgPool, klien_id: i32) -> Result<Option<ResourceOwnership>, sqlx::Error> {
     sqlx::query!(
@@ -58,4 +77,62 @@ pub async fn get_klien_ownership(pool: &PgPool, klien_id: i32) -> Result<Option<
             kanwil_id: row.kanwil_id,
         })
     })
-}
\ No newline at end of file
+}
+
+/// Versi handler dari middleware `authorize_klien_access`, untuk route yang
+/// ID di URL-nya bukan ID klien (misalnya `/remisi-dewasa/:id`).
+pub async fn ensure_klien_access(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    klien_id: i32,
+) -> Result<(), StatusCode> {
+    let resource_ownership = get_klien_ownership(pool, klien_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if !check_permission(user, &resource_ownership) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(())
+}
+
+/// Helper untuk mengambil data kepemilikan Bapas (tanpa PK) dari database.
+pub async fn get_bapas_ownership(pool: &PgPool, bapas_id: i32) -> Result<Option<ResourceOwnership>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT kanwil_id FROM bapas WHERE id = $1 AND deleted_at IS NULL",
+        bapas_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map(|maybe_kanwil| {
+        maybe_kanwil.map(|kanwil_id| ResourceOwnership {
+            pk_id: None,
+            bapas_id: Some(bapas_id),
+            kanwil_id: Some(kanwil_id),
+        })
+    })
+}
+
+/// Akses ke data tingkat Bapas. `hanya_admin = true` menolak Pegawai;
+/// `false` juga mengizinkan Pegawai yang bertugas di Bapas tersebut.
+pub async fn ensure_bapas_access(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    bapas_id: i32,
+    hanya_admin: bool,
+) -> Result<(), StatusCode> {
+    let ownership = get_bapas_ownership(pool, bapas_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let diizinkan = if hanya_admin {
+        user.role != UserRoleEnum::Pegawai && check_permission(user, &ownership)
+    } else {
+        check_petugas_lapor_permission(user, &ownership)
+    };
+    if !diizinkan {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(())
+}
@@ -28,7 +28,7 @@ pub async fn login(
             status_kepegawaian_user AS "status_kepegawaian_user: _",
             email_user, nomor_telepon_user,
             status_aktif_user AS "status_aktif_user: _",
-            role_user AS "role_user: _",
+            role_user AS "role_user: _", kasubsi_user,
             password_hash,api_key_hash, created_at, updated_at, created_by, updated_by, deleted_at
         FROM users 
         WHERE nip_user = $1 
@@ -97,7 +97,7 @@ pub async fn me(
             status_kepegawaian_user AS "status_kepegawaian_user: _",
             email_user, nomor_telepon_user,
             status_aktif_user AS "status_aktif_user: _",
-            role_user AS "role_user: _",
+            role_user AS "role_user: _", kasubsi_user,
             password_hash, api_key_hash,created_at, updated_at, created_by, updated_by, deleted_at
         FROM users 
         WHERE id = $1 AND deleted_at IS NULL
@@ -10,13 +10,13 @@ use axum::{
 use jsonwebtoken::{decode, DecodingKey, Validation};
 use sqlx::PgPool;
 
-use crate::auth::model::{AuthenticatedUser, Claims};
+use crate::auth::model::{AuthenticatedKiosk, AuthenticatedKlien, AuthenticatedUser, AuthenticatedVendor, Claims};
 use crate::types::UserRoleEnum;
 use sha256::digest;
 use crate::users::model::User;
 
 // [FIX] Impor dari modul authorization yang sekarang sudah ada
-use super::authorization::{check_permission, get_klien_ownership};
+use super::authorization::{check_permission, check_petugas_lapor_permission, get_klien_ownership};
 
 const JWT_SECRET: &[u8] = b"your-super-secret-and-long-key";
 
@@ -92,17 +92,8 @@ pub async fn authorize_petugas_lapor_access(
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::NOT_FOUND)?;
 
-    let has_permission = match user.role {
-        UserRoleEnum::SuperAdmin | UserRoleEnum::AdminKanwil | UserRoleEnum::AdminBapas => {
-            check_permission(&user, &resource_ownership)
-        }
-        UserRoleEnum::Pegawai => {
-            // Aturan khusus: Pegawai boleh akses semua klien di Bapasnya
-            user.bapas_id.is_some()
-                && resource_ownership.bapas_id.is_some()
-                && user.bapas_id == resource_ownership.bapas_id
-        }
-    };
+    // Aturan khusus: Pegawai boleh akses semua klien di Bapasnya
+    let has_permission = check_petugas_lapor_permission(&user, &resource_ownership);
 
     if !has_permission {
         return Err(StatusCode::FORBIDDEN);
@@ -168,7 +159,7 @@ pub async fn auth_api_key(
         pangkat_golongan_user, jabatan_user, bapas_id, kanwil_id,
         status_kepegawaian_user AS "status_kepegawaian_user: _",
         email_user, nomor_telepon_user, status_aktif_user AS "status_aktif_user: _",
-        role_user AS "role_user: _", password_hash, created_at, updated_at, 
+        role_user AS "role_user: _", kasubsi_user, password_hash, created_at, updated_at, 
         created_by, updated_by, deleted_at, api_key_hash
         FROM users WHERE api_key_hash = $1 AND deleted_at IS NULL AND status_aktif_user = 'Aktif'
         "#,
@@ -188,4 +179,116 @@ pub async fn auth_api_key(
     req.extensions_mut().insert(authenticated_user);
     
     Ok(next.run(req).await)
-}
\ No newline at end of file
+}
+
+// --- MIDDLEWARE: PERANGKAT KIOSK ---
+// Kiosk mengirim `Authorization: Bearer kk_...`. Hanya dipasang pada router
+// /kiosk, sehingga kunci perangkat tidak berlaku di endpoint lain.
+pub async fn auth_perangkat_kiosk(
+    mut req: Request<Body>,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let pool = req.extensions().get::<PgPool>()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let token = req.headers()
+        .get("authorization")
+        .and_then(|auth_header| auth_header.to_str().ok())
+        .and_then(|auth_value| auth_value.strip_prefix("Bearer "))
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let key_hash = digest(token);
+
+    // Sekaligus mencatat kapan perangkat terakhir terlihat
+    let perangkat = sqlx::query_as!(
+        AuthenticatedKiosk,
+        r#"
+        UPDATE perangkat_kiosk SET terakhir_terlihat_at = NOW()
+        WHERE api_key_hash = $1 AND aktif AND deleted_at IS NULL
+        RETURNING id, bapas_id
+        "#,
+        key_hash
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    req.extensions_mut().insert(perangkat);
+
+    Ok(next.run(req).await)
+}
+
+// --- MIDDLEWARE: VENDOR PEMANTAUAN ELEKTRONIK ---
+// Vendor mengirim `Authorization: Bearer vp_...`. Hanya dipasang pada router /pemantauan.
+pub async fn auth_vendor_pemantauan(
+    mut req: Request<Body>,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let pool = req.extensions().get::<PgPool>()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let token = req.headers()
+        .get("authorization")
+        .and_then(|auth_header| auth_header.to_str().ok())
+        .and_then(|auth_value| auth_value.strip_prefix("Bearer "))
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let key_hash = digest(token);
+
+    let vendor = sqlx::query_as!(
+        AuthenticatedVendor,
+        r#"
+        UPDATE vendor_pemantauan SET terakhir_kirim_at = NOW()
+        WHERE api_key_hash = $1 AND aktif AND deleted_at IS NULL
+        RETURNING id
+        "#,
+        key_hash
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    req.extensions_mut().insert(vendor);
+
+    Ok(next.run(req).await)
+}
+
+// --- MIDDLEWARE: PORTAL KLIEN ---
+// Token portal ditandatangani dengan kunci sendiri. Status klien diperiksa
+// ulang setiap permintaan supaya pencabutan akses online langsung berlaku.
+pub async fn auth_klien(
+    mut req: Request<Body>,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let pool = req.extensions().get::<PgPool>()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let token = req.headers()
+        .get("authorization")
+        .and_then(|auth_header| auth_header.to_str().ok())
+        .and_then(|auth_value| auth_value.strip_prefix("Bearer "))
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let klaim = crate::portal::token::baca(token)?;
+
+    let klien = sqlx::query_as!(
+        AuthenticatedKlien,
+        r#"
+        SELECT k.id AS klien_id, k.bapas_id
+        FROM klien k
+        JOIN akun_portal_klien a ON a.klien_id = k.id
+        WHERE k.id = $1 AND k.online_akses_klien AND k.deleted_at IS NULL
+        "#,
+        klaim.sub
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    req.extensions_mut().insert(klien);
+
+    Ok(next.run(req).await)
+}
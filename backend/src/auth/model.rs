@@ -31,4 +31,24 @@ pub struct AuthenticatedUser {
     pub role: UserRoleEnum,
     pub bapas_id: Option<i32>,
     pub kanwil_id: Option<i32>,
-}
\ No newline at end of file
+}
+
+// Identitas perangkat kiosk yang lolos middleware `auth_perangkat_kiosk`
+#[derive(Debug, Clone)]
+pub struct AuthenticatedKiosk {
+    pub id: i32,
+    pub bapas_id: i32,
+}
+
+// Vendor pemantauan elektronik yang lolos middleware `auth_vendor_pemantauan`
+#[derive(Debug, Clone)]
+pub struct AuthenticatedVendor {
+    pub id: i32,
+}
+
+// Klien yang login ke portal mandiri (token terpisah dari token staf)
+#[derive(Debug, Clone)]
+pub struct AuthenticatedKlien {
+    pub klien_id: i32,
+    pub bapas_id: i32,
+}
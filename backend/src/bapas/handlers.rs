@@ -31,16 +31,17 @@ pub async fn create_bapas(
     let new_bapas = sqlx::query_as!(
         Bapas,
         r#"
-        INSERT INTO bapas (kanwil_id, nama_bapas, kota_bapas, alamat_bapas, nomor_telepon_bapas, email_bapas)
-        VALUES ($1, $2, $3, $4, $5, $6)
-        RETURNING id, kanwil_id, nama_bapas, kota_bapas, alamat_bapas, nomor_telepon_bapas, email_bapas, created_at, updated_at, deleted_at
+        INSERT INTO bapas (kanwil_id, nama_bapas, kota_bapas, alamat_bapas, nomor_telepon_bapas, email_bapas, kode_bapas)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id, kanwil_id, nama_bapas, kota_bapas, alamat_bapas, nomor_telepon_bapas, email_bapas, kode_bapas, created_at, updated_at, deleted_at
         "#,
         payload.kanwil_id,
         payload.nama_bapas,
         payload.kota_bapas,
         payload.alamat_bapas,
         payload.nomor_telepon_bapas,
-        payload.email_bapas
+        payload.email_bapas,
+        payload.kode_bapas
     )
     .fetch_one(&pool)
     .await
@@ -157,9 +158,10 @@ pub async fn update_bapas(
             kota_bapas = COALESCE($3, kota_bapas),
             alamat_bapas = COALESCE($4, alamat_bapas),
             nomor_telepon_bapas = COALESCE($5, nomor_telepon_bapas),
-            email_bapas = COALESCE($6, email_bapas)
-        WHERE id = $7 AND deleted_at IS NULL
-        RETURNING id, kanwil_id, nama_bapas, kota_bapas, alamat_bapas, nomor_telepon_bapas, email_bapas, created_at, updated_at, deleted_at
+            email_bapas = COALESCE($6, email_bapas),
+            kode_bapas = COALESCE($7, kode_bapas)
+        WHERE id = $8 AND deleted_at IS NULL
+        RETURNING id, kanwil_id, nama_bapas, kota_bapas, alamat_bapas, nomor_telepon_bapas, email_bapas, kode_bapas, created_at, updated_at, deleted_at
         "#,
         payload.kanwil_id,
         payload.nama_bapas,
@@ -167,6 +169,7 @@ pub async fn update_bapas(
         payload.alamat_bapas,
         payload.nomor_telepon_bapas,
         payload.email_bapas,
+        payload.kode_bapas,
         id
     )
     .fetch_optional(&pool)
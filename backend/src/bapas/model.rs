@@ -14,6 +14,7 @@ pub struct Bapas {
     pub alamat_bapas: Option<String>,
     pub nomor_telepon_bapas: Option<String>,
     pub email_bapas: Option<String>,
+    pub kode_bapas: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -29,6 +30,7 @@ pub struct CreateBapas {
     pub alamat_bapas: Option<String>,
     pub nomor_telepon_bapas: Option<String>,
     pub email_bapas: Option<String>,
+    pub kode_bapas: Option<String>,
 }
 
 // Data yang dibutuhkan untuk mengupdate Bapas
@@ -40,4 +42,5 @@ pub struct UpdateBapas {
     pub alamat_bapas: Option<String>,
     pub nomor_telepon_bapas: Option<String>,
     pub email_bapas: Option<String>,
+    pub kode_bapas: Option<String>,
 }
\ No newline at end of file
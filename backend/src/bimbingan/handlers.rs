@@ -0,0 +1,374 @@
+// File baru: src/bimbingan/handlers.rs
+
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    Json,
+};
+use sqlx::PgPool;
+use crate::auth::authorization::{check_permission, ensure_klien_access, get_bapas_ownership};
+use crate::auth::model::AuthenticatedUser;
+use crate::klien::handlers_dewasa::ambil_wajib_lapor_klien;
+use crate::types::{BentukBimbinganEnum, StatusKehadiranEnum, UserRoleEnum};
+use super::model::{
+    CreateSesiBimbingan, PesertaSesiBimbingan, RekapBimbinganProgram, RiwayatPembimbingan,
+    SesiBimbingan, SesiBimbinganDetail, SesiBimbinganKlien, UpdateKehadiran, UpdateSesiBimbingan,
+};
+
+async fn get_sesi(pool: &PgPool, id: i32) -> Result<SesiBimbingan, StatusCode> {
+    sqlx::query_as!(
+        SesiBimbingan,
+        r#"
+        SELECT id, bapas_id, pembimbing_id, program_bimbingan AS "program_bimbingan: _",
+            bentuk_bimbingan AS "bentuk_bimbingan: _", topik, waktu_mulai, durasi_menit, tempat, catatan,
+            created_at, updated_at, created_by, updated_by, deleted_at
+        FROM sesi_bimbingan WHERE id = $1 AND deleted_at IS NULL
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)
+}
+
+// Sesi dapat diakses pembimbingnya sendiri atau admin yang berwenang atas Bapas
+async fn ensure_akses_sesi(pool: &PgPool, user: &AuthenticatedUser, sesi: &SesiBimbingan) -> Result<(), StatusCode> {
+    if sesi.pembimbing_id == user.id {
+        return Ok(());
+    }
+    let ownership = get_bapas_ownership(pool, sesi.bapas_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if user.role == UserRoleEnum::Pegawai || !check_permission(user, &ownership) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(())
+}
+
+async fn get_peserta(pool: &PgPool, sesi_id: i32) -> Result<Vec<PesertaSesiBimbingan>, StatusCode> {
+    sqlx::query_as!(
+        PesertaSesiBimbingan,
+        r#"
+        SELECT p.klien_id, k.nama_klien, p.layanan_integrasi_dewasa_id,
+            p.status_kehadiran AS "status_kehadiran: _", p.catatan
+        FROM peserta_sesi_bimbingan p JOIN klien k ON k.id = p.klien_id
+        WHERE p.sesi_bimbingan_id = $1
+        ORDER BY k.nama_klien
+        "#,
+        sesi_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch peserta sesi bimbingan: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+// --- CREATE ---
+// URL: POST /api/sesi-bimbingan
+// Pencatat menjadi pembimbing sesi. Semua peserta harus klien yang boleh ia akses
+// dan berasal dari Bapas yang sama.
+#[axum::debug_handler]
+pub async fn create_sesi_bimbingan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(payload): Json<CreateSesiBimbingan>,
+) -> Result<(StatusCode, Json<SesiBimbinganDetail>), StatusCode> {
+    let jumlah_peserta_valid = match payload.bentuk_bimbingan {
+        BentukBimbinganEnum::Individu => payload.peserta.len() == 1,
+        BentukBimbinganEnum::Kelompok => !payload.peserta.is_empty(),
+    };
+    if !jumlah_peserta_valid || payload.durasi_menit <= 0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut bapas_sesi = None;
+    for peserta in &payload.peserta {
+        ensure_klien_access(&pool, &user, peserta.klien_id).await?;
+        let data = sqlx::query!(
+            r#"
+            SELECT k.bapas_id,
+                ($2::INT IS NULL OR EXISTS (
+                    SELECT 1 FROM layanan_integrasi_dewasa l
+                    WHERE l.id = $2 AND l.klien_id = k.id AND l.deleted_at IS NULL)) AS "layanan_valid!"
+            FROM klien k WHERE k.id = $1
+            "#,
+            peserta.klien_id,
+            peserta.layanan_integrasi_dewasa_id
+        )
+        .fetch_one(&pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        if !data.layanan_valid || bapas_sesi.is_some_and(|b| b != data.bapas_id) {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        bapas_sesi = Some(data.bapas_id);
+    }
+    let bapas_id = bapas_sesi.ok_or(StatusCode::BAD_REQUEST)?;
+
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let sesi = sqlx::query_as!(
+        SesiBimbingan,
+        r#"
+        INSERT INTO sesi_bimbingan (
+            bapas_id, pembimbing_id, program_bimbingan, bentuk_bimbingan, topik, waktu_mulai,
+            durasi_menit, tempat, catatan, created_by, updated_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $2, $2)
+        RETURNING id, bapas_id, pembimbing_id, program_bimbingan AS "program_bimbingan: _",
+            bentuk_bimbingan AS "bentuk_bimbingan: _", topik, waktu_mulai, durasi_menit, tempat, catatan,
+            created_at, updated_at, created_by, updated_by, deleted_at
+        "#,
+        bapas_id,
+        user.id,
+        payload.program_bimbingan as _,
+        payload.bentuk_bimbingan as _,
+        payload.topik,
+        payload.waktu_mulai,
+        payload.durasi_menit,
+        payload.tempat,
+        payload.catatan
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create sesi bimbingan: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    for peserta in &payload.peserta {
+        sqlx::query!(
+            r#"
+            INSERT INTO peserta_sesi_bimbingan (sesi_bimbingan_id, klien_id, layanan_integrasi_dewasa_id, status_kehadiran, catatan)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            sesi.id,
+            peserta.klien_id,
+            peserta.layanan_integrasi_dewasa_id,
+            peserta.status_kehadiran.unwrap_or(StatusKehadiranEnum::Hadir) as _,
+            peserta.catatan
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            // Klien yang sama dua kali melanggar primary key
+            tracing::warn!("Failed to insert peserta sesi bimbingan: {}", e);
+            StatusCode::BAD_REQUEST
+        })?;
+    }
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let peserta = get_peserta(&pool, sesi.id).await?;
+    Ok((StatusCode::CREATED, Json(SesiBimbinganDetail { sesi, peserta })))
+}
+
+// --- READ ONE ---
+// URL: GET /api/sesi-bimbingan/:id
+#[axum::debug_handler]
+pub async fn get_sesi_bimbingan_by_id(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> Result<Json<SesiBimbinganDetail>, StatusCode> {
+    let sesi = get_sesi(&pool, id).await?;
+    ensure_akses_sesi(&pool, &user, &sesi).await?;
+    let peserta = get_peserta(&pool, id).await?;
+    Ok(Json(SesiBimbinganDetail { sesi, peserta }))
+}
+
+// --- UPDATE ---
+// URL: PUT /api/sesi-bimbingan/:id
+#[axum::debug_handler]
+pub async fn update_sesi_bimbingan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateSesiBimbingan>,
+) -> Result<Json<SesiBimbingan>, StatusCode> {
+    let sesi = get_sesi(&pool, id).await?;
+    ensure_akses_sesi(&pool, &user, &sesi).await?;
+    if payload.durasi_menit.is_some_and(|d| d <= 0) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let updated = sqlx::query_as!(
+        SesiBimbingan,
+        r#"
+        UPDATE sesi_bimbingan SET
+            program_bimbingan = COALESCE($1, program_bimbingan),
+            topik = COALESCE($2, topik),
+            waktu_mulai = COALESCE($3, waktu_mulai),
+            durasi_menit = COALESCE($4, durasi_menit),
+            tempat = COALESCE($5, tempat),
+            catatan = COALESCE($6, catatan),
+            updated_by = $7
+        WHERE id = $8 AND deleted_at IS NULL
+        RETURNING id, bapas_id, pembimbing_id, program_bimbingan AS "program_bimbingan: _",
+            bentuk_bimbingan AS "bentuk_bimbingan: _", topik, waktu_mulai, durasi_menit, tempat, catatan,
+            created_at, updated_at, created_by, updated_by, deleted_at
+        "#,
+        payload.program_bimbingan as _,
+        payload.topik,
+        payload.waktu_mulai,
+        payload.durasi_menit,
+        payload.tempat,
+        payload.catatan,
+        user.id,
+        id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update sesi bimbingan {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(updated))
+}
+
+// --- DELETE (SOFT) ---
+// URL: DELETE /api/sesi-bimbingan/:id
+#[axum::debug_handler]
+pub async fn delete_sesi_bimbingan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> StatusCode {
+    let sesi = match get_sesi(&pool, id).await {
+        Ok(sesi) => sesi,
+        Err(status) => return status,
+    };
+    if let Err(status) = ensure_akses_sesi(&pool, &user, &sesi).await {
+        return status;
+    }
+
+    let result = sqlx::query!(
+        "UPDATE sesi_bimbingan SET deleted_at = NOW(), updated_by = $1 WHERE id = $2",
+        user.id,
+        id
+    )
+    .execute(&pool)
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => StatusCode::NO_CONTENT,
+        Ok(_) => StatusCode::NOT_FOUND,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+// --- UPDATE KEHADIRAN ---
+// URL: PUT /api/sesi-bimbingan/:id/peserta/:klien_id
+#[axum::debug_handler]
+pub async fn update_kehadiran_peserta(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path((id, klien_id)): Path<(i32, i32)>,
+    Json(payload): Json<UpdateKehadiran>,
+) -> Result<Json<Vec<PesertaSesiBimbingan>>, StatusCode> {
+    let sesi = get_sesi(&pool, id).await?;
+    ensure_akses_sesi(&pool, &user, &sesi).await?;
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE peserta_sesi_bimbingan SET status_kehadiran = $1, catatan = COALESCE($2, catatan)
+        WHERE sesi_bimbingan_id = $3 AND klien_id = $4
+        "#,
+        payload.status_kehadiran as _,
+        payload.catatan,
+        id,
+        klien_id
+    )
+    .execute(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if result.rows_affected() == 0 {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    Ok(Json(get_peserta(&pool, id).await?))
+}
+
+// --- READ ALL FOR A KLIEN ---
+// URL: GET /api/klien/:klien_id/sesi-bimbingan
+#[axum::debug_handler]
+pub async fn get_all_sesi_bimbingan_for_klien(
+    Extension(pool): Extension<PgPool>,
+    Path(klien_id): Path<i32>,
+) -> Result<Json<Vec<SesiBimbinganKlien>>, StatusCode> {
+    let list = sqlx::query_as!(
+        SesiBimbinganKlien,
+        r#"
+        SELECT s.id AS sesi_bimbingan_id, s.program_bimbingan AS "program_bimbingan: _",
+            s.bentuk_bimbingan AS "bentuk_bimbingan: _", s.topik, s.waktu_mulai, s.durasi_menit,
+            s.pembimbing_id, u.nama_user AS nama_pembimbing, p.layanan_integrasi_dewasa_id,
+            p.status_kehadiran AS "status_kehadiran: _", p.catatan
+        FROM peserta_sesi_bimbingan p
+        JOIN sesi_bimbingan s ON s.id = p.sesi_bimbingan_id AND s.deleted_at IS NULL
+        JOIN users u ON u.id = s.pembimbing_id
+        WHERE p.klien_id = $1
+        ORDER BY s.waktu_mulai DESC
+        "#,
+        klien_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch sesi bimbingan for klien: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// --- RIWAYAT PEMBIMBINGAN ---
+// URL: GET /api/klien/:klien_id/riwayat-pembimbingan
+// Rekap bimbingan per program ditampilkan bersama riwayat wajib lapor.
+#[axum::debug_handler]
+pub async fn get_riwayat_pembimbingan(
+    Extension(pool): Extension<PgPool>,
+    Path(klien_id): Path<i32>,
+) -> Result<Json<RiwayatPembimbingan>, StatusCode> {
+    let rekap_bimbingan = sqlx::query_as!(
+        RekapBimbinganProgram,
+        r#"
+        SELECT s.program_bimbingan AS "program_bimbingan: _",
+            COUNT(*) AS "jumlah_sesi!",
+            COUNT(*) FILTER (WHERE p.status_kehadiran = 'Hadir') AS "jumlah_hadir!",
+            COUNT(*) FILTER (WHERE p.status_kehadiran = 'Izin') AS "jumlah_izin!",
+            COUNT(*) FILTER (WHERE p.status_kehadiran = 'Tidak Hadir') AS "jumlah_tidak_hadir!",
+            COALESCE(SUM(s.durasi_menit) FILTER (WHERE p.status_kehadiran = 'Hadir'), 0)::BIGINT AS "total_menit_hadir!"
+        FROM peserta_sesi_bimbingan p
+        JOIN sesi_bimbingan s ON s.id = p.sesi_bimbingan_id AND s.deleted_at IS NULL
+        WHERE p.klien_id = $1
+        GROUP BY s.program_bimbingan
+        ORDER BY s.program_bimbingan
+        "#,
+        klien_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to build rekap bimbingan: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let wajib_lapor = ambil_wajib_lapor_klien(&pool, klien_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch wajib lapor list: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(RiwayatPembimbingan {
+        rekap_bimbingan,
+        jumlah_wajib_lapor: wajib_lapor.len(),
+        wajib_lapor_terakhir: wajib_lapor.first().map(|w| w.created_at),
+        wajib_lapor,
+    }))
+}
@@ -0,0 +1,117 @@
+// File baru: src/bimbingan/model.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+use crate::klien::model_dewasa::WajibLaporDewasa;
+use crate::types::{BentukBimbinganEnum, ProgramBimbinganEnum, StatusKehadiranEnum};
+
+// Merepresentasikan satu baris dari tabel 'sesi_bimbingan'
+#[derive(Debug, Serialize, FromRow)]
+pub struct SesiBimbingan {
+    pub id: i32,
+    pub bapas_id: i32,
+    pub pembimbing_id: i32,
+    pub program_bimbingan: ProgramBimbinganEnum,
+    pub bentuk_bimbingan: BentukBimbinganEnum,
+    pub topik: String,
+    pub waktu_mulai: DateTime<Utc>,
+    pub durasi_menit: i32,
+    pub tempat: Option<String>,
+    pub catatan: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PesertaSesiBimbingan {
+    pub klien_id: i32,
+    pub nama_klien: String,
+    pub layanan_integrasi_dewasa_id: Option<i32>,
+    pub status_kehadiran: StatusKehadiranEnum,
+    pub catatan: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePesertaSesiBimbingan {
+    pub klien_id: i32,
+    pub layanan_integrasi_dewasa_id: Option<i32>,
+    pub status_kehadiran: Option<StatusKehadiranEnum>,
+    pub catatan: Option<String>,
+}
+
+// Data untuk mencatat sesi baru beserta pesertanya.
+// Sesi Individu harus tepat satu peserta.
+#[derive(Debug, Deserialize)]
+pub struct CreateSesiBimbingan {
+    pub program_bimbingan: ProgramBimbinganEnum,
+    pub bentuk_bimbingan: BentukBimbinganEnum,
+    pub topik: String,
+    pub waktu_mulai: DateTime<Utc>,
+    pub durasi_menit: i32,
+    pub tempat: Option<String>,
+    pub catatan: Option<String>,
+    pub peserta: Vec<CreatePesertaSesiBimbingan>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSesiBimbingan {
+    pub program_bimbingan: Option<ProgramBimbinganEnum>,
+    pub topik: Option<String>,
+    pub waktu_mulai: Option<DateTime<Utc>>,
+    pub durasi_menit: Option<i32>,
+    pub tempat: Option<String>,
+    pub catatan: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateKehadiran {
+    pub status_kehadiran: StatusKehadiranEnum,
+    pub catatan: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SesiBimbinganDetail {
+    #[serde(flatten)]
+    pub sesi: SesiBimbingan,
+    pub peserta: Vec<PesertaSesiBimbingan>,
+}
+
+// Sesi yang diikuti seorang klien, dengan status kehadirannya sendiri
+#[derive(Debug, Serialize, FromRow)]
+pub struct SesiBimbinganKlien {
+    pub sesi_bimbingan_id: i32,
+    pub program_bimbingan: ProgramBimbinganEnum,
+    pub bentuk_bimbingan: BentukBimbinganEnum,
+    pub topik: String,
+    pub waktu_mulai: DateTime<Utc>,
+    pub durasi_menit: i32,
+    pub pembimbing_id: i32,
+    pub nama_pembimbing: String,
+    pub layanan_integrasi_dewasa_id: Option<i32>,
+    pub status_kehadiran: StatusKehadiranEnum,
+    pub catatan: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct RekapBimbinganProgram {
+    pub program_bimbingan: ProgramBimbinganEnum,
+    pub jumlah_sesi: i64,
+    pub jumlah_hadir: i64,
+    pub jumlah_izin: i64,
+    pub jumlah_tidak_hadir: i64,
+    pub total_menit_hadir: i64,
+}
+
+// GET /api/klien/:klien_id/riwayat-pembimbingan
+#[derive(Debug, Serialize)]
+pub struct RiwayatPembimbingan {
+    pub rekap_bimbingan: Vec<RekapBimbinganProgram>,
+    pub jumlah_wajib_lapor: usize,
+    pub wajib_lapor_terakhir: Option<DateTime<Utc>>,
+    pub wajib_lapor: Vec<WajibLaporDewasa>,
+}
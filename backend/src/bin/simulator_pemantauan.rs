@@ -0,0 +1,131 @@
+// src/bin/simulator_pemantauan.rs
+//
+// Membuat jejak GPS tiruan untuk menguji penerimaan titik lacak dan deteksi
+// pelanggaran zona tanpa perangkat vendor sungguhan.
+//
+// Run it with:
+//    cargo run --bin simulator_pemantauan -- --perangkat GPS-001 --lat -6.2 --lon 106.8
+//
+// Tanpa --kunci, kiriman JSON dicetak ke stdout (satu kiriman per baris).
+// Dengan --kunci vp_..., kiriman langsung dikirim ke --url.
+//
+// Opsi:
+//    --perangkat <kode>       kode perangkat vendor (wajib)
+//    --lat, --lon <derajat>   titik awal (mis. rumah klien)
+//    --titik <n>              jumlah titik (bawaan 120)
+//    --interval <detik>       jarak waktu antartitik (bawaan 60)
+//    --langkah <meter>        besar langkah acak (bawaan 15)
+//    --menyimpang <meter>     di sepertiga tengah jejak, menjauh sejauh ini lalu kembali
+//    --kelompok <n>           titik per kiriman (bawaan 100, maks. 1000)
+//    --url <url>              bawaan http://127.0.0.1:3000/api/pemantauan/titik-lacak
+//    --kunci <api key>        API key vendor
+
+use chrono::{Duration, Utc};
+use rand::Rng;
+use serde_json::json;
+use std::collections::HashMap;
+use std::env;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+const METER_PER_DERAJAT: f64 = 111_320.0;
+
+fn ambil<T: std::str::FromStr>(opsi: &HashMap<String, String>, nama: &str, bawaan: T) -> T {
+    match opsi.get(nama) {
+        Some(nilai) => nilai
+            .parse()
+            .unwrap_or_else(|_| panic!("Nilai --{} tidak valid: {}", nama, nilai)),
+        None => bawaan,
+    }
+}
+
+fn kirim_http(url: &str, kunci: &str, body: &str) -> std::io::Result<String> {
+    let sisa = url
+        .strip_prefix("http://")
+        .expect("Simulator hanya mendukung URL http://");
+    let (host, path) = sisa.split_at(sisa.find('/').unwrap_or(sisa.len()));
+    let path = if path.is_empty() { "/" } else { path };
+
+    let mut stream = TcpStream::connect(host)?;
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}\r\nAuthorization: Bearer {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path,
+        host,
+        kunci,
+        body.len(),
+        body
+    )?;
+
+    let mut respons = String::new();
+    stream.read_to_string(&mut respons)?;
+    let status = respons.lines().next().unwrap_or_default().to_string();
+    let isi = respons.split("\r\n\r\n").nth(1).unwrap_or_default();
+    Ok(format!("{} {}", status, isi.trim()))
+}
+
+fn main() {
+    let argumen: Vec<String> = env::args().skip(1).collect();
+    let opsi: HashMap<String, String> = argumen
+        .chunks(2)
+        .filter_map(|pasangan| match pasangan {
+            [nama, nilai] => nama.strip_prefix("--").map(|n| (n.to_string(), nilai.clone())),
+            _ => None,
+        })
+        .collect();
+
+    let perangkat = opsi.get("perangkat").expect("--perangkat wajib diisi").clone();
+    let lat_awal: f64 = ambil(&opsi, "lat", -6.2);
+    let lon_awal: f64 = ambil(&opsi, "lon", 106.816666);
+    let jumlah: usize = ambil(&opsi, "titik", 120);
+    let interval: i64 = ambil(&opsi, "interval", 60);
+    let langkah: f64 = ambil(&opsi, "langkah", 15.0);
+    let menyimpang: f64 = ambil(&opsi, "menyimpang", 0.0);
+    let kelompok: usize = ambil(&opsi, "kelompok", 100);
+    let url: String = ambil(&opsi, "url", "http://127.0.0.1:3000/api/pemantauan/titik-lacak".to_string());
+    let kunci = opsi.get("kunci");
+
+    // Jejak berakhir "sekarang" supaya tidak ada titik di masa depan
+    let mulai = Utc::now() - Duration::seconds(interval * jumlah as i64);
+    let mut rng = rand::thread_rng();
+    let (mut dy, mut dx) = (0.0_f64, 0.0_f64); // Pergeseran acak dari titik awal (meter)
+    let mut titik = Vec::with_capacity(jumlah);
+
+    for i in 0..jumlah {
+        dy += rng.gen_range(-langkah..=langkah);
+        dx += rng.gen_range(-langkah..=langkah);
+
+        // Penyimpangan berbentuk segitiga: naik ke `menyimpang` meter di tengah jejak lalu turun
+        let sepertiga = (jumlah / 3).max(1);
+        let simpang = if menyimpang > 0.0 && (sepertiga..2 * sepertiga).contains(&i) {
+            let posisi = (i - sepertiga) as f64 / sepertiga as f64;
+            menyimpang * (1.0 - (2.0 * posisi - 1.0).abs())
+        } else {
+            0.0
+        };
+
+        let lat = lat_awal + dy / METER_PER_DERAJAT;
+        let lon = lon_awal + (dx + simpang) / (METER_PER_DERAJAT * lat_awal.to_radians().cos());
+        titik.push(json!({
+            "kode_perangkat": perangkat,
+            "waktu_rekam": (mulai + Duration::seconds(interval * i as i64)).to_rfc3339(),
+            "latitude": (lat * 1e6).round() / 1e6,
+            "longitude": (lon * 1e6).round() / 1e6,
+            "akurasi_meter": rng.gen_range(3.0..25.0_f32).round(),
+        }));
+    }
+
+    for (nomor, potongan) in titik.chunks(kelompok.clamp(1, 1000)).enumerate() {
+        let body = json!({ "titik": potongan }).to_string();
+        match kunci {
+            Some(kunci) => match kirim_http(&url, kunci, &body) {
+                Ok(hasil) => println!("Kiriman {}: {}", nomor + 1, hasil),
+                Err(e) => {
+                    eprintln!("Kiriman {} gagal: {}", nomor + 1, e);
+                    std::process::exit(1);
+                }
+            },
+            None => println!("{}", body),
+        }
+    }
+}
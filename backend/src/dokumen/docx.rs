@@ -0,0 +1,112 @@
+// File baru: src/dokumen/docx.rs
+//
+// Menyusun berkas .docx minimal: tiga bagian XML di dalam arsip zip.
+// Word dan LibreOffice sama-sama bisa membukanya tanpa styles.xml.
+
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+use super::model::Baris;
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="xml" ContentType="application/xml"/>
+<Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+</Types>"#;
+
+const RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
+</Relationships>"#;
+
+pub(super) fn escape_xml(teks: &str) -> String {
+    let mut hasil = String::with_capacity(teks.len());
+    for c in teks.chars() {
+        match c {
+            '&' => hasil.push_str("&amp;"),
+            '<' => hasil.push_str("&lt;"),
+            '>' => hasil.push_str("&gt;"),
+            '"' => hasil.push_str("&quot;"),
+            _ => hasil.push(c),
+        }
+    }
+    hasil
+}
+
+fn paragraf(teks: &str, tebal: bool, tengah: bool, ukuran_half_pt: u32) -> String {
+    let ppr = if tengah { r#"<w:pPr><w:jc w:val="center"/></w:pPr>"# } else { "" };
+    let tebal = if tebal { "<w:b/>" } else { "" };
+    format!(
+        r#"<w:p>{}<w:r><w:rPr>{}<w:sz w:val="{}"/></w:rPr><w:t xml:space="preserve">{}</w:t></w:r></w:p>"#,
+        ppr,
+        tebal,
+        ukuran_half_pt,
+        escape_xml(teks)
+    )
+}
+
+// Lebar area tulis A4 dikurangi margin, dalam twip
+const LEBAR_ISI_TWIP: u32 = 11906 - 2 * 1134;
+
+/// Kolom pertama selebar setengah halaman, kolom lain dibagi rata dan rata kanan.
+/// Word mensyaratkan paragraf setelah tabel, jadi selalu ditutup `<w:p/>`.
+fn tabel(isi: &[Vec<String>]) -> String {
+    let jumlah_kolom = isi.iter().map(Vec::len).max().unwrap_or(1).max(1) as u32;
+    let lebar_pertama = if jumlah_kolom > 1 { LEBAR_ISI_TWIP / 2 } else { LEBAR_ISI_TWIP };
+    let lebar_lain = (LEBAR_ISI_TWIP - lebar_pertama) / (jumlah_kolom - 1).max(1);
+
+    let mut xml = String::from(
+        r#"<w:tbl><w:tblPr><w:tblW w:w="5000" w:type="pct"/><w:tblBorders><w:top w:val="single" w:sz="4"/><w:left w:val="single" w:sz="4"/><w:bottom w:val="single" w:sz="4"/><w:right w:val="single" w:sz="4"/><w:insideH w:val="single" w:sz="4"/><w:insideV w:val="single" w:sz="4"/></w:tblBorders></w:tblPr><w:tblGrid>"#,
+    );
+    for k in 0..jumlah_kolom {
+        xml.push_str(&format!(r#"<w:gridCol w:w="{}"/>"#, if k == 0 { lebar_pertama } else { lebar_lain }));
+    }
+    xml.push_str("</w:tblGrid>");
+    for (i, baris) in isi.iter().enumerate() {
+        xml.push_str("<w:tr>");
+        for (k, sel) in baris.iter().enumerate() {
+            xml.push_str(&format!(
+                r#"<w:tc><w:p><w:pPr><w:jc w:val="{}"/></w:pPr><w:r><w:rPr>{}<w:sz w:val="20"/></w:rPr><w:t xml:space="preserve">{}</w:t></w:r></w:p></w:tc>"#,
+                if k == 0 { "left" } else { "right" },
+                if i == 0 { "<w:b/>" } else { "" },
+                escape_xml(sel)
+            ));
+        }
+        xml.push_str("</w:tr>");
+    }
+    xml.push_str("</w:tbl><w:p/>");
+    xml
+}
+
+fn document_xml(baris: &[Baris]) -> String {
+    let mut body = String::new();
+    for b in baris {
+        body.push_str(&match b {
+            Baris::Judul(teks) => paragraf(teks, true, true, 28),
+            Baris::SubJudul(teks) => paragraf(teks, true, false, 24),
+            Baris::Paragraf(teks) => paragraf(teks, false, false, 22),
+            Baris::Kosong => "<w:p/>".to_string(),
+            Baris::Tabel(isi) => tabel(isi),
+        });
+    }
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{}<w:sectPr><w:pgSz w:w="11906" w:h="16838"/><w:pgMar w:top="1134" w:right="1134" w:bottom="1134" w:left="1134" w:header="708" w:footer="708" w:gutter="0"/></w:sectPr></w:body></w:document>"#,
+        body
+    )
+}
+
+pub fn render(baris: &[Baris]) -> Result<Vec<u8>, zip::result::ZipError> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let opsi = SimpleFileOptions::default();
+
+    zip.start_file("[Content_Types].xml", opsi)?;
+    zip.write_all(CONTENT_TYPES.as_bytes())?;
+    zip.start_file("_rels/.rels", opsi)?;
+    zip.write_all(RELS.as_bytes())?;
+    zip.start_file("word/document.xml", opsi)?;
+    zip.write_all(document_xml(baris).as_bytes())?;
+
+    Ok(zip.finish()?.into_inner())
+}
@@ -0,0 +1,282 @@
+// File baru: src/dokumen/handlers.rs
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use sqlx::PgPool;
+use crate::auth::authorization::{check_permission, ensure_klien_access, get_bapas_ownership};
+use crate::auth::model::AuthenticatedUser;
+use crate::types::{JenisDokumenEnum, UserRoleEnum};
+use super::model::{CreateTemplateDokumen, DokumenParams, FormatDokumen, TemplateDokumen, UpdateTemplateDokumen};
+use super::{docx, merge, pdf};
+
+// Template nasional (bapas_id kosong) hanya boleh dikelola SuperAdmin;
+// template Bapas mengikuti aturan hierarki biasa.
+async fn ensure_boleh_kelola(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    bapas_id: Option<i32>,
+) -> Result<(), StatusCode> {
+    let Some(bapas_id) = bapas_id else {
+        return if user.role == UserRoleEnum::SuperAdmin { Ok(()) } else { Err(StatusCode::FORBIDDEN) };
+    };
+    let ownership = get_bapas_ownership(pool, bapas_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if user.role == UserRoleEnum::Pegawai || !check_permission(user, &ownership) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(())
+}
+
+async fn get_template(pool: &PgPool, id: i32) -> Result<Option<TemplateDokumen>, sqlx::Error> {
+    sqlx::query_as!(
+        TemplateDokumen,
+        r#"
+        SELECT id, bapas_id, jenis_dokumen AS "jenis_dokumen: _", nama_template, isi_template,
+            created_at, updated_at, created_by, updated_by, deleted_at
+        FROM template_dokumen
+        WHERE id = $1 AND deleted_at IS NULL
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+// --- CREATE ---
+// URL: POST /api/dokumen/template
+#[axum::debug_handler]
+pub async fn create_template_dokumen(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(payload): Json<CreateTemplateDokumen>,
+) -> Result<(StatusCode, Json<TemplateDokumen>), StatusCode> {
+    ensure_boleh_kelola(&pool, &user, payload.bapas_id).await?;
+
+    let template = sqlx::query_as!(
+        TemplateDokumen,
+        r#"
+        INSERT INTO template_dokumen (bapas_id, jenis_dokumen, nama_template, isi_template, created_by, updated_by)
+        VALUES ($1, $2, $3, $4, $5, $5)
+        RETURNING id, bapas_id, jenis_dokumen AS "jenis_dokumen: _", nama_template, isi_template,
+            created_at, updated_at, created_by, updated_by, deleted_at
+        "#,
+        payload.bapas_id,
+        payload.jenis_dokumen as _,
+        payload.nama_template,
+        payload.isi_template,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create template dokumen: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((StatusCode::CREATED, Json(template)))
+}
+
+// --- READ ALL ---
+// URL: GET /api/dokumen/template
+// Template nasional terlihat oleh semua; template Bapas hanya oleh Bapas/Kanwil terkait.
+#[axum::debug_handler]
+pub async fn get_all_template_dokumen(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<Json<Vec<TemplateDokumen>>, StatusCode> {
+    let semua = user.role == UserRoleEnum::SuperAdmin;
+    let kanwil_id = if user.role == UserRoleEnum::AdminKanwil { user.kanwil_id } else { None };
+
+    let list = sqlx::query_as!(
+        TemplateDokumen,
+        r#"
+        SELECT id, bapas_id, jenis_dokumen AS "jenis_dokumen: _", nama_template, isi_template,
+            created_at, updated_at, created_by, updated_by, deleted_at
+        FROM template_dokumen
+        WHERE deleted_at IS NULL
+          AND ($1 OR bapas_id IS NULL OR bapas_id = $2
+               OR bapas_id IN (SELECT id FROM bapas WHERE kanwil_id = $3))
+        ORDER BY jenis_dokumen, bapas_id NULLS FIRST, id
+        "#,
+        semua,
+        user.bapas_id,
+        kanwil_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch template dokumen: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// --- UPDATE ---
+// URL: PUT /api/dokumen/template/:id
+#[axum::debug_handler]
+pub async fn update_template_dokumen(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateTemplateDokumen>,
+) -> Result<Json<TemplateDokumen>, StatusCode> {
+    let existing = get_template(&pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    ensure_boleh_kelola(&pool, &user, existing.bapas_id).await?;
+
+    let template = sqlx::query_as!(
+        TemplateDokumen,
+        r#"
+        UPDATE template_dokumen SET
+            nama_template = COALESCE($1, nama_template),
+            isi_template = COALESCE($2, isi_template),
+            updated_by = $3
+        WHERE id = $4 AND deleted_at IS NULL
+        RETURNING id, bapas_id, jenis_dokumen AS "jenis_dokumen: _", nama_template, isi_template,
+            created_at, updated_at, created_by, updated_by, deleted_at
+        "#,
+        payload.nama_template,
+        payload.isi_template,
+        user.id,
+        id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update template dokumen {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(template))
+}
+
+// --- DELETE (SOFT) ---
+// URL: DELETE /api/dokumen/template/:id
+#[axum::debug_handler]
+pub async fn delete_template_dokumen(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> StatusCode {
+    let existing = match get_template(&pool, id).await {
+        Ok(Some(template)) => template,
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    if let Err(status) = ensure_boleh_kelola(&pool, &user, existing.bapas_id).await {
+        return status;
+    }
+
+    let result = sqlx::query!(
+        "UPDATE template_dokumen SET deleted_at = NOW(), updated_by = $1 WHERE id = $2",
+        user.id,
+        id
+    )
+    .execute(&pool)
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => StatusCode::NO_CONTENT,
+        Ok(_) => StatusCode::NOT_FOUND,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Urutan pemilihan template: `template_id` yang diminta, template Bapas klien,
+/// template nasional, lalu template bawaan aplikasi.
+async fn pilih_template(
+    pool: &PgPool,
+    bapas_id: i32,
+    jenis: JenisDokumenEnum,
+    template_id: Option<i32>,
+) -> Result<String, StatusCode> {
+    if let Some(template_id) = template_id {
+        let template = get_template(pool, template_id)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .ok_or(StatusCode::NOT_FOUND)?;
+        // Template Bapas lain tidak boleh dipakai
+        if template.jenis_dokumen != jenis || template.bapas_id.is_some_and(|id| id != bapas_id) {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        return Ok(template.isi_template);
+    }
+
+    let isi = sqlx::query_scalar!(
+        r#"
+        SELECT isi_template FROM template_dokumen
+        WHERE jenis_dokumen = $1 AND deleted_at IS NULL
+          AND (bapas_id = $2 OR bapas_id IS NULL)
+        ORDER BY bapas_id NULLS LAST, updated_at DESC
+        LIMIT 1
+        "#,
+        jenis as _,
+        bapas_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to select template dokumen: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(isi.unwrap_or_else(|| merge::template_bawaan(jenis).to_string()))
+}
+
+// --- GENERATE ---
+// URL: GET /api/penerimaan-dewasa/:id/dokumen?jenis_dokumen=Litmas&format=docx
+#[axum::debug_handler]
+pub async fn generate_dokumen_penerimaan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Query(params): Query<DokumenParams>,
+) -> Result<Response, StatusCode> {
+    let (klien, field) = merge::kumpulkan_field(&pool, id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to collect dokumen fields for penerimaan {}: {}", id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    ensure_klien_access(&pool, &user, klien.id).await?;
+
+    let template = pilih_template(&pool, klien.bapas_id, params.jenis_dokumen, params.template_id).await?;
+    let baris = merge::susun_baris(&merge::isi_template(&template, &field));
+
+    let nama_dasar = match params.jenis_dokumen {
+        JenisDokumenEnum::Litmas => "litmas",
+        JenisDokumenEnum::SuratTugas => "surat_tugas",
+        JenisDokumenEnum::SuratPengakhiran => "surat_pengakhiran",
+    };
+    let (content_type, ekstensi, isi) = match params.format {
+        FormatDokumen::Docx => {
+            let isi = docx::render(&baris).map_err(|e| {
+                tracing::error!("Failed to build docx: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+            ("application/vnd.openxmlformats-officedocument.wordprocessingml.document", "docx", isi)
+        }
+        FormatDokumen::Pdf => ("application/pdf", "pdf", pdf::render(&baris)),
+    };
+
+    let headers = [
+        (header::CONTENT_TYPE, content_type.to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}_penerimaan_{}.{}\"", nama_dasar, id, ekstensi),
+        ),
+    ];
+
+    Ok((headers, isi).into_response())
+}
@@ -0,0 +1,337 @@
+// File baru: src/dokumen/merge.rs
+//
+// Mengumpulkan field dari klien, penerimaan, riwayat hukum, dan layanan integrasi,
+// lalu mengisi placeholder `{{grup.field}}` di template dokumen.
+
+use std::collections::BTreeMap;
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::PgPool;
+use crate::klien::handlers_dewasa::lengkapi_perhitungan_pidana;
+use crate::klien::model_core::Klien;
+use crate::klien::model_dewasa::{LayananIntegrasiDewasa, PenerimaanDewasa, RiwayatHukumDewasa};
+use crate::penomoran::service::hari_ini;
+use crate::types::JenisDokumenEnum;
+use super::model::Baris;
+
+pub type FieldDokumen = BTreeMap<String, String>;
+
+// Kolom audit tidak relevan untuk isi surat
+const FIELD_DIABAIKAN: [&str; 5] = ["created_at", "updated_at", "created_by", "updated_by", "deleted_at"];
+
+const NAMA_BULAN: [&str; 12] = [
+    "Januari", "Februari", "Maret", "April", "Mei", "Juni",
+    "Juli", "Agustus", "September", "Oktober", "November", "Desember",
+];
+
+pub fn tanggal_indonesia(tanggal: NaiveDate) -> String {
+    format!("{} {} {}", tanggal.day(), NAMA_BULAN[tanggal.month0() as usize], tanggal.year())
+}
+
+/// "November 2025"
+pub fn bulan_indonesia(tanggal: NaiveDate) -> String {
+    format!("{} {}", NAMA_BULAN[tanggal.month0() as usize], tanggal.year())
+}
+
+fn nilai_ke_teks(nilai: &Value) -> String {
+    match nilai {
+        Value::Null => String::new(),
+        Value::Bool(true) => "Ya".to_string(),
+        Value::Bool(false) => "Tidak".to_string(),
+        Value::String(teks) => match NaiveDate::parse_from_str(teks, "%Y-%m-%d") {
+            Ok(tanggal) => tanggal_indonesia(tanggal),
+            Err(_) => teks.clone(),
+        },
+        lainnya => lainnya.to_string(),
+    }
+}
+
+fn ratakan(prefix: &str, nilai: &Value, hasil: &mut FieldDokumen) {
+    match nilai {
+        Value::Object(map) => {
+            for (kunci, isi) in map {
+                if FIELD_DIABAIKAN.contains(&kunci.as_str()) {
+                    continue;
+                }
+                ratakan(&format!("{}.{}", prefix, kunci), isi, hasil);
+            }
+        }
+        // Array (mis. pemeriksaan masa bimbingan) tidak dipakai di template
+        Value::Array(_) => {}
+        lainnya => {
+            hasil.insert(prefix.to_string(), nilai_ke_teks(lainnya));
+        }
+    }
+}
+
+/// Menambahkan semua field sebuah struct ke peta field dengan awalan `grup.`.
+/// Enum ikut label serde-nya, tanggal diformat "29 Februari 2024".
+pub fn tambah_grup<T: Serialize>(grup: &str, data: &T, hasil: &mut FieldDokumen) {
+    if let Ok(nilai) = serde_json::to_value(data) {
+        ratakan(grup, &nilai, hasil);
+    }
+}
+
+/// Mengumpulkan field untuk sebuah penerimaan. Riwayat hukum dan layanan
+/// integrasi diambil yang terbaru milik klien yang sama.
+/// Mengembalikan `None` jika penerimaan tidak ditemukan.
+pub async fn kumpulkan_field(pool: &PgPool, penerimaan_id: i32) -> Result<Option<(Klien, FieldDokumen)>, sqlx::Error> {
+    let Some(penerimaan) = sqlx::query_as!(
+        PenerimaanDewasa,
+        r#"
+        SELECT
+            id, klien_id, tanggal_permintaan_lapas_dewasa, tanggal_surat_tugas_dewasa,
+            perihal_dewasa, no_register_litmas_dewasa, nomor_surat_permintaan_lapas_dewasa,
+            jenis_permintaan_litmas_lapas_dewasa, nama_instansi_dewasa as "nama_instansi_dewasa: _",
+            kelas_instansi_dewasa, daerah_instansi_dewasa, nama_penjamin_dewasa,
+            alamat_penjamin_dewasa, kelurahan_penjamin_dewasa, kecamatan_penjamin_dewasa,
+            kota_kabupaten_penjamin_dewasa, keterangan, catatan, created_at, updated_at,
+            created_by, updated_by, deleted_at
+        FROM penerimaan_dewasa
+        WHERE id = $1 AND deleted_at IS NULL
+        "#,
+        penerimaan_id
+    )
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let klien = sqlx::query_as!(
+        Klien,
+        r#"
+        SELECT
+            id, tipe_klien AS "tipe_klien: _", nama_klien, alamat_klien, tempat_lahir_klien,
+            tanggal_lahir_klien, jenis_kelamin_klien AS "jenis_kelamin_klien: _", agama_klien, pekerjaan_klien AS "pekerjaan_klien: _",
+            pendidikan_terakhir_klien AS "pendidikan_terakhir_klien: _", bapas_id, pk_id, kanwil_id, online_akses_klien,
+            pengulangan_klien, kewarganegaraan_klien AS "kewarganegaraan_klien: _", negara_asal_klien, suku_klien,
+            keterangan_klien, catatan_klien, created_at, updated_at, created_by,
+            updated_by, deleted_at
+        FROM klien WHERE id = $1
+        "#,
+        penerimaan.klien_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let riwayat = sqlx::query_as!(
+        RiwayatHukumDewasa,
+        r#"
+        SELECT * FROM riwayat_hukum_dewasa
+        WHERE klien_id = $1 AND deleted_at IS NULL
+        ORDER BY tanggal_surat_keputusan_pengadilan_dewasa DESC NULLS LAST, id DESC
+        LIMIT 1
+        "#,
+        klien.id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let layanan = sqlx::query_as!(
+        LayananIntegrasiDewasa,
+        r#"
+        SELECT * FROM layanan_integrasi_dewasa
+        WHERE klien_id = $1 AND deleted_at IS NULL
+        ORDER BY masa_bimbingan_awal_dewasa DESC NULLS LAST, id DESC
+        LIMIT 1
+        "#,
+        klien.id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let pk = sqlx::query!(
+        r#"
+        SELECT nama_user, nip_user, gelar_depan_user, gelar_belakang_user,
+            pangkat_golongan_user, jabatan_user
+        FROM users WHERE id = $1
+        "#,
+        klien.pk_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let bapas = sqlx::query!(
+        "SELECT nama_bapas, kota_bapas, alamat_bapas, nomor_telepon_bapas, email_bapas FROM bapas WHERE id = $1",
+        klien.bapas_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let mut field = FieldDokumen::new();
+    tambah_grup("klien", &klien, &mut field);
+    tambah_grup("penerimaan", &penerimaan, &mut field);
+    if let Some(riwayat) = riwayat {
+        let detail = lengkapi_perhitungan_pidana(pool, klien.id, vec![riwayat]).await?;
+        if let Some(detail) = detail.first() {
+            tambah_grup("riwayat", detail, &mut field);
+        }
+    }
+    if let Some(layanan) = layanan {
+        tambah_grup("layanan", &layanan, &mut field);
+    }
+
+    let nama_lengkap_pk = [pk.gelar_depan_user.as_deref(), Some(pk.nama_user.as_str())]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let nama_lengkap_pk = match pk.gelar_belakang_user.as_deref() {
+        Some(gelar) => format!("{}, {}", nama_lengkap_pk, gelar),
+        None => nama_lengkap_pk,
+    };
+    field.insert("pk.nama".into(), nama_lengkap_pk);
+    field.insert("pk.nip".into(), pk.nip_user);
+    field.insert("pk.pangkat_golongan".into(), pk.pangkat_golongan_user.unwrap_or_default());
+    field.insert("pk.jabatan".into(), pk.jabatan_user.unwrap_or_default());
+
+    field.insert("bapas.nama".into(), bapas.nama_bapas);
+    field.insert("bapas.kota".into(), bapas.kota_bapas);
+    field.insert("bapas.alamat".into(), bapas.alamat_bapas.unwrap_or_default());
+    field.insert("bapas.telepon".into(), bapas.nomor_telepon_bapas.unwrap_or_default());
+    field.insert("bapas.email".into(), bapas.email_bapas.unwrap_or_default());
+
+    field.insert("tanggal_hari_ini".into(), tanggal_indonesia(hari_ini()));
+
+    Ok(Some((klien, field)))
+}
+
+/// Mengganti `{{nama}}` dengan nilai field. Placeholder yang tidak dikenal
+/// dibiarkan apa adanya supaya mudah terlihat saat memeriksa template.
+pub fn isi_template(template: &str, field: &FieldDokumen) -> String {
+    let mut hasil = String::with_capacity(template.len());
+    let mut sisa = template;
+    while let Some(buka) = sisa.find("{{") {
+        let Some(tutup) = sisa[buka..].find("}}").map(|i| i + buka) else { break };
+        hasil.push_str(&sisa[..buka]);
+        let nama = sisa[buka + 2..tutup].trim();
+        match field.get(nama) {
+            Some(nilai) => hasil.push_str(nilai),
+            None => hasil.push_str(&sisa[buka..tutup + 2]),
+        }
+        sisa = &sisa[tutup + 2..];
+    }
+    hasil.push_str(sisa);
+    hasil
+}
+
+pub fn susun_baris(teks: &str) -> Vec<Baris> {
+    teks.lines()
+        .map(|baris| {
+            let baris = baris.trim_end();
+            if let Some(judul) = baris.strip_prefix("## ") {
+                Baris::SubJudul(judul.to_string())
+            } else if let Some(judul) = baris.strip_prefix("# ") {
+                Baris::Judul(judul.to_string())
+            } else if baris.is_empty() {
+                Baris::Kosong
+            } else {
+                Baris::Paragraf(baris.to_string())
+            }
+        })
+        .collect()
+}
+
+/// Template bawaan dipakai jika Bapas maupun pusat belum menyimpan template.
+pub fn template_bawaan(jenis: JenisDokumenEnum) -> &'static str {
+    match jenis {
+        JenisDokumenEnum::Litmas => TEMPLATE_LITMAS,
+        JenisDokumenEnum::SuratTugas => TEMPLATE_SURAT_TUGAS,
+        JenisDokumenEnum::SuratPengakhiran => TEMPLATE_SURAT_PENGAKHIRAN,
+    }
+}
+
+const TEMPLATE_LITMAS: &str = "\
+# {{bapas.nama}}
+{{bapas.alamat}}
+
+# LAPORAN PENELITIAN KEMASYARAKATAN
+Nomor Register: {{penerimaan.no_register_litmas_dewasa}}
+Perihal: {{penerimaan.perihal_dewasa}}
+
+## I. IDENTITAS KLIEN
+Nama: {{klien.nama_klien}}
+Tempat/Tanggal Lahir: {{klien.tempat_lahir_klien}}, {{klien.tanggal_lahir_klien}}
+Jenis Kelamin: {{klien.jenis_kelamin_klien}}
+Agama: {{klien.agama_klien}}
+Pendidikan Terakhir: {{klien.pendidikan_terakhir_klien}}
+Pekerjaan: {{klien.pekerjaan_klien}}
+Kewarganegaraan: {{klien.kewarganegaraan_klien}}
+Alamat: {{klien.alamat_klien}}
+
+## II. DASAR PERMINTAAN
+Instansi Peminta: {{penerimaan.nama_instansi_dewasa}} {{penerimaan.kelas_instansi_dewasa}} {{penerimaan.daerah_instansi_dewasa}}
+Nomor Surat Permintaan: {{penerimaan.nomor_surat_permintaan_lapas_dewasa}}
+Tanggal Permintaan: {{penerimaan.tanggal_permintaan_lapas_dewasa}}
+Jenis Permintaan Litmas: {{penerimaan.jenis_permintaan_litmas_lapas_dewasa}}
+
+## III. RIWAYAT HUKUM
+Tindak Pidana: {{riwayat.kategori_tindak_pidana_dewasa}} ({{riwayat.pasal_tindak_pidana_dewasa}})
+Putusan Pengadilan: {{riwayat.nomor_surat_keputusan_pengadilan_dewasa}} tanggal {{riwayat.tanggal_surat_keputusan_pengadilan_dewasa}}
+Lama Pidana: {{riwayat.pidana_tahun_dewasa}} tahun {{riwayat.pidana_bulan_dewasa}} bulan {{riwayat.pidana_hari_dewasa}} hari
+Pertama Ditahan: {{riwayat.pertama_ditahan_dewasa}}
+Remisi: {{riwayat.perhitungan.total_remisi_hari}} hari
+1/2 Masa Pidana: {{riwayat.perhitungan.tanggal_setengah}}
+2/3 Masa Pidana: {{riwayat.perhitungan.tanggal_dua_pertiga}}
+Tanggal Ekspirasi: {{riwayat.perhitungan.tanggal_ekspirasi}}
+
+## IV. PENJAMIN
+Nama Penjamin: {{penerimaan.nama_penjamin_dewasa}}
+Alamat: {{penerimaan.alamat_penjamin_dewasa}}, {{penerimaan.kelurahan_penjamin_dewasa}}, {{penerimaan.kecamatan_penjamin_dewasa}}, {{penerimaan.kota_kabupaten_penjamin_dewasa}}
+
+## V. KESIMPULAN DAN REKOMENDASI
+
+
+{{bapas.kota}}, {{tanggal_hari_ini}}
+Pembimbing Kemasyarakatan,
+
+
+{{pk.nama}}
+NIP. {{pk.nip}}
+";
+
+const TEMPLATE_SURAT_TUGAS: &str = "\
+# {{bapas.nama}}
+{{bapas.alamat}}
+
+# SURAT TUGAS
+
+Yang bertanda tangan di bawah ini Kepala {{bapas.nama}} menugaskan:
+Nama: {{pk.nama}}
+NIP: {{pk.nip}}
+Pangkat/Golongan: {{pk.pangkat_golongan}}
+Jabatan: {{pk.jabatan}}
+
+Untuk melaksanakan penelitian kemasyarakatan terhadap klien:
+Nama: {{klien.nama_klien}}
+Nomor Register: {{penerimaan.no_register_litmas_dewasa}}
+Perihal: {{penerimaan.perihal_dewasa}}
+Berdasarkan permintaan {{penerimaan.nama_instansi_dewasa}} {{penerimaan.daerah_instansi_dewasa}} nomor {{penerimaan.nomor_surat_permintaan_lapas_dewasa}} tanggal {{penerimaan.tanggal_permintaan_lapas_dewasa}}.
+
+Demikian surat tugas ini dibuat untuk dilaksanakan dengan penuh tanggung jawab.
+
+{{bapas.kota}}, {{penerimaan.tanggal_surat_tugas_dewasa}}
+Kepala {{bapas.nama}}
+";
+
+const TEMPLATE_SURAT_PENGAKHIRAN: &str = "\
+# {{bapas.nama}}
+{{bapas.alamat}}
+
+# SURAT KETERANGAN PENGAKHIRAN BIMBINGAN
+Nomor: {{layanan.nomor_surat_pengakhiran_dewasa}}
+
+Menerangkan bahwa klien:
+Nama: {{klien.nama_klien}}
+Alamat: {{klien.alamat_klien}}
+Nomor SK Integrasi: {{layanan.nomor_sk_dewasa}} tanggal {{layanan.tanggal_sk_integrasi_dewasa}}
+Jenis Bimbingan: {{layanan.jenis_bimbingan_dewasa}}
+Masa Bimbingan: {{layanan.masa_bimbingan_awal_dewasa}} s.d. {{layanan.masa_bimbingan_akhir_dewasa}}
+
+telah selesai menjalani masa bimbingan di {{bapas.nama}} dengan Pembimbing Kemasyarakatan {{pk.nama}}.
+
+{{bapas.kota}}, {{layanan.tanggal_surat_pengakhiran_dewasa}}
+Kepala {{bapas.nama}}
+";
@@ -0,0 +1,7 @@
+pub mod model;
+pub mod merge;
+pub mod docx;
+pub mod pdf;
+pub mod xlsx;
+pub mod zip_aliran;
+pub mod handlers;
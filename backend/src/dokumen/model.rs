@@ -0,0 +1,65 @@
+// File baru: src/dokumen/model.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+use crate::types::JenisDokumenEnum;
+
+// Merepresentasikan satu baris dari tabel 'template_dokumen'
+#[derive(Debug, Serialize, FromRow)]
+pub struct TemplateDokumen {
+    pub id: i32,
+    pub bapas_id: Option<i32>,
+    pub jenis_dokumen: JenisDokumenEnum,
+    pub nama_template: String,
+    pub isi_template: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+// Data untuk membuat template baru. bapas_id kosong = template nasional (SuperAdmin)
+#[derive(Debug, Deserialize)]
+pub struct CreateTemplateDokumen {
+    pub bapas_id: Option<i32>,
+    pub jenis_dokumen: JenisDokumenEnum,
+    pub nama_template: String,
+    pub isi_template: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateTemplateDokumen {
+    pub nama_template: Option<String>,
+    pub isi_template: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FormatDokumen {
+    Docx,
+    Pdf,
+}
+
+// Query string untuk GET /api/penerimaan-dewasa/:id/dokumen
+#[derive(Debug, Deserialize)]
+pub struct DokumenParams {
+    pub jenis_dokumen: JenisDokumenEnum,
+    pub format: FormatDokumen,
+    pub template_id: Option<i32>,
+}
+
+/// Satu baris isi dokumen setelah template diisi.
+/// Di template: `# ` = judul, `## ` = subjudul, baris kosong = jarak.
+/// `Tabel` tidak berasal dari template; dipakai laporan yang disusun dari kode
+/// (baris pertama = kepala tabel, kolom pertama lebih lebar).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Baris {
+    Judul(String),
+    SubJudul(String),
+    Paragraf(String),
+    Kosong,
+    Tabel(Vec<Vec<String>>),
+}
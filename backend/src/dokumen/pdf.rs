@@ -0,0 +1,262 @@
+// File baru: src/dokumen/pdf.rs
+//
+// Penulis PDF sederhana tanpa dependensi: halaman A4, font standar Helvetica
+// (tidak perlu di-embed), teks dibungkus per kata. Cukup untuk surat dinas;
+// tata letak yang lebih rumit sebaiknya memakai template DOCX.
+
+use super::model::Baris;
+
+const LEBAR_HALAMAN: f32 = 595.0;
+const TINGGI_HALAMAN: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const UKURAN_ISI: f32 = 11.0;
+const UKURAN_SUBJUDUL: f32 = 12.0;
+const UKURAN_JUDUL: f32 = 13.0;
+
+// Lebar glyph Helvetica (per 1000 unit) untuk karakter ASCII 32..=126
+const LEBAR_HELVETICA: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Font standar PDF hanya mengenal WinAnsi; karakter di luar Latin-1 diganti '?'.
+fn ke_latin1(teks: &str) -> Vec<u8> {
+    teks.chars()
+        .map(|c| match c as u32 {
+            0x20..=0x7E | 0xA0..=0xFF => c as u32 as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+fn lebar_teks(teks: &[u8], ukuran: f32, tebal: bool) -> f32 {
+    let total: u32 = teks
+        .iter()
+        .map(|&b| match b {
+            0x20..=0x7E => LEBAR_HELVETICA[(b - 0x20) as usize] as u32,
+            _ => 556,
+        })
+        .sum();
+    // Helvetica-Bold sedikit lebih lebar; cukup didekati agar tidak melewati margin
+    let faktor = if tebal { 1.08 } else { 1.0 };
+    total as f32 * ukuran / 1000.0 * faktor
+}
+
+fn bungkus(teks: &[u8], ukuran: f32, tebal: bool) -> Vec<Vec<u8>> {
+    let lebar_maks = LEBAR_HALAMAN - 2.0 * MARGIN;
+    let mut hasil = Vec::new();
+    let mut baris: Vec<u8> = Vec::new();
+    for kata in teks.split(|&b| b == b' ').filter(|k| !k.is_empty()) {
+        let mut calon = baris.clone();
+        if !calon.is_empty() {
+            calon.push(b' ');
+        }
+        calon.extend_from_slice(kata);
+        if !baris.is_empty() && lebar_teks(&calon, ukuran, tebal) > lebar_maks {
+            hasil.push(std::mem::take(&mut baris));
+            baris.extend_from_slice(kata);
+        } else {
+            baris = calon;
+        }
+    }
+    if !baris.is_empty() || hasil.is_empty() {
+        hasil.push(baris);
+    }
+    hasil
+}
+
+fn escape_pdf(teks: &[u8]) -> Vec<u8> {
+    let mut hasil = Vec::with_capacity(teks.len());
+    for &b in teks {
+        if matches!(b, b'(' | b')' | b'\\') {
+            hasil.push(b'\\');
+        }
+        hasil.push(b);
+    }
+    hasil
+}
+
+/// Memotong teks dari belakang sampai muat di `lebar_maks`.
+fn potong(teks: &[u8], lebar_maks: f32, ukuran: f32, tebal: bool) -> Vec<u8> {
+    let mut hasil = teks.to_vec();
+    while !hasil.is_empty() && lebar_teks(&hasil, ukuran, tebal) > lebar_maks {
+        hasil.pop();
+    }
+    hasil
+}
+
+fn garis(stream: &mut Vec<u8>, y: f32) {
+    stream.extend_from_slice(
+        format!("0.5 w {:.2} {:.2} m {:.2} {:.2} l S\n", MARGIN, y, LEBAR_HALAMAN - MARGIN, y).as_bytes(),
+    );
+}
+
+/// Tabel tanpa pembungkusan sel: kolom pertama selebar setengah halaman dan
+/// rata kiri, kolom lain dibagi rata dan rata kanan (angka). Garis di atas dan
+/// di bawah kepala tabel serta di bawah baris terakhir.
+fn susun_tabel(isi: &[Vec<String>], halaman: &mut Vec<Vec<u8>>, stream: &mut Vec<u8>, y: &mut f32) {
+    let lebar_isi = LEBAR_HALAMAN - 2.0 * MARGIN;
+    let jarak = UKURAN_ISI * 1.6;
+
+    for (i, baris) in isi.iter().enumerate() {
+        let kepala = i == 0;
+        if *y - jarak < MARGIN {
+            halaman.push(std::mem::take(stream));
+            *y = TINGGI_HALAMAN - MARGIN;
+        }
+        if kepala {
+            garis(stream, *y - 2.0);
+        }
+        *y -= jarak;
+
+        let lebar_pertama = if baris.len() > 1 { lebar_isi / 2.0 } else { lebar_isi };
+        let lebar_lain = (lebar_isi - lebar_pertama) / baris.len().saturating_sub(1).max(1) as f32;
+        let font = if kepala { "F2" } else { "F1" };
+        for (k, sel) in baris.iter().enumerate() {
+            let lebar_sel = if k == 0 { lebar_pertama } else { lebar_lain };
+            let teks = potong(&ke_latin1(sel), lebar_sel - 6.0, UKURAN_ISI, kepala);
+            if teks.is_empty() {
+                continue;
+            }
+            let x = if k == 0 {
+                MARGIN
+            } else {
+                MARGIN + lebar_pertama + lebar_lain * k as f32 - 3.0 - lebar_teks(&teks, UKURAN_ISI, kepala)
+            };
+            stream.extend_from_slice(format!("BT /{} {} Tf {:.2} {:.2} Td (", font, UKURAN_ISI, x, *y).as_bytes());
+            stream.extend_from_slice(&escape_pdf(&teks));
+            stream.extend_from_slice(b") Tj ET\n");
+        }
+
+        if kepala || i + 1 == isi.len() {
+            garis(stream, *y - 4.5);
+        }
+    }
+}
+
+/// Menyusun isi setiap halaman sebagai content stream, beserta posisi y
+/// terakhir pada halaman terakhir.
+fn susun_halaman(baris: &[Baris]) -> (Vec<Vec<u8>>, f32) {
+    let mut halaman = Vec::new();
+    let mut stream: Vec<u8> = Vec::new();
+    let mut y = TINGGI_HALAMAN - MARGIN;
+
+    for b in baris {
+        let (teks, ukuran, tebal, tengah) = match b {
+            Baris::Judul(teks) => (teks.as_str(), UKURAN_JUDUL, true, true),
+            Baris::SubJudul(teks) => (teks.as_str(), UKURAN_SUBJUDUL, true, false),
+            Baris::Paragraf(teks) => (teks.as_str(), UKURAN_ISI, false, false),
+            Baris::Kosong => ("", UKURAN_ISI, false, false),
+            Baris::Tabel(isi) => {
+                susun_tabel(isi, &mut halaman, &mut stream, &mut y);
+                continue;
+            }
+        };
+        let font = if tebal { "F2" } else { "F1" };
+        let jarak = ukuran * 1.4;
+
+        for potongan in bungkus(&ke_latin1(teks), ukuran, tebal) {
+            if y - jarak < MARGIN {
+                halaman.push(std::mem::take(&mut stream));
+                y = TINGGI_HALAMAN - MARGIN;
+            }
+            y -= jarak;
+            if potongan.is_empty() {
+                continue;
+            }
+            let x = if tengah {
+                (LEBAR_HALAMAN - lebar_teks(&potongan, ukuran, tebal)) / 2.0
+            } else {
+                MARGIN
+            };
+            stream.extend_from_slice(format!("BT /{} {} Tf {:.2} {:.2} Td (", font, ukuran, x, y).as_bytes());
+            stream.extend_from_slice(&escape_pdf(&potongan));
+            stream.extend_from_slice(b") Tj ET\n");
+        }
+    }
+    halaman.push(stream);
+    (halaman, y)
+}
+
+pub fn render(baris: &[Baris]) -> Vec<u8> {
+    tulis(susun_halaman(baris).0)
+}
+
+/// Seperti `render`, ditambah kode QR di tengah, di bawah teks terakhir.
+/// `modul` adalah matriks QR per baris (true = hitam); quiet zone ditambahkan di sini.
+pub fn render_dengan_qr(baris: &[Baris], modul: &[Vec<bool>], ukuran_modul: f32) -> Vec<u8> {
+    let (mut halaman, mut y) = susun_halaman(baris);
+    let lebar_qr = (modul.len() + 8) as f32 * ukuran_modul;
+    if y - lebar_qr < MARGIN {
+        halaman.push(Vec::new());
+        y = TINGGI_HALAMAN - MARGIN;
+    }
+
+    let stream = halaman.last_mut().expect("minimal satu halaman");
+    let x0 = (LEBAR_HALAMAN - lebar_qr) / 2.0 + 4.0 * ukuran_modul;
+    let y0 = y - 4.0 * ukuran_modul;
+    stream.extend_from_slice(b"0 0 0 rg\n");
+    for (r, baris_modul) in modul.iter().enumerate() {
+        for (c, &hitam) in baris_modul.iter().enumerate() {
+            if hitam {
+                let x = x0 + c as f32 * ukuran_modul;
+                let y = y0 - (r + 1) as f32 * ukuran_modul;
+                stream.extend_from_slice(
+                    format!("{:.2} {:.2} {:.2} {:.2} re\n", x, y, ukuran_modul, ukuran_modul).as_bytes(),
+                );
+            }
+        }
+    }
+    stream.extend_from_slice(b"f\n");
+    tulis(halaman)
+}
+
+fn tulis(halaman: Vec<Vec<u8>>) -> Vec<u8> {
+
+    // Nomor objek: 1 catalog, 2 pages, 3-4 font, lalu pasangan (page, content)
+    let mut objek: Vec<Vec<u8>> = Vec::new();
+    objek.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+    let kids = (0..halaman.len())
+        .map(|i| format!("{} 0 R", 5 + i * 2))
+        .collect::<Vec<_>>()
+        .join(" ");
+    objek.push(format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids, halaman.len()).into_bytes());
+    objek.push(b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec());
+    objek.push(b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_vec());
+    for (i, stream) in halaman.iter().enumerate() {
+        objek.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                LEBAR_HALAMAN, TINGGI_HALAMAN, 6 + i * 2
+            )
+            .into_bytes(),
+        );
+        let mut isi = format!("<< /Length {} >>\nstream\n", stream.len()).into_bytes();
+        isi.extend_from_slice(stream);
+        isi.extend_from_slice(b"\nendstream");
+        objek.push(isi);
+    }
+
+    let mut pdf: Vec<u8> = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offset = Vec::with_capacity(objek.len());
+    for (i, isi) in objek.iter().enumerate() {
+        offset.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+        pdf.extend_from_slice(isi);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+
+    let posisi_xref = pdf.len();
+    pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objek.len() + 1).as_bytes());
+    for o in offset {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", o).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objek.len() + 1, posisi_xref).as_bytes(),
+    );
+    pdf
+}
@@ -0,0 +1,190 @@
+// File baru: src/dokumen/xlsx.rs
+//
+// Menyusun berkas .xlsx minimal (SpreadsheetML di dalam arsip zip), satu
+// lembar kerja per `Lembar`. Teks disimpan sebagai inline string sehingga
+// sharedStrings.xml tidak diperlukan; styles.xml hanya berisi format tebal.
+
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+use super::docx::escape_xml;
+
+const RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>
+</Relationships>"#;
+
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+<fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts>
+<fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills>
+<borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders>
+<cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs>
+<cellXfs count="2"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/></cellXfs>
+</styleSheet>"#;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sel {
+    Teks(String),
+    Tebal(String),
+    Angka(f64),
+    Kosong,
+}
+
+#[derive(Debug, Clone)]
+pub struct Lembar {
+    pub nama: String,
+    /// Lebar kolom dalam satuan karakter, dari kolom A; kolom sisanya memakai lebar bawaan.
+    pub lebar_kolom: Vec<f32>,
+    pub baris: Vec<Vec<Sel>>,
+}
+
+/// Nama kolom gaya Excel: 0 -> A, 25 -> Z, 26 -> AA.
+pub fn nama_kolom(mut indeks: usize) -> String {
+    let mut hasil = Vec::new();
+    loop {
+        hasil.push(b'A' + (indeks % 26) as u8);
+        if indeks < 26 {
+            break;
+        }
+        indeks = indeks / 26 - 1;
+    }
+    hasil.reverse();
+    String::from_utf8(hasil).expect("huruf ASCII")
+}
+
+/// Karakter kontrol selain tab dan baris baru tidak sah di XML.
+fn teks_xml(teks: &str) -> String {
+    let bersih: String = teks
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .collect();
+    escape_xml(&bersih)
+}
+
+/// Nama lembar maksimal 31 karakter dan tidak boleh memuat []:*?/\
+fn nama_lembar(nama: &str, urutan: usize) -> String {
+    let bersih: String = nama
+        .chars()
+        .filter(|c| !matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\'))
+        .take(31)
+        .collect();
+    if bersih.trim().is_empty() {
+        format!("Lembar{}", urutan)
+    } else {
+        bersih
+    }
+}
+
+fn sel_xml(referensi: &str, sel: &Sel) -> String {
+    match sel {
+        Sel::Teks(teks) => format!(r#"<c r="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#, referensi, teks_xml(teks)),
+        Sel::Tebal(teks) => format!(
+            r#"<c r="{}" s="1" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+            referensi,
+            teks_xml(teks)
+        ),
+        // NaN/tak hingga tidak bisa disimpan sebagai angka
+        Sel::Angka(angka) if angka.is_finite() => format!(r#"<c r="{}"><v>{}</v></c>"#, referensi, angka),
+        Sel::Angka(_) | Sel::Kosong => String::new(),
+    }
+}
+
+/// Kepala dokumen worksheet sampai `<sheetData>` dibuka.
+pub fn awal_lembar(lebar_kolom: &[f32]) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+    );
+    if !lebar_kolom.is_empty() {
+        xml.push_str("<cols>");
+        for (i, lebar) in lebar_kolom.iter().enumerate() {
+            xml.push_str(&format!(r#"<col min="{0}" max="{0}" width="{1}" customWidth="1"/>"#, i + 1, lebar));
+        }
+        xml.push_str("</cols>");
+    }
+    xml.push_str("<sheetData>");
+    xml
+}
+
+pub const AKHIR_LEMBAR: &str = "</sheetData></worksheet>";
+
+/// Satu elemen `<row>`; `nomor_baris` dimulai dari 1.
+pub fn baris_xml(nomor_baris: usize, sel: &[Sel]) -> String {
+    let mut xml = format!(r#"<row r="{}">"#, nomor_baris);
+    for (k, isi) in sel.iter().enumerate() {
+        xml.push_str(&sel_xml(&format!("{}{}", nama_kolom(k), nomor_baris), isi));
+    }
+    xml.push_str("</row>");
+    xml
+}
+
+/// Bagian-bagian paket selain isi worksheet: (nama berkas, isi).
+pub fn bagian_paket(nama: &[String]) -> Vec<(&'static str, String)> {
+    let mut content_types = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="xml" ContentType="application/xml"/>
+<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>
+<Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>"#,
+    );
+    let mut workbook = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>"#,
+    );
+    let mut workbook_rels = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rIdStyles" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>"#,
+    );
+    for (i, nama) in nama.iter().enumerate() {
+        let nomor = i + 1;
+        content_types.push_str(&format!(
+            r#"<Override PartName="/xl/worksheets/sheet{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+            nomor
+        ));
+        workbook.push_str(&format!(
+            r#"<sheet name="{}" sheetId="{}" r:id="rId{}"/>"#,
+            escape_xml(&nama_lembar(nama, nomor)),
+            nomor,
+            nomor
+        ));
+        workbook_rels.push_str(&format!(
+            r#"<Relationship Id="rId{0}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{0}.xml"/>"#,
+            nomor
+        ));
+    }
+    content_types.push_str("</Types>");
+    workbook.push_str("</sheets></workbook>");
+    workbook_rels.push_str("</Relationships>");
+
+    vec![
+        ("[Content_Types].xml", content_types),
+        ("_rels/.rels", RELS.to_string()),
+        ("xl/workbook.xml", workbook),
+        ("xl/_rels/workbook.xml.rels", workbook_rels),
+        ("xl/styles.xml", STYLES.to_string()),
+    ]
+}
+
+pub fn render(lembar: &[Lembar]) -> Result<Vec<u8>, zip::result::ZipError> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let opsi = SimpleFileOptions::default();
+
+    let nama: Vec<String> = lembar.iter().map(|l| l.nama.clone()).collect();
+    for (berkas, isi) in bagian_paket(&nama) {
+        zip.start_file(berkas, opsi)?;
+        zip.write_all(isi.as_bytes())?;
+    }
+    for (i, l) in lembar.iter().enumerate() {
+        zip.start_file(format!("xl/worksheets/sheet{}.xml", i + 1), opsi)?;
+        zip.write_all(awal_lembar(&l.lebar_kolom).as_bytes())?;
+        for (nomor, sel) in l.baris.iter().enumerate() {
+            zip.write_all(baris_xml(nomor + 1, sel).as_bytes())?;
+        }
+        zip.write_all(AKHIR_LEMBAR.as_bytes())?;
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
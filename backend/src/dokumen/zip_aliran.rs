@@ -0,0 +1,186 @@
+// File baru: src/dokumen/zip_aliran.rs
+//
+// Penulis arsip zip yang tidak membutuhkan Seek, untuk berkas yang dialirkan
+// langsung ke respons HTTP. Setiap entri dikompresi deflate dan ukurannya
+// ditulis belakangan di data descriptor; central directory ditulis saat
+// `tutup`. ZIP64 tidak didukung: entri atau arsip di atas 4 GiB menghasilkan error.
+//
+// Keluaran ditampung di buffer internal dan diambil pemanggil lewat `ambil`.
+
+use crc32fast::Hasher;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::io::{self, Write};
+
+const TANDA_HEADER_LOKAL: u32 = 0x0403_4b50;
+const TANDA_DATA_DESCRIPTOR: u32 = 0x0807_4b50;
+const TANDA_CENTRAL_DIRECTORY: u32 = 0x0201_4b50;
+const TANDA_AKHIR_DIRECTORY: u32 = 0x0605_4b50;
+
+const VERSI: u16 = 20;
+// Bit 3: ukuran & CRC ada di data descriptor; bit 11: nama berkas UTF-8
+const FLAG: u16 = 0x0808;
+const METODE_DEFLATE: u16 = 8;
+// 1980-01-01 00:00, sama seperti bawaan crate zip
+const WAKTU_DOS: u16 = 0;
+const TANGGAL_DOS: u16 = 0x0021;
+
+struct EntriSelesai {
+    nama: String,
+    offset: u32,
+    crc: u32,
+    ukuran_terkompresi: u32,
+    ukuran_asli: u32,
+}
+
+struct EntriAktif {
+    nama: String,
+    offset: u32,
+    crc: Hasher,
+    ukuran_asli: u64,
+    ukuran_terkompresi: u64,
+    kompresor: DeflateEncoder<Vec<u8>>,
+}
+
+#[derive(Default)]
+pub struct ZipAliran {
+    keluaran: Vec<u8>,
+    posisi: u64,
+    selesai: Vec<EntriSelesai>,
+    aktif: Option<EntriAktif>,
+}
+
+fn terlalu_besar() -> io::Error {
+    io::Error::other("arsip melebihi batas zip tanpa ZIP64")
+}
+
+fn ke_u32(nilai: u64) -> io::Result<u32> {
+    u32::try_from(nilai).map_err(|_| terlalu_besar())
+}
+
+impl ZipAliran {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn keluarkan(&mut self, data: &[u8]) {
+        self.keluaran.extend_from_slice(data);
+        self.posisi += data.len() as u64;
+    }
+
+    /// Memulai entri baru; entri sebelumnya ditutup lebih dulu.
+    pub fn mulai_berkas(&mut self, nama: &str) -> io::Result<()> {
+        self.selesai_berkas()?;
+        let offset = ke_u32(self.posisi)?;
+
+        let mut header = Vec::with_capacity(30 + nama.len());
+        header.extend_from_slice(&TANDA_HEADER_LOKAL.to_le_bytes());
+        header.extend_from_slice(&VERSI.to_le_bytes());
+        header.extend_from_slice(&FLAG.to_le_bytes());
+        header.extend_from_slice(&METODE_DEFLATE.to_le_bytes());
+        header.extend_from_slice(&WAKTU_DOS.to_le_bytes());
+        header.extend_from_slice(&TANGGAL_DOS.to_le_bytes());
+        // CRC dan ukuran menyusul di data descriptor
+        header.extend_from_slice(&[0; 12]);
+        header.extend_from_slice(&(nama.len() as u16).to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes());
+        header.extend_from_slice(nama.as_bytes());
+        self.keluarkan(&header);
+
+        self.aktif = Some(EntriAktif {
+            nama: nama.to_string(),
+            offset,
+            crc: Hasher::new(),
+            ukuran_asli: 0,
+            ukuran_terkompresi: 0,
+            kompresor: DeflateEncoder::new(Vec::new(), Compression::default()),
+        });
+        Ok(())
+    }
+
+    pub fn tulis(&mut self, data: &[u8]) -> io::Result<()> {
+        let Some(entri) = self.aktif.as_mut() else {
+            return Err(io::Error::other("belum ada entri zip yang dimulai"));
+        };
+        entri.crc.update(data);
+        entri.ukuran_asli += data.len() as u64;
+        entri.kompresor.write_all(data)?;
+        let hasil = std::mem::take(entri.kompresor.get_mut());
+        entri.ukuran_terkompresi += hasil.len() as u64;
+        self.keluarkan(&hasil);
+        Ok(())
+    }
+
+    pub fn selesai_berkas(&mut self) -> io::Result<()> {
+        let Some(entri) = self.aktif.take() else {
+            return Ok(());
+        };
+        let sisa = entri.kompresor.finish()?;
+        self.keluarkan(&sisa);
+
+        let crc = entri.crc.finalize();
+        let ukuran_terkompresi = ke_u32(entri.ukuran_terkompresi + sisa.len() as u64)?;
+        let ukuran_asli = ke_u32(entri.ukuran_asli)?;
+
+        let mut descriptor = Vec::with_capacity(16);
+        descriptor.extend_from_slice(&TANDA_DATA_DESCRIPTOR.to_le_bytes());
+        descriptor.extend_from_slice(&crc.to_le_bytes());
+        descriptor.extend_from_slice(&ukuran_terkompresi.to_le_bytes());
+        descriptor.extend_from_slice(&ukuran_asli.to_le_bytes());
+        self.keluarkan(&descriptor);
+
+        self.selesai.push(EntriSelesai {
+            nama: entri.nama,
+            offset: entri.offset,
+            crc,
+            ukuran_terkompresi,
+            ukuran_asli,
+        });
+        Ok(())
+    }
+
+    /// Mengambil byte yang sudah siap dikirim.
+    pub fn ambil(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.keluaran)
+    }
+
+    /// Menutup entri terakhir dan menulis central directory; mengembalikan sisa keluaran.
+    pub fn tutup(mut self) -> io::Result<Vec<u8>> {
+        self.selesai_berkas()?;
+        let awal_directory = ke_u32(self.posisi)?;
+        let jumlah = u16::try_from(self.selesai.len()).map_err(|_| terlalu_besar())?;
+
+        let mut directory = Vec::new();
+        for e in &self.selesai {
+            directory.extend_from_slice(&TANDA_CENTRAL_DIRECTORY.to_le_bytes());
+            directory.extend_from_slice(&VERSI.to_le_bytes());
+            directory.extend_from_slice(&VERSI.to_le_bytes());
+            directory.extend_from_slice(&FLAG.to_le_bytes());
+            directory.extend_from_slice(&METODE_DEFLATE.to_le_bytes());
+            directory.extend_from_slice(&WAKTU_DOS.to_le_bytes());
+            directory.extend_from_slice(&TANGGAL_DOS.to_le_bytes());
+            directory.extend_from_slice(&e.crc.to_le_bytes());
+            directory.extend_from_slice(&e.ukuran_terkompresi.to_le_bytes());
+            directory.extend_from_slice(&e.ukuran_asli.to_le_bytes());
+            directory.extend_from_slice(&(e.nama.len() as u16).to_le_bytes());
+            // extra, komentar, nomor disk, atribut internal (2 byte masing-masing), atribut eksternal (4 byte)
+            directory.extend_from_slice(&[0; 12]);
+            directory.extend_from_slice(&e.offset.to_le_bytes());
+            directory.extend_from_slice(e.nama.as_bytes());
+        }
+        let ukuran_directory = ke_u32(directory.len() as u64)?;
+        self.keluarkan(&directory);
+
+        let mut akhir = Vec::with_capacity(22);
+        akhir.extend_from_slice(&TANDA_AKHIR_DIRECTORY.to_le_bytes());
+        akhir.extend_from_slice(&[0; 4]);
+        akhir.extend_from_slice(&jumlah.to_le_bytes());
+        akhir.extend_from_slice(&jumlah.to_le_bytes());
+        akhir.extend_from_slice(&ukuran_directory.to_le_bytes());
+        akhir.extend_from_slice(&awal_directory.to_le_bytes());
+        akhir.extend_from_slice(&0u16.to_le_bytes());
+        self.keluarkan(&akhir);
+
+        Ok(self.keluaran)
+    }
+}
@@ -0,0 +1,312 @@
+// File baru: src/ekspor/aliran.rs
+//
+// Ekspor dialirkan baris demi baris dari stream sqlx ke body respons chunked.
+// Produsen berjalan di task terpisah dan mengirim potongan lewat channel
+// berkapasitas kecil, sehingga query ikut tertahan saat klien lambat membaca
+// (backpressure). Jika klien memutus koneksi, body di-drop, pengiriman gagal,
+// dan task berhenti sehingga stream query dan koneksinya dilepas.
+//
+// Status akhir dikirim sebagai HTTP trailer `x-ekspor-status` (bagi klien yang
+// mengirim `TE: trailers`). Jika ekspor gagal di tengah jalan, CSV dan NDJSON
+// juga diakhiri baris penanda; arsip XLSX dibiarkan tanpa central directory
+// sehingga terbaca sebagai berkas rusak, bukan data yang tampak lengkap.
+
+use axum::body::Bytes;
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+use futures_util::TryStreamExt;
+use http_body::Frame;
+use serde_json::{Map, Value};
+use sqlx::types::Json as SqlJson;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use std::convert::Infallible;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+use crate::dokumen::xlsx::{awal_lembar, bagian_paket, baris_xml, Sel, AKHIR_LEMBAR};
+use crate::dokumen::zip_aliran::ZipAliran;
+use super::model::FormatEkspor;
+
+// Potongan dikirim setelah terkumpul sekitar 64 KiB; paling banyak 4 potongan
+// menunggu di antrian sebelum produsen ikut menunggu.
+const UKURAN_POTONGAN: usize = 64 * 1024;
+const KAPASITAS_ANTRIAN: usize = 4;
+
+pub const TRAILER_STATUS: &str = "x-ekspor-status";
+pub const TRAILER_JUMLAH_BARIS: &str = "x-ekspor-jumlah-baris";
+
+/// Keterangan siapa mengekspor apa dan kapan; dikirim sebagai header respons
+/// dan, untuk XLSX, sebagai lembar "Metadata".
+pub struct MetadataEkspor {
+    pub entitas: &'static str,
+    pub oleh: String,
+    pub pada: String,
+    pub filter: String,
+}
+
+impl MetadataEkspor {
+    pub fn header(&self) -> [(HeaderName, String); 4] {
+        [
+            (HeaderName::from_static("x-ekspor-entitas"), self.entitas.to_string()),
+            (HeaderName::from_static("x-ekspor-oleh"), self.oleh.clone()),
+            (HeaderName::from_static("x-ekspor-pada"), self.pada.clone()),
+            (HeaderName::from_static("x-ekspor-filter"), self.filter.clone()),
+        ]
+    }
+}
+
+// === BODY RESPONS ===
+
+pub struct BodyAliran {
+    rx: mpsc::Receiver<Frame<Bytes>>,
+}
+
+impl http_body::Body for BodyAliran {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        self.rx.poll_recv(cx).map(|frame| frame.map(Ok))
+    }
+}
+
+// Klien sudah tidak membaca body
+struct Terputus;
+
+struct Pengirim {
+    tx: mpsc::Sender<Frame<Bytes>>,
+    buffer: Vec<u8>,
+}
+
+impl Pengirim {
+    async fn tulis(&mut self, data: &[u8]) -> Result<(), Terputus> {
+        self.buffer.extend_from_slice(data);
+        if self.buffer.len() >= UKURAN_POTONGAN {
+            self.kirim().await?;
+        }
+        Ok(())
+    }
+
+    async fn kirim(&mut self) -> Result<(), Terputus> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let potongan = Bytes::from(std::mem::take(&mut self.buffer));
+        self.tx.send(Frame::data(potongan)).await.map_err(|_| Terputus)
+    }
+
+    async fn akhiri(mut self, berhasil: bool, jumlah_baris: u64) -> Result<(), Terputus> {
+        self.kirim().await?;
+        let mut trailer = HeaderMap::new();
+        trailer.insert(
+            HeaderName::from_static(TRAILER_STATUS),
+            HeaderValue::from_static(if berhasil { "selesai" } else { "gagal" }),
+        );
+        trailer.insert(HeaderName::from_static(TRAILER_JUMLAH_BARIS), HeaderValue::from(jumlah_baris));
+        self.tx.send(Frame::trailers(trailer)).await.map_err(|_| Terputus)
+    }
+}
+
+// === PENYANDI FORMAT ===
+
+fn teks_nilai(nilai: &Value) -> String {
+    match nilai {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        lain => lain.to_string(),
+    }
+}
+
+fn sel_nilai(nilai: &Value) -> Sel {
+    match nilai {
+        Value::Null => Sel::Kosong,
+        Value::Number(n) => n.as_f64().map(Sel::Angka).unwrap_or_else(|| Sel::Teks(n.to_string())),
+        lain => Sel::Teks(teks_nilai(lain)),
+    }
+}
+
+fn csv_record<I, T>(record: I) -> io::Result<Vec<u8>>
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    let mut wtr = csv::WriterBuilder::new().buffer_capacity(1024).from_writer(Vec::new());
+    wtr.write_record(record)?;
+    wtr.into_inner().map_err(|e| e.into_error())
+}
+
+enum Penyandi {
+    Csv,
+    Ndjson(Vec<String>),
+    Xlsx(ZipAliran),
+}
+
+impl Penyandi {
+    /// Penyandi beserta byte pembuka (header CSV, bagian paket dan kepala lembar XLSX)
+    fn mulai(format: FormatEkspor, kolom: &[String], metadata: &MetadataEkspor) -> io::Result<(Self, Vec<u8>)> {
+        match format {
+            FormatEkspor::Csv => Ok((Penyandi::Csv, csv_record(kolom)?)),
+            FormatEkspor::Ndjson => Ok((Penyandi::Ndjson(kolom.to_vec()), Vec::new())),
+            FormatEkspor::Xlsx => {
+                let mut zip = ZipAliran::new();
+                for (berkas, isi) in bagian_paket(&[metadata.entitas.to_string(), "Metadata".to_string()]) {
+                    zip.mulai_berkas(berkas)?;
+                    zip.tulis(isi.as_bytes())?;
+                }
+                zip.mulai_berkas("xl/worksheets/sheet1.xml")?;
+                zip.tulis(awal_lembar(&vec![18.0; kolom.len()]).as_bytes())?;
+                let kepala: Vec<Sel> = kolom.iter().map(|k| Sel::Tebal(k.clone())).collect();
+                zip.tulis(baris_xml(1, &kepala).as_bytes())?;
+                let awal = zip.ambil();
+                Ok((Penyandi::Xlsx(zip), awal))
+            }
+        }
+    }
+
+    /// `nomor` dimulai dari 1 untuk baris data pertama
+    fn baris(&mut self, nomor: u64, nilai: Vec<Value>) -> io::Result<Vec<u8>> {
+        match self {
+            Penyandi::Csv => csv_record(nilai.iter().map(teks_nilai)),
+            Penyandi::Ndjson(kolom) => {
+                let objek: Map<String, Value> = kolom.iter().cloned().zip(nilai).collect();
+                let mut isi = serde_json::to_vec(&Value::Object(objek))?;
+                isi.push(b'\n');
+                Ok(isi)
+            }
+            Penyandi::Xlsx(zip) => {
+                let sel: Vec<Sel> = nilai.iter().map(sel_nilai).collect();
+                zip.tulis(baris_xml(nomor as usize + 1, &sel).as_bytes())?;
+                Ok(zip.ambil())
+            }
+        }
+    }
+
+    fn akhir(self, jumlah_baris: u64, metadata: &MetadataEkspor) -> io::Result<Vec<u8>> {
+        match self {
+            Penyandi::Csv | Penyandi::Ndjson(_) => Ok(Vec::new()),
+            Penyandi::Xlsx(mut zip) => {
+                zip.tulis(AKHIR_LEMBAR.as_bytes())?;
+                zip.mulai_berkas("xl/worksheets/sheet2.xml")?;
+                zip.tulis(awal_lembar(&[18.0, 48.0]).as_bytes())?;
+                let pasangan = [
+                    ("Entitas", metadata.entitas.to_string()),
+                    ("Diekspor oleh", metadata.oleh.clone()),
+                    ("Diekspor pada", metadata.pada.clone()),
+                    ("Filter", metadata.filter.clone()),
+                    ("Jumlah baris", jumlah_baris.to_string()),
+                ];
+                for (i, (kunci, nilai)) in pasangan.into_iter().enumerate() {
+                    zip.tulis(baris_xml(i + 1, &[Sel::Tebal(kunci.to_string()), Sel::Teks(nilai)]).as_bytes())?;
+                }
+                zip.tulis(AKHIR_LEMBAR.as_bytes())?;
+                zip.tutup()
+            }
+        }
+    }
+
+    /// Baris penutup saat ekspor terhenti karena error
+    fn penanda_gagal(&self, jumlah_baris: u64) -> Vec<u8> {
+        let pesan = format!("EKSPOR GAGAL: terhenti setelah {} baris", jumlah_baris);
+        match self {
+            Penyandi::Csv => format!("# {}\n", pesan).into_bytes(),
+            Penyandi::Ndjson(_) => {
+                let mut isi = serde_json::json!({ "_ekspor_gagal": pesan }).to_string().into_bytes();
+                isi.push(b'\n');
+                isi
+            }
+            Penyandi::Xlsx(_) => Vec::new(),
+        }
+    }
+}
+
+// === PRODUSEN ===
+
+/// Menjalankan query di task terpisah dan mengembalikan body yang mengalirkan hasilnya.
+pub fn alirkan(
+    pool: PgPool,
+    qb: QueryBuilder<'static, Postgres>,
+    format: FormatEkspor,
+    kolom: Vec<String>,
+    metadata: MetadataEkspor,
+    user_id: i32,
+) -> BodyAliran {
+    let (tx, rx) = mpsc::channel(KAPASITAS_ANTRIAN);
+    tokio::spawn(async move {
+        let pengirim = Pengirim { tx, buffer: Vec::with_capacity(UKURAN_POTONGAN) };
+        match produksi(&pool, qb, format, &kolom, &metadata, pengirim).await {
+            Ok((true, jumlah)) => tracing::info!(
+                "Export {} ({} rows, {}) by user {} filter {}",
+                metadata.entitas,
+                jumlah,
+                format.ekstensi(),
+                user_id,
+                metadata.filter
+            ),
+            Ok((false, jumlah)) => {
+                tracing::error!("Export {} by user {} aborted after {} rows", metadata.entitas, user_id, jumlah)
+            }
+            Err(Terputus) => {
+                tracing::info!("Export {} by user {} cancelled: client disconnected", metadata.entitas, user_id)
+            }
+        }
+    });
+    BodyAliran { rx }
+}
+
+/// `Ok((berhasil, jumlah_baris))`, atau `Err(Terputus)` jika klien pergi.
+async fn produksi(
+    pool: &PgPool,
+    mut qb: QueryBuilder<'static, Postgres>,
+    format: FormatEkspor,
+    kolom: &[String],
+    metadata: &MetadataEkspor,
+    mut pengirim: Pengirim,
+) -> Result<(bool, u64), Terputus> {
+    let (mut penyandi, awal) = match Penyandi::mulai(format, kolom, metadata) {
+        Ok(hasil) => hasil,
+        Err(e) => {
+            tracing::error!("Failed to start export {}: {}", metadata.entitas, e);
+            pengirim.akhiri(false, 0).await?;
+            return Ok((false, 0));
+        }
+    };
+    pengirim.tulis(&awal).await?;
+
+    let mut jumlah: u64 = 0;
+    let galat = {
+        let mut baris = qb.build_query_scalar::<SqlJson<Vec<Value>>>().fetch(pool);
+        loop {
+            match baris.try_next().await {
+                Ok(Some(SqlJson(nilai))) => {
+                    jumlah += 1;
+                    match penyandi.baris(jumlah, nilai) {
+                        Ok(isi) => pengirim.tulis(&isi).await?,
+                        Err(e) => break Some(e.to_string()),
+                    }
+                }
+                Ok(None) => break None,
+                Err(e) => break Some(e.to_string()),
+            }
+        }
+    };
+
+    if let Some(galat) = galat {
+        tracing::error!("Export {} failed: {}", metadata.entitas, galat);
+        pengirim.tulis(&penyandi.penanda_gagal(jumlah)).await?;
+        pengirim.akhiri(false, jumlah).await?;
+        return Ok((false, jumlah));
+    }
+
+    match penyandi.akhir(jumlah, metadata) {
+        Ok(isi) => {
+            pengirim.tulis(&isi).await?;
+            pengirim.akhiri(true, jumlah).await?;
+            Ok((true, jumlah))
+        }
+        Err(e) => {
+            tracing::error!("Failed to finish export {}: {}", metadata.entitas, e);
+            pengirim.akhiri(false, jumlah).await?;
+            Ok((false, jumlah))
+        }
+    }
+}
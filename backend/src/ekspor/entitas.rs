@@ -0,0 +1,210 @@
+// File baru: src/ekspor/entitas.rs
+//
+// Daftar entitas yang bisa diekspor. Kolom dibaca dari information_schema agar
+// tabel yang bertambah kolom ikut terekspor tanpa perubahan kode; hanya kolom
+// rahasia yang dikecualikan. Nama kolom yang masuk ke teks query selalu berasal
+// dari katalog tersebut, bukan dari input pengguna.
+//
+// Nilai enum keluar sebagai label enum Postgres, yang sama dengan label serde
+// di types.rs (setiap varian memakai rename serde dan sqlx yang sama).
+
+use axum::http::StatusCode;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use crate::auth::model::AuthenticatedUser;
+use crate::klien::handlers_core::{terapkan_filter_klien, GetAllKlienParams};
+use crate::peta::handlers::{koordinat_kasar, DESIMAL_KOORDINAT_KASAR};
+use crate::types::UserRoleEnum;
+use super::model::EksporParams;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cakupan {
+    // Tabel klien itu sendiri
+    Klien,
+    // Tabel turunan dengan kolom klien_id; cakupan mengikuti klien induknya
+    TurunanKlien,
+    Pengguna,
+}
+
+#[derive(Debug)]
+pub struct EntitasEkspor {
+    pub nama: &'static str,
+    pub tabel: &'static str,
+    pub kolom_tanggal: &'static str,
+    // Ekspresi DATE (WIB) untuk filter dari/sampai
+    tanggal_sql: &'static str,
+    pub cakupan: Cakupan,
+}
+
+const fn entitas(
+    nama: &'static str,
+    tabel: &'static str,
+    kolom_tanggal: &'static str,
+    tanggal_sql: &'static str,
+    cakupan: Cakupan,
+) -> EntitasEkspor {
+    EntitasEkspor { nama, tabel, kolom_tanggal, tanggal_sql, cakupan }
+}
+
+const DIBUAT_WIB: &str = "(t.created_at AT TIME ZONE 'Asia/Jakarta')::DATE";
+
+pub const DAFTAR_ENTITAS: &[EntitasEkspor] = &[
+    entitas("klien", "klien", "created_at", DIBUAT_WIB, Cakupan::Klien),
+    entitas("penerimaan_dewasa", "penerimaan_dewasa", "tanggal_permintaan_lapas_dewasa", "t.tanggal_permintaan_lapas_dewasa", Cakupan::TurunanKlien),
+    entitas("penerimaan_anak", "penerimaan_anak", "tanggal_permintaan_lapas_anak", "t.tanggal_permintaan_lapas_anak", Cakupan::TurunanKlien),
+    entitas("riwayat_hukum_dewasa", "riwayat_hukum_dewasa", "tanggal_surat_keputusan_pengadilan_dewasa", "t.tanggal_surat_keputusan_pengadilan_dewasa", Cakupan::TurunanKlien),
+    entitas("riwayat_hukum_anak", "riwayat_hukum_anak", "tanggal_surat_keputusan_pengadilan_anak", "t.tanggal_surat_keputusan_pengadilan_anak", Cakupan::TurunanKlien),
+    entitas("layanan_integrasi_dewasa", "layanan_integrasi_dewasa", "tanggal_sk_integrasi_dewasa", "t.tanggal_sk_integrasi_dewasa", Cakupan::TurunanKlien),
+    entitas("layanan_integrasi_anak", "layanan_integrasi_anak", "tanggal_sk_integrasi_anak", "t.tanggal_sk_integrasi_anak", Cakupan::TurunanKlien),
+    entitas("proses_hukum_dewasa", "proses_hukum_dewasa", "tanggal_proses_dewasa", "t.tanggal_proses_dewasa", Cakupan::TurunanKlien),
+    entitas("proses_hukum_anak", "proses_hukum_anak", "tanggal_proses_anak", "t.tanggal_proses_anak", Cakupan::TurunanKlien),
+    entitas("remisi_dewasa", "remisi_dewasa", "tanggal_sk_remisi_dewasa", "t.tanggal_sk_remisi_dewasa", Cakupan::TurunanKlien),
+    // Laporan offline memakai waktu lapor di perangkat, bukan waktu sinkronisasi
+    entitas(
+        "wajib_lapor_dewasa",
+        "wajib_lapor_dewasa",
+        "waktu_lapor_klien",
+        "(COALESCE(t.waktu_lapor_klien, t.created_at) AT TIME ZONE 'Asia/Jakarta')::DATE",
+        Cakupan::TurunanKlien,
+    ),
+    entitas("wajib_lapor_anak", "wajib_lapor_anak", "created_at", DIBUAT_WIB, Cakupan::TurunanKlien),
+    entitas("users", "users", "created_at", DIBUAT_WIB, Cakupan::Pengguna),
+];
+
+// Hash kredensial tidak pernah ikut diekspor
+pub const KOLOM_RAHASIA: [&str; 3] = ["password_hash", "api_key_hash", "pin_klien_hash"];
+
+// Kolom posisi (latitude_dewasa, longitude_anak, ...) yang dibulatkan untuk
+// role tingkat wilayah, sama seperti di peta
+pub fn kolom_koordinat(kolom: &str) -> bool {
+    kolom.starts_with("latitude") || kolom.starts_with("longitude")
+}
+
+pub fn cari_entitas(nama: &str) -> Option<&'static EntitasEkspor> {
+    DAFTAR_ENTITAS.iter().find(|e| e.nama == nama)
+}
+
+impl EntitasEkspor {
+    /// Klien dan tabel turunannya; hanya entitas ini yang masuk change-feed
+    pub fn terkait_klien(&self) -> bool {
+        self.cakupan != Cakupan::Pengguna
+    }
+}
+
+/// Kolom tabel sesuai urutan definisi, tanpa kolom rahasia
+pub async fn daftar_kolom(pool: &PgPool, entitas: &EntitasEkspor) -> Result<Vec<String>, sqlx::Error> {
+    let kolom = sqlx::query_scalar!(
+        r#"
+        SELECT column_name AS "column_name!" FROM information_schema.columns
+        WHERE table_schema = 'public' AND table_name = $1
+        ORDER BY ordinal_position
+        "#,
+        entitas.tabel
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(kolom.into_iter().filter(|k| !KOLOM_RAHASIA.contains(&k.as_str())).collect())
+}
+
+/// Memilih kolom yang diminta (dipisah koma) dari kolom yang tersedia.
+/// Kolom yang tidak dikenal menghasilkan 400.
+pub fn pilih_kolom(tersedia: Vec<String>, diminta: Option<&str>) -> Result<Vec<String>, StatusCode> {
+    let Some(diminta) = diminta.filter(|d| !d.trim().is_empty()) else {
+        return Ok(tersedia);
+    };
+    let mut hasil: Vec<String> = Vec::new();
+    for nama in diminta.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let kolom = tersedia.iter().find(|k| k.as_str() == nama).ok_or(StatusCode::BAD_REQUEST)?;
+        if !hasil.contains(kolom) {
+            hasil.push(kolom.clone());
+        }
+    }
+    Ok(hasil)
+}
+
+/// Query yang mengembalikan satu kolom `baris` (JSONB array sesuai urutan `kolom`),
+/// dibatasi cakupan role pengguna dan filter tanggal.
+pub fn susun_query(
+    entitas: &EntitasEkspor,
+    kolom: &[String],
+    params: &EksporParams,
+    user: &AuthenticatedUser,
+) -> Result<QueryBuilder<'static, Postgres>, StatusCode> {
+    if let (Some(dari), Some(sampai)) = (params.dari, params.sampai) {
+        if dari > sampai {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let kasar = koordinat_kasar(user);
+    let ekspresi: Vec<String> = kolom
+        .iter()
+        .map(|k| {
+            if kasar && kolom_koordinat(k) {
+                format!("round(t.\"{}\"::NUMERIC, {})", k, DESIMAL_KOORDINAT_KASAR)
+            } else {
+                format!("t.\"{}\"", k)
+            }
+        })
+        .collect();
+    let mut qb: QueryBuilder<'static, Postgres> = QueryBuilder::new(format!(
+        "SELECT jsonb_build_array({}) AS baris FROM {} t",
+        ekspresi.join(", "),
+        entitas.tabel
+    ));
+
+    let filter_klien = GetAllKlienParams { pk_id: params.pk_id, bapas_id: params.bapas_id, kanwil_id: params.kanwil_id };
+    match entitas.cakupan {
+        Cakupan::Klien => {
+            qb.push(" WHERE t.deleted_at IS NULL");
+            terapkan_filter_klien(&mut qb, user, &filter_klien, "t.");
+        }
+        Cakupan::TurunanKlien => {
+            qb.push(" JOIN klien k ON k.id = t.klien_id WHERE t.deleted_at IS NULL AND k.deleted_at IS NULL");
+            terapkan_filter_klien(&mut qb, user, &filter_klien, "k.");
+        }
+        Cakupan::Pengguna => {
+            qb.push(" WHERE t.deleted_at IS NULL");
+            terapkan_filter_pengguna(&mut qb, user, params);
+        }
+    }
+
+    if let Some(dari) = params.dari {
+        qb.push(format!(" AND {} >= ", entitas.tanggal_sql)).push_bind(dari);
+    }
+    if let Some(sampai) = params.sampai {
+        qb.push(format!(" AND {} <= ", entitas.tanggal_sql)).push_bind(sampai);
+    }
+    qb.push(" ORDER BY t.id");
+    Ok(qb)
+}
+
+// Pegawai hanya dirinya sendiri; admin sebatas wilayahnya. Pegawai Bapas
+// dihitung masuk Kanwil tempat Bapas-nya berada.
+fn terapkan_filter_pengguna(qb: &mut QueryBuilder<'static, Postgres>, user: &AuthenticatedUser, params: &EksporParams) {
+    match user.role {
+        UserRoleEnum::SuperAdmin => {}
+        UserRoleEnum::AdminKanwil => {
+            qb.push(" AND (t.kanwil_id = ")
+                .push_bind(user.kanwil_id)
+                .push(" OR t.bapas_id IN (SELECT id FROM bapas WHERE kanwil_id = ")
+                .push_bind(user.kanwil_id)
+                .push("))");
+        }
+        UserRoleEnum::AdminBapas => {
+            qb.push(" AND t.bapas_id = ").push_bind(user.bapas_id);
+        }
+        UserRoleEnum::Pegawai => {
+            qb.push(" AND t.id = ").push_bind(user.id);
+        }
+    }
+    if let Some(kanwil_id) = params.kanwil_id {
+        qb.push(" AND (t.kanwil_id = ")
+            .push_bind(kanwil_id)
+            .push(" OR t.bapas_id IN (SELECT id FROM bapas WHERE kanwil_id = ")
+            .push_bind(kanwil_id)
+            .push("))");
+    }
+    if let Some(bapas_id) = params.bapas_id {
+        qb.push(" AND t.bapas_id = ").push_bind(bapas_id);
+    }
+}
@@ -0,0 +1,136 @@
+// File baru: src/ekspor/handlers.rs
+
+use axum::{
+    body::Body,
+    extract::{Extension, Path, Query},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{SecondsFormat, Utc};
+use sqlx::PgPool;
+use crate::auth::model::AuthenticatedUser;
+use super::aliran::{alirkan, MetadataEkspor, TRAILER_JUMLAH_BARIS, TRAILER_STATUS};
+use super::entitas::{cari_entitas, daftar_kolom, pilih_kolom, susun_query, DAFTAR_ENTITAS};
+use super::model::{EksporParams, FormatEkspor, HalamanPerubahan, InfoEntitas, PerubahanParams};
+use super::perubahan::{ambil_perubahan, dekode_kursor};
+
+fn ringkas_filter(params: &EksporParams) -> String {
+    let mut bagian = Vec::new();
+    if let Some(dari) = params.dari {
+        bagian.push(format!("dari={}", dari));
+    }
+    if let Some(sampai) = params.sampai {
+        bagian.push(format!("sampai={}", sampai));
+    }
+    if let Some(pk_id) = params.pk_id {
+        bagian.push(format!("pk_id={}", pk_id));
+    }
+    if let Some(bapas_id) = params.bapas_id {
+        bagian.push(format!("bapas_id={}", bapas_id));
+    }
+    if let Some(kanwil_id) = params.kanwil_id {
+        bagian.push(format!("kanwil_id={}", kanwil_id));
+    }
+    if bagian.is_empty() {
+        "-".to_string()
+    } else {
+        bagian.join("; ")
+    }
+}
+
+// --- KATALOG ---
+// URL: GET /api/export
+#[axum::debug_handler]
+pub async fn get_katalog_ekspor(
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<Vec<InfoEntitas>>, StatusCode> {
+    let mut katalog = Vec::with_capacity(DAFTAR_ENTITAS.len());
+    for entitas in DAFTAR_ENTITAS {
+        let kolom = daftar_kolom(&pool, entitas).await.map_err(|e| {
+            tracing::error!("Failed to read export columns: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        katalog.push(InfoEntitas { entitas: entitas.nama, kolom_tanggal: entitas.kolom_tanggal, kolom });
+    }
+    Ok(Json(katalog))
+}
+
+// --- EKSPOR ---
+// URL: GET /api/export/:entitas[.csv|.xlsx|.ndjson]?format=&kolom=a,b&dari=&sampai=&pk_id=&bapas_id=&kanwil_id=
+// Akhiran path mengalahkan parameter `format`; tanpa keduanya hasilnya CSV.
+// Hasil dialirkan; kesalahan validasi tetap dijawab dengan status sebelum body dimulai.
+#[axum::debug_handler]
+pub async fn ekspor_entitas(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(nama): Path<String>,
+    Query(params): Query<EksporParams>,
+) -> Result<Response, StatusCode> {
+    let (nama_entitas, format) = match nama.rsplit_once('.') {
+        Some((nama, ekstensi)) => (nama, FormatEkspor::dari_ekstensi(ekstensi).ok_or(StatusCode::NOT_FOUND)?),
+        None => (nama.as_str(), params.format.unwrap_or_default()),
+    };
+    let entitas = cari_entitas(nama_entitas).ok_or(StatusCode::NOT_FOUND)?;
+
+    let tersedia = daftar_kolom(&pool, entitas).await.map_err(|e| {
+        tracing::error!("Failed to read export columns: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let kolom = pilih_kolom(tersedia, params.kolom.as_deref())?;
+    let qb = susun_query(entitas, &kolom, &params, &user)?;
+
+    let nip = sqlx::query_scalar!("SELECT nip_user FROM users WHERE id = $1", user.id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .unwrap_or_default();
+    let sekarang = Utc::now();
+    let metadata = MetadataEkspor {
+        entitas: entitas.nama,
+        oleh: format!("{} (id {})", nip, user.id),
+        pada: sekarang.to_rfc3339_opts(SecondsFormat::Secs, true),
+        filter: ringkas_filter(&params),
+    };
+    let header_metadata = metadata.header();
+    let trailer = format!("{}, {}", TRAILER_STATUS, TRAILER_JUMLAH_BARIS);
+    let body = alirkan(pool, qb, format, kolom, metadata, user.id);
+
+    let headers = [
+        (header::CONTENT_TYPE, format.content_type().to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"{}_{}.{}\"",
+                entitas.nama,
+                sekarang.format("%Y%m%d%H%M%S"),
+                format.ekstensi()
+            ),
+        ),
+        (header::TRAILER, trailer),
+    ];
+    Ok((headers, header_metadata, Body::new(body)).into_response())
+}
+
+// --- CHANGE FEED ---
+// URL: GET /api/export/perubahan?kursor=&batas=
+// Tanpa kursor feed dimulai dari awal log; kursor_berikutnya dipakai untuk permintaan
+// selanjutnya, juga ketika halaman kosong.
+#[axum::debug_handler]
+pub async fn get_perubahan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(params): Query<PerubahanParams>,
+) -> Result<Json<HalamanPerubahan>, StatusCode> {
+    let kursor = match params.kursor.as_deref().filter(|k| !k.is_empty()) {
+        Some(kursor) => dekode_kursor(kursor).ok_or(StatusCode::BAD_REQUEST)?,
+        None => (0, 0),
+    };
+    let batas = params.batas.unwrap_or(500).clamp(1, 5000);
+
+    let halaman = ambil_perubahan(&pool, &user, kursor, batas).await.map_err(|e| {
+        tracing::error!("Failed to fetch change feed: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(halaman))
+}
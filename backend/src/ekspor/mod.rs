@@ -0,0 +1,5 @@
+pub mod model;
+pub mod entitas;
+pub mod aliran;
+pub mod perubahan;
+pub mod handlers;
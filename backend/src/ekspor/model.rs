@@ -0,0 +1,89 @@
+// File baru: src/ekspor/model.rs
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FormatEkspor {
+    #[default]
+    Csv,
+    Xlsx,
+    Ndjson,
+}
+
+impl FormatEkspor {
+    /// Format dari akhiran path, mis. "klien.xlsx"
+    pub fn dari_ekstensi(ekstensi: &str) -> Option<Self> {
+        match ekstensi {
+            "csv" => Some(FormatEkspor::Csv),
+            "xlsx" => Some(FormatEkspor::Xlsx),
+            "ndjson" => Some(FormatEkspor::Ndjson),
+            _ => None,
+        }
+    }
+
+    pub fn ekstensi(self) -> &'static str {
+        match self {
+            FormatEkspor::Csv => "csv",
+            FormatEkspor::Xlsx => "xlsx",
+            FormatEkspor::Ndjson => "ndjson",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            FormatEkspor::Csv => "text/csv; charset=utf-8",
+            FormatEkspor::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            FormatEkspor::Ndjson => "application/x-ndjson",
+        }
+    }
+}
+
+// Query string ekspor. `kolom` dipisah koma; kosong = semua kolom.
+// `dari`/`sampai` (inklusif, WIB) berlaku pada kolom tanggal utama entitas.
+#[derive(Debug, Deserialize)]
+pub struct EksporParams {
+    pub format: Option<FormatEkspor>,
+    pub kolom: Option<String>,
+    pub dari: Option<NaiveDate>,
+    pub sampai: Option<NaiveDate>,
+    pub pk_id: Option<i32>,
+    pub bapas_id: Option<i32>,
+    pub kanwil_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InfoEntitas {
+    pub entitas: &'static str,
+    pub kolom_tanggal: &'static str,
+    pub kolom: Vec<String>,
+}
+
+// Query string change-feed. Tanpa `kursor`, feed dimulai dari awal log.
+#[derive(Debug, Deserialize)]
+pub struct PerubahanParams {
+    pub kursor: Option<String>,
+    pub batas: Option<i64>,
+}
+
+// Satu entri feed. `data` adalah keadaan baris saat feed dibaca (bukan saat
+// perubahan terjadi) dan kosong untuk tombstone ('delete'). Konsumen sebaiknya
+// memperlakukan 'insert' dan 'update' sebagai upsert.
+#[derive(Debug, Serialize)]
+pub struct Perubahan {
+    pub tabel: String,
+    pub id: i64,
+    pub klien_id: i32,
+    pub operasi: String,
+    pub dicatat_at: DateTime<Utc>,
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HalamanPerubahan {
+    pub perubahan: Vec<Perubahan>,
+    pub kursor_berikutnya: String,
+    pub ada_lagi: bool,
+}
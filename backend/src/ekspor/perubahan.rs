@@ -0,0 +1,157 @@
+// File baru: src/ekspor/perubahan.rs
+//
+// Change-feed dari tabel log_perubahan (diisi trigger, lihat migrasi
+// log_perubahan). Kursor adalah posisi (xid, id) entri terakhir yang sudah
+// dikirim, dikodekan sebagai heksadesimal dan diperlakukan opak oleh konsumen.
+//
+// Entri hanya dibaca dari transaksi yang lebih tua dari xmin snapshot, jadi
+// transaksi yang masih berjalan menahan feed sampai selesai, alih-alih
+// terlewat. Cakupan role memakai cakupan klien saat perubahan dicatat.
+//
+// `data` berisi keadaan baris saat feed dibaca dan hanya diisi jika baris itu
+// masih aktif dan masih dalam cakupan pengguna. Selain itu `data` null; entri
+// delete untuk baris tersebut (penghapusan atau perpindahan klien) menyusul
+// di feed.
+
+use serde_json::Value;
+use sqlx::types::Json as SqlJson;
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
+use std::collections::BTreeMap;
+use chrono::{DateTime, Utc};
+use crate::auth::model::AuthenticatedUser;
+use crate::klien::handlers_core::{terapkan_filter_klien, GetAllKlienParams};
+use crate::peta::handlers::{bulatkan_koordinat, koordinat_kasar};
+use super::entitas::{kolom_koordinat, Cakupan, DAFTAR_ENTITAS, KOLOM_RAHASIA};
+use super::model::{HalamanPerubahan, Perubahan};
+
+#[derive(Debug, FromRow)]
+struct EntriLog {
+    id: i64,
+    xid: i64,
+    tabel: String,
+    baris_id: i64,
+    klien_id: i32,
+    operasi: String,
+    dicatat_at: DateTime<Utc>,
+}
+
+pub fn enkode_kursor(xid: i64, id: i64) -> String {
+    format!("{:016x}{:016x}", xid, id)
+}
+
+pub fn dekode_kursor(kursor: &str) -> Option<(i64, i64)> {
+    if kursor.len() != 32 || !kursor.is_ascii() {
+        return None;
+    }
+    let (xid, id) = kursor.split_at(16);
+    Some((i64::from_str_radix(xid, 16).ok()?, i64::from_str_radix(id, 16).ok()?))
+}
+
+// Presisi koordinat mengikuti role, sama seperti ekspor dan peta
+fn kaburkan_koordinat(isi: &mut Value) {
+    let Some(objek) = isi.as_object_mut() else {
+        return;
+    };
+    for (kolom, nilai) in objek.iter_mut() {
+        if let Some(angka) = nilai.as_f64().filter(|_| kolom_koordinat(kolom)) {
+            *nilai = serde_json::json!(bulatkan_koordinat(angka));
+        }
+    }
+}
+
+/// Keadaan baris saat ini (tanpa kolom rahasia) untuk entri insert/update,
+/// dibatasi cakupan pengguna saat ini
+async fn ambil_data(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    entri: &[EntriLog],
+) -> Result<BTreeMap<(String, i64), Value>, sqlx::Error> {
+    let mut per_tabel: BTreeMap<&str, Vec<i64>> = BTreeMap::new();
+    for e in entri.iter().filter(|e| e.operasi != "delete") {
+        per_tabel.entry(e.tabel.as_str()).or_default().push(e.baris_id);
+    }
+
+    let rahasia: Vec<String> = KOLOM_RAHASIA.iter().map(|k| k.to_string()).collect();
+    let params = GetAllKlienParams { pk_id: None, bapas_id: None, kanwil_id: None };
+    let kasar = koordinat_kasar(user);
+    let mut data = BTreeMap::new();
+    for (tabel, id) in per_tabel {
+        // Nama tabel di teks query hanya dari daftar entitas, bukan dari isi log
+        let Some(entitas) = DAFTAR_ENTITAS.iter().find(|e| e.tabel == tabel && e.terkait_klien()) else {
+            continue;
+        };
+        let mut qb: QueryBuilder<Postgres> = QueryBuilder::new("SELECT t.id::BIGINT, to_jsonb(t) - ");
+        qb.push_bind(rahasia.clone()).push(format!("::TEXT[] FROM {} t", entitas.tabel));
+        if entitas.cakupan == Cakupan::Klien {
+            qb.push(" WHERE t.deleted_at IS NULL AND t.id = ANY(").push_bind(id).push(")");
+            terapkan_filter_klien(&mut qb, user, &params, "t.");
+        } else {
+            qb.push(" JOIN klien k ON k.id = t.klien_id WHERE t.deleted_at IS NULL AND k.deleted_at IS NULL AND t.id = ANY(")
+                .push_bind(id)
+                .push(")");
+            terapkan_filter_klien(&mut qb, user, &params, "k.");
+        }
+        let baris: Vec<(i64, SqlJson<Value>)> = qb.build_query_as().fetch_all(pool).await?;
+        for (baris_id, SqlJson(mut isi)) in baris {
+            if kasar {
+                kaburkan_koordinat(&mut isi);
+            }
+            data.insert((entitas.tabel.to_string(), baris_id), isi);
+        }
+    }
+    Ok(data)
+}
+
+pub async fn ambil_perubahan(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    kursor: (i64, i64),
+    batas: i64,
+) -> Result<HalamanPerubahan, sqlx::Error> {
+    let mut qb: QueryBuilder<Postgres> = QueryBuilder::new(
+        "SELECT l.id, l.xid, l.tabel, l.baris_id, l.klien_id, l.operasi, l.dicatat_at FROM log_perubahan l WHERE (l.xid, l.id) > (",
+    );
+    // Log juga memuat tabel khusus sinkronisasi lapangan yang bukan entitas ekspor
+    let tabel: Vec<String> = DAFTAR_ENTITAS
+        .iter()
+        .filter(|e| e.terkait_klien())
+        .map(|e| e.tabel.to_string())
+        .collect();
+    qb.push_bind(kursor.0)
+        .push(", ")
+        .push_bind(kursor.1)
+        .push(") AND l.xid < pg_snapshot_xmin(pg_current_snapshot())::TEXT::BIGINT AND l.tabel = ANY(")
+        .push_bind(tabel)
+        .push(")");
+    let params = GetAllKlienParams { pk_id: None, bapas_id: None, kanwil_id: None };
+    terapkan_filter_klien(&mut qb, user, &params, "l.");
+    // Satu entri ekstra untuk mengetahui apakah masih ada halaman berikutnya
+    qb.push(" ORDER BY l.xid, l.id LIMIT ").push_bind(batas + 1);
+
+    let mut entri: Vec<EntriLog> = qb.build_query_as().fetch_all(pool).await?;
+    let ada_lagi = entri.len() as i64 > batas;
+    entri.truncate(batas as usize);
+
+    let kursor_berikutnya = entri
+        .last()
+        .map(|e| enkode_kursor(e.xid, e.id))
+        .unwrap_or_else(|| enkode_kursor(kursor.0, kursor.1));
+
+    let data = ambil_data(pool, user, &entri).await?;
+    let perubahan = entri
+        .into_iter()
+        .map(|e| {
+            let isi = if e.operasi == "delete" { None } else { data.get(&(e.tabel.clone(), e.baris_id)).cloned() };
+            Perubahan {
+                tabel: e.tabel,
+                id: e.baris_id,
+                klien_id: e.klien_id,
+                operasi: e.operasi,
+                dicatat_at: e.dicatat_at,
+                data: isi,
+            }
+        })
+        .collect();
+
+    Ok(HalamanPerubahan { perubahan, kursor_berikutnya, ada_lagi })
+}
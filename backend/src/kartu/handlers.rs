@@ -0,0 +1,201 @@
+// File baru: src/kartu/handlers.rs
+
+use axum::{
+    extract::{Extension, Path},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use qrcode::{Color, QrCode};
+use sqlx::PgPool;
+use crate::auth::authorization::ensure_klien_access;
+use crate::auth::model::AuthenticatedUser;
+use crate::dokumen::merge::tanggal_indonesia;
+use crate::dokumen::model::Baris;
+use crate::dokumen::pdf;
+use super::model::{CabutKartuKlien, KartuKlien};
+use super::token;
+
+// Ukuran satu modul QR di PDF (pt); kartu versi kecil tetap mudah dipindai
+const UKURAN_MODUL_QR: f32 = 4.0;
+
+async fn get_kartu(pool: &PgPool, id: i32) -> Result<KartuKlien, StatusCode> {
+    sqlx::query_as!(
+        KartuKlien,
+        r#"
+        SELECT id, klien_id, bapas_id, diterbitkan_at, created_by, dicabut_at, dicabut_by, alasan_pencabutan
+        FROM kartu_klien WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)
+}
+
+// --- TERBITKAN KARTU ---
+// URL: POST /api/klien/:klien_id/kartu-klien
+// Kartu aktif sebelumnya otomatis dicabut.
+#[axum::debug_handler]
+pub async fn create_kartu_klien(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(klien_id): Path<i32>, // Diambil dari URL, sudah diautorisasi oleh middleware
+) -> Result<(StatusCode, Json<KartuKlien>), StatusCode> {
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    sqlx::query!(
+        r#"
+        UPDATE kartu_klien
+        SET dicabut_at = NOW(), dicabut_by = $1, alasan_pencabutan = 'Diganti kartu baru'
+        WHERE klien_id = $2 AND dicabut_at IS NULL
+        "#,
+        user.id,
+        klien_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let kartu = sqlx::query_as!(
+        KartuKlien,
+        r#"
+        INSERT INTO kartu_klien (klien_id, bapas_id, created_by)
+        SELECT id, bapas_id, $2 FROM klien WHERE id = $1 AND deleted_at IS NULL
+        RETURNING id, klien_id, bapas_id, diterbitkan_at, created_by, dicabut_at, dicabut_by, alasan_pencabutan
+        "#,
+        klien_id,
+        user.id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create kartu klien: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok((StatusCode::CREATED, Json(kartu)))
+}
+
+// --- READ ALL FOR A KLIEN ---
+// URL: GET /api/klien/:klien_id/kartu-klien
+#[axum::debug_handler]
+pub async fn get_all_kartu_for_klien(
+    Extension(pool): Extension<PgPool>,
+    Path(klien_id): Path<i32>,
+) -> Result<Json<Vec<KartuKlien>>, StatusCode> {
+    let list = sqlx::query_as!(
+        KartuKlien,
+        r#"
+        SELECT id, klien_id, bapas_id, diterbitkan_at, created_by, dicabut_at, dicabut_by, alasan_pencabutan
+        FROM kartu_klien WHERE klien_id = $1 ORDER BY diterbitkan_at DESC
+        "#,
+        klien_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch kartu klien: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// --- CETAK KARTU (PDF) ---
+// URL: GET /api/kartu-klien/:id/cetak
+#[axum::debug_handler]
+pub async fn cetak_kartu_klien(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> Result<Response, StatusCode> {
+    let kartu = get_kartu(&pool, id).await?;
+    ensure_klien_access(&pool, &user, kartu.klien_id).await?;
+    if kartu.dicabut_at.is_some() {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let data = sqlx::query!(
+        r#"
+        SELECT k.nama_klien, k.tanggal_lahir_klien, b.nama_bapas
+        FROM klien k JOIN bapas b ON b.id = $2
+        WHERE k.id = $1
+        "#,
+        kartu.klien_id,
+        kartu.bapas_id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let token = token::terbitkan(&kartu)?;
+    let qr = QrCode::new(token.as_bytes()).map_err(|e| {
+        tracing::error!("Failed to encode kartu klien QR: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let modul: Vec<Vec<bool>> = qr
+        .to_colors()
+        .chunks(qr.width())
+        .map(|baris| baris.iter().map(|&c| c == Color::Dark).collect())
+        .collect();
+
+    let baris = vec![
+        Baris::Judul(data.nama_bapas),
+        Baris::Judul("KARTU WAJIB LAPOR KLIEN".to_string()),
+        Baris::Kosong,
+        Baris::Paragraf(format!("Nama: {}", data.nama_klien)),
+        Baris::Paragraf(format!(
+            "Tanggal lahir: {}",
+            data.tanggal_lahir_klien.map(tanggal_indonesia).unwrap_or_else(|| "-".to_string())
+        )),
+        Baris::Paragraf(format!("Nomor kartu: {}", kartu.id)),
+        Baris::Paragraf(format!("Diterbitkan: {}", tanggal_indonesia(kartu.diterbitkan_at.date_naive()))),
+        Baris::Paragraf("Tunjukkan kode QR ini pada kiosk saat wajib lapor.".to_string()),
+    ];
+    let isi = pdf::render_dengan_qr(&baris, &modul, UKURAN_MODUL_QR);
+
+    let headers = [
+        (header::CONTENT_TYPE, "application/pdf".to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"kartu_klien_{}.pdf\"", kartu.id),
+        ),
+    ];
+    Ok((headers, isi).into_response())
+}
+
+// --- CABUT KARTU ---
+// URL: POST /api/kartu-klien/:id/cabut
+#[axum::debug_handler]
+pub async fn cabut_kartu_klien(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<CabutKartuKlien>,
+) -> Result<Json<KartuKlien>, StatusCode> {
+    let kartu = get_kartu(&pool, id).await?;
+    ensure_klien_access(&pool, &user, kartu.klien_id).await?;
+
+    let kartu = sqlx::query_as!(
+        KartuKlien,
+        r#"
+        UPDATE kartu_klien
+        SET dicabut_at = NOW(), dicabut_by = $1, alasan_pencabutan = $2
+        WHERE id = $3 AND dicabut_at IS NULL
+        RETURNING id, klien_id, bapas_id, diterbitkan_at, created_by, dicabut_at, dicabut_by, alasan_pencabutan
+        "#,
+        user.id,
+        payload.alasan_pencabutan,
+        id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::CONFLICT)?; // Sudah dicabut sebelumnya
+
+    Ok(Json(kartu))
+}
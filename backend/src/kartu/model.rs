@@ -0,0 +1,41 @@
+// File baru: src/kartu/model.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+use crate::klien::model_dewasa::CreateWajibLapor;
+
+// Merepresentasikan satu baris dari tabel 'kartu_klien'
+#[derive(Debug, Serialize, FromRow)]
+pub struct KartuKlien {
+    pub id: i32,
+    pub klien_id: i32,
+    pub bapas_id: i32,
+    pub diterbitkan_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub dicabut_at: Option<DateTime<Utc>>,
+    pub dicabut_by: Option<i32>,
+    pub alasan_pencabutan: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CabutKartuKlien {
+    pub alasan_pencabutan: Option<String>,
+}
+
+// Isi token di dalam QR. Tidak ada `exp`: masa berlaku dikendalikan lewat pencabutan.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KlaimKartu {
+    pub kartu_id: i32,
+    pub klien_id: i32,
+    pub bapas_id: i32,
+    pub iat: i64,
+}
+
+// Body POST /api/kiosk/wajib-lapor-dewasa: token hasil pindai QR + data lapor
+#[derive(Debug, Deserialize)]
+pub struct KioskWajibLapor {
+    pub token_kartu: String,
+    #[serde(flatten)]
+    pub lapor: CreateWajibLapor,
+}
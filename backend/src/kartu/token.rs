@@ -0,0 +1,68 @@
+// File baru: src/kartu/token.rs
+//
+// Token kartu klien: JWT HS256 dengan kunci terpisah dari token login
+// (KARTU_KLIEN_SECRET), sehingga token login tidak bisa dipakai sebagai kartu
+// dan sebaliknya. Token dapat dibentuk ulang dari baris `kartu_klien`, jadi
+// tidak perlu disimpan.
+
+use axum::http::StatusCode;
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use sqlx::PgPool;
+use std::env;
+use super::model::{KartuKlien, KlaimKartu};
+
+fn kunci() -> Result<Vec<u8>, StatusCode> {
+    env::var("KARTU_KLIEN_SECRET").map(String::into_bytes).map_err(|_| {
+        tracing::error!("KARTU_KLIEN_SECRET must be set");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+pub fn terbitkan(kartu: &KartuKlien) -> Result<String, StatusCode> {
+    let klaim = KlaimKartu {
+        kartu_id: kartu.id,
+        klien_id: kartu.klien_id,
+        bapas_id: kartu.bapas_id,
+        iat: kartu.diterbitkan_at.timestamp(),
+    };
+    encode(&Header::new(Algorithm::HS256), &klaim, &EncodingKey::from_secret(&kunci()?))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn baca(token: &str) -> Result<KlaimKartu, StatusCode> {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.validate_exp = false;
+    validation.required_spec_claims.clear();
+    decode::<KlaimKartu>(token, &DecodingKey::from_secret(&kunci()?), &validation)
+        .map(|data| data.claims)
+        .map_err(|_| StatusCode::UNAUTHORIZED)
+}
+
+/// Memeriksa token hasil pindai kiosk dan mengembalikan klien_id.
+/// UNAUTHORIZED untuk tanda tangan salah atau kartu dicabut/diganti;
+/// FORBIDDEN jika kartu (atau klien saat ini) milik Bapas lain dari kiosk.
+pub async fn verifikasi(pool: &PgPool, bapas_kiosk: i32, token: &str) -> Result<i32, StatusCode> {
+    let klaim = baca(token)?;
+
+    let kartu = sqlx::query!(
+        r#"
+        SELECT kk.klien_id, kk.bapas_id, kk.dicabut_at, k.bapas_id AS bapas_klien
+        FROM kartu_klien kk
+        JOIN klien k ON k.id = kk.klien_id AND k.deleted_at IS NULL
+        WHERE kk.id = $1
+        "#,
+        klaim.kartu_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    if kartu.dicabut_at.is_some() || kartu.klien_id != klaim.klien_id || kartu.bapas_id != klaim.bapas_id {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    if bapas_kiosk != kartu.bapas_id || kartu.bapas_klien != kartu.bapas_id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(kartu.klien_id)
+}
@@ -0,0 +1,304 @@
+// File baru: src/kiosk/handlers.rs
+
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use sha256::digest;
+use sqlx::PgPool;
+use crate::auth::authorization::{check_permission, get_bapas_ownership};
+use crate::auth::model::{AuthenticatedKiosk, AuthenticatedUser};
+use crate::penomoran::service::hari_ini;
+use crate::types::UserRoleEnum;
+use super::model::{
+    CreatePerangkatKiosk, HeartbeatKiosk, KesehatanPerangkatKiosk, PerangkatKiosk, PerangkatKioskDenganKunci,
+    StatusPerangkatKiosk, UpdatePerangkatKiosk,
+};
+
+// Perangkat dianggap offline jika tidak menghubungi server selama ini
+const BATAS_ONLINE_MENIT: i64 = 10;
+
+fn buat_api_key() -> String {
+    let acak: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(40)
+        .map(char::from)
+        .collect();
+    format!("kk_{}", acak) // Prefix berbeda dari API key user (ak_)
+}
+
+fn status_perangkat(aktif: bool, terakhir_terlihat_at: Option<DateTime<Utc>>) -> StatusPerangkatKiosk {
+    match terakhir_terlihat_at {
+        _ if !aktif => StatusPerangkatKiosk::Nonaktif,
+        None => StatusPerangkatKiosk::BelumTerhubung,
+        Some(t) if Utc::now() - t <= Duration::minutes(BATAS_ONLINE_MENIT) => StatusPerangkatKiosk::Online,
+        Some(_) => StatusPerangkatKiosk::Offline,
+    }
+}
+
+// Hanya admin (bukan Pegawai) dengan wewenang atas Bapas tersebut
+async fn ensure_admin_bapas(pool: &PgPool, user: &AuthenticatedUser, bapas_id: i32) -> Result<(), StatusCode> {
+    let ownership = get_bapas_ownership(pool, bapas_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if user.role == UserRoleEnum::Pegawai || !check_permission(user, &ownership) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(())
+}
+
+async fn get_perangkat(pool: &PgPool, id: i32) -> Result<PerangkatKiosk, StatusCode> {
+    sqlx::query_as!(
+        PerangkatKiosk,
+        r#"
+        SELECT id, bapas_id, nama_perangkat, lokasi, aktif, terakhir_terlihat_at, versi_aplikasi,
+            created_at, updated_at, created_by, updated_by
+        FROM perangkat_kiosk WHERE id = $1 AND deleted_at IS NULL
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)
+}
+
+// === ADMIN: REGISTRI PERANGKAT ===
+
+// --- CREATE ---
+// URL: POST /api/bapas/:bapas_id/perangkat-kiosk
+#[axum::debug_handler]
+pub async fn create_perangkat_kiosk(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(bapas_id): Path<i32>,
+    Json(payload): Json<CreatePerangkatKiosk>,
+) -> Result<(StatusCode, Json<PerangkatKioskDenganKunci>), StatusCode> {
+    ensure_admin_bapas(&pool, &user, bapas_id).await?;
+
+    let api_key = buat_api_key();
+    let perangkat = sqlx::query_as!(
+        PerangkatKiosk,
+        r#"
+        INSERT INTO perangkat_kiosk (bapas_id, nama_perangkat, lokasi, api_key_hash, created_by, updated_by)
+        VALUES ($1, $2, $3, $4, $5, $5)
+        RETURNING id, bapas_id, nama_perangkat, lokasi, aktif, terakhir_terlihat_at, versi_aplikasi,
+            created_at, updated_at, created_by, updated_by
+        "#,
+        bapas_id,
+        payload.nama_perangkat,
+        payload.lokasi,
+        digest(api_key.clone()),
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create perangkat kiosk: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((StatusCode::CREATED, Json(PerangkatKioskDenganKunci { perangkat, api_key })))
+}
+
+// --- READ ALL + KESEHATAN ---
+// URL: GET /api/bapas/:bapas_id/perangkat-kiosk
+#[axum::debug_handler]
+pub async fn get_all_perangkat_kiosk(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(bapas_id): Path<i32>,
+) -> Result<Json<Vec<KesehatanPerangkatKiosk>>, StatusCode> {
+    ensure_admin_bapas(&pool, &user, bapas_id).await?;
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT p.id, p.bapas_id, p.nama_perangkat, p.lokasi, p.aktif, p.terakhir_terlihat_at,
+            p.versi_aplikasi, p.created_at, p.updated_at, p.created_by, p.updated_by,
+            COUNT(w.id) FILTER (
+                WHERE (w.created_at AT TIME ZONE 'Asia/Jakarta')::DATE = $2
+            ) AS "jumlah_lapor_hari_ini!",
+            MAX(w.created_at) AS lapor_terakhir_at
+        FROM perangkat_kiosk p
+        LEFT JOIN wajib_lapor_dewasa w ON w.perangkat_kiosk_id = p.id AND w.deleted_at IS NULL
+        WHERE p.bapas_id = $1 AND p.deleted_at IS NULL
+        GROUP BY p.id
+        ORDER BY p.nama_perangkat
+        "#,
+        bapas_id,
+        hari_ini()
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch perangkat kiosk: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let list = rows
+        .into_iter()
+        .map(|r| KesehatanPerangkatKiosk {
+            status: status_perangkat(r.aktif, r.terakhir_terlihat_at),
+            jumlah_lapor_hari_ini: r.jumlah_lapor_hari_ini,
+            lapor_terakhir_at: r.lapor_terakhir_at,
+            perangkat: PerangkatKiosk {
+                id: r.id,
+                bapas_id: r.bapas_id,
+                nama_perangkat: r.nama_perangkat,
+                lokasi: r.lokasi,
+                aktif: r.aktif,
+                terakhir_terlihat_at: r.terakhir_terlihat_at,
+                versi_aplikasi: r.versi_aplikasi,
+                created_at: r.created_at,
+                updated_at: r.updated_at,
+                created_by: r.created_by,
+                updated_by: r.updated_by,
+            },
+        })
+        .collect();
+
+    Ok(Json(list))
+}
+
+// --- UPDATE (nama, lokasi, aktif/nonaktif) ---
+// URL: PUT /api/perangkat-kiosk/:id
+#[axum::debug_handler]
+pub async fn update_perangkat_kiosk(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdatePerangkatKiosk>,
+) -> Result<Json<PerangkatKiosk>, StatusCode> {
+    let existing = get_perangkat(&pool, id).await?;
+    ensure_admin_bapas(&pool, &user, existing.bapas_id).await?;
+
+    let perangkat = sqlx::query_as!(
+        PerangkatKiosk,
+        r#"
+        UPDATE perangkat_kiosk SET
+            nama_perangkat = COALESCE($1, nama_perangkat),
+            lokasi = COALESCE($2, lokasi),
+            aktif = COALESCE($3, aktif),
+            updated_by = $4
+        WHERE id = $5 AND deleted_at IS NULL
+        RETURNING id, bapas_id, nama_perangkat, lokasi, aktif, terakhir_terlihat_at, versi_aplikasi,
+            created_at, updated_at, created_by, updated_by
+        "#,
+        payload.nama_perangkat,
+        payload.lokasi,
+        payload.aktif,
+        user.id,
+        id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update perangkat kiosk {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(perangkat))
+}
+
+// --- ROTASI API KEY ---
+// URL: POST /api/perangkat-kiosk/:id/rotasi-kunci
+// Kunci lama langsung tidak berlaku.
+#[axum::debug_handler]
+pub async fn rotasi_kunci_perangkat_kiosk(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> Result<Json<PerangkatKioskDenganKunci>, StatusCode> {
+    let existing = get_perangkat(&pool, id).await?;
+    ensure_admin_bapas(&pool, &user, existing.bapas_id).await?;
+
+    let api_key = buat_api_key();
+    let perangkat = sqlx::query_as!(
+        PerangkatKiosk,
+        r#"
+        UPDATE perangkat_kiosk SET api_key_hash = $1, updated_by = $2
+        WHERE id = $3 AND deleted_at IS NULL
+        RETURNING id, bapas_id, nama_perangkat, lokasi, aktif, terakhir_terlihat_at, versi_aplikasi,
+            created_at, updated_at, created_by, updated_by
+        "#,
+        digest(api_key.clone()),
+        user.id,
+        id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(PerangkatKioskDenganKunci { perangkat, api_key }))
+}
+
+// --- DELETE (SOFT) ---
+// URL: DELETE /api/perangkat-kiosk/:id
+#[axum::debug_handler]
+pub async fn delete_perangkat_kiosk(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> StatusCode {
+    let existing = match get_perangkat(&pool, id).await {
+        Ok(perangkat) => perangkat,
+        Err(status) => return status,
+    };
+    if let Err(status) = ensure_admin_bapas(&pool, &user, existing.bapas_id).await {
+        return status;
+    }
+
+    let result = sqlx::query!(
+        "UPDATE perangkat_kiosk SET deleted_at = NOW(), aktif = FALSE, updated_by = $1 WHERE id = $2",
+        user.id,
+        id
+    )
+    .execute(&pool)
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => StatusCode::NO_CONTENT,
+        Ok(_) => StatusCode::NOT_FOUND,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+// === ENDPOINT PERANGKAT (autentikasi API key kiosk) ===
+
+// URL: GET /api/kiosk/perangkat
+// Dipakai aplikasi kiosk untuk memeriksa konfigurasinya sendiri.
+#[axum::debug_handler]
+pub async fn get_perangkat_saya(
+    Extension(pool): Extension<PgPool>,
+    Extension(kiosk): Extension<AuthenticatedKiosk>,
+) -> Result<Json<PerangkatKiosk>, StatusCode> {
+    Ok(Json(get_perangkat(&pool, kiosk.id).await?))
+}
+
+// URL: POST /api/kiosk/heartbeat
+// `terakhir_terlihat_at` sudah diperbarui oleh middleware; di sini versi aplikasi ikut dicatat.
+#[axum::debug_handler]
+pub async fn heartbeat_perangkat_kiosk(
+    Extension(pool): Extension<PgPool>,
+    Extension(kiosk): Extension<AuthenticatedKiosk>,
+    Json(payload): Json<HeartbeatKiosk>,
+) -> StatusCode {
+    let result = sqlx::query!(
+        "UPDATE perangkat_kiosk SET versi_aplikasi = COALESCE($1, versi_aplikasi) WHERE id = $2",
+        payload.versi_aplikasi,
+        kiosk.id
+    )
+    .execute(&pool)
+    .await;
+
+    match result {
+        Ok(_) => StatusCode::NO_CONTENT,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
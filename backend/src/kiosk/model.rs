@@ -0,0 +1,66 @@
+// File baru: src/kiosk/model.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+
+// Merepresentasikan satu baris dari tabel 'perangkat_kiosk' (tanpa hash kunci)
+#[derive(Debug, Serialize, FromRow)]
+pub struct PerangkatKiosk {
+    pub id: i32,
+    pub bapas_id: i32,
+    pub nama_perangkat: String,
+    pub lokasi: Option<String>,
+    pub aktif: bool,
+    pub terakhir_terlihat_at: Option<DateTime<Utc>>,
+    pub versi_aplikasi: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePerangkatKiosk {
+    pub nama_perangkat: String,
+    pub lokasi: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePerangkatKiosk {
+    pub nama_perangkat: Option<String>,
+    pub lokasi: Option<String>,
+    pub aktif: Option<bool>,
+}
+
+// Dikembalikan saat registrasi/rotasi; API key hanya ditampilkan sekali
+#[derive(Debug, Serialize)]
+pub struct PerangkatKioskDenganKunci {
+    #[serde(flatten)]
+    pub perangkat: PerangkatKiosk,
+    pub api_key: String,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum StatusPerangkatKiosk {
+    Online,
+    Offline,
+    BelumTerhubung,
+    Nonaktif,
+}
+
+// Baris daftar kesehatan perangkat untuk admin
+#[derive(Debug, Serialize)]
+pub struct KesehatanPerangkatKiosk {
+    #[serde(flatten)]
+    pub perangkat: PerangkatKiosk,
+    pub status: StatusPerangkatKiosk,
+    pub jumlah_lapor_hari_ini: i64,
+    pub lapor_terakhir_at: Option<DateTime<Utc>>,
+}
+
+// Body POST /api/kiosk/heartbeat
+#[derive(Debug, Deserialize)]
+pub struct HeartbeatKiosk {
+    pub versi_aplikasi: Option<String>,
+}
@@ -5,13 +5,11 @@ use axum::{
     http::StatusCode,
     Json,
 };
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, QueryBuilder};
 use crate::auth::model::AuthenticatedUser;
 use crate::types::UserRoleEnum;
 use super::model_core::{CreateKlien, Klien, UpdateKlien};
 use serde::Deserialize;
-use axum::response::{IntoResponse, Response};
-use axum::http::header;
 
 #[derive(Deserialize)]
 pub struct GetAllKlienParams {
@@ -20,58 +18,70 @@ pub struct GetAllKlienParams {
     pub kanwil_id: Option<i32>,
 }
 
-// --- READ ALL (dengan filter dan otorisasi) ---
-#[axum::debug_handler]
-pub async fn get_all_klien(
-    Extension(pool): Extension<PgPool>,
-    Extension(user): Extension<AuthenticatedUser>,
-    Query(params): Query<GetAllKlienParams>,
-) -> Result<Json<Vec<Klien>>, StatusCode> {
-    
-    let mut query_builder = sqlx::QueryBuilder::new(
-        r#"
-        SELECT id, tipe_klien, nama_klien, alamat_klien, tempat_lahir_klien, 
-        tanggal_lahir_klien, jenis_kelamin_klien, agama_klien, pekerjaan_klien, 
-        pendidikan_terakhir_klien, bapas_id, pk_id, kanwil_id, online_akses_klien, 
-        pengulangan_klien, kewarganegaraan_klien, negara_asal_klien, suku_klien, 
-        keterangan_klien, catatan_klien, created_at, updated_at, created_by, 
-        updated_by, deleted_at
-        FROM klien WHERE deleted_at IS NULL
-        "#
-    );
-
+/// Filter role dan parameter milik `get_all_klien`, dipakai juga oleh endpoint lain
+/// yang menampilkan kumpulan klien (mis. peta). `alias` adalah prefix kolom tabel
+/// klien di query pemanggil, mis. "k." atau "" bila tanpa alias.
+pub fn terapkan_filter_klien(
+    query_builder: &mut QueryBuilder<'_, Postgres>,
+    user: &AuthenticatedUser,
+    params: &GetAllKlienParams,
+    alias: &str,
+) {
     // Terapkan filter berdasarkan role user untuk keamanan
     match user.role {
         UserRoleEnum::SuperAdmin => {
             // SuperAdmin bisa filter berdasarkan kanwil atau bapas apa pun
             if let Some(kanwil_id) = params.kanwil_id {
-                query_builder.push(" AND kanwil_id = ").push_bind(kanwil_id);
+                query_builder.push(format!(" AND {}kanwil_id = ", alias)).push_bind(kanwil_id);
             }
             if let Some(bapas_id) = params.bapas_id {
-                query_builder.push(" AND bapas_id = ").push_bind(bapas_id);
+                query_builder.push(format!(" AND {}bapas_id = ", alias)).push_bind(bapas_id);
             }
         },
         UserRoleEnum::AdminKanwil => {
             // AdminKanwil hanya bisa melihat data di dalam kanwilnya
-            query_builder.push(" AND kanwil_id = ").push_bind(user.kanwil_id);
+            query_builder.push(format!(" AND {}kanwil_id = ", alias)).push_bind(user.kanwil_id);
             if let Some(bapas_id) = params.bapas_id { // Bisa filter bapas di bawahnya
-                query_builder.push(" AND bapas_id = ").push_bind(bapas_id);
+                query_builder.push(format!(" AND {}bapas_id = ", alias)).push_bind(bapas_id);
             }
         },
         UserRoleEnum::AdminBapas => {
             // AdminBapas hanya bisa melihat data di dalam bapasnya
-            query_builder.push(" AND bapas_id = ").push_bind(user.bapas_id);
+            query_builder.push(format!(" AND {}bapas_id = ", alias)).push_bind(user.bapas_id);
         },
         UserRoleEnum::Pegawai => {
             // Pegawai hanya bisa melihat klien miliknya sendiri
-            query_builder.push(" AND pk_id = ").push_bind(user.id);
+            query_builder.push(format!(" AND {}pk_id = ", alias)).push_bind(user.id);
         }
     }
 
     // Pegawai bisa filter klien miliknya sendiri, jadi ini tetap berlaku
     if let Some(pk_id) = params.pk_id {
-        query_builder.push(" AND pk_id = ").push_bind(pk_id);
+        query_builder.push(format!(" AND {}pk_id = ", alias)).push_bind(pk_id);
     }
+}
+
+// --- READ ALL (dengan filter dan otorisasi) ---
+#[axum::debug_handler]
+pub async fn get_all_klien(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(params): Query<GetAllKlienParams>,
+) -> Result<Json<Vec<Klien>>, StatusCode> {
+    
+    let mut query_builder = sqlx::QueryBuilder::new(
+        r#"
+        SELECT id, tipe_klien, nama_klien, alamat_klien, tempat_lahir_klien, 
+        tanggal_lahir_klien, jenis_kelamin_klien, agama_klien, pekerjaan_klien, 
+        pendidikan_terakhir_klien, bapas_id, pk_id, kanwil_id, online_akses_klien, 
+        pengulangan_klien, kewarganegaraan_klien, negara_asal_klien, suku_klien, 
+        keterangan_klien, catatan_klien, created_at, updated_at, created_by, 
+        updated_by, deleted_at
+        FROM klien WHERE deleted_at IS NULL
+        "#
+    );
+
+    terapkan_filter_klien(&mut query_builder, &user, &params, "");
     
     query_builder.push(" ORDER BY nama_klien");
 
@@ -289,51 +299,3 @@ pub async fn delete_klien(
     }
 }
 
-
-pub async fn export_klien_csv(
-    Extension(pool): Extension<PgPool>,
-    Extension(user): Extension<AuthenticatedUser>,
-) -> Result<Response, StatusCode> {
-    // [LOGIKA OTORISASI]
-    // Gunakan QueryBuilder persis seperti di get_all_klien untuk memfilter data
-    let mut query_builder = sqlx::QueryBuilder::new("SELECT * FROM klien WHERE deleted_at IS NULL");
-    match user.role {
-        UserRoleEnum::AdminKanwil => {
-            query_builder.push(" AND kanwil_id = ").push_bind(user.kanwil_id);
-        }
-        UserRoleEnum::AdminBapas => {
-            query_builder.push(" AND bapas_id = ").push_bind(user.bapas_id);
-        }
-        UserRoleEnum::Pegawai => {
-            query_builder.push(" AND pk_id = ").push_bind(user.id);
-        }
-        UserRoleEnum::SuperAdmin => { /* tidak perlu filter tambahan */ }
-    }
-    
-    let klien_list = query_builder.build_query_as::<Klien>()
-        .fetch_all(&pool).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    // [PEMBUATAN CSV]
-    let mut wtr = csv::Writer::from_writer(vec![]);
-    // Tulis header
-    wtr.write_record(&["id", "nama_klien", "alamat_klien", "tipe_klien"]).unwrap();
-    // Tulis data baris
-    for klien in klien_list {
-        wtr.write_record(&[
-            klien.id.to_string(),
-            klien.nama_klien,
-            klien.alamat_klien.unwrap_or_default(),
-            format!("{:?}", klien.tipe_klien),
-        ]).unwrap();
-    }
-    
-    let csv_data = wtr.into_inner().unwrap();
-
-    // [KIRIM RESPONS]
-    let headers = [
-        (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
-        (header::CONTENT_DISPOSITION, "attachment; filename=\"klien_export.csv\""),
-    ];
-
-    Ok((headers, csv_data).into_response())
-}
\ No newline at end of file
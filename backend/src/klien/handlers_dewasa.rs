@@ -5,15 +5,29 @@ use axum::{
     http::StatusCode,
     Json,
 };
-use sqlx::PgPool;
-use crate::auth::model::AuthenticatedUser;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{PgConnection, PgPool};
+use crate::auth::model::{AuthenticatedKiosk, AuthenticatedUser};
 use super::model_dewasa::{
     CreatePenerimaanDewasa, PenerimaanDewasa,
-    CreateRiwayatHukumDewasa, RiwayatHukumDewasa, UpdateRiwayatHukumDewasa,
+    CreateRiwayatHukumDewasa, RiwayatHukumDewasa, RiwayatHukumDewasaDetail, UpdateRiwayatHukumDewasa,
     CreateLayananIntegrasiDewasa, LayananIntegrasiDewasa, UpdateLayananIntegrasiDewasa,
     CreateProsesHukumDewasa, ProsesHukumDewasa, UpdateProsesHukumDewasa,
-    CreateWajibLapor, WajibLaporDewasa}; // Nanti kita tambah UpdatePenerimaanDewasa
-
+    CreateRemisiDewasa, RemisiDewasa, UpdateRemisiDewasa,
+    CreateWajibLapor, HapusWajibLapor, VerifikasiRantaiWajibLapor, WajibLaporDewasa}; // Nanti kita tambah UpdatePenerimaanDewasa
+use crate::auth::authorization::ensure_klien_access;
+use crate::penomoran::service::isi_nomor_register_klien;
+use crate::litmas::service as alur_litmas;
+use crate::kartu::model::KioskWajibLapor;
+use crate::kartu::token as kartu_token;
+use crate::perangkat::handlers as perangkat_handlers;
+use crate::perangkat::model::MandiriWajibLapor;
+use crate::peringatan::service as peringatan;
+use crate::types::{JenisPeringatanEnum, JenisRegisterEnum};
+use std::collections::HashMap;
+
+use super::kalkulasi_pidana::{self, LayananIntegrasi, MasaPidana};
+use super::rantai_lapor;
 use bcrypt::verify;
 
 
@@ -44,6 +58,17 @@ pub async fn create_penerimaan_dewasa(
     Json(payload): Json<CreatePenerimaanDewasa>,
 ) -> Result<Json<PenerimaanDewasa>, StatusCode> {
     
+    // Nomor register diambil dalam transaksi yang sama dengan INSERT agar tidak ada celah
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let no_register_litmas = isi_nomor_register_klien(
+        &mut tx, klien_id, JenisRegisterEnum::Litmas, payload.no_register_litmas_dewasa,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to allocate nomor register litmas: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
     let new_penerimaan = sqlx::query_as!(
         PenerimaanDewasa,
         r#"
@@ -69,7 +94,7 @@ pub async fn create_penerimaan_dewasa(
         payload.tanggal_permintaan_lapas_dewasa,
         payload.tanggal_surat_tugas_dewasa,
         payload.perihal_dewasa,
-        payload.no_register_litmas_dewasa,
+        no_register_litmas,
         payload.nomor_surat_permintaan_lapas_dewasa,
         payload.jenis_permintaan_litmas_lapas_dewasa,
         payload.nama_instansi_dewasa as _,
@@ -84,13 +109,27 @@ pub async fn create_penerimaan_dewasa(
         payload.catatan,
         user.id
     )
-    .fetch_one(&pool)
+    .fetch_one(&mut *tx)
     .await
     .map_err(|e| {
+        if e.as_database_error().is_some_and(|db_err| db_err.is_unique_violation()) {
+            return StatusCode::CONFLICT; // Nomor register litmas sudah dipakai
+        }
         tracing::error!("Failed to create penerimaan dewasa: {}", e);
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
+    alur_litmas::buat_alur(
+        &mut tx, new_penerimaan.id, klien_id, new_penerimaan.tanggal_permintaan_lapas_dewasa, user.id,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create alur litmas: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
     Ok(Json(new_penerimaan))
 }
 
@@ -210,6 +249,9 @@ pub async fn update_penerimaan_dewasa(
     .fetch_optional(&pool)
     .await
     .map_err(|e| {
+        if e.as_database_error().is_some_and(|db_err| db_err.is_unique_violation()) {
+            return StatusCode::CONFLICT; // Nomor register litmas sudah dipakai
+        }
         tracing::error!("Failed to update penerimaan {}: {}", id, e);
         StatusCode::INTERNAL_SERVER_ERROR
     })?
@@ -322,7 +364,7 @@ pub async fn create_riwayat_hukum_dewasa(
 pub async fn get_all_riwayat_hukum_for_klien(
     Extension(pool): Extension<PgPool>,
     Path(klien_id): Path<i32>,
-) -> Result<Json<Vec<RiwayatHukumDewasa>>, StatusCode> {
+) -> Result<Json<Vec<RiwayatHukumDewasaDetail>>, StatusCode> {
     
     let list = sqlx::query_as!(
         RiwayatHukumDewasa,
@@ -336,7 +378,14 @@ pub async fn get_all_riwayat_hukum_for_klien(
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
-    Ok(Json(list))
+    let detail = lengkapi_perhitungan_pidana(&pool, klien_id, list)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to compute perhitungan pidana for klien {}: {}", klien_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(detail))
 }
 
 // --- READ ONE BY ITS OWN ID ---
@@ -345,7 +394,7 @@ pub async fn get_all_riwayat_hukum_for_klien(
 pub async fn get_riwayat_hukum_by_id(
     Extension(pool): Extension<PgPool>,
     Path(id): Path<i32>,
-) -> Result<Json<RiwayatHukumDewasa>, StatusCode> {
+) -> Result<Json<RiwayatHukumDewasaDetail>, StatusCode> {
     
     let riwayat = sqlx::query_as!(
         RiwayatHukumDewasa,
@@ -357,7 +406,94 @@ pub async fn get_riwayat_hukum_by_id(
     .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
     .ok_or(StatusCode::NOT_FOUND)?;
 
-    Ok(Json(riwayat))
+    let klien_id = riwayat.klien_id;
+    let detail = lengkapi_perhitungan_pidana(&pool, klien_id, vec![riwayat])
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to compute perhitungan pidana for riwayat {}: {}", id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .pop()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(detail))
+}
+
+// Menambahkan hasil kalkulasi_pidana ke setiap riwayat, lalu mencocokkan
+// tanggal ekspirasinya dengan masa bimbingan layanan integrasi milik riwayat
+// itu (lihat kalkulasi_pidana::pilih_layanan).
+pub async fn lengkapi_perhitungan_pidana(
+    pool: &PgPool,
+    klien_id: i32,
+    list: Vec<RiwayatHukumDewasa>,
+) -> Result<Vec<RiwayatHukumDewasaDetail>, sqlx::Error> {
+    let layanan: Vec<LayananIntegrasi> = sqlx::query_as!(
+        LayananIntegrasi,
+        r#"
+        SELECT id, tanggal_sk_integrasi_dewasa AS "tanggal_sk!",
+            masa_bimbingan_akhir_dewasa AS "masa_bimbingan_akhir!"
+        FROM layanan_integrasi_dewasa
+        WHERE klien_id = $1 AND deleted_at IS NULL
+            AND tanggal_sk_integrasi_dewasa IS NOT NULL AND masa_bimbingan_akhir_dewasa IS NOT NULL
+        "#,
+        klien_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // Semua tanggal putusan klien, termasuk riwayat yang tidak ada di `list`
+    let putusan: Vec<NaiveDate> = sqlx::query_scalar!(
+        r#"
+        SELECT DISTINCT tanggal_surat_keputusan_pengadilan_dewasa AS "tanggal!"
+        FROM riwayat_hukum_dewasa
+        WHERE klien_id = $1 AND deleted_at IS NULL AND tanggal_surat_keputusan_pengadilan_dewasa IS NOT NULL
+        ORDER BY 1
+        "#,
+        klien_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let total_remisi: HashMap<i32, i64> = sqlx::query!(
+        r#"
+        SELECT riwayat_hukum_dewasa_id, SUM(jumlah_hari_remisi_dewasa)::BIGINT AS "total_hari!"
+        FROM remisi_dewasa
+        WHERE klien_id = $1 AND deleted_at IS NULL
+        GROUP BY riwayat_hukum_dewasa_id
+        "#,
+        klien_id
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| (row.riwayat_hukum_dewasa_id, row.total_hari))
+    .collect();
+
+    Ok(list
+        .into_iter()
+        .map(|riwayat| {
+            let remisi_hari = total_remisi.get(&riwayat.id).copied().unwrap_or(0);
+            let perhitungan = riwayat.pertama_ditahan_dewasa.zip(MasaPidana::dari_kolom(
+                riwayat.pidana_tahun_dewasa,
+                riwayat.pidana_bulan_dewasa,
+                riwayat.pidana_hari_dewasa,
+            ))
+            .and_then(|(mulai, pidana)| kalkulasi_pidana::hitung(mulai, pidana, remisi_hari));
+
+            let pasangan = riwayat.tanggal_surat_keputusan_pengadilan_dewasa.and_then(|tanggal| {
+                let berikutnya = putusan.iter().copied().find(|&p| p > tanggal);
+                kalkulasi_pidana::pilih_layanan(tanggal, berikutnya, &layanan)
+            });
+            let pemeriksaan_masa_bimbingan = perhitungan
+                .as_ref()
+                .zip(pasangan)
+                .map(|(p, l)| kalkulasi_pidana::periksa_masa_bimbingan(p, l.id, l.masa_bimbingan_akhir))
+                .into_iter()
+                .collect();
+
+            RiwayatHukumDewasaDetail { riwayat, perhitungan, pemeriksaan_masa_bimbingan }
+        })
+        .collect())
 }
 
 // --- UPDATE ---
@@ -453,6 +589,237 @@ pub async fn delete_riwayat_hukum_dewasa(
 
 
 
+// === REMISI DEWASA CRUD HANDLERS ===
+
+// --- CREATE ---
+// URL: POST /api/klien/:klien_id/remisi-dewasa
+#[axum::debug_handler]
+pub async fn create_remisi_dewasa(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(klien_id): Path<i32>,
+    Json(payload): Json<CreateRemisiDewasa>,
+) -> Result<Json<RemisiDewasa>, StatusCode> {
+    if payload.jumlah_hari_remisi_dewasa < 0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    // Riwayat hukum yang dirujuk harus milik klien yang sama
+    let riwayat_klien_id = sqlx::query_scalar!(
+        "SELECT klien_id FROM riwayat_hukum_dewasa WHERE id = $1 AND deleted_at IS NULL",
+        payload.riwayat_hukum_dewasa_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if riwayat_klien_id != Some(klien_id) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let new_remisi = sqlx::query_as!(
+        RemisiDewasa,
+        r#"
+        INSERT INTO remisi_dewasa (
+            klien_id, riwayat_hukum_dewasa_id, nomor_sk_remisi_dewasa, jenis_remisi_dewasa,
+            tanggal_sk_remisi_dewasa, jumlah_hari_remisi_dewasa, keterangan, catatan,
+            created_by, updated_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $9)
+        RETURNING
+            id, klien_id, riwayat_hukum_dewasa_id, nomor_sk_remisi_dewasa,
+            jenis_remisi_dewasa AS "jenis_remisi_dewasa: _", tanggal_sk_remisi_dewasa,
+            jumlah_hari_remisi_dewasa, keterangan, catatan, created_at, updated_at,
+            created_by, updated_by, deleted_at
+        "#,
+        klien_id,
+        payload.riwayat_hukum_dewasa_id,
+        payload.nomor_sk_remisi_dewasa,
+        payload.jenis_remisi_dewasa as _,
+        payload.tanggal_sk_remisi_dewasa,
+        payload.jumlah_hari_remisi_dewasa,
+        payload.keterangan,
+        payload.catatan,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create remisi: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(new_remisi))
+}
+
+// --- READ ALL FOR A SPECIFIC KLIEN ---
+// URL: GET /api/klien/:klien_id/remisi-dewasa
+#[axum::debug_handler]
+pub async fn get_all_remisi_for_klien(
+    Extension(pool): Extension<PgPool>,
+    Path(klien_id): Path<i32>,
+) -> Result<Json<Vec<RemisiDewasa>>, StatusCode> {
+
+    let list = sqlx::query_as!(
+        RemisiDewasa,
+        r#"
+        SELECT
+            id, klien_id, riwayat_hukum_dewasa_id, nomor_sk_remisi_dewasa,
+            jenis_remisi_dewasa AS "jenis_remisi_dewasa: _", tanggal_sk_remisi_dewasa,
+            jumlah_hari_remisi_dewasa, keterangan, catatan, created_at, updated_at,
+            created_by, updated_by, deleted_at
+        FROM remisi_dewasa
+        WHERE klien_id = $1 AND deleted_at IS NULL
+        ORDER BY tanggal_sk_remisi_dewasa DESC
+        "#,
+        klien_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch remisi list: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// --- READ ONE BY ITS OWN ID ---
+// URL: GET /api/remisi-dewasa/:id
+#[axum::debug_handler]
+pub async fn get_remisi_by_id(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> Result<Json<RemisiDewasa>, StatusCode> {
+
+    let remisi = sqlx::query_as!(
+        RemisiDewasa,
+        r#"
+        SELECT
+            id, klien_id, riwayat_hukum_dewasa_id, nomor_sk_remisi_dewasa,
+            jenis_remisi_dewasa AS "jenis_remisi_dewasa: _", tanggal_sk_remisi_dewasa,
+            jumlah_hari_remisi_dewasa, keterangan, catatan, created_at, updated_at,
+            created_by, updated_by, deleted_at
+        FROM remisi_dewasa
+        WHERE id = $1 AND deleted_at IS NULL
+        "#,
+        id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    ensure_klien_access(&pool, &user, remisi.klien_id).await?;
+
+    Ok(Json(remisi))
+}
+
+// --- UPDATE ---
+// URL: PUT /api/remisi-dewasa/:id
+#[axum::debug_handler]
+pub async fn update_remisi_dewasa(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateRemisiDewasa>,
+) -> Result<Json<RemisiDewasa>, StatusCode> {
+    if payload.jumlah_hari_remisi_dewasa.is_some_and(|hari| hari < 0) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let klien_id = sqlx::query_scalar!(
+        "SELECT klien_id FROM remisi_dewasa WHERE id = $1 AND deleted_at IS NULL",
+        id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    ensure_klien_access(&pool, &user, klien_id).await?;
+
+    let updated_remisi = sqlx::query_as!(
+        RemisiDewasa,
+        r#"
+        UPDATE remisi_dewasa
+        SET
+            nomor_sk_remisi_dewasa = COALESCE($1, nomor_sk_remisi_dewasa),
+            jenis_remisi_dewasa = COALESCE($2, jenis_remisi_dewasa),
+            tanggal_sk_remisi_dewasa = COALESCE($3, tanggal_sk_remisi_dewasa),
+            jumlah_hari_remisi_dewasa = COALESCE($4, jumlah_hari_remisi_dewasa),
+            keterangan = COALESCE($5, keterangan),
+            catatan = COALESCE($6, catatan),
+            updated_by = $7
+        WHERE id = $8 AND deleted_at IS NULL
+        RETURNING
+            id, klien_id, riwayat_hukum_dewasa_id, nomor_sk_remisi_dewasa,
+            jenis_remisi_dewasa AS "jenis_remisi_dewasa: _", tanggal_sk_remisi_dewasa,
+            jumlah_hari_remisi_dewasa, keterangan, catatan, created_at, updated_at,
+            created_by, updated_by, deleted_at
+        "#,
+        payload.nomor_sk_remisi_dewasa,
+        payload.jenis_remisi_dewasa as _,
+        payload.tanggal_sk_remisi_dewasa,
+        payload.jumlah_hari_remisi_dewasa,
+        payload.keterangan,
+        payload.catatan,
+        user.id,
+        id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update remisi {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(updated_remisi))
+}
+
+// --- DELETE (SOFT) ---
+// URL: DELETE /api/remisi-dewasa/:id
+#[axum::debug_handler]
+pub async fn delete_remisi_dewasa(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> StatusCode {
+    let klien_id = match sqlx::query_scalar!(
+        "SELECT klien_id FROM remisi_dewasa WHERE id = $1 AND deleted_at IS NULL",
+        id
+    )
+    .fetch_optional(&pool)
+    .await {
+        Ok(Some(klien_id)) => klien_id,
+        Ok(None) => return StatusCode::NOT_FOUND,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    if let Err(status) = ensure_klien_access(&pool, &user, klien_id).await {
+        return status;
+    }
+
+    let result = sqlx::query!(
+        "UPDATE remisi_dewasa SET deleted_at = NOW(), updated_by = $1 WHERE id = $2",
+        user.id,
+        id
+    )
+    .execute(&pool)
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => StatusCode::NO_CONTENT,
+        Ok(_) => StatusCode::NOT_FOUND,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+
+
+
 // === LAYANAN INTEGRASI DEWASA CRUD HANDLERS ===
 
 // --- CREATE ---
@@ -465,6 +832,16 @@ pub async fn create_layanan_integrasi_dewasa(
     Json(payload): Json<CreateLayananIntegrasiDewasa>,
 ) -> Result<Json<LayananIntegrasiDewasa>, StatusCode> {
     
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let nomor_register_integrasi = isi_nomor_register_klien(
+        &mut tx, klien_id, JenisRegisterEnum::Integrasi, payload.nomor_register_integrasi_dewasa,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to allocate nomor register integrasi: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
     let new_layanan = sqlx::query_as!(
         LayananIntegrasiDewasa,
         r#"
@@ -480,7 +857,7 @@ pub async fn create_layanan_integrasi_dewasa(
         klien_id,
         payload.nomor_sk_dewasa,
         payload.tanggal_sk_integrasi_dewasa,
-        payload.nomor_register_integrasi_dewasa,
+        nomor_register_integrasi,
         payload.masa_bimbingan_awal_dewasa,
         payload.masa_bimbingan_akhir_dewasa,
         payload.petugas_layanan_id,
@@ -492,13 +869,18 @@ pub async fn create_layanan_integrasi_dewasa(
         payload.catatan,
         user.id
     )
-    .fetch_one(&pool)
+    .fetch_one(&mut *tx)
     .await
     .map_err(|e| {
+        if e.as_database_error().is_some_and(|db_err| db_err.is_unique_violation()) {
+            return StatusCode::CONFLICT; // Nomor register integrasi sudah dipakai
+        }
         tracing::error!("Failed to create layanan integrasi: {}", e);
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
     Ok(Json(new_layanan))
 }
 
@@ -591,7 +973,12 @@ pub async fn update_layanan_integrasi_dewasa(
     )
     .fetch_optional(&pool)
     .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .map_err(|e| {
+        if e.as_database_error().is_some_and(|db_err| db_err.is_unique_violation()) {
+            return StatusCode::CONFLICT; // Nomor register integrasi sudah dipakai
+        }
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
     .ok_or(StatusCode::NOT_FOUND)?;
 
     Ok(Json(updated_layanan))
@@ -648,6 +1035,25 @@ pub async fn create_proses_hukum_dewasa(
     Json(payload): Json<CreateProsesHukumDewasa>,
 ) -> Result<Json<ProsesHukumDewasa>, StatusCode> {
     
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let klien_id = sqlx::query_scalar!(
+        "SELECT klien_id FROM penerimaan_dewasa WHERE id = $1 AND deleted_at IS NULL",
+        penerimaan_dewasa_id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    let nomor_register_proses_hukum = isi_nomor_register_klien(
+        &mut tx, klien_id, JenisRegisterEnum::ProsesHukum, payload.nomor_register_proses_hukum_dewasa,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to allocate nomor register proses hukum: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
     let new_proses = sqlx::query_as!(
         ProsesHukumDewasa,
         r#"
@@ -660,19 +1066,24 @@ pub async fn create_proses_hukum_dewasa(
         "#,
         penerimaan_dewasa_id,
         payload.jenis_proses_hukum_dewasa,
-        payload.nomor_register_proses_hukum_dewasa,
+        nomor_register_proses_hukum,
         payload.tanggal_proses_dewasa,
         payload.keterangan,
         payload.catatan,
         user.id
     )
-    .fetch_one(&pool)
+    .fetch_one(&mut *tx)
     .await
     .map_err(|e| {
+        if e.as_database_error().is_some_and(|db_err| db_err.is_unique_violation()) {
+            return StatusCode::CONFLICT; // Nomor register proses hukum sudah dipakai
+        }
         tracing::error!("Failed to create proses hukum: {}", e);
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
     Ok(Json(new_proses))
 }
 
@@ -751,7 +1162,12 @@ pub async fn update_proses_hukum_dewasa(
     )
     .fetch_optional(&pool)
     .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .map_err(|e| {
+        if e.as_database_error().is_some_and(|db_err| db_err.is_unique_violation()) {
+            return StatusCode::CONFLICT; // Nomor register proses hukum sudah dipakai
+        }
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
     .ok_or(StatusCode::NOT_FOUND)?;
 
     Ok(Json(updated_proses))
@@ -800,21 +1216,11 @@ pub async fn petugas_wajib_lapor_dewasa(
     Path(klien_id): Path<i32>,
     Json(payload): Json<CreateWajibLapor>,
 ) -> StatusCode {
-     let result = sqlx::query!(
-        r#"
-        INSERT INTO wajib_lapor_dewasa 
-            (klien_id, metode_lapor_dewasa, created_by, photo_path_dewasa, latitude_dewasa, longitude_dewasa)
-        VALUES ($1, 'Petugas', $2, $3, $4, $5)
-        "#,
-        klien_id,
-        user.id,
-        payload.photo_path_dewasa,
-        payload.latitude_dewasa,
-        payload.longitude_dewasa
-    )
-    .execute(&pool)
-    .await;
-    
+    let result = match pool.acquire().await {
+        Ok(mut conn) => simpan_wajib_lapor_petugas(&mut conn, klien_id, user.id, &payload, None).await,
+        Err(e) => Err(e),
+    };
+
     match result {
         Ok(_) => StatusCode::CREATED,
         Err(e) => {
@@ -824,25 +1230,59 @@ pub async fn petugas_wajib_lapor_dewasa(
     }
 }
 
+// Dipakai juga oleh sinkronisasi offline; `waktu_lapor_klien` diisi waktu perangkat
+// jika laporan dibuat saat tidak ada sinyal.
+pub async fn simpan_wajib_lapor_petugas(
+    conn: &mut PgConnection,
+    klien_id: i32,
+    user_id: i32,
+    payload: &CreateWajibLapor,
+    waktu_lapor_klien: Option<DateTime<Utc>>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        INSERT INTO wajib_lapor_dewasa 
+            (klien_id, metode_lapor_dewasa, created_by, photo_path_dewasa, photo_hash_dewasa, latitude_dewasa, longitude_dewasa, waktu_lapor_klien)
+        VALUES ($1, 'Petugas', $2, $3, $4, $5, $6, $7)
+        RETURNING id
+        "#,
+        klien_id,
+        user_id,
+        payload.photo_path_dewasa,
+        payload.photo_hash_dewasa,
+        payload.latitude_dewasa,
+        payload.longitude_dewasa,
+        waktu_lapor_klien
+    )
+    .fetch_one(conn)
+    .await
+}
+
 // --- CREATE (KIOSK) ---
-// URL: POST /api/kiosk/klien/:klien_id/wajib-lapor-dewasa
+// URL: POST /api/kiosk/wajib-lapor-dewasa (autentikasi perangkat kiosk, bukan user)
+// Klien diidentifikasi dari token kartu ber-QR yang dipindai kiosk, bukan dari ID ketikan operator.
 pub async fn kiosk_wajib_lapor_dewasa(
     Extension(pool): Extension<PgPool>,
-    Extension(user): Extension<AuthenticatedUser>, // User "kiosk"
-    Path(klien_id): Path<i32>,
-    Json(payload): Json<CreateWajibLapor>,
+    Extension(kiosk): Extension<AuthenticatedKiosk>,
+    Json(payload): Json<KioskWajibLapor>,
 ) -> StatusCode {
+    let klien_id = match kartu_token::verifikasi(&pool, kiosk.bapas_id, &payload.token_kartu).await {
+        Ok(klien_id) => klien_id,
+        Err(status) => return status,
+    };
+
     let result = sqlx::query!(
         r#"
         INSERT INTO wajib_lapor_dewasa 
-            (klien_id, metode_lapor_dewasa, created_by, photo_path_dewasa, latitude_dewasa, longitude_dewasa)
-        VALUES ($1, 'Self-Service', $2, $3, $4, $5)
+            (klien_id, metode_lapor_dewasa, perangkat_kiosk_id, photo_path_dewasa, photo_hash_dewasa, latitude_dewasa, longitude_dewasa)
+        VALUES ($1, 'Self-Service', $2, $3, $4, $5, $6)
         "#,
         klien_id,
-        user.id, // ID dari user "kiosk"
-        payload.photo_path_dewasa,
-        payload.latitude_dewasa,
-        payload.longitude_dewasa
+        kiosk.id,
+        payload.lapor.photo_path_dewasa,
+        payload.lapor.photo_hash_dewasa,
+        payload.lapor.latitude_dewasa,
+        payload.lapor.longitude_dewasa
     )
     .execute(&pool)
     .await;
@@ -858,10 +1298,11 @@ pub async fn kiosk_wajib_lapor_dewasa(
 
 // --- CREATE (MANDIRI) ---
 // URL: POST /api/mandiri/klien/:klien_id/wajib-lapor-dewasa
+// Selain PIN, wajib menyertakan token perangkat yang didaftarkan lewat kode dari PK.
 pub async fn mandiri_wajib_lapor_dewasa(
     Extension(pool): Extension<PgPool>,
     Path(klien_id): Path<i32>,
-    Json(payload): Json<CreateWajibLapor>,
+    Json(payload): Json<MandiriWajibLapor>,
 ) -> StatusCode {
     // 1. Fetch klien untuk memeriksa akses online dan hash PIN
     let klien_data = match sqlx::query!("SELECT online_akses_klien, pin_klien_hash FROM klien WHERE id = $1", klien_id)
@@ -882,46 +1323,99 @@ pub async fn mandiri_wajib_lapor_dewasa(
     Some(hash) => hash,
     None => return StatusCode::FORBIDDEN,
 };
-    let pin_from_payload = match payload.pin {
+    let pin_from_payload = match payload.lapor.pin.as_deref() {
         Some(pin) => pin,
         None => return StatusCode::UNAUTHORIZED, // PIN tidak disediakan
     };
 
-    if !verify(&pin_from_payload, &pin_hash).unwrap_or(false) {
+    if !verify(pin_from_payload, &pin_hash).unwrap_or(false) {
         return StatusCode::UNAUTHORIZED; // PIN salah
     }
 
-    // 4. Jika semua verifikasi lolos, INSERT data
-    let result = sqlx::query!(
-        r#"
-        INSERT INTO wajib_lapor_dewasa 
-            (klien_id, metode_lapor_dewasa, photo_path_dewasa, latitude_dewasa, longitude_dewasa)
-        VALUES ($1, 'Online', $2, $3, $4)
-        "#,
-        klien_id,
-        payload.photo_path_dewasa,
-        payload.latitude_dewasa,
-        payload.longitude_dewasa
-    )
-    .execute(&pool)
+    // 4. Verifikasi perangkat terdaftar (belum dicabut, milik klien ini)
+    let perangkat = match perangkat_handlers::cari_perangkat_aktif(&pool, klien_id, &payload.token_perangkat).await {
+        Ok(Some(perangkat)) => perangkat,
+        Ok(None) => return StatusCode::UNAUTHORIZED,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    // 5. Jika semua verifikasi lolos, INSERT data
+    let result: Result<(), sqlx::Error> = async {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO wajib_lapor_dewasa 
+                (klien_id, metode_lapor_dewasa, perangkat_klien_id, photo_path_dewasa, photo_hash_dewasa, latitude_dewasa, longitude_dewasa)
+            VALUES ($1, 'Online', $2, $3, $4, $5, $6)
+            "#,
+            klien_id,
+            perangkat.id,
+            payload.lapor.photo_path_dewasa,
+            payload.lapor.photo_hash_dewasa,
+            payload.lapor.latitude_dewasa,
+            payload.lapor.longitude_dewasa
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // Check-in pertama dari perangkat ini: beri tahu PK. Update bersyarat ini
+        // mengunci baris perangkat, jadi dua check-in pertama yang bersamaan
+        // hanya menghasilkan satu peringatan
+        let pertama = sqlx::query_scalar!(
+            "UPDATE perangkat_klien SET terakhir_dipakai_at = NOW() WHERE id = $1 AND terakhir_dipakai_at IS NULL RETURNING id",
+            perangkat.id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .is_some();
+        if pertama {
+            let pesan = format!("Check-in mandiri pertama dari perangkat baru \"{}\"", perangkat.nama_perangkat);
+            peringatan::kirim_ke_pk(
+                &mut tx,
+                klien_id,
+                JenisPeringatanEnum::PerangkatBaru,
+                &pesan,
+                Some(perangkat.id.into()),
+            )
+            .await?;
+        } else {
+            sqlx::query!("UPDATE perangkat_klien SET terakhir_dipakai_at = NOW() WHERE id = $1", perangkat.id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await
+    }
     .await;
 
     match result {
         Ok(_) => StatusCode::CREATED,
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        Err(e) => {
+            tracing::error!("Failed to create wajib lapor (mandiri): {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
     }
 }
 
 
-// --- READ ALL FOR A KLIEN ---
-// URL: GET /api/klien/:klien_id/wajib-lapor-dewasa
 // --- READ ALL FOR A KLIEN ---
 // URL: GET /api/klien/:klien_id/wajib-lapor-dewasa
 pub async fn get_all_wajib_lapor_for_klien(
     Extension(pool): Extension<PgPool>,
     Path(klien_id): Path<i32>,
 ) -> Result<Json<Vec<WajibLaporDewasa>>, StatusCode> {
-    let list = sqlx::query_as!(
+    let list = ambil_wajib_lapor_klien(&pool, klien_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch wajib lapor list: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(list))
+}
+
+// Dipakai juga oleh riwayat pembimbingan (modul bimbingan)
+pub async fn ambil_wajib_lapor_klien(pool: &PgPool, klien_id: i32) -> Result<Vec<WajibLaporDewasa>, sqlx::Error> {
+    sqlx::query_as!(
         WajibLaporDewasa,
         r#"
         SELECT 
@@ -933,21 +1427,21 @@ pub async fn get_all_wajib_lapor_for_klien(
             metode_lapor_dewasa AS "metode_lapor_dewasa: _",
             created_by,
             deleted_at,
-            created_at -- [FIX] Kolom ini harus ada dan tidak ada koma setelahnya
+            created_at, -- [FIX] Kolom ini harus ada
+            waktu_lapor_klien,
+            perangkat_kiosk_id,
+            perangkat_klien_id,
+            photo_hash_dewasa,
+            nomor_urut,
+            hash_rantai
         FROM wajib_lapor_dewasa 
         WHERE klien_id = $1 AND deleted_at IS NULL 
         ORDER BY created_at DESC
         "#,
         klien_id
     )
-    .fetch_all(&pool)
+    .fetch_all(pool)
     .await
-    .map_err(|e| {
-        tracing::error!("Failed to fetch wajib lapor list: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
-
-    Ok(Json(list))
 }
 
 
@@ -957,13 +1451,23 @@ pub async fn delete_wajib_lapor_dewasa(
     Extension(pool): Extension<PgPool>,
     Extension(user): Extension<AuthenticatedUser>,
     Path(id): Path<i64>,
+    Json(payload): Json<HapusWajibLapor>,
 ) -> StatusCode {
-    // Otorisasi sudah ditangani oleh middleware `authorize_wajib_lapor_access` yang akan kita buat.
-    // Untuk sekarang, kita bisa tambahkan cek role sederhana.
-    
+    // Otorisasi sudah ditangani oleh middleware `authorize_wajib_lapor_delete_access`.
+    if payload.alasan_penghapusan.trim().is_empty() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    // Baris tetap di rantai hash; hanya ditandai terhapus beserta alasan dan pelakunya
     let result = sqlx::query!(
-        "UPDATE wajib_lapor_dewasa SET deleted_at = NOW() WHERE id = $1",
-        id
+        r#"
+        UPDATE wajib_lapor_dewasa
+        SET deleted_at = NOW(), deleted_by = $2, alasan_penghapusan = $3
+        WHERE id = $1 AND deleted_at IS NULL
+        "#,
+        id,
+        user.id,
+        payload.alasan_penghapusan.trim()
     )
     .execute(&pool)
     .await;
@@ -973,4 +1477,19 @@ pub async fn delete_wajib_lapor_dewasa(
         Ok(_) => StatusCode::NOT_FOUND,
         Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
     }
-}
\ No newline at end of file
+}
+
+// --- VERIFIKASI RANTAI HASH ---
+// URL: GET /api/klien/:klien_id/wajib-lapor-dewasa/verifikasi
+#[axum::debug_handler]
+pub async fn verifikasi_rantai_wajib_lapor(
+    Extension(pool): Extension<PgPool>,
+    Path(klien_id): Path<i32>,
+) -> Result<Json<VerifikasiRantaiWajibLapor>, StatusCode> {
+    let hasil = rantai_lapor::verifikasi_rantai(&pool, klien_id).await.map_err(|e| {
+        tracing::error!("Failed to verify wajib lapor chain: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(hasil))
+}
@@ -0,0 +1,276 @@
+// File baru: src/klien/kalkulasi_pidana.rs
+//
+// Mesin perhitungan masa pidana untuk riwayat hukum dewasa.
+// Semua fungsi di sini murni (tanpa akses database) agar mudah diuji.
+
+use chrono::{Days, Months, NaiveDate};
+use serde::Serialize;
+
+/// Lama pidana sesuai putusan pengadilan (tahun, bulan, hari).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MasaPidana {
+    pub tahun: u32,
+    pub bulan: u32,
+    pub hari: u32,
+}
+
+impl MasaPidana {
+    /// Membentuk masa pidana dari kolom `pidana_*_dewasa`.
+    /// Mengembalikan `None` jika semua kolom kosong atau ada nilai negatif.
+    pub fn dari_kolom(tahun: Option<i32>, bulan: Option<i32>, hari: Option<i32>) -> Option<Self> {
+        if tahun.is_none() && bulan.is_none() && hari.is_none() {
+            return None;
+        }
+        Some(MasaPidana {
+            tahun: u32::try_from(tahun.unwrap_or(0)).ok()?,
+            bulan: u32::try_from(bulan.unwrap_or(0)).ok()?,
+            hari: u32::try_from(hari.unwrap_or(0)).ok()?,
+        })
+    }
+
+    /// Tanggal berakhirnya pidana jika dijalani penuh sejak `mulai`.
+    /// Penambahan bulan mengikuti kalender: 31 Januari + 1 bulan = akhir Februari.
+    pub fn berakhir_sejak(&self, mulai: NaiveDate) -> Option<NaiveDate> {
+        let total_bulan = self.tahun.checked_mul(12)?.checked_add(self.bulan)?;
+        mulai
+            .checked_add_months(Months::new(total_bulan))?
+            .checked_add_days(Days::new(u64::from(self.hari)))
+    }
+}
+
+/// Hasil perhitungan yang ditampilkan bersama data riwayat hukum.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct PerhitunganPidana {
+    pub tanggal_mulai: NaiveDate,
+    pub total_hari_pidana: i64,
+    pub total_remisi_hari: i64,
+    /// Tanggal bebas jika pidana dijalani penuh tanpa remisi.
+    pub tanggal_ekspirasi_awal: NaiveDate,
+    /// Tanggal bebas setelah dikurangi remisi.
+    pub tanggal_ekspirasi: NaiveDate,
+    /// Ambang 1/2 masa pidana (asimilasi).
+    pub tanggal_setengah: NaiveDate,
+    /// Ambang 2/3 masa pidana (syarat CB/PB).
+    pub tanggal_dua_pertiga: NaiveDate,
+}
+
+/// Menghitung ekspirasi serta ambang 1/2 dan 2/3.
+///
+/// Remisi mengurangi masa pidana yang harus dijalani, sehingga ambang dihitung
+/// dari masa pidana setelah remisi dan dibulatkan ke atas (hari yang belum
+/// dijalani penuh tidak dihitung). Remisi tidak pernah membuat ekspirasi
+/// lebih awal dari tanggal mulai.
+pub fn hitung(mulai: NaiveDate, pidana: MasaPidana, remisi_hari: i64) -> Option<PerhitunganPidana> {
+    let ekspirasi_awal = pidana.berakhir_sejak(mulai)?;
+    let total_hari = (ekspirasi_awal - mulai).num_days();
+    let remisi = remisi_hari.clamp(0, total_hari);
+    let hari_dijalani = total_hari - remisi;
+
+    let geser = |hari: i64| mulai.checked_add_days(Days::new(u64::try_from(hari).ok()?));
+
+    Some(PerhitunganPidana {
+        tanggal_mulai: mulai,
+        total_hari_pidana: total_hari,
+        total_remisi_hari: remisi,
+        tanggal_ekspirasi_awal: ekspirasi_awal,
+        tanggal_ekspirasi: geser(hari_dijalani)?,
+        tanggal_setengah: geser(bagi_ke_atas(hari_dijalani, 1, 2))?,
+        tanggal_dua_pertiga: geser(bagi_ke_atas(hari_dijalani, 2, 3))?,
+    })
+}
+
+fn bagi_ke_atas(hari: i64, pembilang: i64, penyebut: i64) -> i64 {
+    (hari * pembilang + penyebut - 1) / penyebut
+}
+
+/// Selisih (hari) yang masih dianggap sesuai; pembulatan tanggal di SK integrasi
+/// sering berbeda satu hari dari perhitungan.
+pub const TOLERANSI_HARI_MASA_BIMBINGAN: i64 = 1;
+
+/// Layanan integrasi yang bisa dipasangkan dengan riwayat hukum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayananIntegrasi {
+    pub id: i32,
+    pub tanggal_sk: NaiveDate,
+    pub masa_bimbingan_akhir: NaiveDate,
+}
+
+/// Layanan integrasi untuk satu riwayat: SK terbit sejak putusan riwayat itu
+/// dan sebelum putusan riwayat berikutnya milik klien yang sama. Jika ada
+/// beberapa, dipakai yang SK-nya paling akhir.
+pub fn pilih_layanan(
+    tanggal_putusan: NaiveDate,
+    putusan_berikutnya: Option<NaiveDate>,
+    layanan: &[LayananIntegrasi],
+) -> Option<&LayananIntegrasi> {
+    layanan
+        .iter()
+        .filter(|l| l.tanggal_sk >= tanggal_putusan && putusan_berikutnya.is_none_or(|b| l.tanggal_sk < b))
+        .max_by_key(|l| (l.tanggal_sk, l.id))
+}
+
+/// Hasil pencocokan ekspirasi dengan `masa_bimbingan_akhir_dewasa` pada layanan integrasi.
+#[derive(Debug, Clone, Serialize)]
+pub struct PemeriksaanMasaBimbingan {
+    pub layanan_integrasi_id: i32,
+    pub masa_bimbingan_akhir_dewasa: NaiveDate,
+    pub tanggal_ekspirasi: NaiveDate,
+    /// Positif jika masa bimbingan berakhir setelah ekspirasi.
+    pub selisih_hari: i64,
+    pub sesuai: bool,
+}
+
+pub fn periksa_masa_bimbingan(
+    perhitungan: &PerhitunganPidana,
+    layanan_integrasi_id: i32,
+    masa_bimbingan_akhir: NaiveDate,
+) -> PemeriksaanMasaBimbingan {
+    let selisih_hari = (masa_bimbingan_akhir - perhitungan.tanggal_ekspirasi).num_days();
+    PemeriksaanMasaBimbingan {
+        layanan_integrasi_id,
+        masa_bimbingan_akhir_dewasa: masa_bimbingan_akhir,
+        tanggal_ekspirasi: perhitungan.tanggal_ekspirasi,
+        selisih_hari,
+        sesuai: selisih_hari.abs() <= TOLERANSI_HARI_MASA_BIMBINGAN,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn tgl(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn pidana(tahun: u32, bulan: u32, hari: u32) -> MasaPidana {
+        MasaPidana { tahun, bulan, hari }
+    }
+
+    #[test]
+    fn akhir_bulan_dijepit_ke_hari_terakhir() {
+        let h = hitung(tgl(2023, 1, 31), pidana(0, 1, 0), 0).unwrap();
+        assert_eq!(h.tanggal_ekspirasi, tgl(2023, 2, 28));
+        let h = hitung(tgl(2024, 1, 31), pidana(0, 1, 0), 0).unwrap();
+        assert_eq!(h.tanggal_ekspirasi, tgl(2024, 2, 29));
+    }
+
+    #[test]
+    fn tahun_kabisat() {
+        let h = hitung(tgl(2024, 2, 29), pidana(1, 0, 0), 0).unwrap();
+        assert_eq!(h.tanggal_ekspirasi, tgl(2025, 2, 28));
+        assert_eq!(h.total_hari_pidana, 365);
+        let h = hitung(tgl(2023, 3, 1), pidana(1, 0, 0), 0).unwrap();
+        assert_eq!(h.total_hari_pidana, 366);
+    }
+
+    #[test]
+    fn ambang_dan_remisi() {
+        // 1 tahun sejak 1 Januari 2023 = 365 hari, remisi 30 hari -> 335 hari dijalani.
+        let h = hitung(tgl(2023, 1, 1), pidana(1, 0, 0), 30).unwrap();
+        assert_eq!(h.tanggal_ekspirasi_awal, tgl(2024, 1, 1));
+        assert_eq!(h.tanggal_ekspirasi, tgl(2023, 12, 2));
+        assert_eq!(h.tanggal_setengah, tgl(2023, 1, 1) + Days::new(168));
+        assert_eq!(h.tanggal_dua_pertiga, tgl(2023, 1, 1) + Days::new(224));
+    }
+
+    fn layanan(id: i32, tanggal_sk: NaiveDate) -> LayananIntegrasi {
+        LayananIntegrasi { id, tanggal_sk, masa_bimbingan_akhir: tanggal_sk + Days::new(100) }
+    }
+
+    #[test]
+    fn layanan_dipasangkan_dengan_riwayatnya_sendiri() {
+        let daftar = [layanan(1, tgl(2019, 6, 1)), layanan(2, tgl(2023, 3, 1))];
+        // Riwayat lama hanya melihat layanan sebelum putusan berikutnya
+        let lama = pilih_layanan(tgl(2018, 1, 10), Some(tgl(2022, 5, 1)), &daftar);
+        assert_eq!(lama.map(|l| l.id), Some(1));
+        let baru = pilih_layanan(tgl(2022, 5, 1), None, &daftar);
+        assert_eq!(baru.map(|l| l.id), Some(2));
+    }
+
+    #[test]
+    fn layanan_dengan_sk_terakhir_dipilih() {
+        let daftar = [layanan(1, tgl(2023, 3, 1)), layanan(2, tgl(2023, 9, 1)), layanan(3, tgl(2021, 1, 1))];
+        assert_eq!(pilih_layanan(tgl(2022, 5, 1), None, &daftar).map(|l| l.id), Some(2));
+        assert_eq!(pilih_layanan(tgl(2024, 1, 1), None, &daftar), None);
+    }
+
+    #[test]
+    fn selisih_satu_hari_masih_sesuai() {
+        let h = hitung(tgl(2023, 1, 1), pidana(1, 0, 0), 0).unwrap();
+        assert!(periksa_masa_bimbingan(&h, 1, tgl(2024, 1, 2)).sesuai);
+        assert!(periksa_masa_bimbingan(&h, 1, tgl(2023, 12, 31)).sesuai);
+        let lewat = periksa_masa_bimbingan(&h, 1, tgl(2024, 1, 3));
+        assert!(!lewat.sesuai);
+        assert_eq!(lewat.selisih_hari, 2);
+    }
+
+    #[test]
+    fn kolom_kosong_atau_negatif_tidak_dihitung() {
+        assert_eq!(MasaPidana::dari_kolom(None, None, None), None);
+        assert_eq!(MasaPidana::dari_kolom(Some(-1), None, None), None);
+        assert_eq!(MasaPidana::dari_kolom(Some(2), None, Some(5)), Some(pidana(2, 0, 5)));
+    }
+
+    fn arb_tanggal() -> impl Strategy<Value = NaiveDate> {
+        (1990i32..2060, 1u32..=12, 1u32..=31).prop_map(|(y, m, d)| {
+            (0..4)
+                .find_map(|mundur| NaiveDate::from_ymd_opt(y, m, d - mundur))
+                .unwrap()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn urutan_tanggal_selalu_konsisten(
+            mulai in arb_tanggal(),
+            tahun in 0u32..30,
+            bulan in 0u32..24,
+            hari in 0u32..400,
+            remisi in 0i64..2000,
+        ) {
+            let h = hitung(mulai, pidana(tahun, bulan, hari), remisi).unwrap();
+            prop_assert!(h.tanggal_mulai <= h.tanggal_setengah);
+            prop_assert!(h.tanggal_setengah <= h.tanggal_dua_pertiga);
+            prop_assert!(h.tanggal_dua_pertiga <= h.tanggal_ekspirasi);
+            prop_assert!(h.tanggal_ekspirasi <= h.tanggal_ekspirasi_awal);
+            prop_assert_eq!(
+                (h.tanggal_ekspirasi_awal - h.tanggal_ekspirasi).num_days(),
+                h.total_remisi_hari
+            );
+        }
+
+        #[test]
+        fn remisi_tidak_pernah_memundurkan_ekspirasi(
+            mulai in arb_tanggal(),
+            tahun in 0u32..10,
+            bulan in 0u32..12,
+            remisi_a in 0i64..1000,
+            tambahan in 0i64..1000,
+        ) {
+            let a = hitung(mulai, pidana(tahun, bulan, 0), remisi_a).unwrap();
+            let b = hitung(mulai, pidana(tahun, bulan, 0), remisi_a + tambahan).unwrap();
+            prop_assert!(b.tanggal_ekspirasi <= a.tanggal_ekspirasi);
+            prop_assert!(b.tanggal_dua_pertiga <= a.tanggal_dua_pertiga);
+        }
+
+        #[test]
+        fn ekspirasi_jatuh_pada_hari_yang_sama_atau_akhir_bulan(
+            mulai in arb_tanggal(),
+            tahun in 0u32..30,
+            bulan in 0u32..24,
+        ) {
+            use chrono::Datelike;
+            let h = hitung(mulai, pidana(tahun, bulan, 0), 0).unwrap();
+            let akhir = h.tanggal_ekspirasi;
+            let hari_terakhir_bulan = akhir.with_day(1).unwrap()
+                .checked_add_months(Months::new(1)).unwrap()
+                .pred_opt().unwrap().day();
+            prop_assert!(
+                akhir.day() == mulai.day()
+                    || (akhir.day() == hari_terakhir_bulan && mulai.day() > hari_terakhir_bulan)
+            );
+        }
+    }
+}
@@ -1,6 +1,8 @@
 // File baru: src/klien/mod.rs
 pub mod model_core;
 pub mod model_dewasa;
+pub mod kalkulasi_pidana;
+pub mod rantai_lapor;
 // Daftarkan handler baru kita
 pub mod handlers_core;
 pub mod handlers_dewasa;
@@ -4,7 +4,8 @@ use chrono::{DateTime, Utc, NaiveDate};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use crate::types::{MetodeLaporEnum, NamaInstansiEnum};
+use crate::types::{JenisRemisiEnum, MetodeLaporEnum, NamaInstansiEnum};
+use super::kalkulasi_pidana::{PemeriksaanMasaBimbingan, PerhitunganPidana};
 use std::fmt::Debug;
 // === LayananIntegrasiDewasa Models ===
 
@@ -201,6 +202,15 @@ pub struct RiwayatHukumDewasa {
     pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+// Respons GET riwayat hukum: data asli + hasil perhitungan masa pidana
+#[derive(Debug, serde::Serialize)]
+pub struct RiwayatHukumDewasaDetail {
+    #[serde(flatten)]
+    pub riwayat: RiwayatHukumDewasa,
+    pub perhitungan: Option<PerhitunganPidana>,
+    pub pemeriksaan_masa_bimbingan: Vec<PemeriksaanMasaBimbingan>,
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub struct CreateRiwayatHukumDewasa {
     pub klien_id: i32,
@@ -231,6 +241,47 @@ pub struct UpdateRiwayatHukumDewasa {
     pub catatan: Option<String>,
 }
 
+// === RemisiDewasa Models ===
+
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+pub struct RemisiDewasa {
+    pub id: i32,
+    pub klien_id: i32,
+    pub riwayat_hukum_dewasa_id: i32,
+    pub nomor_sk_remisi_dewasa: Option<String>,
+    pub jenis_remisi_dewasa: JenisRemisiEnum,
+    pub tanggal_sk_remisi_dewasa: Option<chrono::NaiveDate>,
+    pub jumlah_hari_remisi_dewasa: i32,
+    pub keterangan: Option<String>,
+    pub catatan: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct CreateRemisiDewasa {
+    pub riwayat_hukum_dewasa_id: i32,
+    pub nomor_sk_remisi_dewasa: Option<String>,
+    pub jenis_remisi_dewasa: JenisRemisiEnum,
+    pub tanggal_sk_remisi_dewasa: Option<chrono::NaiveDate>,
+    pub jumlah_hari_remisi_dewasa: i32,
+    pub keterangan: Option<String>,
+    pub catatan: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct UpdateRemisiDewasa {
+    pub nomor_sk_remisi_dewasa: Option<String>,
+    pub jenis_remisi_dewasa: Option<JenisRemisiEnum>,
+    pub tanggal_sk_remisi_dewasa: Option<chrono::NaiveDate>,
+    pub jumlah_hari_remisi_dewasa: Option<i32>,
+    pub keterangan: Option<String>,
+    pub catatan: Option<String>,
+}
+
 #[derive(Debug, serde::Serialize, sqlx::FromRow)]
 pub struct WajibLaporDewasa {
     pub id: i64,
@@ -243,6 +294,16 @@ pub struct WajibLaporDewasa {
     pub created_by: Option<i32>,
     pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
     pub created_at: chrono::DateTime<chrono::Utc>, // [PENTING] Kita akan pakai ini
+    // Waktu menurut perangkat untuk laporan yang disinkronkan belakangan
+    pub waktu_lapor_klien: Option<chrono::DateTime<chrono::Utc>>,
+    // Diisi untuk check-in lewat perangkat kiosk
+    pub perangkat_kiosk_id: Option<i32>,
+    // Diisi untuk check-in mandiri dari perangkat klien yang terdaftar
+    pub perangkat_klien_id: Option<i32>,
+    pub photo_hash_dewasa: Option<String>,
+    // Posisi dan segel dalam rantai hash wajib lapor klien
+    pub nomor_urut: i64,
+    pub hash_rantai: String,
 }
 
 // Struct ini juga LEBIH SEDERHANA
@@ -252,9 +313,62 @@ pub struct CreateWajibLapor {
     pub photo_path_dewasa: String,
     pub latitude_dewasa: rust_decimal::Decimal,
     pub longitude_dewasa: rust_decimal::Decimal,
+    // SHA-256 (hex) berkas foto, dihitung perangkat; ikut disegel dalam rantai hash
+    #[serde(default, deserialize_with = "hash_foto_opsional")]
+    pub photo_hash_dewasa: Option<String>,
     pub pin: Option<String>,
 }
 
+fn hash_foto_opsional<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match <Option<String> as serde::Deserialize>::deserialize(deserializer)? {
+        Some(h) if h.len() == 64 && h.chars().all(|c| c.is_ascii_hexdigit()) => Ok(Some(h.to_ascii_lowercase())),
+        Some(_) => Err(serde::de::Error::custom("photo_hash_dewasa harus berupa SHA-256 hex (64 karakter)")),
+        None => Ok(None),
+    }
+}
+
+// Body DELETE /api/wajib-lapor-dewasa/:id; data lapor adalah bukti, jadi alasan wajib
+#[derive(Debug, serde::Deserialize)]
+pub struct HapusWajibLapor {
+    pub alasan_penghapusan: String,
+}
+
+#[derive(Debug, serde::Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum JenisMasalahRantai {
+    // Isi baris tidak cocok lagi dengan hash yang tersimpan
+    IsiBerubah,
+    // hash_sebelumnya tidak menunjuk ke hash baris sebelumnya
+    RantaiPutus,
+    // Ada nomor urut yang hilang (baris dihapus permanen)
+    NomorHilang,
+    // Baris terakhir tidak cocok dengan ujung rantai yang tercatat
+    UjungTidakCocok,
+    DihapusTanpaAlasan,
+    // Status hapus/pulih berubah tanpa disegel ulang oleh aplikasi
+    PenghapusanBerubah,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct MasalahRantai {
+    pub wajib_lapor_id: Option<i64>,
+    pub nomor_urut: Option<i64>,
+    pub jenis: JenisMasalahRantai,
+    pub keterangan: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct VerifikasiRantaiWajibLapor {
+    pub klien_id: i32,
+    pub utuh: bool,
+    pub jumlah_baris: usize,
+    pub jumlah_tercatat: i64,
+    pub jumlah_dihapus: usize,
+    pub masalah: Vec<MasalahRantai>,
+}
+
 // 3. Struct untuk MENGUPDATE data (jika diperlukan di masa depan).
 //    Untuk saat ini, kita tidak punya endpoint UPDATE untuk wajib lapor,
 //    tapi ada baiknya didefinisikan untuk kelengkapan.
@@ -0,0 +1,249 @@
+// File baru: src/klien/rantai_lapor.rs
+//
+// Verifikasi rantai hash wajib lapor dewasa. Hash dihitung ulang oleh fungsi
+// SQL `hash_wajib_lapor_dewasa` (definisi yang sama dengan trigger penyegel),
+// lalu setiap baris dicocokkan dengan baris sebelumnya dan ujung rantai.
+// Status penghapusan dicocokkan dengan segelnya sendiri (hash_penghapusan).
+//
+// Hash berupa HMAC dengan kunci RANTAI_LAPOR_SECRET. Kunci hanya hidup sebagai
+// setting sesi di koneksi milik aplikasi (lihat `pasang_kunci`), tidak pernah
+// disimpan di database.
+
+use sqlx::{PgConnection, PgPool};
+use super::model_dewasa::{JenisMasalahRantai, MasalahRantai, VerifikasiRantaiWajibLapor};
+
+struct BarisRantai {
+    id: i64,
+    nomor_urut: i64,
+    hash_sebelumnya: Option<String>,
+    hash_rantai: String,
+    hash_hitung: String,
+    hash_penghapusan: String,
+    hash_penghapusan_hitung: String,
+    dihapus: bool,
+    alasan_penghapusan: Option<String>,
+}
+
+fn masalah(baris: Option<&BarisRantai>, jenis: JenisMasalahRantai, keterangan: String) -> MasalahRantai {
+    MasalahRantai {
+        wajib_lapor_id: baris.map(|b| b.id),
+        nomor_urut: baris.map(|b| b.nomor_urut),
+        jenis,
+        keterangan,
+    }
+}
+
+fn periksa(baris: &[BarisRantai], jumlah_tercatat: i64, hash_terakhir: Option<&str>) -> Vec<MasalahRantai> {
+    let mut hasil = Vec::new();
+    let mut nomor_berikutnya = 1;
+    let mut hash_lalu: Option<&str> = None;
+
+    for b in baris {
+        if b.nomor_urut != nomor_berikutnya {
+            let rentang = if b.nomor_urut - 1 == nomor_berikutnya {
+                nomor_berikutnya.to_string()
+            } else {
+                format!("{} sampai {}", nomor_berikutnya, b.nomor_urut - 1)
+            };
+            hasil.push(masalah(
+                Some(b),
+                JenisMasalahRantai::NomorHilang,
+                format!("nomor urut {} tidak ditemukan", rentang),
+            ));
+        } else if b.hash_sebelumnya.as_deref() != hash_lalu {
+            hasil.push(masalah(
+                Some(b),
+                JenisMasalahRantai::RantaiPutus,
+                "hash_sebelumnya tidak sama dengan hash baris sebelumnya".to_string(),
+            ));
+        }
+        if b.hash_rantai != b.hash_hitung {
+            hasil.push(masalah(
+                Some(b),
+                JenisMasalahRantai::IsiBerubah,
+                "isi baris berubah setelah disegel".to_string(),
+            ));
+        }
+        if b.hash_penghapusan != b.hash_penghapusan_hitung {
+            hasil.push(masalah(
+                Some(b),
+                JenisMasalahRantai::PenghapusanBerubah,
+                "status penghapusan berubah di luar aplikasi".to_string(),
+            ));
+        }
+        if b.dihapus && b.alasan_penghapusan.as_deref().is_none_or(|a| a.trim().is_empty()) {
+            hasil.push(masalah(
+                Some(b),
+                JenisMasalahRantai::DihapusTanpaAlasan,
+                "baris dihapus tanpa alasan tercatat".to_string(),
+            ));
+        }
+        nomor_berikutnya = b.nomor_urut + 1;
+        hash_lalu = Some(&b.hash_rantai);
+    }
+
+    let nomor_terakhir = baris.last().map_or(0, |b| b.nomor_urut);
+    if nomor_terakhir != jumlah_tercatat || hash_lalu != hash_terakhir {
+        hasil.push(masalah(
+            baris.last(),
+            JenisMasalahRantai::UjungTidakCocok,
+            format!(
+                "ujung rantai mencatat {} baris, baris terakhir bernomor {}",
+                jumlah_tercatat, nomor_terakhir
+            ),
+        ));
+    }
+    hasil
+}
+
+/// Dipanggil untuk setiap koneksi baru di pool; trigger penyegel dan verifikasi
+/// menolak bekerja tanpa kunci ini.
+pub async fn pasang_kunci(conn: &mut PgConnection, kunci: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT set_config('aksara.kunci_rantai_lapor', $1, false)")
+        .bind(kunci)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+pub async fn verifikasi_rantai(pool: &PgPool, klien_id: i32) -> Result<VerifikasiRantaiWajibLapor, sqlx::Error> {
+    // Baris terhapus (soft delete) tetap bagian dari rantai
+    let baris = sqlx::query_as!(
+        BarisRantai,
+        r#"
+        SELECT w.id, w.nomor_urut, w.hash_sebelumnya, w.hash_rantai,
+            hash_wajib_lapor_dewasa(w) AS "hash_hitung!",
+            w.hash_penghapusan, hash_penghapusan_wajib_lapor_dewasa(w) AS "hash_penghapusan_hitung!",
+            w.deleted_at IS NOT NULL AS "dihapus!", w.alasan_penghapusan
+        FROM wajib_lapor_dewasa w
+        WHERE w.klien_id = $1
+        ORDER BY w.nomor_urut
+        "#,
+        klien_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let ujung = sqlx::query!(
+        "SELECT jumlah, hash_terakhir FROM rantai_wajib_lapor_dewasa WHERE klien_id = $1",
+        klien_id
+    )
+    .fetch_optional(pool)
+    .await?;
+    let (jumlah_tercatat, hash_terakhir) = ujung.map_or((0, None), |u| (u.jumlah, u.hash_terakhir));
+
+    let masalah = periksa(&baris, jumlah_tercatat, hash_terakhir.as_deref());
+    Ok(VerifikasiRantaiWajibLapor {
+        klien_id,
+        utuh: masalah.is_empty(),
+        jumlah_baris: baris.len(),
+        jumlah_tercatat,
+        jumlah_dihapus: baris.iter().filter(|b| b.dihapus).count(),
+        masalah,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn baris(nomor_urut: i64, hash_sebelumnya: Option<&str>, hash_rantai: &str) -> BarisRantai {
+        BarisRantai {
+            id: nomor_urut * 10,
+            nomor_urut,
+            hash_sebelumnya: hash_sebelumnya.map(str::to_string),
+            hash_rantai: hash_rantai.to_string(),
+            hash_hitung: hash_rantai.to_string(),
+            hash_penghapusan: format!("h{}", hash_rantai),
+            hash_penghapusan_hitung: format!("h{}", hash_rantai),
+            dihapus: false,
+            alasan_penghapusan: None,
+        }
+    }
+
+    fn rantai_utuh() -> Vec<BarisRantai> {
+        vec![baris(1, None, "a"), baris(2, Some("a"), "b"), baris(3, Some("b"), "c")]
+    }
+
+    fn jenis(masalah: &[MasalahRantai]) -> Vec<JenisMasalahRantai> {
+        masalah.iter().map(|m| m.jenis).collect()
+    }
+
+    #[test]
+    fn rantai_utuh_tanpa_masalah() {
+        assert!(periksa(&rantai_utuh(), 3, Some("c")).is_empty());
+    }
+
+    #[test]
+    fn rantai_kosong_cocok_dengan_ujung_kosong() {
+        assert!(periksa(&[], 0, None).is_empty());
+    }
+
+    #[test]
+    fn isi_berubah_terdeteksi() {
+        let mut rantai = rantai_utuh();
+        rantai[1].hash_hitung = "x".to_string();
+        let hasil = periksa(&rantai, 3, Some("c"));
+        assert_eq!(jenis(&hasil), vec![JenisMasalahRantai::IsiBerubah]);
+        assert_eq!(hasil[0].wajib_lapor_id, Some(20));
+    }
+
+    #[test]
+    fn baris_tengah_hilang_dilaporkan_dengan_rentang() {
+        let rantai = vec![baris(1, None, "a"), baris(4, Some("d"), "e")];
+        let hasil = periksa(&rantai, 4, Some("e"));
+        assert_eq!(jenis(&hasil), vec![JenisMasalahRantai::NomorHilang]);
+        assert_eq!(hasil[0].keterangan, "nomor urut 2 sampai 3 tidak ditemukan");
+        assert_eq!(hasil[0].nomor_urut, Some(4));
+    }
+
+    #[test]
+    fn hash_sebelumnya_tidak_cocok_memutus_rantai() {
+        let mut rantai = rantai_utuh();
+        rantai[2].hash_sebelumnya = Some("z".to_string());
+        let hasil = periksa(&rantai, 3, Some("c"));
+        assert_eq!(jenis(&hasil), vec![JenisMasalahRantai::RantaiPutus]);
+        assert_eq!(hasil[0].nomor_urut, Some(3));
+    }
+
+    #[test]
+    fn baris_terakhir_hilang_terdeteksi_dari_ujung() {
+        let mut rantai = rantai_utuh();
+        rantai.pop();
+        let hasil = periksa(&rantai, 3, Some("c"));
+        assert_eq!(jenis(&hasil), vec![JenisMasalahRantai::UjungTidakCocok]);
+    }
+
+    #[test]
+    fn ujung_dengan_hash_berbeda_terdeteksi() {
+        let hasil = periksa(&rantai_utuh(), 3, Some("x"));
+        assert_eq!(jenis(&hasil), vec![JenisMasalahRantai::UjungTidakCocok]);
+    }
+
+    #[test]
+    fn penghapusan_tanpa_segel_ulang_terdeteksi() {
+        let mut rantai = rantai_utuh();
+        rantai[1].dihapus = true;
+        rantai[1].alasan_penghapusan = Some("Foto ganda".to_string());
+        rantai[1].hash_penghapusan_hitung = "y".to_string();
+        let hasil = periksa(&rantai, 3, Some("c"));
+        assert_eq!(jenis(&hasil), vec![JenisMasalahRantai::PenghapusanBerubah]);
+        assert_eq!(hasil[0].wajib_lapor_id, Some(20));
+    }
+
+    #[test]
+    fn hapus_tanpa_alasan_dilaporkan() {
+        let mut rantai = rantai_utuh();
+        rantai[0].dihapus = true;
+        rantai[1].dihapus = true;
+        rantai[1].alasan_penghapusan = Some("  ".to_string());
+        rantai[2].dihapus = true;
+        rantai[2].alasan_penghapusan = Some("Foto ganda".to_string());
+        let hasil = periksa(&rantai, 3, Some("c"));
+        assert_eq!(
+            jenis(&hasil),
+            vec![JenisMasalahRantai::DihapusTanpaAlasan, JenisMasalahRantai::DihapusTanpaAlasan]
+        );
+        assert_eq!(hasil.iter().map(|m| m.wajib_lapor_id).collect::<Vec<_>>(), vec![Some(10), Some(20)]);
+    }
+}
@@ -0,0 +1,513 @@
+// File baru: src/kunjungan/handlers.rs
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Datelike, Months, NaiveDate};
+use sqlx::{PgConnection, PgPool};
+use crate::auth::authorization::ensure_klien_access;
+use crate::auth::model::AuthenticatedUser;
+use crate::penomoran::service::hari_ini;
+use crate::types::UserRoleEnum;
+use super::model::{
+    CatatanLapangan, CreateKunjunganRumah, CreateTindakLanjut, GetAllKunjunganParams, KunjunganRumah,
+    KunjunganRumahDetail, KunjunganRumahRingkas, OrangDitemui, TindakLanjutKunjungan, UpdateKunjunganRumah,
+    UpdateTindakLanjut,
+};
+
+async fn get_kunjungan(pool: &PgPool, id: i32) -> Result<KunjunganRumah, StatusCode> {
+    sqlx::query_as!(
+        KunjunganRumah,
+        r#"
+        SELECT id, klien_id, penerimaan_dewasa_id, petugas_id, waktu_kunjungan,
+            tujuan_kunjungan AS "tujuan_kunjungan: _", alamat_kunjungan, latitude, longitude, photo_paths,
+            temuan_kondisi_rumah, temuan_hubungan_keluarga, temuan_lingkungan, temuan_penjamin,
+            temuan_pekerjaan, kesimpulan, created_at, updated_at, created_by, updated_by, deleted_at
+        FROM kunjungan_rumah WHERE id = $1 AND deleted_at IS NULL
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)
+}
+
+pub async fn muat_detail(pool: &PgPool, kunjungan: KunjunganRumah) -> Result<KunjunganRumahDetail, StatusCode> {
+    let orang_ditemui = sqlx::query_as!(
+        OrangDitemui,
+        "SELECT nama, hubungan_dengan_klien FROM orang_ditemui_kunjungan WHERE kunjungan_rumah_id = $1 ORDER BY id",
+        kunjungan.id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let tindak_lanjut = sqlx::query_as!(
+        TindakLanjutKunjungan,
+        "SELECT * FROM tindak_lanjut_kunjungan WHERE kunjungan_rumah_id = $1 ORDER BY id",
+        kunjungan.id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(KunjunganRumahDetail { kunjungan, orang_ditemui, tindak_lanjut })
+}
+
+async fn simpan_orang_ditemui(
+    conn: &mut PgConnection,
+    kunjungan_id: i32,
+    orang: &[OrangDitemui],
+) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM orang_ditemui_kunjungan WHERE kunjungan_rumah_id = $1", kunjungan_id)
+        .execute(&mut *conn)
+        .await?;
+    for o in orang {
+        sqlx::query!(
+            "INSERT INTO orang_ditemui_kunjungan (kunjungan_rumah_id, nama, hubungan_dengan_klien) VALUES ($1, $2, $3)",
+            kunjungan_id,
+            o.nama,
+            o.hubungan_dengan_klien
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+// Dipakai juga oleh sinkronisasi offline. Penerimaan yang bukan milik klien
+// ditolak dengan BAD_REQUEST.
+pub async fn simpan_kunjungan_rumah(
+    conn: &mut PgConnection,
+    klien_id: i32,
+    user_id: i32,
+    payload: &CreateKunjunganRumah,
+) -> Result<KunjunganRumah, StatusCode> {
+    if let Some(penerimaan_id) = payload.penerimaan_dewasa_id {
+        let milik_klien = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM penerimaan_dewasa WHERE id = $1 AND klien_id = $2 AND deleted_at IS NULL) AS "ada!""#,
+            penerimaan_id,
+            klien_id
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        if !milik_klien {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let kunjungan = sqlx::query_as!(
+        KunjunganRumah,
+        r#"
+        INSERT INTO kunjungan_rumah (
+            klien_id, penerimaan_dewasa_id, petugas_id, waktu_kunjungan, tujuan_kunjungan, alamat_kunjungan,
+            latitude, longitude, photo_paths, temuan_kondisi_rumah, temuan_hubungan_keluarga,
+            temuan_lingkungan, temuan_penjamin, temuan_pekerjaan, kesimpulan, created_by, updated_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $3, $3)
+        RETURNING id, klien_id, penerimaan_dewasa_id, petugas_id, waktu_kunjungan,
+            tujuan_kunjungan AS "tujuan_kunjungan: _", alamat_kunjungan, latitude, longitude, photo_paths,
+            temuan_kondisi_rumah, temuan_hubungan_keluarga, temuan_lingkungan, temuan_penjamin,
+            temuan_pekerjaan, kesimpulan, created_at, updated_at, created_by, updated_by, deleted_at
+        "#,
+        klien_id,
+        payload.penerimaan_dewasa_id,
+        user_id,
+        payload.waktu_kunjungan,
+        payload.tujuan_kunjungan as _,
+        payload.alamat_kunjungan,
+        payload.latitude,
+        payload.longitude,
+        &payload.photo_paths,
+        payload.temuan_kondisi_rumah,
+        payload.temuan_hubungan_keluarga,
+        payload.temuan_lingkungan,
+        payload.temuan_penjamin,
+        payload.temuan_pekerjaan,
+        payload.kesimpulan
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create kunjungan rumah: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    simpan_orang_ditemui(conn, kunjungan.id, &payload.orang_ditemui)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    for tindak in &payload.tindak_lanjut {
+        sqlx::query!(
+            "INSERT INTO tindak_lanjut_kunjungan (kunjungan_rumah_id, uraian, batas_waktu, updated_by) VALUES ($1, $2, $3, $4)",
+            kunjungan.id,
+            tindak.uraian,
+            tindak.batas_waktu,
+            user_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    }
+    Ok(kunjungan)
+}
+
+// --- CREATE ---
+// URL: POST /api/klien/:klien_id/kunjungan-rumah
+#[axum::debug_handler]
+pub async fn create_kunjungan_rumah(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(klien_id): Path<i32>, // Diambil dari URL, sudah diautorisasi oleh middleware
+    Json(payload): Json<CreateKunjunganRumah>,
+) -> Result<(StatusCode, Json<KunjunganRumahDetail>), StatusCode> {
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let kunjungan = simpan_kunjungan_rumah(&mut tx, klien_id, user.id, &payload).await?;
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok((StatusCode::CREATED, Json(muat_detail(&pool, kunjungan).await?)))
+}
+
+// --- READ ALL FOR A KLIEN ---
+// URL: GET /api/klien/:klien_id/kunjungan-rumah
+#[axum::debug_handler]
+pub async fn get_all_kunjungan_for_klien(
+    Extension(pool): Extension<PgPool>,
+    Path(klien_id): Path<i32>,
+) -> Result<Json<Vec<KunjunganRumah>>, StatusCode> {
+    let list = sqlx::query_as!(
+        KunjunganRumah,
+        r#"
+        SELECT id, klien_id, penerimaan_dewasa_id, petugas_id, waktu_kunjungan,
+            tujuan_kunjungan AS "tujuan_kunjungan: _", alamat_kunjungan, latitude, longitude, photo_paths,
+            temuan_kondisi_rumah, temuan_hubungan_keluarga, temuan_lingkungan, temuan_penjamin,
+            temuan_pekerjaan, kesimpulan, created_at, updated_at, created_by, updated_by, deleted_at
+        FROM kunjungan_rumah
+        WHERE klien_id = $1 AND deleted_at IS NULL
+        ORDER BY waktu_kunjungan DESC
+        "#,
+        klien_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch kunjungan rumah for klien: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// --- CATATAN LAPANGAN PER KLIEN ---
+// URL: GET /api/klien/:klien_id/catatan-lapangan
+#[axum::debug_handler]
+pub async fn get_all_catatan_lapangan_for_klien(
+    Extension(pool): Extension<PgPool>,
+    Path(klien_id): Path<i32>,
+) -> Result<Json<Vec<CatatanLapangan>>, StatusCode> {
+    let list = sqlx::query_as!(
+        CatatanLapangan,
+        "SELECT * FROM catatan_lapangan WHERE klien_id = $1 AND deleted_at IS NULL ORDER BY waktu_catat DESC",
+        klien_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch catatan lapangan for klien: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// --- READ ALL (PER PK / PER BULAN) ---
+// URL: GET /api/kunjungan-rumah?petugas_id=&bapas_id=&tahun=&bulan=
+#[axum::debug_handler]
+pub async fn get_all_kunjungan_rumah(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(params): Query<GetAllKunjunganParams>,
+) -> Result<Json<Vec<KunjunganRumahRingkas>>, StatusCode> {
+    let mut query_builder = sqlx::QueryBuilder::new(
+        r#"
+        SELECT kr.id, kr.klien_id, k.nama_klien, kr.penerimaan_dewasa_id, kr.petugas_id,
+            u.nama_user AS nama_petugas, kr.waktu_kunjungan, kr.tujuan_kunjungan, kr.kesimpulan,
+            (SELECT COUNT(*) FROM tindak_lanjut_kunjungan t
+             WHERE t.kunjungan_rumah_id = kr.id AND NOT t.selesai) AS jumlah_tindak_lanjut_terbuka
+        FROM kunjungan_rumah kr
+        JOIN klien k ON k.id = kr.klien_id
+        JOIN users u ON u.id = kr.petugas_id
+        WHERE kr.deleted_at IS NULL
+        "#,
+    );
+
+    // Terapkan filter berdasarkan role user untuk keamanan
+    match user.role {
+        UserRoleEnum::SuperAdmin => {
+            if let Some(bapas_id) = params.bapas_id {
+                query_builder.push(" AND k.bapas_id = ").push_bind(bapas_id);
+            }
+        }
+        UserRoleEnum::AdminKanwil => {
+            query_builder.push(" AND k.kanwil_id = ").push_bind(user.kanwil_id);
+            if let Some(bapas_id) = params.bapas_id {
+                query_builder.push(" AND k.bapas_id = ").push_bind(bapas_id);
+            }
+        }
+        UserRoleEnum::AdminBapas => {
+            query_builder.push(" AND k.bapas_id = ").push_bind(user.bapas_id);
+        }
+        UserRoleEnum::Pegawai => {
+            // Pegawai hanya melihat kunjungan yang ia lakukan sendiri
+            query_builder.push(" AND kr.petugas_id = ").push_bind(user.id);
+        }
+    }
+
+    if let Some(petugas_id) = params.petugas_id {
+        query_builder.push(" AND kr.petugas_id = ").push_bind(petugas_id);
+    }
+
+    // Bulan dihitung menurut WIB; tanpa tahun dianggap tahun berjalan
+    if params.tahun.is_some() || params.bulan.is_some() {
+        let tahun = params.tahun.unwrap_or_else(|| hari_ini().year());
+        let (awal, akhir) = match params.bulan {
+            Some(bulan) => {
+                let awal = NaiveDate::from_ymd_opt(tahun, bulan, 1).ok_or(StatusCode::BAD_REQUEST)?;
+                (awal, awal + Months::new(1))
+            }
+            None => {
+                let awal = NaiveDate::from_ymd_opt(tahun, 1, 1).ok_or(StatusCode::BAD_REQUEST)?;
+                (awal, awal + Months::new(12))
+            }
+        };
+        query_builder
+            .push(" AND (kr.waktu_kunjungan AT TIME ZONE 'Asia/Jakarta')::DATE >= ")
+            .push_bind(awal)
+            .push(" AND (kr.waktu_kunjungan AT TIME ZONE 'Asia/Jakarta')::DATE < ")
+            .push_bind(akhir);
+    }
+
+    query_builder.push(" ORDER BY kr.waktu_kunjungan DESC");
+
+    let list = query_builder
+        .build_query_as::<KunjunganRumahRingkas>()
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch kunjungan rumah list: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(list))
+}
+
+// --- READ ONE ---
+// URL: GET /api/kunjungan-rumah/:id
+#[axum::debug_handler]
+pub async fn get_kunjungan_rumah_by_id(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> Result<Json<KunjunganRumahDetail>, StatusCode> {
+    let kunjungan = get_kunjungan(&pool, id).await?;
+    if kunjungan.petugas_id != user.id {
+        ensure_klien_access(&pool, &user, kunjungan.klien_id).await?;
+    }
+    Ok(Json(muat_detail(&pool, kunjungan).await?))
+}
+
+// --- UPDATE ---
+// URL: PUT /api/kunjungan-rumah/:id
+#[axum::debug_handler]
+pub async fn update_kunjungan_rumah(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateKunjunganRumah>,
+) -> Result<Json<KunjunganRumahDetail>, StatusCode> {
+    let existing = get_kunjungan(&pool, id).await?;
+    if existing.petugas_id != user.id {
+        ensure_klien_access(&pool, &user, existing.klien_id).await?;
+    }
+
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let kunjungan = sqlx::query_as!(
+        KunjunganRumah,
+        r#"
+        UPDATE kunjungan_rumah SET
+            waktu_kunjungan = COALESCE($1, waktu_kunjungan),
+            tujuan_kunjungan = COALESCE($2, tujuan_kunjungan),
+            alamat_kunjungan = COALESCE($3, alamat_kunjungan),
+            latitude = COALESCE($4, latitude),
+            longitude = COALESCE($5, longitude),
+            photo_paths = COALESCE($6, photo_paths),
+            temuan_kondisi_rumah = COALESCE($7, temuan_kondisi_rumah),
+            temuan_hubungan_keluarga = COALESCE($8, temuan_hubungan_keluarga),
+            temuan_lingkungan = COALESCE($9, temuan_lingkungan),
+            temuan_penjamin = COALESCE($10, temuan_penjamin),
+            temuan_pekerjaan = COALESCE($11, temuan_pekerjaan),
+            kesimpulan = COALESCE($12, kesimpulan),
+            updated_by = $13
+        WHERE id = $14 AND deleted_at IS NULL
+        RETURNING id, klien_id, penerimaan_dewasa_id, petugas_id, waktu_kunjungan,
+            tujuan_kunjungan AS "tujuan_kunjungan: _", alamat_kunjungan, latitude, longitude, photo_paths,
+            temuan_kondisi_rumah, temuan_hubungan_keluarga, temuan_lingkungan, temuan_penjamin,
+            temuan_pekerjaan, kesimpulan, created_at, updated_at, created_by, updated_by, deleted_at
+        "#,
+        payload.waktu_kunjungan,
+        payload.tujuan_kunjungan as _,
+        payload.alamat_kunjungan,
+        payload.latitude,
+        payload.longitude,
+        payload.photo_paths.as_deref(),
+        payload.temuan_kondisi_rumah,
+        payload.temuan_hubungan_keluarga,
+        payload.temuan_lingkungan,
+        payload.temuan_penjamin,
+        payload.temuan_pekerjaan,
+        payload.kesimpulan,
+        user.id,
+        id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update kunjungan rumah {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    if let Some(orang) = &payload.orang_ditemui {
+        simpan_orang_ditemui(&mut tx, id, orang)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    }
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(muat_detail(&pool, kunjungan).await?))
+}
+
+// --- DELETE (SOFT) ---
+// URL: DELETE /api/kunjungan-rumah/:id
+#[axum::debug_handler]
+pub async fn delete_kunjungan_rumah(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> StatusCode {
+    let existing = match get_kunjungan(&pool, id).await {
+        Ok(kunjungan) => kunjungan,
+        Err(status) => return status,
+    };
+    if let Err(status) = ensure_klien_access(&pool, &user, existing.klien_id).await {
+        return status;
+    }
+
+    let result = sqlx::query!(
+        "UPDATE kunjungan_rumah SET deleted_at = NOW(), updated_by = $1 WHERE id = $2",
+        user.id,
+        id
+    )
+    .execute(&pool)
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => StatusCode::NO_CONTENT,
+        Ok(_) => StatusCode::NOT_FOUND,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+// --- TINDAK LANJUT ---
+// URL: POST /api/kunjungan-rumah/:id/tindak-lanjut
+#[axum::debug_handler]
+pub async fn create_tindak_lanjut_kunjungan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<CreateTindakLanjut>,
+) -> Result<(StatusCode, Json<TindakLanjutKunjungan>), StatusCode> {
+    let kunjungan = get_kunjungan(&pool, id).await?;
+    if kunjungan.petugas_id != user.id {
+        ensure_klien_access(&pool, &user, kunjungan.klien_id).await?;
+    }
+
+    let tindak = sqlx::query_as!(
+        TindakLanjutKunjungan,
+        r#"
+        INSERT INTO tindak_lanjut_kunjungan (kunjungan_rumah_id, uraian, batas_waktu, updated_by)
+        VALUES ($1, $2, $3, $4)
+        RETURNING *
+        "#,
+        id,
+        payload.uraian,
+        payload.batas_waktu,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create tindak lanjut kunjungan: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((StatusCode::CREATED, Json(tindak)))
+}
+
+// URL: PUT /api/tindak-lanjut-kunjungan/:id
+// Menandai selesai mengisi tanggal_selesai dengan hari ini (WIB).
+#[axum::debug_handler]
+pub async fn update_tindak_lanjut_kunjungan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateTindakLanjut>,
+) -> Result<Json<TindakLanjutKunjungan>, StatusCode> {
+    let kunjungan_id = sqlx::query_scalar!(
+        "SELECT kunjungan_rumah_id FROM tindak_lanjut_kunjungan WHERE id = $1",
+        id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)?;
+    let kunjungan = get_kunjungan(&pool, kunjungan_id).await?;
+    if kunjungan.petugas_id != user.id {
+        ensure_klien_access(&pool, &user, kunjungan.klien_id).await?;
+    }
+
+    let tindak = sqlx::query_as!(
+        TindakLanjutKunjungan,
+        r#"
+        UPDATE tindak_lanjut_kunjungan SET
+            uraian = COALESCE($1, uraian),
+            batas_waktu = COALESCE($2, batas_waktu),
+            selesai = COALESCE($3, selesai),
+            tanggal_selesai = CASE
+                WHEN $3 IS TRUE THEN COALESCE(tanggal_selesai, $4)
+                WHEN $3 IS FALSE THEN NULL
+                ELSE tanggal_selesai
+            END,
+            updated_by = $5
+        WHERE id = $6
+        RETURNING *
+        "#,
+        payload.uraian,
+        payload.batas_waktu,
+        payload.selesai,
+        hari_ini(),
+        user.id,
+        id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update tindak lanjut kunjungan {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(tindak))
+}
@@ -0,0 +1,156 @@
+// File baru: src/kunjungan/model.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use crate::types::TujuanKunjunganEnum;
+
+// Merepresentasikan satu baris dari tabel 'kunjungan_rumah'
+#[derive(Debug, Serialize, FromRow)]
+pub struct KunjunganRumah {
+    pub id: i32,
+    pub klien_id: i32,
+    pub penerimaan_dewasa_id: Option<i32>,
+    pub petugas_id: i32,
+    pub waktu_kunjungan: DateTime<Utc>,
+    pub tujuan_kunjungan: TujuanKunjunganEnum,
+    pub alamat_kunjungan: Option<String>,
+    pub latitude: Option<Decimal>,
+    pub longitude: Option<Decimal>,
+    pub photo_paths: Vec<String>,
+    pub temuan_kondisi_rumah: Option<String>,
+    pub temuan_hubungan_keluarga: Option<String>,
+    pub temuan_lingkungan: Option<String>,
+    pub temuan_penjamin: Option<String>,
+    pub temuan_pekerjaan: Option<String>,
+    pub kesimpulan: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct OrangDitemui {
+    pub nama: String,
+    pub hubungan_dengan_klien: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct TindakLanjutKunjungan {
+    pub id: i32,
+    pub kunjungan_rumah_id: i32,
+    pub uraian: String,
+    pub batas_waktu: Option<NaiveDate>,
+    pub selesai: bool,
+    pub tanggal_selesai: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub updated_by: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTindakLanjut {
+    pub uraian: String,
+    pub batas_waktu: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateTindakLanjut {
+    pub uraian: Option<String>,
+    pub batas_waktu: Option<NaiveDate>,
+    pub selesai: Option<bool>,
+}
+
+// Data untuk mencatat kunjungan. Petugas = pengguna yang mencatat.
+#[derive(Debug, Deserialize)]
+pub struct CreateKunjunganRumah {
+    pub penerimaan_dewasa_id: Option<i32>,
+    pub waktu_kunjungan: DateTime<Utc>,
+    pub tujuan_kunjungan: TujuanKunjunganEnum,
+    pub alamat_kunjungan: Option<String>,
+    pub latitude: Option<Decimal>,
+    pub longitude: Option<Decimal>,
+    #[serde(default)]
+    pub photo_paths: Vec<String>,
+    pub temuan_kondisi_rumah: Option<String>,
+    pub temuan_hubungan_keluarga: Option<String>,
+    pub temuan_lingkungan: Option<String>,
+    pub temuan_penjamin: Option<String>,
+    pub temuan_pekerjaan: Option<String>,
+    pub kesimpulan: Option<String>,
+    #[serde(default)]
+    pub orang_ditemui: Vec<OrangDitemui>,
+    #[serde(default)]
+    pub tindak_lanjut: Vec<CreateTindakLanjut>,
+}
+
+// Daftar orang ditemui dan foto diganti seluruhnya jika dikirim
+#[derive(Debug, Deserialize)]
+pub struct UpdateKunjunganRumah {
+    pub waktu_kunjungan: Option<DateTime<Utc>>,
+    pub tujuan_kunjungan: Option<TujuanKunjunganEnum>,
+    pub alamat_kunjungan: Option<String>,
+    pub latitude: Option<Decimal>,
+    pub longitude: Option<Decimal>,
+    pub photo_paths: Option<Vec<String>>,
+    pub temuan_kondisi_rumah: Option<String>,
+    pub temuan_hubungan_keluarga: Option<String>,
+    pub temuan_lingkungan: Option<String>,
+    pub temuan_penjamin: Option<String>,
+    pub temuan_pekerjaan: Option<String>,
+    pub kesimpulan: Option<String>,
+    pub orang_ditemui: Option<Vec<OrangDitemui>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KunjunganRumahDetail {
+    #[serde(flatten)]
+    pub kunjungan: KunjunganRumah,
+    pub orang_ditemui: Vec<OrangDitemui>,
+    pub tindak_lanjut: Vec<TindakLanjutKunjungan>,
+}
+
+// Query string untuk GET /api/kunjungan-rumah
+#[derive(Debug, Deserialize)]
+pub struct GetAllKunjunganParams {
+    pub petugas_id: Option<i32>,
+    pub bapas_id: Option<i32>,
+    pub tahun: Option<i32>,
+    pub bulan: Option<u32>,
+}
+
+// Baris daftar kunjungan per PK/bulan
+#[derive(Debug, Serialize, FromRow)]
+pub struct KunjunganRumahRingkas {
+    pub id: i32,
+    pub klien_id: i32,
+    pub nama_klien: String,
+    pub penerimaan_dewasa_id: Option<i32>,
+    pub petugas_id: i32,
+    pub nama_petugas: String,
+    pub waktu_kunjungan: DateTime<Utc>,
+    pub tujuan_kunjungan: TujuanKunjunganEnum,
+    pub kesimpulan: Option<String>,
+    pub jumlah_tindak_lanjut_terbuka: i64,
+}
+
+// Catatan singkat PK di lapangan, bisa terkait dengan sebuah kunjungan.
+// Saat ini dibuat lewat sinkronisasi offline.
+#[derive(Debug, Serialize, FromRow)]
+pub struct CatatanLapangan {
+    pub id: i32,
+    pub klien_id: i32,
+    pub kunjungan_rumah_id: Option<i32>,
+    pub isi: String,
+    pub waktu_catat: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,248 @@
+// File baru: src/laporan/handlers.rs
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Months;
+use sqlx::types::Json as SqlJson;
+use sqlx::PgPool;
+use crate::auth::authorization::{check_permission, ensure_bapas_access, ResourceOwnership};
+use crate::auth::model::AuthenticatedUser;
+use crate::dokumen::{pdf, xlsx};
+use crate::penomoran::service::hari_ini;
+use crate::types::UserRoleEnum;
+use super::model::{
+    CakupanLaporan, FilterLaporanBulanan, FormatLaporan, IsiLaporan, LaporanBulanan, RingkasanLaporanBulanan,
+    UnduhLaporanParams,
+};
+use super::{service, susun};
+
+/// Laporan Bapas: staf Bapas tersebut boleh melihat, hanya admin yang boleh mengirim.
+/// Laporan Kanwil: hanya SuperAdmin atau AdminKanwil dari Kanwil tersebut.
+async fn ensure_cakupan_access(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    bapas_id: Option<i32>,
+    kanwil_id: Option<i32>,
+    kirim: bool,
+) -> Result<(), StatusCode> {
+    match (bapas_id, kanwil_id) {
+        (Some(bapas_id), None) => ensure_bapas_access(pool, user, bapas_id, kirim).await,
+        (None, Some(kanwil_id)) => {
+            let ada = sqlx::query_scalar!(
+                r#"SELECT EXISTS(SELECT 1 FROM kanwil WHERE id = $1 AND deleted_at IS NULL) AS "ada!""#,
+                kanwil_id
+            )
+            .fetch_one(pool)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            if !ada {
+                return Err(StatusCode::NOT_FOUND);
+            }
+            let ownership = ResourceOwnership { kanwil_id: Some(kanwil_id), ..Default::default() };
+            let admin_kanwil = matches!(user.role, UserRoleEnum::SuperAdmin | UserRoleEnum::AdminKanwil);
+            if !admin_kanwil || !check_permission(user, &ownership) {
+                return Err(StatusCode::FORBIDDEN);
+            }
+            Ok(())
+        }
+        // Tepat satu dari bapas_id / kanwil_id
+        _ => Err(StatusCode::BAD_REQUEST),
+    }
+}
+
+async fn get_laporan(pool: &PgPool, id: i32) -> Result<LaporanBulanan, StatusCode> {
+    sqlx::query!(
+        r#"
+        SELECT id, bapas_id, kanwil_id, periode, isi_laporan AS "isi_laporan: SqlJson<IsiLaporan>",
+            created_at, created_by
+        FROM laporan_bulanan WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch laporan bulanan: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .map(|r| LaporanBulanan {
+        id: r.id,
+        bapas_id: r.bapas_id,
+        kanwil_id: r.kanwil_id,
+        periode: r.periode,
+        isi_laporan: r.isi_laporan.0,
+        created_at: r.created_at,
+        created_by: r.created_by,
+    })
+    .ok_or(StatusCode::NOT_FOUND)
+}
+
+// --- PRATINJAU (dihitung langsung, tidak disimpan) ---
+// URL: GET /api/laporan-bulanan/pratinjau?bapas_id=1&periode=2025-11
+#[axum::debug_handler]
+pub async fn get_pratinjau_laporan_bulanan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(cakupan): Query<CakupanLaporan>,
+) -> Result<Json<IsiLaporan>, StatusCode> {
+    let periode = service::parse_periode(&cakupan.periode).ok_or(StatusCode::BAD_REQUEST)?;
+    ensure_cakupan_access(&pool, &user, cakupan.bapas_id, cakupan.kanwil_id, false).await?;
+
+    let isi = service::hitung_laporan(&pool, cakupan.bapas_id, cakupan.kanwil_id, periode)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to compute laporan bulanan: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok(Json(isi))
+}
+
+// --- KIRIM (bekukan snapshot) ---
+// URL: POST /api/laporan-bulanan
+// Hanya untuk bulan yang sudah berakhir; satu laporan per cakupan per bulan.
+#[axum::debug_handler]
+pub async fn create_laporan_bulanan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(payload): Json<CakupanLaporan>,
+) -> Result<(StatusCode, Json<LaporanBulanan>), StatusCode> {
+    let periode = service::parse_periode(&payload.periode).ok_or(StatusCode::BAD_REQUEST)?;
+    if periode + Months::new(1) > hari_ini() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    ensure_cakupan_access(&pool, &user, payload.bapas_id, payload.kanwil_id, true).await?;
+
+    let isi = service::hitung_laporan(&pool, payload.bapas_id, payload.kanwil_id, periode)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to compute laporan bulanan: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let row = sqlx::query!(
+        r#"
+        INSERT INTO laporan_bulanan (bapas_id, kanwil_id, periode, isi_laporan, created_by)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, created_at
+        "#,
+        payload.bapas_id,
+        payload.kanwil_id,
+        periode,
+        SqlJson(&isi) as _,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        if e.as_database_error().is_some_and(|d| d.is_unique_violation()) {
+            return StatusCode::CONFLICT;
+        }
+        tracing::error!("Failed to save laporan bulanan: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(LaporanBulanan {
+            id: row.id,
+            bapas_id: payload.bapas_id,
+            kanwil_id: payload.kanwil_id,
+            periode,
+            isi_laporan: isi,
+            created_at: row.created_at,
+            created_by: Some(user.id),
+        }),
+    ))
+}
+
+// --- GET ALL ---
+// URL: GET /api/laporan-bulanan?bapas_id=1 atau ?kanwil_id=1[&tahun=2025]
+// Untuk kanwil_id, ikut ditampilkan laporan yang dikirim Bapas di Kanwil tersebut.
+#[axum::debug_handler]
+pub async fn get_all_laporan_bulanan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(filter): Query<FilterLaporanBulanan>,
+) -> Result<Json<Vec<RingkasanLaporanBulanan>>, StatusCode> {
+    ensure_cakupan_access(&pool, &user, filter.bapas_id, filter.kanwil_id, false).await?;
+
+    let daftar = sqlx::query_as!(
+        RingkasanLaporanBulanan,
+        r#"
+        SELECT l.id, l.bapas_id, l.kanwil_id, l.isi_laporan->>'nama_wilayah' AS "nama_wilayah!",
+            l.periode, l.created_at, l.created_by
+        FROM laporan_bulanan l
+        LEFT JOIN bapas b ON b.id = l.bapas_id
+        WHERE ($1::INT IS NULL OR l.bapas_id = $1)
+            AND ($2::INT IS NULL OR l.kanwil_id = $2 OR b.kanwil_id = $2)
+            AND ($3::INT IS NULL OR EXTRACT(YEAR FROM l.periode) = $3)
+        ORDER BY l.periode DESC, l.kanwil_id NULLS LAST, l.bapas_id
+        "#,
+        filter.bapas_id,
+        filter.kanwil_id,
+        filter.tahun
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch laporan bulanan: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(daftar))
+}
+
+// --- GET BY ID ---
+// URL: GET /api/laporan-bulanan/:id
+#[axum::debug_handler]
+pub async fn get_laporan_bulanan_by_id(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> Result<Json<LaporanBulanan>, StatusCode> {
+    let laporan = get_laporan(&pool, id).await?;
+    ensure_cakupan_access(&pool, &user, laporan.bapas_id, laporan.kanwil_id, false).await?;
+    Ok(Json(laporan))
+}
+
+// --- UNDUH ---
+// URL: GET /api/laporan-bulanan/:id/unduh?format=xlsx|pdf
+#[axum::debug_handler]
+pub async fn unduh_laporan_bulanan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Query(params): Query<UnduhLaporanParams>,
+) -> Result<Response, StatusCode> {
+    let laporan = get_laporan(&pool, id).await?;
+    ensure_cakupan_access(&pool, &user, laporan.bapas_id, laporan.kanwil_id, false).await?;
+
+    let (content_type, ekstensi, isi) = match params.format {
+        FormatLaporan::Xlsx => {
+            let isi = xlsx::render(&[susun::susun_lembar(&laporan.isi_laporan)]).map_err(|e| {
+                tracing::error!("Failed to build laporan xlsx: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+            ("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet", "xlsx", isi)
+        }
+        FormatLaporan::Pdf => ("application/pdf", "pdf", pdf::render(&susun::susun_baris(&laporan.isi_laporan))),
+    };
+
+    let headers = [
+        (header::CONTENT_TYPE, content_type.to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"laporan_bulanan_{}_{}.{}\"",
+                laporan.periode.format("%Y-%m"),
+                laporan.id,
+                ekstensi
+            ),
+        ),
+    ];
+    Ok((headers, isi).into_response())
+}
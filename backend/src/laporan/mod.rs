@@ -0,0 +1,4 @@
+pub mod model;
+pub mod service;
+pub mod susun;
+pub mod handlers;
@@ -0,0 +1,86 @@
+// File baru: src/laporan/model.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, NaiveDate, Utc};
+use crate::types::TipeKlienEnum;
+
+// Cakupan laporan: isi tepat salah satu. Periode berformat "YYYY-MM".
+#[derive(Debug, Deserialize)]
+pub struct CakupanLaporan {
+    pub bapas_id: Option<i32>,
+    pub kanwil_id: Option<i32>,
+    pub periode: String,
+}
+
+// Query string GET /api/laporan-bulanan
+#[derive(Debug, Deserialize)]
+pub struct FilterLaporanBulanan {
+    pub bapas_id: Option<i32>,
+    pub kanwil_id: Option<i32>,
+    pub tahun: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FormatLaporan {
+    Xlsx,
+    Pdf,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UnduhLaporanParams {
+    pub format: FormatLaporan,
+}
+
+// Hasil hitung mentah: satu baris per (label, tipe klien)
+#[derive(Debug, FromRow)]
+pub struct BarisHitung {
+    pub label: String,
+    pub tipe_klien: TipeKlienEnum,
+    pub jumlah: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BarisRekap {
+    pub label: String,
+    pub dewasa: i64,
+    pub anak: i64,
+}
+
+/// Isi laporan yang dibekukan ke kolom `isi_laporan`. Urutan tabel mengikuti
+/// format baku laporan bulanan Bapas ke Kanwil.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IsiLaporan {
+    pub nama_wilayah: String,
+    pub periode: NaiveDate,
+    pub penerimaan: Vec<BarisRekap>,
+    pub litmas_selesai: Vec<BarisRekap>,
+    pub dalam_bimbingan: Vec<BarisRekap>,
+    pub pengakhiran: Vec<BarisRekap>,
+    pub pelanggaran: Vec<BarisRekap>,
+    pub dihitung_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LaporanBulanan {
+    pub id: i32,
+    pub bapas_id: Option<i32>,
+    pub kanwil_id: Option<i32>,
+    pub periode: NaiveDate,
+    pub isi_laporan: IsiLaporan,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+}
+
+// Daftar laporan tanpa isi
+#[derive(Debug, Serialize, FromRow)]
+pub struct RingkasanLaporanBulanan {
+    pub id: i32,
+    pub bapas_id: Option<i32>,
+    pub kanwil_id: Option<i32>,
+    pub nama_wilayah: String,
+    pub periode: NaiveDate,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+}
@@ -0,0 +1,221 @@
+// File baru: src/laporan/service.rs
+//
+// Perhitungan angka laporan bulanan. Cakupan Kanwil mengikuti Bapas tempat
+// klien terdaftar (bapas.kanwil_id), bukan klien.kanwil_id yang bisa kosong.
+// Semua tanggal dibandingkan dalam WIB; `akhir` adalah tanggal 1 bulan berikutnya.
+
+use chrono::{Months, NaiveDate, Utc};
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use crate::types::TipeKlienEnum;
+use super::model::{BarisHitung, BarisRekap, IsiLaporan};
+
+/// "2025-11" -> 2025-11-01
+pub fn parse_periode(teks: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", teks.trim()), "%Y-%m-%d").ok()
+}
+
+/// Menggabungkan baris (label, tipe klien) menjadi satu baris per label
+fn rekap(baris: Vec<BarisHitung>) -> Vec<BarisRekap> {
+    let mut per_label: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+    for b in baris {
+        let jumlah = per_label.entry(b.label).or_default();
+        match b.tipe_klien {
+            TipeKlienEnum::Dewasa => jumlah.0 += b.jumlah,
+            TipeKlienEnum::Anak => jumlah.1 += b.jumlah,
+        }
+    }
+    per_label
+        .into_iter()
+        .map(|(label, (dewasa, anak))| BarisRekap { label, dewasa, anak })
+        .collect()
+}
+
+pub async fn nama_wilayah(pool: &PgPool, bapas_id: Option<i32>, kanwil_id: Option<i32>) -> Result<Option<String>, sqlx::Error> {
+    match (bapas_id, kanwil_id) {
+        (Some(bapas_id), _) => {
+            sqlx::query_scalar!("SELECT nama_bapas FROM bapas WHERE id = $1 AND deleted_at IS NULL", bapas_id)
+                .fetch_optional(pool)
+                .await
+        }
+        (None, Some(kanwil_id)) => {
+            sqlx::query_scalar!("SELECT nama_kanwil FROM kanwil WHERE id = $1 AND deleted_at IS NULL", kanwil_id)
+                .fetch_optional(pool)
+                .await
+        }
+        (None, None) => Ok(None),
+    }
+}
+
+pub async fn hitung_laporan(
+    pool: &PgPool,
+    bapas_id: Option<i32>,
+    kanwil_id: Option<i32>,
+    periode: NaiveDate,
+) -> Result<IsiLaporan, sqlx::Error> {
+    let awal = periode;
+    let akhir = periode + Months::new(1);
+    let nama_wilayah = nama_wilayah(pool, bapas_id, kanwil_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+
+    // 1. Penerimaan baru menurut jenis permintaan litmas
+    let penerimaan = sqlx::query_as!(
+        BarisHitung,
+        r#"
+        SELECT COALESCE(p.jenis_permintaan, 'Tidak Diketahui') AS "label!",
+            k.tipe_klien AS "tipe_klien!: TipeKlienEnum", COUNT(*) AS "jumlah!"
+        FROM (
+            SELECT klien_id, jenis_permintaan_litmas_lapas_dewasa AS jenis_permintaan,
+                COALESCE(tanggal_permintaan_lapas_dewasa, (created_at AT TIME ZONE 'Asia/Jakarta')::DATE) AS tanggal
+            FROM penerimaan_dewasa WHERE deleted_at IS NULL
+            UNION ALL
+            SELECT klien_id, jenis_permintaan_litmas_lapas_anak,
+                COALESCE(tanggal_permintaan_lapas_anak, (created_at AT TIME ZONE 'Asia/Jakarta')::DATE)
+            FROM penerimaan_anak WHERE deleted_at IS NULL
+        ) p
+        JOIN klien k ON k.id = p.klien_id
+        JOIN bapas b ON b.id = k.bapas_id
+        WHERE k.deleted_at IS NULL
+            AND ($1::INT IS NULL OR k.bapas_id = $1) AND ($2::INT IS NULL OR b.kanwil_id = $2)
+            AND p.tanggal >= $3 AND p.tanggal < $4
+        GROUP BY 1, 2
+        "#,
+        bapas_id,
+        kanwil_id,
+        awal,
+        akhir
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // 2. Litmas yang dikirim pada bulan ini. Alur Litmas baru tercatat untuk klien dewasa.
+    let litmas_selesai = sqlx::query_as!(
+        BarisHitung,
+        r#"
+        SELECT COALESCE(p.jenis_permintaan_litmas_lapas_dewasa, 'Tidak Diketahui') AS "label!",
+            k.tipe_klien AS "tipe_klien!: TipeKlienEnum", COUNT(DISTINCT a.id) AS "jumlah!"
+        FROM riwayat_alur_litmas_dewasa r
+        JOIN alur_litmas_dewasa a ON a.id = r.alur_litmas_dewasa_id
+        JOIN penerimaan_dewasa p ON p.id = a.penerimaan_dewasa_id
+        JOIN klien k ON k.id = a.klien_id
+        JOIN bapas b ON b.id = k.bapas_id
+        WHERE r.ke_status = 'Dikirim' AND p.deleted_at IS NULL AND k.deleted_at IS NULL
+            AND ($1::INT IS NULL OR k.bapas_id = $1) AND ($2::INT IS NULL OR b.kanwil_id = $2)
+            AND (r.created_at AT TIME ZONE 'Asia/Jakarta')::DATE >= $3
+            AND (r.created_at AT TIME ZONE 'Asia/Jakarta')::DATE < $4
+        GROUP BY 1, 2
+        "#,
+        bapas_id,
+        kanwil_id,
+        awal,
+        akhir
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // 3. Klien dalam bimbingan menurut jenis program: masa bimbingan beririsan dengan
+    //    bulan ini dan belum diakhiri sebelum bulan ini dimulai
+    let dalam_bimbingan = sqlx::query_as!(
+        BarisHitung,
+        r#"
+        SELECT COALESCE(l.jenis_bimbingan, 'Tidak Diketahui') AS "label!",
+            k.tipe_klien AS "tipe_klien!: TipeKlienEnum", COUNT(DISTINCT l.klien_id) AS "jumlah!"
+        FROM (
+            SELECT klien_id, jenis_bimbingan_dewasa AS jenis_bimbingan,
+                COALESCE(masa_bimbingan_awal_dewasa, tanggal_sk_integrasi_dewasa) AS masa_awal,
+                masa_bimbingan_akhir_dewasa AS masa_akhir,
+                CASE WHEN pengakhiran_dewasa THEN tanggal_surat_pengakhiran_dewasa END AS diakhiri
+            FROM layanan_integrasi_dewasa WHERE deleted_at IS NULL
+            UNION ALL
+            SELECT klien_id, jenis_bimbingan_anak,
+                COALESCE(masa_bimbingan_awal_anak, tanggal_sk_integrasi_anak),
+                masa_bimbingan_akhir_anak,
+                CASE WHEN pengakhiran_anak THEN tanggal_surat_pengakhiran_anak END
+            FROM layanan_integrasi_anak WHERE deleted_at IS NULL
+        ) l
+        JOIN klien k ON k.id = l.klien_id
+        JOIN bapas b ON b.id = k.bapas_id
+        WHERE k.deleted_at IS NULL
+            AND ($1::INT IS NULL OR k.bapas_id = $1) AND ($2::INT IS NULL OR b.kanwil_id = $2)
+            AND l.masa_awal < $4
+            AND (l.masa_akhir IS NULL OR l.masa_akhir >= $3)
+            AND (l.diakhiri IS NULL OR l.diakhiri >= $3)
+        GROUP BY 1, 2
+        "#,
+        bapas_id,
+        kanwil_id,
+        awal,
+        akhir
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // 4. Pengakhiran (pencabutan) menurut tanggal surat pengakhiran
+    let pengakhiran = sqlx::query_as!(
+        BarisHitung,
+        r#"
+        SELECT COALESCE(l.jenis_bimbingan, 'Tidak Diketahui') AS "label!",
+            k.tipe_klien AS "tipe_klien!: TipeKlienEnum", COUNT(*) AS "jumlah!"
+        FROM (
+            SELECT klien_id, jenis_bimbingan_dewasa AS jenis_bimbingan, tanggal_surat_pengakhiran_dewasa AS tanggal
+            FROM layanan_integrasi_dewasa WHERE deleted_at IS NULL AND pengakhiran_dewasa
+            UNION ALL
+            SELECT klien_id, jenis_bimbingan_anak, tanggal_surat_pengakhiran_anak
+            FROM layanan_integrasi_anak WHERE deleted_at IS NULL AND pengakhiran_anak
+        ) l
+        JOIN klien k ON k.id = l.klien_id
+        JOIN bapas b ON b.id = k.bapas_id
+        WHERE k.deleted_at IS NULL
+            AND ($1::INT IS NULL OR k.bapas_id = $1) AND ($2::INT IS NULL OR b.kanwil_id = $2)
+            AND l.tanggal >= $3 AND l.tanggal < $4
+        GROUP BY 1, 2
+        "#,
+        bapas_id,
+        kanwil_id,
+        awal,
+        akhir
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // 5. Pelanggaran: episode pelanggaran zona yang dimulai bulan ini dan
+    //    ketidakhadiran tanpa izin pada sesi bimbingan
+    let pelanggaran = sqlx::query_as!(
+        BarisHitung,
+        r#"
+        SELECT v.jenis AS "label!", k.tipe_klien AS "tipe_klien!: TipeKlienEnum", COUNT(*) AS "jumlah!"
+        FROM (
+            SELECT klien_id, 'Pelanggaran Zona Pemantauan' AS jenis,
+                (mulai_at AT TIME ZONE 'Asia/Jakarta')::DATE AS tanggal
+            FROM pelanggaran_zona
+            UNION ALL
+            SELECT p.klien_id, 'Tidak Hadir Bimbingan', (s.waktu_mulai AT TIME ZONE 'Asia/Jakarta')::DATE
+            FROM peserta_sesi_bimbingan p
+            JOIN sesi_bimbingan s ON s.id = p.sesi_bimbingan_id
+            WHERE s.deleted_at IS NULL AND p.status_kehadiran = 'Tidak Hadir'
+        ) v
+        JOIN klien k ON k.id = v.klien_id
+        JOIN bapas b ON b.id = k.bapas_id
+        WHERE k.deleted_at IS NULL
+            AND ($1::INT IS NULL OR k.bapas_id = $1) AND ($2::INT IS NULL OR b.kanwil_id = $2)
+            AND v.tanggal >= $3 AND v.tanggal < $4
+        GROUP BY 1, 2
+        "#,
+        bapas_id,
+        kanwil_id,
+        awal,
+        akhir
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(IsiLaporan {
+        nama_wilayah,
+        periode,
+        penerimaan: rekap(penerimaan),
+        litmas_selesai: rekap(litmas_selesai),
+        dalam_bimbingan: rekap(dalam_bimbingan),
+        pengakhiran: rekap(pengakhiran),
+        pelanggaran: rekap(pelanggaran),
+        dihitung_at: Utc::now(),
+    })
+}
@@ -0,0 +1,99 @@
+// File baru: src/laporan/susun.rs
+//
+// Tata letak baku laporan bulanan: kop, lalu lima tabel berurutan dengan kolom
+// Uraian | Dewasa | Anak | Jumlah dan baris Jumlah di bawahnya. Dipakai bersama
+// oleh ekspor PDF (baris dokumen) dan XLSX (satu lembar kerja).
+
+use crate::dokumen::merge::{bulan_indonesia, tanggal_indonesia};
+use crate::dokumen::model::Baris;
+use crate::dokumen::xlsx::{Lembar, Sel};
+use super::model::{BarisRekap, IsiLaporan};
+
+const KEPALA_KOLOM: [&str; 4] = ["Uraian", "Dewasa", "Anak", "Jumlah"];
+
+fn tabel_laporan(isi: &IsiLaporan) -> [(&'static str, &[BarisRekap]); 5] {
+    [
+        ("I. PENERIMAAN KLIEN BARU", &isi.penerimaan),
+        ("II. LITMAS SELESAI", &isi.litmas_selesai),
+        ("III. KLIEN DALAM BIMBINGAN MENURUT PROGRAM", &isi.dalam_bimbingan),
+        ("IV. PENGAKHIRAN BIMBINGAN", &isi.pengakhiran),
+        ("V. PELANGGARAN", &isi.pelanggaran),
+    ]
+}
+
+fn total(baris: &[BarisRekap]) -> (i64, i64) {
+    baris.iter().fold((0, 0), |(d, a), b| (d + b.dewasa, a + b.anak))
+}
+
+fn kop(isi: &IsiLaporan) -> [String; 3] {
+    [
+        "LAPORAN BULANAN".to_string(),
+        isi.nama_wilayah.to_uppercase(),
+        format!("Periode: {}", bulan_indonesia(isi.periode)),
+    ]
+}
+
+fn catatan_kaki(isi: &IsiLaporan) -> String {
+    format!("Angka dibekukan pada {}.", tanggal_indonesia(isi.dihitung_at.date_naive()))
+}
+
+pub fn susun_baris(isi: &IsiLaporan) -> Vec<Baris> {
+    let [judul, wilayah, periode] = kop(isi);
+    let mut hasil = vec![Baris::Judul(judul), Baris::Judul(wilayah), Baris::Paragraf(periode)];
+
+    for (judul, baris) in tabel_laporan(isi) {
+        hasil.push(Baris::Kosong);
+        hasil.push(Baris::SubJudul(judul.to_string()));
+        let mut tabel = vec![KEPALA_KOLOM.iter().map(|k| k.to_string()).collect::<Vec<_>>()];
+        for b in baris {
+            tabel.push(vec![
+                b.label.clone(),
+                b.dewasa.to_string(),
+                b.anak.to_string(),
+                (b.dewasa + b.anak).to_string(),
+            ]);
+        }
+        let (dewasa, anak) = total(baris);
+        tabel.push(vec!["Jumlah".to_string(), dewasa.to_string(), anak.to_string(), (dewasa + anak).to_string()]);
+        hasil.push(Baris::Tabel(tabel));
+    }
+
+    hasil.push(Baris::Kosong);
+    hasil.push(Baris::Paragraf(catatan_kaki(isi)));
+    hasil
+}
+
+pub fn susun_lembar(isi: &IsiLaporan) -> Lembar {
+    let [judul, wilayah, periode] = kop(isi);
+    let mut baris = vec![vec![Sel::Tebal(judul)], vec![Sel::Tebal(wilayah)], vec![Sel::Teks(periode)]];
+
+    for (judul, isi_tabel) in tabel_laporan(isi) {
+        baris.push(vec![Sel::Kosong]);
+        baris.push(vec![Sel::Tebal(judul.to_string())]);
+        baris.push(KEPALA_KOLOM.iter().map(|k| Sel::Tebal(k.to_string())).collect());
+        for b in isi_tabel {
+            baris.push(vec![
+                Sel::Teks(b.label.clone()),
+                Sel::Angka(b.dewasa as f64),
+                Sel::Angka(b.anak as f64),
+                Sel::Angka((b.dewasa + b.anak) as f64),
+            ]);
+        }
+        let (dewasa, anak) = total(isi_tabel);
+        baris.push(vec![
+            Sel::Tebal("Jumlah".to_string()),
+            Sel::Angka(dewasa as f64),
+            Sel::Angka(anak as f64),
+            Sel::Angka((dewasa + anak) as f64),
+        ]);
+    }
+
+    baris.push(vec![Sel::Kosong]);
+    baris.push(vec![Sel::Teks(catatan_kaki(isi))]);
+
+    Lembar {
+        nama: format!("Laporan {}", isi.periode.format("%Y-%m")),
+        lebar_kolom: vec![48.0, 10.0, 10.0, 10.0],
+        baris,
+    }
+}
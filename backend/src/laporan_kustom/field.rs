@@ -0,0 +1,177 @@
+// File baru: src/laporan_kustom/field.rs
+//
+// Daftar putih field yang boleh dipakai laporan ad-hoc. Hanya ekspresi SQL di
+// sini yang pernah masuk ke teks query; nilai dari pengguna selalu di-bind.
+// Relasi satu-ke-banyak (penerimaan, riwayat hukum, layanan) menggabungkan tabel
+// dewasa dan anak. Menampilkan field-nya menghasilkan satu baris per data terkait;
+// jika hanya dipakai di filter, relasi itu diperiksa dengan EXISTS (lihat kompilasi).
+
+use serde::Serialize;
+use super::model::OperatorFilter;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TipeField {
+    Teks,
+    Angka,
+    Tanggal,
+    Boolean,
+    Enum,
+}
+
+impl TipeField {
+    pub fn operator(self) -> &'static [OperatorFilter] {
+        use OperatorFilter::*;
+        match self {
+            TipeField::Teks => &[Eq, Ne, Contains, In, IsNull, NotNull],
+            TipeField::Enum => &[Eq, Ne, In, IsNull, NotNull],
+            TipeField::Angka => &[Eq, Ne, Gt, Gte, Lt, Lte, In, IsNull, NotNull],
+            TipeField::Tanggal => &[Eq, Ne, Gt, Gte, Lt, Lte, IsNull, NotNull],
+            TipeField::Boolean => &[Eq, Ne, IsNull, NotNull],
+        }
+    }
+}
+
+// Urutan varian = urutan JOIN
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Relasi {
+    Klien,
+    Bapas,
+    Pk,
+    Penerimaan,
+    RiwayatHukum,
+    Layanan,
+}
+
+impl Relasi {
+    /// Relasi yang bisa memberi lebih dari satu baris per klien
+    pub fn satu_ke_banyak(self) -> bool {
+        self.sumber().is_some()
+    }
+
+    /// Subquery gabungan tabel dewasa dan anak beserta aliasnya, untuk relasi satu-ke-banyak
+    pub fn sumber(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Relasi::Klien | Relasi::Bapas | Relasi::Pk => None,
+            Relasi::Penerimaan => Some((
+                r#"
+                    SELECT klien_id, tanggal_permintaan_lapas_dewasa AS tanggal_permintaan,
+                        jenis_permintaan_litmas_lapas_dewasa AS jenis_permintaan,
+                        nama_instansi_dewasa AS nama_instansi, daerah_instansi_dewasa AS daerah_instansi
+                    FROM penerimaan_dewasa WHERE deleted_at IS NULL
+                    UNION ALL
+                    SELECT klien_id, tanggal_permintaan_lapas_anak, jenis_permintaan_litmas_lapas_anak,
+                        nama_instansi_anak, daerah_instansi_anak
+                    FROM penerimaan_anak WHERE deleted_at IS NULL
+                "#,
+                "pn",
+            )),
+            Relasi::RiwayatHukum => Some((
+                r#"
+                    SELECT klien_id, kategori_tindak_pidana_dewasa AS kategori_tindak_pidana,
+                        pasal_tindak_pidana_dewasa AS pasal_tindak_pidana,
+                        tanggal_surat_keputusan_pengadilan_dewasa AS tanggal_putusan,
+                        pidana_tahun_dewasa AS pidana_tahun, pidana_bulan_dewasa AS pidana_bulan
+                    FROM riwayat_hukum_dewasa WHERE deleted_at IS NULL
+                    UNION ALL
+                    SELECT klien_id, kategori_tindak_pidana_anak, pasal_tindak_pidana_anak,
+                        tanggal_surat_keputusan_pengadilan_anak, pidana_tahun_anak, pidana_bulan_anak
+                    FROM riwayat_hukum_anak WHERE deleted_at IS NULL
+                "#,
+                "rh",
+            )),
+            Relasi::Layanan => Some((
+                r#"
+                    SELECT klien_id, jenis_bimbingan_dewasa AS jenis_bimbingan,
+                        tanggal_sk_integrasi_dewasa AS tanggal_sk,
+                        masa_bimbingan_awal_dewasa AS masa_bimbingan_awal,
+                        masa_bimbingan_akhir_dewasa AS masa_bimbingan_akhir,
+                        COALESCE(pengakhiran_dewasa, FALSE) AS pengakhiran
+                    FROM layanan_integrasi_dewasa WHERE deleted_at IS NULL
+                    UNION ALL
+                    SELECT klien_id, jenis_bimbingan_anak, tanggal_sk_integrasi_anak, masa_bimbingan_awal_anak,
+                        masa_bimbingan_akhir_anak, COALESCE(pengakhiran_anak, FALSE)
+                    FROM layanan_integrasi_anak WHERE deleted_at IS NULL
+                "#,
+                "li",
+            )),
+        }
+    }
+
+    pub fn join_sql(self) -> String {
+        match self {
+            Relasi::Klien => String::new(),
+            Relasi::Bapas => " JOIN bapas b ON b.id = k.bapas_id".to_string(),
+            Relasi::Pk => " JOIN users pk ON pk.id = k.pk_id".to_string(),
+            _ => self
+                .sumber()
+                .map(|(sumber, alias)| format!(" LEFT JOIN ({}) {} ON {}.klien_id = k.id", sumber, alias, alias))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldLaporan {
+    pub kunci: &'static str,
+    pub label: &'static str,
+    pub tipe: TipeField,
+    pub relasi: Relasi,
+    #[serde(skip)]
+    pub sql: &'static str,
+}
+
+const fn field(kunci: &'static str, label: &'static str, tipe: TipeField, relasi: Relasi, sql: &'static str) -> FieldLaporan {
+    FieldLaporan { kunci, label, tipe, relasi, sql }
+}
+
+use Relasi::*;
+use TipeField::*;
+
+pub const DAFTAR_FIELD: &[FieldLaporan] = &[
+    // Klien
+    field("klien.id", "ID Klien", Angka, Klien, "k.id"),
+    field("klien.nama_klien", "Nama Klien", Teks, Klien, "k.nama_klien"),
+    field("klien.tipe_klien", "Tipe Klien", Enum, Klien, "k.tipe_klien"),
+    field("klien.jenis_kelamin", "Jenis Kelamin", Enum, Klien, "k.jenis_kelamin_klien"),
+    field("klien.tempat_lahir", "Tempat Lahir", Teks, Klien, "k.tempat_lahir_klien"),
+    field("klien.tanggal_lahir", "Tanggal Lahir", Tanggal, Klien, "k.tanggal_lahir_klien"),
+    field("klien.umur", "Umur (tahun)", Angka, Klien, "DATE_PART('year', AGE(k.tanggal_lahir_klien))::INT"),
+    field("klien.agama", "Agama", Teks, Klien, "k.agama_klien"),
+    field("klien.pekerjaan", "Pekerjaan", Enum, Klien, "k.pekerjaan_klien"),
+    field("klien.pendidikan", "Pendidikan Terakhir", Enum, Klien, "k.pendidikan_terakhir_klien"),
+    field("klien.kewarganegaraan", "Kewarganegaraan", Enum, Klien, "k.kewarganegaraan_klien"),
+    field("klien.suku", "Suku", Teks, Klien, "k.suku_klien"),
+    field("klien.pengulangan", "Pengulangan", Boolean, Klien, "k.pengulangan_klien"),
+    field("klien.online_akses", "Akses Online", Boolean, Klien, "k.online_akses_klien"),
+    field("klien.tanggal_terdaftar", "Tanggal Terdaftar", Tanggal, Klien, "(k.created_at AT TIME ZONE 'Asia/Jakarta')::DATE"),
+    field("klien.bapas_id", "ID Bapas", Angka, Klien, "k.bapas_id"),
+    field("klien.pk_id", "ID PK", Angka, Klien, "k.pk_id"),
+    // Bapas dan PK
+    field("bapas.nama_bapas", "Nama Bapas", Teks, Bapas, "b.nama_bapas"),
+    field("bapas.kota_bapas", "Kota Bapas", Teks, Bapas, "b.kota_bapas"),
+    field("pk.nama_user", "Nama PK", Teks, Pk, "pk.nama_user"),
+    field("pk.nip_user", "NIP PK", Teks, Pk, "pk.nip_user"),
+    // Penerimaan
+    field("penerimaan.tanggal_permintaan", "Tanggal Permintaan", Tanggal, Penerimaan, "pn.tanggal_permintaan"),
+    field("penerimaan.jenis_permintaan", "Jenis Permintaan Litmas", Teks, Penerimaan, "pn.jenis_permintaan"),
+    field("penerimaan.nama_instansi", "Instansi Peminta", Enum, Penerimaan, "pn.nama_instansi"),
+    field("penerimaan.daerah_instansi", "Daerah Instansi", Teks, Penerimaan, "pn.daerah_instansi"),
+    // Riwayat hukum
+    field("riwayat_hukum.kategori_tindak_pidana", "Kategori Tindak Pidana", Teks, RiwayatHukum, "rh.kategori_tindak_pidana"),
+    field("riwayat_hukum.pasal_tindak_pidana", "Pasal", Teks, RiwayatHukum, "rh.pasal_tindak_pidana"),
+    field("riwayat_hukum.tanggal_putusan", "Tanggal Putusan", Tanggal, RiwayatHukum, "rh.tanggal_putusan"),
+    field("riwayat_hukum.pidana_tahun", "Pidana (tahun)", Angka, RiwayatHukum, "rh.pidana_tahun"),
+    field("riwayat_hukum.pidana_bulan", "Pidana (bulan)", Angka, RiwayatHukum, "rh.pidana_bulan"),
+    // Layanan integrasi
+    field("layanan.jenis_bimbingan", "Jenis Bimbingan", Teks, Layanan, "li.jenis_bimbingan"),
+    field("layanan.tanggal_sk", "Tanggal SK", Tanggal, Layanan, "li.tanggal_sk"),
+    field("layanan.masa_bimbingan_awal", "Awal Masa Bimbingan", Tanggal, Layanan, "li.masa_bimbingan_awal"),
+    field("layanan.masa_bimbingan_akhir", "Akhir Masa Bimbingan", Tanggal, Layanan, "li.masa_bimbingan_akhir"),
+    field("layanan.pengakhiran", "Pengakhiran", Boolean, Layanan, "li.pengakhiran"),
+];
+
+pub fn cari_field(kunci: &str) -> Option<&'static FieldLaporan> {
+    DAFTAR_FIELD.iter().find(|f| f.kunci == kunci)
+}
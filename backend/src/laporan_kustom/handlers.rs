@@ -0,0 +1,332 @@
+// File baru: src/laporan_kustom/handlers.rs
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::Value;
+use sqlx::types::Json as SqlJson;
+use sqlx::PgPool;
+use crate::auth::model::AuthenticatedUser;
+use crate::dokumen::xlsx::{self, Lembar, Sel};
+use crate::types::UserRoleEnum;
+use super::field::{FieldLaporan, DAFTAR_FIELD};
+use super::kompilasi::kompilasi;
+use super::model::{
+    CreateLaporanKustom, DefinisiLaporan, FormatHasil, HasilLaporan, JalankanParams, LaporanKustom,
+    UpdateLaporanKustom,
+};
+
+const BATAS_BAWAAN: i64 = 1000;
+const BATAS_MAKS_JSON: i64 = 10_000;
+const BATAS_MAKS_EKSPOR: i64 = 50_000;
+
+/// Laporan terlihat oleh pembuatnya, oleh pegawai Bapas yang sama jika dibagikan,
+/// dan oleh SuperAdmin.
+fn boleh_lihat(user: &AuthenticatedUser, laporan: &LaporanKustom) -> bool {
+    user.role == UserRoleEnum::SuperAdmin
+        || laporan.created_by == Some(user.id)
+        || (laporan.dibagikan && laporan.bapas_id.is_some() && laporan.bapas_id == user.bapas_id)
+}
+
+/// Mengubah atau menghapus: pembuat, SuperAdmin, atau AdminBapas dari Bapas laporan.
+fn boleh_ubah(user: &AuthenticatedUser, laporan: &LaporanKustom) -> bool {
+    match user.role {
+        UserRoleEnum::SuperAdmin => true,
+        UserRoleEnum::AdminBapas if laporan.bapas_id.is_some() && laporan.bapas_id == user.bapas_id => true,
+        _ => laporan.created_by == Some(user.id),
+    }
+}
+
+async fn get_laporan(pool: &PgPool, id: i32) -> Result<LaporanKustom, StatusCode> {
+    sqlx::query_as!(
+        LaporanKustom,
+        r#"
+        SELECT id, bapas_id, nama_laporan, deskripsi, definisi AS "definisi: SqlJson<DefinisiLaporan>",
+            dibagikan, created_at, updated_at, created_by, updated_by
+        FROM laporan_kustom WHERE id = $1 AND deleted_at IS NULL
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch laporan kustom: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn jalankan(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    definisi: &DefinisiLaporan,
+    params: &JalankanParams,
+    nama_berkas: &str,
+) -> Result<Response, StatusCode> {
+    let batas = match params.format {
+        FormatHasil::Json => params.batas.unwrap_or(BATAS_BAWAAN).clamp(1, BATAS_MAKS_JSON),
+        FormatHasil::Csv | FormatHasil::Xlsx => BATAS_MAKS_EKSPOR,
+    };
+    let (mut qb, kolom) = kompilasi(definisi, user, batas)?;
+    let baris: Vec<Vec<Value>> = qb
+        .build_query_scalar::<SqlJson<Vec<Value>>>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to run laporan kustom: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .into_iter()
+        .map(|b| b.0)
+        .collect();
+
+    match params.format {
+        FormatHasil::Json => Ok(Json(HasilLaporan { kolom, baris }).into_response()),
+        FormatHasil::Csv => {
+            let mut wtr = csv::Writer::from_writer(vec![]);
+            wtr.write_record(&kolom).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            for b in &baris {
+                wtr.write_record(b.iter().map(teks_sel)).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            }
+            let isi = wtr.into_inner().map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            let headers = [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.csv\"", nama_berkas)),
+            ];
+            Ok((headers, isi).into_response())
+        }
+        FormatHasil::Xlsx => {
+            let mut isi_lembar = vec![kolom.iter().map(|k| Sel::Tebal(k.clone())).collect::<Vec<_>>()];
+            isi_lembar.extend(baris.iter().map(|b| b.iter().map(sel_xlsx).collect::<Vec<_>>()));
+            let lembar = Lembar {
+                nama: "Laporan".to_string(),
+                lebar_kolom: vec![20.0; kolom.len()],
+                baris: isi_lembar,
+            };
+            let isi = xlsx::render(&[lembar]).map_err(|e| {
+                tracing::error!("Failed to build laporan kustom xlsx: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+            let headers = [
+                (
+                    header::CONTENT_TYPE,
+                    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet".to_string(),
+                ),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.xlsx\"", nama_berkas)),
+            ];
+            Ok((headers, isi).into_response())
+        }
+    }
+}
+
+fn teks_sel(nilai: &Value) -> String {
+    match nilai {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        lain => lain.to_string(),
+    }
+}
+
+fn sel_xlsx(nilai: &Value) -> Sel {
+    match nilai {
+        Value::Null => Sel::Kosong,
+        Value::Number(n) => n.as_f64().map(Sel::Angka).unwrap_or_else(|| Sel::Teks(n.to_string())),
+        lain => Sel::Teks(teks_sel(lain)),
+    }
+}
+
+// --- DAFTAR FIELD ---
+// URL: GET /api/laporan-kustom/field
+#[axum::debug_handler]
+pub async fn get_field_laporan_kustom() -> Json<&'static [FieldLaporan]> {
+    Json(DAFTAR_FIELD)
+}
+
+// --- JALANKAN AD-HOC (tanpa disimpan) ---
+// URL: POST /api/laporan-kustom/jalankan?format=json|csv|xlsx&batas=
+#[axum::debug_handler]
+pub async fn jalankan_laporan_ad_hoc(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(params): Query<JalankanParams>,
+    Json(definisi): Json<DefinisiLaporan>,
+) -> Result<Response, StatusCode> {
+    jalankan(&pool, &user, &definisi, &params, "laporan_kustom").await
+}
+
+// --- CREATE ---
+// URL: POST /api/laporan-kustom
+#[axum::debug_handler]
+pub async fn create_laporan_kustom(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(payload): Json<CreateLaporanKustom>,
+) -> Result<(StatusCode, Json<LaporanKustom>), StatusCode> {
+    // Definisi yang tidak valid ditolak sebelum disimpan
+    kompilasi(&payload.definisi, &user, 1)?;
+    if payload.nama_laporan.trim().is_empty() || (payload.dibagikan && user.bapas_id.is_none()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO laporan_kustom (bapas_id, nama_laporan, deskripsi, definisi, dibagikan, created_by, updated_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $6)
+        RETURNING id
+        "#,
+        user.bapas_id,
+        payload.nama_laporan.trim(),
+        payload.deskripsi,
+        SqlJson(&payload.definisi) as _,
+        payload.dibagikan,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to save laporan kustom: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((StatusCode::CREATED, Json(get_laporan(&pool, id).await?)))
+}
+
+// --- GET ALL ---
+// URL: GET /api/laporan-kustom
+#[axum::debug_handler]
+pub async fn get_all_laporan_kustom(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<Json<Vec<LaporanKustom>>, StatusCode> {
+    let daftar = sqlx::query_as!(
+        LaporanKustom,
+        r#"
+        SELECT id, bapas_id, nama_laporan, deskripsi, definisi AS "definisi: SqlJson<DefinisiLaporan>",
+            dibagikan, created_at, updated_at, created_by, updated_by
+        FROM laporan_kustom
+        WHERE deleted_at IS NULL
+            AND ($1 OR created_by = $2 OR (dibagikan AND bapas_id = $3))
+        ORDER BY nama_laporan, id
+        "#,
+        user.role == UserRoleEnum::SuperAdmin,
+        user.id,
+        user.bapas_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch laporan kustom: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(daftar))
+}
+
+// --- GET BY ID ---
+// URL: GET /api/laporan-kustom/:id
+#[axum::debug_handler]
+pub async fn get_laporan_kustom_by_id(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> Result<Json<LaporanKustom>, StatusCode> {
+    let laporan = get_laporan(&pool, id).await?;
+    if !boleh_lihat(&user, &laporan) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(Json(laporan))
+}
+
+// --- UPDATE ---
+// URL: PUT /api/laporan-kustom/:id
+#[axum::debug_handler]
+pub async fn update_laporan_kustom(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateLaporanKustom>,
+) -> Result<Json<LaporanKustom>, StatusCode> {
+    let lama = get_laporan(&pool, id).await?;
+    if !boleh_ubah(&user, &lama) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    if let Some(definisi) = &payload.definisi {
+        kompilasi(definisi, &user, 1)?;
+    }
+    if payload.nama_laporan.as_deref().is_some_and(|n| n.trim().is_empty())
+        || (payload.dibagikan == Some(true) && lama.bapas_id.is_none())
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE laporan_kustom SET
+            nama_laporan = COALESCE($1, nama_laporan),
+            deskripsi = COALESCE($2, deskripsi),
+            definisi = COALESCE($3, definisi),
+            dibagikan = COALESCE($4, dibagikan),
+            updated_by = $5
+        WHERE id = $6 AND deleted_at IS NULL
+        "#,
+        payload.nama_laporan.as_deref().map(str::trim),
+        payload.deskripsi,
+        payload.definisi.as_ref().map(SqlJson) as _,
+        payload.dibagikan,
+        user.id,
+        id
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update laporan kustom: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(get_laporan(&pool, id).await?))
+}
+
+// --- DELETE (SOFT) ---
+// URL: DELETE /api/laporan-kustom/:id
+#[axum::debug_handler]
+pub async fn delete_laporan_kustom(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, StatusCode> {
+    let laporan = get_laporan(&pool, id).await?;
+    if !boleh_ubah(&user, &laporan) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    sqlx::query!(
+        "UPDATE laporan_kustom SET deleted_at = NOW(), updated_by = $1 WHERE id = $2",
+        user.id,
+        id
+    )
+    .execute(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// --- JALANKAN LAPORAN TERSIMPAN ---
+// URL: GET /api/laporan-kustom/:id/jalankan?format=json|csv|xlsx&batas=
+// Data tetap dibatasi cakupan role pengguna yang menjalankan, bukan pembuat laporan.
+#[axum::debug_handler]
+pub async fn jalankan_laporan_kustom(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Query(params): Query<JalankanParams>,
+) -> Result<Response, StatusCode> {
+    let laporan = get_laporan(&pool, id).await?;
+    if !boleh_lihat(&user, &laporan) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    jalankan(&pool, &user, &laporan.definisi, &params, &format!("laporan_kustom_{}", laporan.id)).await
+}
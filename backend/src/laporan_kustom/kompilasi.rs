@@ -0,0 +1,298 @@
+// File baru: src/laporan_kustom/kompilasi.rs
+//
+// Menerjemahkan DefinisiLaporan menjadi satu query berparameter. Teks SQL hanya
+// disusun dari ekspresi DAFTAR_FIELD dan kata kunci tetap; semua nilai filter
+// di-bind. Filter role klien selalu diterapkan, jadi definisi yang dibagikan
+// menghasilkan data sesuai hak akses orang yang menjalankannya.
+//
+// Relasi satu-ke-banyak yang tampil di kolom atau kelompok di-JOIN, sehingga
+// satu baris mewakili satu data terkait. Paling banyak satu relasi seperti itu
+// boleh di-JOIN; dua atau lebih akan saling mengalikan baris dan membuat COUNT
+// ikut berlipat. Relasi yang hanya dipakai di filter diperiksa dengan EXISTS
+// (semua filternya berlaku pada data terkait yang sama) tanpa menggandakan klien.
+
+use axum::http::StatusCode;
+use chrono::NaiveDate;
+use serde_json::Value;
+use sqlx::{Postgres, QueryBuilder};
+use std::collections::{BTreeMap, BTreeSet};
+use crate::auth::model::AuthenticatedUser;
+use crate::klien::handlers_core::{terapkan_filter_klien, GetAllKlienParams};
+use super::field::{cari_field, FieldLaporan, Relasi, TipeField};
+use super::model::{Agregat, DefinisiLaporan, FilterLaporan, KolomLaporan, OperatorFilter};
+
+pub const MAKS_KOLOM: usize = 30;
+
+fn field_valid(kunci: &str) -> Result<&'static FieldLaporan, StatusCode> {
+    cari_field(kunci).ok_or(StatusCode::BAD_REQUEST)
+}
+
+// Teks dan enum dibandingkan sebagai TEXT agar nilai enum Postgres bisa di-bind sebagai string
+fn ekspresi_pembanding(field: &FieldLaporan) -> String {
+    match field.tipe {
+        TipeField::Teks | TipeField::Enum => format!("{}::TEXT", field.sql),
+        _ => field.sql.to_string(),
+    }
+}
+
+/// Ekspresi SELECT dan label satu kolom
+fn susun_kolom(kolom: &KolomLaporan) -> Result<(String, String, Option<&'static FieldLaporan>), StatusCode> {
+    let field = kolom.field.as_deref().map(field_valid).transpose()?;
+    match (kolom.agregat, field) {
+        (None, Some(f)) => Ok((f.sql.to_string(), f.label.to_string(), Some(f))),
+        (None, None) => Err(StatusCode::BAD_REQUEST),
+        (Some(Agregat::Count), None) => Ok(("COUNT(*)".to_string(), "Jumlah".to_string(), None)),
+        (Some(Agregat::Count), Some(f)) => Ok((
+            format!("COUNT(DISTINCT {})", f.sql),
+            format!("Jumlah {}", f.label),
+            Some(f),
+        )),
+        // MIN/MAX tidak terdefinisi untuk boolean
+        (Some(_), None) => Err(StatusCode::BAD_REQUEST),
+        (Some(_), Some(f)) if f.tipe == TipeField::Boolean => Err(StatusCode::BAD_REQUEST),
+        (Some(Agregat::Min), Some(f)) => Ok((format!("MIN({})", f.sql), format!("Minimum {}", f.label), Some(f))),
+        (Some(Agregat::Max), Some(f)) => Ok((format!("MAX({})", f.sql), format!("Maksimum {}", f.label), Some(f))),
+    }
+}
+
+fn nilai_teks(nilai: &Value) -> Result<String, StatusCode> {
+    nilai.as_str().map(str::to_string).ok_or(StatusCode::BAD_REQUEST)
+}
+
+fn nilai_tanggal(nilai: &Value) -> Result<NaiveDate, StatusCode> {
+    nilai
+        .as_str()
+        .and_then(|t| NaiveDate::parse_from_str(t, "%Y-%m-%d").ok())
+        .ok_or(StatusCode::BAD_REQUEST)
+}
+
+fn push_nilai(qb: &mut QueryBuilder<'static, Postgres>, tipe: TipeField, nilai: &Value) -> Result<(), StatusCode> {
+    match tipe {
+        TipeField::Teks | TipeField::Enum => {
+            qb.push_bind(nilai_teks(nilai)?);
+        }
+        TipeField::Angka => {
+            qb.push_bind(nilai.as_i64().ok_or(StatusCode::BAD_REQUEST)?);
+        }
+        TipeField::Tanggal => {
+            qb.push_bind(nilai_tanggal(nilai)?);
+        }
+        TipeField::Boolean => {
+            qb.push_bind(nilai.as_bool().ok_or(StatusCode::BAD_REQUEST)?);
+        }
+    }
+    Ok(())
+}
+
+fn push_filter(qb: &mut QueryBuilder<'static, Postgres>, filter: &FilterLaporan) -> Result<(), StatusCode> {
+    let field = field_valid(&filter.field)?;
+    if !field.tipe.operator().contains(&filter.operator) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let expr = ekspresi_pembanding(field);
+
+    qb.push(" AND ");
+    match filter.operator {
+        OperatorFilter::IsNull => {
+            qb.push(format!("{} IS NULL", field.sql));
+        }
+        OperatorFilter::NotNull => {
+            qb.push(format!("{} IS NOT NULL", field.sql));
+        }
+        OperatorFilter::Contains => {
+            qb.push("POSITION(LOWER(")
+                .push_bind(nilai_teks(&filter.nilai)?)
+                .push(format!(") IN LOWER({})) > 0", expr));
+        }
+        OperatorFilter::In => {
+            let daftar = filter.nilai.as_array().filter(|d| !d.is_empty()).ok_or(StatusCode::BAD_REQUEST)?;
+            qb.push(format!("{} = ANY(", expr));
+            if field.tipe == TipeField::Angka {
+                let angka = daftar.iter().map(|v| v.as_i64().ok_or(StatusCode::BAD_REQUEST)).collect::<Result<Vec<_>, _>>()?;
+                qb.push_bind(angka);
+            } else {
+                let teks = daftar.iter().map(nilai_teks).collect::<Result<Vec<_>, _>>()?;
+                qb.push_bind(teks);
+            }
+            qb.push(")");
+        }
+        op => {
+            let simbol = match op {
+                OperatorFilter::Eq => "=",
+                OperatorFilter::Ne => "<>",
+                OperatorFilter::Gt => ">",
+                OperatorFilter::Gte => ">=",
+                OperatorFilter::Lt => "<",
+                _ => "<=",
+            };
+            qb.push(format!("{} {} ", expr, simbol));
+            push_nilai(qb, field.tipe, &filter.nilai)?;
+        }
+    }
+    Ok(())
+}
+
+/// Mengompilasi definisi menjadi query yang mengembalikan satu kolom `baris`
+/// (JSONB array sesuai urutan `kolom`) beserta label kolomnya.
+pub fn kompilasi(
+    definisi: &DefinisiLaporan,
+    user: &AuthenticatedUser,
+    batas: i64,
+) -> Result<(QueryBuilder<'static, Postgres>, Vec<String>), StatusCode> {
+    if definisi.kolom.is_empty() || definisi.kolom.len() > MAKS_KOLOM {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut ekspresi = Vec::with_capacity(definisi.kolom.len());
+    let mut label = Vec::with_capacity(definisi.kolom.len());
+    let mut relasi: BTreeSet<Relasi> = BTreeSet::new();
+    for kolom in &definisi.kolom {
+        let (expr, nama, field) = susun_kolom(kolom)?;
+        if let Some(f) = field {
+            relasi.insert(f.relasi);
+        }
+        ekspresi.push(expr);
+        label.push(nama);
+    }
+
+    let kelompok = definisi
+        .kelompok
+        .iter()
+        .map(|k| field_valid(k))
+        .collect::<Result<Vec<_>, _>>()?;
+    relasi.extend(kelompok.iter().map(|f| f.relasi));
+
+    // Dengan agregat atau pengelompokan, kolom biasa harus menjadi bagian kelompok
+    let ada_agregat = definisi.kolom.iter().any(|k| k.agregat.is_some());
+    if ada_agregat || !kelompok.is_empty() {
+        let tidak_dikelompokkan = definisi
+            .kolom
+            .iter()
+            .filter(|k| k.agregat.is_none())
+            .any(|k| !definisi.kelompok.iter().any(|g| Some(g) == k.field.as_ref()));
+        if tidak_dikelompokkan {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    if relasi.iter().filter(|r| r.satu_ke_banyak()).count() > 1 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut filter_join = Vec::new();
+    let mut filter_exists: BTreeMap<Relasi, Vec<&FilterLaporan>> = BTreeMap::new();
+    for filter in &definisi.filter {
+        let r = field_valid(&filter.field)?.relasi;
+        if r.satu_ke_banyak() && !relasi.contains(&r) {
+            filter_exists.entry(r).or_default().push(filter);
+        } else {
+            relasi.insert(r);
+            filter_join.push(filter);
+        }
+    }
+
+    if definisi.urutan.iter().any(|u| u.kolom >= ekspresi.len()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut qb: QueryBuilder<'static, Postgres> =
+        QueryBuilder::new(format!("SELECT jsonb_build_array({}) AS baris FROM klien k", ekspresi.join(", ")));
+    for r in &relasi {
+        qb.push(r.join_sql());
+    }
+    qb.push(" WHERE k.deleted_at IS NULL");
+
+    let params = GetAllKlienParams { pk_id: None, bapas_id: None, kanwil_id: None };
+    terapkan_filter_klien(&mut qb, user, &params, "k.");
+
+    for filter in filter_join {
+        push_filter(&mut qb, filter)?;
+    }
+    for (r, daftar) in filter_exists {
+        let Some((sumber, alias)) = r.sumber() else {
+            continue;
+        };
+        qb.push(format!(" AND EXISTS (SELECT 1 FROM ({}) {} WHERE {}.klien_id = k.id", sumber, alias, alias));
+        for filter in daftar {
+            push_filter(&mut qb, filter)?;
+        }
+        qb.push(")");
+    }
+
+    // Agregat tanpa kelompok menghasilkan tepat satu baris
+    if !kelompok.is_empty() {
+        let kolom_kelompok: Vec<&str> = kelompok.iter().map(|f| f.sql).collect();
+        qb.push(format!(" GROUP BY {}", kolom_kelompok.join(", ")));
+    }
+
+    if !definisi.urutan.is_empty() {
+        let urutan: Vec<String> = definisi
+            .urutan
+            .iter()
+            .map(|u| format!("{} {} NULLS LAST", ekspresi[u.kolom], if u.menurun { "DESC" } else { "ASC" }))
+            .collect();
+        qb.push(format!(" ORDER BY {}", urutan.join(", ")));
+    }
+
+    qb.push(" LIMIT ").push_bind(batas);
+    Ok((qb, label))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::UserRoleEnum;
+    use serde_json::json;
+
+    fn admin() -> AuthenticatedUser {
+        AuthenticatedUser { id: 1, role: UserRoleEnum::SuperAdmin, bapas_id: None, kanwil_id: None }
+    }
+
+    fn definisi(isi: Value) -> DefinisiLaporan {
+        serde_json::from_value(isi).unwrap()
+    }
+
+    #[test]
+    fn dua_relasi_satu_ke_banyak_ditolak() {
+        let d = definisi(json!({
+            "kolom": [{"field": "riwayat_hukum.kategori_tindak_pidana"}, {"field": "layanan.jenis_bimbingan"}]
+        }));
+        assert_eq!(kompilasi(&d, &admin(), 10).err(), Some(StatusCode::BAD_REQUEST));
+    }
+
+    #[test]
+    fn relasi_hanya_filter_memakai_exists() {
+        let d = definisi(json!({
+            "kolom": [{"field": "riwayat_hukum.kategori_tindak_pidana"}, {"agregat": "count"}],
+            "kelompok": ["riwayat_hukum.kategori_tindak_pidana"],
+            "filter": [
+                {"field": "layanan.pengakhiran", "operator": "eq", "nilai": false},
+                {"field": "penerimaan.nama_instansi", "operator": "eq", "nilai": "Lapas"},
+                {"field": "riwayat_hukum.pidana_tahun", "operator": "gte", "nilai": 2}
+            ]
+        }));
+        let (qb, _) = kompilasi(&d, &admin(), 10).unwrap();
+        let sql = qb.sql();
+        assert_eq!(sql.matches("LEFT JOIN").count(), 1);
+        assert!(sql.contains(") rh ON rh.klien_id = k.id"));
+        assert!(sql.contains(") li WHERE li.klien_id = k.id AND li.pengakhiran = "));
+        assert!(sql.contains(") pn WHERE pn.klien_id = k.id AND pn.nama_instansi::TEXT = "));
+        assert!(sql.contains("AND rh.pidana_tahun >= "));
+    }
+
+    #[test]
+    fn filter_pada_relasi_yang_sama_satu_exists() {
+        let d = definisi(json!({
+            "kolom": [{"agregat": "count"}],
+            "filter": [
+                {"field": "layanan.pengakhiran", "operator": "eq", "nilai": false},
+                {"field": "layanan.tanggal_sk", "operator": "gte", "nilai": "2024-01-01"}
+            ]
+        }));
+        let (qb, _) = kompilasi(&d, &admin(), 10).unwrap();
+        let sql = qb.sql();
+        assert!(!sql.contains("LEFT JOIN"));
+        assert_eq!(sql.matches("EXISTS").count(), 1);
+        assert!(sql.contains("li.pengakhiran = $1 AND li.tanggal_sk >= $2)"));
+    }
+}
@@ -0,0 +1,4 @@
+pub mod model;
+pub mod field;
+pub mod kompilasi;
+pub mod handlers;
@@ -0,0 +1,125 @@
+// File baru: src/laporan_kustom/model.rs
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{types::Json, FromRow};
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Agregat {
+    Count,
+    Min,
+    Max,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OperatorFilter {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Contains,
+    In,
+    IsNull,
+    NotNull,
+}
+
+// Satu kolom hasil: field apa adanya, atau agregat atas field.
+// `{"agregat": "count"}` tanpa field = jumlah baris.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KolomLaporan {
+    pub field: Option<String>,
+    pub agregat: Option<Agregat>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterLaporan {
+    pub field: String,
+    pub operator: OperatorFilter,
+    // Tipe mengikuti field; daftar untuk operator "in", kosong untuk is_null/not_null
+    #[serde(default)]
+    pub nilai: Value,
+}
+
+// `kolom` = indeks pada DefinisiLaporan.kolom
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrutanLaporan {
+    pub kolom: usize,
+    #[serde(default)]
+    pub menurun: bool,
+}
+
+/// Definisi laporan. Jika ada agregat atau `kelompok`, setiap kolom tanpa agregat
+/// harus ikut dikelompokkan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefinisiLaporan {
+    pub kolom: Vec<KolomLaporan>,
+    #[serde(default)]
+    pub filter: Vec<FilterLaporan>,
+    #[serde(default)]
+    pub kelompok: Vec<String>,
+    #[serde(default)]
+    pub urutan: Vec<UrutanLaporan>,
+}
+
+// Merepresentasikan satu baris dari tabel 'laporan_kustom'
+#[derive(Debug, Serialize, FromRow)]
+pub struct LaporanKustom {
+    pub id: i32,
+    pub bapas_id: Option<i32>,
+    pub nama_laporan: String,
+    pub deskripsi: Option<String>,
+    pub definisi: Json<DefinisiLaporan>,
+    pub dibagikan: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+}
+
+// Laporan disimpan untuk Bapas pembuatnya; `dibagikan` membuatnya terlihat
+// oleh pegawai lain di Bapas tersebut.
+#[derive(Debug, Deserialize)]
+pub struct CreateLaporanKustom {
+    pub nama_laporan: String,
+    pub deskripsi: Option<String>,
+    pub definisi: DefinisiLaporan,
+    #[serde(default)]
+    pub dibagikan: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateLaporanKustom {
+    pub nama_laporan: Option<String>,
+    pub deskripsi: Option<String>,
+    pub definisi: Option<DefinisiLaporan>,
+    pub dibagikan: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FormatHasil {
+    #[default]
+    Json,
+    Csv,
+    Xlsx,
+}
+
+// Query string untuk menjalankan laporan
+#[derive(Debug, Deserialize)]
+pub struct JalankanParams {
+    #[serde(default)]
+    pub format: FormatHasil,
+    // Hanya untuk format json; ekspor memakai batas maksimum
+    pub batas: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HasilLaporan {
+    pub kolom: Vec<String>,
+    pub baris: Vec<Vec<Value>>,
+}
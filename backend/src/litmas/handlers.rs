@@ -0,0 +1,299 @@
+// File baru: src/litmas/handlers.rs
+
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    Json,
+};
+use sqlx::PgPool;
+use crate::auth::authorization::{check_permission, get_bapas_ownership, get_klien_ownership};
+use crate::auth::model::AuthenticatedUser;
+use crate::penomoran::service::hari_ini;
+use crate::types::{StatusLitmasEnum, UserRoleEnum};
+use super::model::{AlurLitmasDetail, AlurLitmasDewasa, LitmasTerlambat, RiwayatAlurLitmasDewasa, TransisiLitmas};
+use super::service::{self, PeranLitmas};
+
+async fn get_alur(pool: &PgPool, penerimaan_id: i32) -> Result<Option<AlurLitmasDewasa>, sqlx::Error> {
+    sqlx::query_as!(
+        AlurLitmasDewasa,
+        r#"
+        SELECT a.id, a.penerimaan_dewasa_id, a.klien_id, a.status_litmas AS "status_litmas: _",
+            a.petugas_litmas_id, a.reviewer_litmas_id, a.tanggal_jatuh_tempo,
+            a.created_at, a.updated_at, a.created_by, a.updated_by
+        FROM alur_litmas_dewasa a
+        JOIN penerimaan_dewasa p ON p.id = a.penerimaan_dewasa_id
+        WHERE a.penerimaan_dewasa_id = $1 AND p.deleted_at IS NULL
+        "#,
+        penerimaan_id
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+// Mengembalikan peran pengguna dan apakah ia berwenang atas klien.
+// Petugas dan reviewer litmas belum tentu PK klien, jadi mereka tetap
+// diberi akses ke alur yang ditugaskan kepada mereka.
+async fn peran_pengguna(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    alur: &AlurLitmasDewasa,
+) -> Result<(PeranLitmas, bool), StatusCode> {
+    let ownership = get_klien_ownership(pool, alur.klien_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let akses_klien = check_permission(user, &ownership);
+    let peran = PeranLitmas {
+        admin: user.role != UserRoleEnum::Pegawai && akses_klien,
+        petugas: alur.petugas_litmas_id == Some(user.id),
+        reviewer: alur.reviewer_litmas_id == Some(user.id),
+    };
+    Ok((peran, akses_klien))
+}
+
+// Petugas dan reviewer harus pegawai aktif di Bapas klien
+async fn pegawai_bapas_klien(pool: &PgPool, user_id: i32, klien_id: i32) -> Result<bool, StatusCode> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM users u JOIN klien k ON k.bapas_id = u.bapas_id
+            WHERE u.id = $1 AND k.id = $2 AND u.deleted_at IS NULL AND u.status_aktif_user = 'Aktif'
+        ) AS "ada!"
+        "#,
+        user_id,
+        klien_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+// Reviewer litmas adalah Kasubsi yang ditandai admin lewat kasubsi_user
+async fn user_kasubsi(pool: &PgPool, user_id: i32) -> Result<bool, StatusCode> {
+    sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM users WHERE id = $1 AND kasubsi_user AND deleted_at IS NULL) AS "ada!""#,
+        user_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+// --- READ ---
+// URL: GET /api/penerimaan-dewasa/:id/alur-litmas
+#[axum::debug_handler]
+pub async fn get_alur_litmas(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(penerimaan_id): Path<i32>,
+) -> Result<Json<AlurLitmasDetail>, StatusCode> {
+    let alur = get_alur(&pool, penerimaan_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let (peran, akses_klien) = peran_pengguna(&pool, &user, &alur).await?;
+    if !(akses_klien || peran.petugas || peran.reviewer) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let riwayat = sqlx::query_as!(
+        RiwayatAlurLitmasDewasa,
+        r#"
+        SELECT id, alur_litmas_dewasa_id, dari_status AS "dari_status: _", ke_status AS "ke_status: _",
+            komentar, created_at, created_by
+        FROM riwayat_alur_litmas_dewasa
+        WHERE alur_litmas_dewasa_id = $1
+        ORDER BY created_at, id
+        "#,
+        alur.id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch riwayat alur litmas: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let terlambat = alur.tanggal_jatuh_tempo.is_some_and(|tempo| tempo < hari_ini());
+    Ok(Json(AlurLitmasDetail { alur, terlambat, riwayat }))
+}
+
+// --- TRANSISI STATUS ---
+// URL: POST /api/penerimaan-dewasa/:id/alur-litmas/transisi
+#[axum::debug_handler]
+pub async fn transisi_alur_litmas(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(penerimaan_id): Path<i32>,
+    Json(payload): Json<TransisiLitmas>,
+) -> Result<Json<AlurLitmasDewasa>, StatusCode> {
+    let alur = get_alur(&pool, penerimaan_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let (peran, _) = peran_pengguna(&pool, &user, &alur).await?;
+
+    // Penunjukan petugas/reviewer hanya oleh admin
+    if (payload.petugas_litmas_id.is_some() || payload.reviewer_litmas_id.is_some()) && !peran.admin {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    for calon in [payload.petugas_litmas_id, payload.reviewer_litmas_id].into_iter().flatten() {
+        if !pegawai_bapas_klien(&pool, calon, alur.klien_id).await? {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let petugas = payload.petugas_litmas_id.or(alur.petugas_litmas_id);
+    let reviewer = payload.reviewer_litmas_id.or(alur.reviewer_litmas_id);
+    let komentar = payload.komentar.filter(|k| !k.trim().is_empty());
+
+    // Petugas tidak boleh mereview atau menyetujui litmasnya sendiri
+    let tahap_review = matches!(payload.ke_status, StatusLitmasEnum::Direview | StatusLitmasEnum::Disetujui);
+    if let Some(reviewer) = reviewer.filter(|_| payload.reviewer_litmas_id.is_some() || tahap_review) {
+        if Some(reviewer) == petugas {
+            return Err(StatusCode::CONFLICT);
+        }
+        if !user_kasubsi(&pool, reviewer).await? {
+            return Err(StatusCode::FORBIDDEN);
+        }
+    }
+    if payload.ke_status == StatusLitmasEnum::Disetujui && petugas == Some(user.id) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    service::periksa_transisi(
+        alur.status_litmas,
+        payload.ke_status,
+        peran,
+        petugas.is_some(),
+        reviewer.is_some(),
+        komentar.is_some(),
+    )?;
+
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let acuan = service::tanggal_acuan(&mut tx, penerimaan_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // Status lama ikut dicek agar dua permintaan bersamaan tidak sama-sama lolos
+    let updated = sqlx::query_as!(
+        AlurLitmasDewasa,
+        r#"
+        UPDATE alur_litmas_dewasa SET
+            status_litmas = $1,
+            petugas_litmas_id = $2,
+            reviewer_litmas_id = $3,
+            tanggal_jatuh_tempo = $4,
+            updated_by = $5
+        WHERE id = $6 AND status_litmas = $7
+        RETURNING id, penerimaan_dewasa_id, klien_id, status_litmas AS "status_litmas: _",
+            petugas_litmas_id, reviewer_litmas_id, tanggal_jatuh_tempo,
+            created_at, updated_at, created_by, updated_by
+        "#,
+        payload.ke_status as _,
+        petugas,
+        reviewer,
+        service::jatuh_tempo(acuan, payload.ke_status),
+        user.id,
+        alur.id,
+        alur.status_litmas as _
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update alur litmas {}: {}", alur.id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::CONFLICT)?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO riwayat_alur_litmas_dewasa (alur_litmas_dewasa_id, dari_status, ke_status, komentar, created_by)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        alur.id,
+        alur.status_litmas as _,
+        payload.ke_status as _,
+        komentar,
+        user.id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // Tanggal surat tugas terisi otomatis saat litmas ditugaskan
+    if payload.ke_status == StatusLitmasEnum::Ditugaskan {
+        sqlx::query!(
+            r#"
+            UPDATE penerimaan_dewasa
+            SET tanggal_surat_tugas_dewasa = COALESCE(tanggal_surat_tugas_dewasa, $1), updated_by = $2
+            WHERE id = $3
+            "#,
+            hari_ini(),
+            user.id,
+            penerimaan_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    }
+
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(updated))
+}
+
+// --- DAFTAR TERLAMBAT ---
+// URL: GET /api/bapas/:bapas_id/litmas-terlambat
+#[axum::debug_handler]
+pub async fn get_litmas_terlambat(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(bapas_id): Path<i32>,
+) -> Result<Json<Vec<LitmasTerlambat>>, StatusCode> {
+    let ownership = get_bapas_ownership(&pool, bapas_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    // Pegawai boleh melihat daftar Bapasnya sendiri
+    let boleh_lihat = check_permission(&user, &ownership)
+        || (user.role == UserRoleEnum::Pegawai && user.bapas_id == Some(bapas_id));
+    if !boleh_lihat {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let list = sqlx::query_as!(
+        LitmasTerlambat,
+        r#"
+        SELECT
+            a.penerimaan_dewasa_id, a.klien_id, k.nama_klien, p.no_register_litmas_dewasa,
+            a.status_litmas AS "status_litmas: _",
+            a.petugas_litmas_id, pt.nama_user AS "nama_petugas?",
+            a.reviewer_litmas_id, rv.nama_user AS "nama_reviewer?",
+            a.tanggal_jatuh_tempo AS "tanggal_jatuh_tempo!",
+            ($2 - a.tanggal_jatuh_tempo) AS "hari_terlambat!"
+        FROM alur_litmas_dewasa a
+        JOIN penerimaan_dewasa p ON p.id = a.penerimaan_dewasa_id AND p.deleted_at IS NULL
+        JOIN klien k ON k.id = a.klien_id AND k.deleted_at IS NULL
+        LEFT JOIN users pt ON pt.id = a.petugas_litmas_id
+        LEFT JOIN users rv ON rv.id = a.reviewer_litmas_id
+        WHERE k.bapas_id = $1
+          AND a.status_litmas <> 'Dikirim'
+          AND a.tanggal_jatuh_tempo < $2
+        ORDER BY a.tanggal_jatuh_tempo, a.id
+        "#,
+        bapas_id,
+        hari_ini()
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch litmas terlambat: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
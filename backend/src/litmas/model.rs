@@ -0,0 +1,69 @@
+// File baru: src/litmas/model.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, NaiveDate, Utc};
+use crate::types::StatusLitmasEnum;
+
+// Merepresentasikan satu baris dari tabel 'alur_litmas_dewasa'
+#[derive(Debug, Serialize, FromRow)]
+pub struct AlurLitmasDewasa {
+    pub id: i32,
+    pub penerimaan_dewasa_id: i32,
+    pub klien_id: i32,
+    pub status_litmas: StatusLitmasEnum,
+    pub petugas_litmas_id: Option<i32>,
+    pub reviewer_litmas_id: Option<i32>,
+    pub tanggal_jatuh_tempo: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+}
+
+// Merepresentasikan satu baris dari tabel 'riwayat_alur_litmas_dewasa'
+#[derive(Debug, Serialize, FromRow)]
+pub struct RiwayatAlurLitmasDewasa {
+    pub id: i32,
+    pub alur_litmas_dewasa_id: i32,
+    pub dari_status: StatusLitmasEnum,
+    pub ke_status: StatusLitmasEnum,
+    pub komentar: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+}
+
+// Respons GET alur litmas: status terkini beserta jejak perpindahannya
+#[derive(Debug, Serialize)]
+pub struct AlurLitmasDetail {
+    #[serde(flatten)]
+    pub alur: AlurLitmasDewasa,
+    pub terlambat: bool,
+    pub riwayat: Vec<RiwayatAlurLitmasDewasa>,
+}
+
+// Data untuk memindahkan status. Petugas wajib saat menugaskan,
+// komentar wajib saat reviewer mengembalikan draft untuk direvisi.
+#[derive(Debug, Deserialize)]
+pub struct TransisiLitmas {
+    pub ke_status: StatusLitmasEnum,
+    pub petugas_litmas_id: Option<i32>,
+    pub reviewer_litmas_id: Option<i32>,
+    pub komentar: Option<String>,
+}
+
+// Satu baris daftar litmas yang melewati jatuh tempo di sebuah Bapas
+#[derive(Debug, Serialize, FromRow)]
+pub struct LitmasTerlambat {
+    pub penerimaan_dewasa_id: i32,
+    pub klien_id: i32,
+    pub nama_klien: String,
+    pub no_register_litmas_dewasa: Option<String>,
+    pub status_litmas: StatusLitmasEnum,
+    pub petugas_litmas_id: Option<i32>,
+    pub nama_petugas: Option<String>,
+    pub reviewer_litmas_id: Option<i32>,
+    pub nama_reviewer: Option<String>,
+    pub tanggal_jatuh_tempo: NaiveDate,
+    pub hari_terlambat: i32,
+}
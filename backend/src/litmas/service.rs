@@ -0,0 +1,110 @@
+// File baru: src/litmas/service.rs
+//
+// Aturan alur Litmas:
+//   Diterima -> Ditugaskan -> Draft -> Direview -> Disetujui -> Dikirim
+// dengan satu jalur balik Direview -> Draft ketika reviewer meminta revisi.
+// Jatuh tempo setiap tahap dihitung dari tanggal permintaan Lapas.
+
+use axum::http::StatusCode;
+use chrono::{Days, NaiveDate};
+use sqlx::PgConnection;
+use crate::penomoran::service::hari_ini;
+use crate::types::StatusLitmasEnum;
+
+/// Peran pengguna terhadap satu alur litmas. Satu orang bisa memegang lebih dari satu.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PeranLitmas {
+    /// Admin Bapas/Kanwil/SuperAdmin yang berwenang atas klien
+    pub admin: bool,
+    pub petugas: bool,
+    pub reviewer: bool,
+}
+
+/// Batas (hari sejak tanggal permintaan) untuk menyelesaikan tahap yang sedang berjalan.
+pub fn batas_hari(status: StatusLitmasEnum) -> Option<u64> {
+    match status {
+        StatusLitmasEnum::Diterima => Some(2),
+        StatusLitmasEnum::Ditugaskan | StatusLitmasEnum::Draft => Some(10),
+        StatusLitmasEnum::Direview => Some(12),
+        StatusLitmasEnum::Disetujui => Some(14),
+        StatusLitmasEnum::Dikirim => None,
+    }
+}
+
+pub fn jatuh_tempo(tanggal_permintaan: NaiveDate, status: StatusLitmasEnum) -> Option<NaiveDate> {
+    batas_hari(status).and_then(|hari| tanggal_permintaan.checked_add_days(Days::new(hari)))
+}
+
+/// Memeriksa apakah perpindahan status sah dan boleh dilakukan oleh peran tersebut.
+/// `FORBIDDEN` untuk peran yang salah, `CONFLICT` untuk perpindahan yang tidak ada
+/// di alur, `BAD_REQUEST` jika data pendukung (petugas, reviewer, komentar) kurang.
+pub fn periksa_transisi(
+    dari: StatusLitmasEnum,
+    ke: StatusLitmasEnum,
+    peran: PeranLitmas,
+    ada_petugas: bool,
+    ada_reviewer: bool,
+    ada_komentar: bool,
+) -> Result<(), StatusCode> {
+    use StatusLitmasEnum::*;
+
+    let boleh = match (dari, ke) {
+        (Diterima, Ditugaskan) => peran.admin,
+        (Ditugaskan, Draft) | (Draft, Direview) | (Disetujui, Dikirim) => peran.admin || peran.petugas,
+        (Direview, Disetujui) | (Direview, Draft) => peran.admin || peran.reviewer,
+        _ => return Err(StatusCode::CONFLICT),
+    };
+    if !boleh {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let lengkap = match (dari, ke) {
+        (Diterima, Ditugaskan) => ada_petugas,
+        (Draft, Direview) => ada_reviewer,
+        (Direview, Draft) => ada_komentar,
+        _ => true,
+    };
+    if !lengkap {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok(())
+}
+
+/// Tanggal acuan jatuh tempo: tanggal permintaan Lapas, atau tanggal penerimaan dicatat.
+pub async fn tanggal_acuan(conn: &mut PgConnection, penerimaan_id: i32) -> Result<NaiveDate, sqlx::Error> {
+    let tanggal = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(tanggal_permintaan_lapas_dewasa, (created_at AT TIME ZONE 'Asia/Jakarta')::DATE) AS "tanggal!"
+        FROM penerimaan_dewasa WHERE id = $1
+        "#,
+        penerimaan_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(tanggal)
+}
+
+/// Membuat alur berstatus Diterima untuk penerimaan baru.
+/// Dipanggil di dalam transaksi yang sama dengan INSERT penerimaan.
+pub async fn buat_alur(
+    conn: &mut PgConnection,
+    penerimaan_id: i32,
+    klien_id: i32,
+    tanggal_permintaan: Option<NaiveDate>,
+    user_id: i32,
+) -> Result<(), sqlx::Error> {
+    let acuan = tanggal_permintaan.unwrap_or_else(hari_ini);
+    sqlx::query!(
+        r#"
+        INSERT INTO alur_litmas_dewasa (penerimaan_dewasa_id, klien_id, tanggal_jatuh_tempo, created_by, updated_by)
+        VALUES ($1, $2, $3, $4, $4)
+        "#,
+        penerimaan_id,
+        klien_id,
+        jatuh_tempo(acuan, StatusLitmasEnum::Diterima),
+        user_id
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
@@ -7,6 +7,26 @@ mod users;
 mod auth; 
 mod klien;
 mod kanwil;
+mod penomoran;
+mod dokumen;
+mod litmas;
+mod tpp;
+mod bimbingan;
+mod kunjungan;
+mod sinkronisasi;
+mod kartu;
+mod kiosk;
+mod portal;
+mod perangkat;
+mod peringatan;
+mod antrian;
+mod pemantauan;
+mod peta;
+mod statistik;
+mod laporan;
+mod residivis;
+mod laporan_kustom;
+mod ekspor;
 pub mod utils;
 
 use axum::{extract::Extension, Router};
@@ -22,9 +42,15 @@ async fn main() {
 dotenv().ok();
 tracing_subscriber::fmt().with_target(false).with_env_filter("info").init();
 let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+let kunci_rantai_lapor = env::var("RANTAI_LAPOR_SECRET").expect("RANTAI_LAPOR_SECRET must be set");
 
 let pool = PgPoolOptions::new()
     .max_connections(5)
+    // Kunci HMAC rantai wajib lapor dipasang sebagai setting sesi di setiap koneksi
+    .after_connect(move |conn, _meta| {
+        let kunci = kunci_rantai_lapor.clone();
+        Box::pin(async move { klien::rantai_lapor::pasang_kunci(conn, &kunci).await })
+    })
     .acquire_timeout(Duration::from_secs(3)) // Timeout for getting a connection from the pool
     .idle_timeout(Duration::from_secs(30)) // Close idle connections after 30s
     .test_before_acquire(true) // Ping the DB before handing out a connection
@@ -34,6 +60,9 @@ let pool = PgPoolOptions::new()
 
 info!("Database connection pool established successfully.");
 
+// Penyegaran berkala ringkasan analitik dashboard
+statistik::penyegaran::jalankan_penjadwal(pool.clone());
+
 
 
 // Set up CORS
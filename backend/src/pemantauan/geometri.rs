@@ -0,0 +1,207 @@
+// File baru: src/pemantauan/geometri.rs
+//
+// Perhitungan geometri sederhana untuk zona pemantauan. Zona berukuran kota
+// atau lebih kecil, jadi poligon diperlakukan datar pada koordinat lat/lon.
+
+use chrono::{DateTime, FixedOffset, NaiveTime, Utc};
+use crate::types::JenisZonaEnum;
+use super::model::ZonaPemantauan;
+
+const JARI_JARI_BUMI_METER: f64 = 6_371_008.8;
+
+/// Jarak lingkaran besar (haversine) dalam meter.
+pub fn jarak_meter(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+    let a = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * JARI_JARI_BUMI_METER * a.sqrt().asin()
+}
+
+/// Uji titik dalam poligon (ray casting). Titik poligon berformat [lat, lon].
+pub fn di_dalam_poligon(lat: f64, lon: f64, poligon: &[[f64; 2]]) -> bool {
+    let mut di_dalam = false;
+    let mut j = poligon.len().wrapping_sub(1);
+    for (i, &[lat_i, lon_i]) in poligon.iter().enumerate() {
+        let [lat_j, lon_j] = poligon[j];
+        if (lat_i > lat) != (lat_j > lat) && lon < (lon_j - lon_i) * (lat - lat_i) / (lat_j - lat_i) + lon_i {
+            di_dalam = !di_dalam;
+        }
+        j = i;
+    }
+    di_dalam
+}
+
+/// Jendela jam WIB; jam_selesai lebih kecil dari jam_mulai berarti melewati tengah malam.
+fn dalam_jendela_jam(mulai: NaiveTime, selesai: NaiveTime, waktu: DateTime<Utc>) -> bool {
+    let wib = FixedOffset::east_opt(7 * 3600).expect("offset WIB valid");
+    let jam = waktu.with_timezone(&wib).time();
+    if mulai <= selesai {
+        mulai <= jam && jam < selesai
+    } else {
+        jam >= mulai || jam < selesai
+    }
+}
+
+pub fn koordinat_valid(lat: f64, lon: f64) -> bool {
+    (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon)
+}
+
+impl ZonaPemantauan {
+    pub fn berisi(&self, lat: f64, lon: f64) -> bool {
+        match (&self.poligon, self.latitude_pusat, self.longitude_pusat, self.radius_meter) {
+            (Some(poligon), _, _, _) => di_dalam_poligon(lat, lon, poligon),
+            (None, Some(lat_p), Some(lon_p), Some(radius)) => jarak_meter(lat, lon, lat_p, lon_p) <= radius,
+            _ => false,
+        }
+    }
+
+    pub fn berlaku_pada(&self, waktu: DateTime<Utc>) -> bool {
+        match (self.jam_mulai, self.jam_selesai) {
+            (Some(mulai), Some(selesai)) => dalam_jendela_jam(mulai, selesai, waktu),
+            _ => true,
+        }
+    }
+
+    /// Titik melanggar zona: keluar dari zona inklusi atau masuk ke zona eksklusi,
+    /// hanya selama jam berlaku zona.
+    pub fn dilanggar_oleh(&self, lat: f64, lon: f64, waktu: DateTime<Utc>) -> bool {
+        if !self.berlaku_pada(waktu) {
+            return false;
+        }
+        match self.jenis_zona {
+            JenisZonaEnum::Inklusi => !self.berisi(lat, lon),
+            JenisZonaEnum::Eksklusi => self.berisi(lat, lon),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use sqlx::types::Json;
+
+    fn jam(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    // Jam WIB pada tanggal tetap, dikonversi ke UTC
+    fn wib(h: u32, m: u32) -> DateTime<Utc> {
+        FixedOffset::east_opt(7 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2025, 11, 15, h, m, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn zona_lingkaran(lat: f64, lon: f64, radius: f64) -> ZonaPemantauan {
+        let sekarang = Utc::now();
+        ZonaPemantauan {
+            id: 1,
+            klien_id: 1,
+            nama_zona: "Rumah".to_string(),
+            jenis_zona: JenisZonaEnum::Inklusi,
+            latitude_pusat: Some(lat),
+            longitude_pusat: Some(lon),
+            radius_meter: Some(radius),
+            poligon: None,
+            jam_mulai: None,
+            jam_selesai: None,
+            aktif: true,
+            created_at: sekarang,
+            updated_at: sekarang,
+            created_by: None,
+            updated_by: None,
+        }
+    }
+
+    const LUAR: [[f64; 2]; 5] = [[0.0, 0.0], [0.0, 10.0], [10.0, 10.0], [10.0, 0.0], [0.0, 0.0]];
+    const LUBANG: [[f64; 2]; 5] = [[4.0, 4.0], [4.0, 6.0], [6.0, 6.0], [6.0, 4.0], [4.0, 4.0]];
+
+    #[test]
+    fn titik_dalam_dan_luar_poligon() {
+        assert!(di_dalam_poligon(5.0, 5.0, &LUAR));
+        assert!(!di_dalam_poligon(11.0, 5.0, &LUAR));
+        assert!(!di_dalam_poligon(5.0, -0.1, &LUAR));
+        assert!(!di_dalam_poligon(5.0, 5.0, &[]));
+    }
+
+    #[test]
+    fn poligon_berlubang_sebagai_cincin_luar_dikurangi_lubang() {
+        // Cara peta memeriksa Polygon GeoJSON: di cincin luar dan tidak di lubangnya
+        let berisi = |lat, lon| di_dalam_poligon(lat, lon, &LUAR) && !di_dalam_poligon(lat, lon, &LUBANG);
+        assert!(berisi(2.0, 2.0));
+        assert!(!berisi(5.0, 5.0));
+        assert!(!berisi(12.0, 5.0));
+    }
+
+    #[test]
+    fn poligon_berlubang_dalam_satu_cincin() {
+        // Cincin luar lalu lubang (arah berlawanan) lewat sambungan di lon = 0..4;
+        // aturan ganjil-genap membuat bagian lubang berada di luar poligon
+        let cincin = [
+            [0.0, 0.0], [0.0, 10.0], [10.0, 10.0], [10.0, 0.0], [5.0, 0.0],
+            [5.0, 4.0], [4.0, 4.0], [4.0, 6.0], [6.0, 6.0], [6.0, 4.0], [5.0, 4.0],
+            [5.0, 0.0], [0.0, 0.0],
+        ];
+        assert!(di_dalam_poligon(2.0, 2.0, &cincin));
+        assert!(di_dalam_poligon(8.0, 8.0, &cincin));
+        assert!(!di_dalam_poligon(5.5, 5.0, &cincin));
+        assert!(!di_dalam_poligon(4.5, 5.5, &cincin));
+    }
+
+    #[test]
+    fn jarak_satu_derajat_lintang() {
+        let satu_derajat = JARI_JARI_BUMI_METER * std::f64::consts::PI / 180.0;
+        assert!((jarak_meter(0.0, 0.0, 1.0, 0.0) - satu_derajat).abs() < 1e-6);
+        assert!((jarak_meter(-6.2, 106.8, -6.3, 106.9) - jarak_meter(-6.3, 106.9, -6.2, 106.8)).abs() < 1e-9);
+        assert_eq!(jarak_meter(-6.2, 106.8, -6.2, 106.8), 0.0);
+    }
+
+    #[test]
+    fn titik_tepat_di_radius_masih_di_dalam() {
+        let (lat, lon) = (-6.2001, 106.8002);
+        let radius = jarak_meter(lat, lon, -6.2, 106.8);
+        assert!(zona_lingkaran(-6.2, 106.8, radius).berisi(lat, lon));
+        assert!(!zona_lingkaran(-6.2, 106.8, radius - 0.01).berisi(lat, lon));
+    }
+
+    #[test]
+    fn poligon_didahulukan_dari_lingkaran() {
+        let mut zona = zona_lingkaran(50.0, 50.0, 10.0);
+        zona.poligon = Some(Json(LUAR.to_vec()));
+        assert!(zona.berisi(5.0, 5.0));
+        assert!(!zona.berisi(50.0, 50.0));
+    }
+
+    #[test]
+    fn jendela_jam_biasa() {
+        assert!(dalam_jendela_jam(jam(8, 0), jam(17, 0), wib(8, 0)));
+        assert!(dalam_jendela_jam(jam(8, 0), jam(17, 0), wib(16, 59)));
+        assert!(!dalam_jendela_jam(jam(8, 0), jam(17, 0), wib(17, 0)));
+        assert!(!dalam_jendela_jam(jam(8, 0), jam(17, 0), wib(7, 59)));
+    }
+
+    #[test]
+    fn jendela_jam_melewati_tengah_malam() {
+        let (mulai, selesai) = (jam(22, 0), jam(5, 0));
+        assert!(dalam_jendela_jam(mulai, selesai, wib(22, 0)));
+        assert!(dalam_jendela_jam(mulai, selesai, wib(23, 30)));
+        assert!(dalam_jendela_jam(mulai, selesai, wib(0, 0)));
+        assert!(dalam_jendela_jam(mulai, selesai, wib(4, 59)));
+        assert!(!dalam_jendela_jam(mulai, selesai, wib(5, 0)));
+        assert!(!dalam_jendela_jam(mulai, selesai, wib(12, 0)));
+    }
+
+    #[test]
+    fn zona_eksklusi_hanya_dilanggar_dalam_jam_berlaku() {
+        let mut zona = zona_lingkaran(-6.2, 106.8, 500.0);
+        zona.jenis_zona = JenisZonaEnum::Eksklusi;
+        zona.jam_mulai = Some(jam(22, 0));
+        zona.jam_selesai = Some(jam(5, 0));
+        assert!(zona.dilanggar_oleh(-6.2, 106.8, wib(1, 0)));
+        assert!(!zona.dilanggar_oleh(-6.2, 106.8, wib(12, 0)));
+        assert!(!zona.dilanggar_oleh(-6.3, 106.8, wib(1, 0)));
+    }
+}
@@ -0,0 +1,546 @@
+// File baru: src/pemantauan/handlers.rs
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use sha256::digest;
+use sqlx::{types::Json as SqlJson, PgPool};
+use crate::auth::authorization::ensure_klien_access;
+use crate::auth::model::{AuthenticatedUser, AuthenticatedVendor};
+use crate::types::UserRoleEnum;
+use super::geometri::koordinat_valid;
+use super::model::{
+    CreatePemantauanElektronik, CreateVendorPemantauan, CreateZonaPemantauan, FilterTitikLacak,
+    HasilKirimTitikLacak, KirimTitikLacak, PelanggaranZona, PemantauanElektronik, TitikLacak,
+    UpdateVendorPemantauan, UpdateZonaPemantauan, VendorPemantauan, VendorPemantauanDenganKunci,
+    ZonaPemantauan,
+};
+use super::service;
+
+// Batas jumlah titik pada satu permintaan jejak
+const MAKS_TITIK_JEJAK: i64 = 10_000;
+
+fn buat_api_key() -> String {
+    let acak: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(40)
+        .map(char::from)
+        .collect();
+    format!("vp_{}", acak) // Prefix berbeda dari kunci user (ak_) dan kiosk (kk_)
+}
+
+// Vendor berlaku nasional, jadi hanya SuperAdmin yang mengelolanya
+fn ensure_super_admin(user: &AuthenticatedUser) -> Result<(), StatusCode> {
+    if user.role != UserRoleEnum::SuperAdmin {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(())
+}
+
+async fn get_zona(pool: &PgPool, id: i32) -> Result<ZonaPemantauan, StatusCode> {
+    sqlx::query_as!(
+        ZonaPemantauan,
+        r#"
+        SELECT id, klien_id, nama_zona, jenis_zona AS "jenis_zona: _", latitude_pusat, longitude_pusat,
+            radius_meter, poligon AS "poligon: _", jam_mulai, jam_selesai, aktif,
+            created_at, updated_at, created_by, updated_by
+        FROM zona_pemantauan WHERE id = $1 AND deleted_at IS NULL
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn get_pemantauan(pool: &PgPool, id: i32) -> Result<PemantauanElektronik, StatusCode> {
+    sqlx::query_as!(
+        PemantauanElektronik,
+        r#"
+        SELECT p.id, p.klien_id, p.vendor_id, v.nama_vendor, p.kode_perangkat, p.dasar_penetapan,
+            p.mulai_at, p.selesai_at, p.created_at, p.updated_at, p.created_by, p.updated_by
+        FROM pemantauan_elektronik p
+        JOIN vendor_pemantauan v ON v.id = p.vendor_id
+        WHERE p.id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)
+}
+
+// === VENDOR (SuperAdmin) ===
+
+// --- CREATE ---
+// URL: POST /api/vendor-pemantauan
+#[axum::debug_handler]
+pub async fn create_vendor_pemantauan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(payload): Json<CreateVendorPemantauan>,
+) -> Result<(StatusCode, Json<VendorPemantauanDenganKunci>), StatusCode> {
+    ensure_super_admin(&user)?;
+
+    let api_key = buat_api_key();
+    let vendor = sqlx::query_as!(
+        VendorPemantauan,
+        r#"
+        INSERT INTO vendor_pemantauan (nama_vendor, api_key_hash, created_by, updated_by)
+        VALUES ($1, $2, $3, $3)
+        RETURNING id, nama_vendor, aktif, terakhir_kirim_at, created_at, updated_at, created_by, updated_by
+        "#,
+        payload.nama_vendor,
+        digest(api_key.clone()),
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create vendor pemantauan: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((StatusCode::CREATED, Json(VendorPemantauanDenganKunci { vendor, api_key })))
+}
+
+// --- READ ALL ---
+// URL: GET /api/vendor-pemantauan
+// Semua staf boleh melihat daftar vendor (untuk memilih saat penugasan perangkat).
+#[axum::debug_handler]
+pub async fn get_all_vendor_pemantauan(
+    Extension(pool): Extension<PgPool>,
+) -> Result<Json<Vec<VendorPemantauan>>, StatusCode> {
+    let list = sqlx::query_as!(
+        VendorPemantauan,
+        r#"
+        SELECT id, nama_vendor, aktif, terakhir_kirim_at, created_at, updated_at, created_by, updated_by
+        FROM vendor_pemantauan WHERE deleted_at IS NULL
+        ORDER BY nama_vendor
+        "#
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch vendor pemantauan: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// --- UPDATE (nama, aktif/nonaktif) ---
+// URL: PUT /api/vendor-pemantauan/:id
+#[axum::debug_handler]
+pub async fn update_vendor_pemantauan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateVendorPemantauan>,
+) -> Result<Json<VendorPemantauan>, StatusCode> {
+    ensure_super_admin(&user)?;
+
+    let vendor = sqlx::query_as!(
+        VendorPemantauan,
+        r#"
+        UPDATE vendor_pemantauan SET
+            nama_vendor = COALESCE($1, nama_vendor),
+            aktif = COALESCE($2, aktif),
+            updated_by = $3
+        WHERE id = $4 AND deleted_at IS NULL
+        RETURNING id, nama_vendor, aktif, terakhir_kirim_at, created_at, updated_at, created_by, updated_by
+        "#,
+        payload.nama_vendor,
+        payload.aktif,
+        user.id,
+        id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(vendor))
+}
+
+// --- ROTASI API KEY ---
+// URL: POST /api/vendor-pemantauan/:id/rotasi-kunci
+#[axum::debug_handler]
+pub async fn rotasi_kunci_vendor_pemantauan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> Result<Json<VendorPemantauanDenganKunci>, StatusCode> {
+    ensure_super_admin(&user)?;
+
+    let api_key = buat_api_key();
+    let vendor = sqlx::query_as!(
+        VendorPemantauan,
+        r#"
+        UPDATE vendor_pemantauan SET api_key_hash = $1, updated_by = $2
+        WHERE id = $3 AND deleted_at IS NULL
+        RETURNING id, nama_vendor, aktif, terakhir_kirim_at, created_at, updated_at, created_by, updated_by
+        "#,
+        digest(api_key.clone()),
+        user.id,
+        id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(VendorPemantauanDenganKunci { vendor, api_key }))
+}
+
+// === PENUGASAN PERANGKAT ===
+
+// --- CREATE ---
+// URL: POST /api/klien/:klien_id/pemantauan-elektronik
+#[axum::debug_handler]
+pub async fn create_pemantauan_elektronik(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(klien_id): Path<i32>,
+    Json(payload): Json<CreatePemantauanElektronik>,
+) -> Result<(StatusCode, Json<PemantauanElektronik>), StatusCode> {
+    let kode_perangkat = payload.kode_perangkat.trim();
+    if kode_perangkat.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO pemantauan_elektronik (klien_id, vendor_id, kode_perangkat, dasar_penetapan, mulai_at, created_by, updated_by)
+        SELECT $1, v.id, $3, $4, COALESCE($5, NOW()), $6, $6
+        FROM vendor_pemantauan v WHERE v.id = $2 AND v.aktif AND v.deleted_at IS NULL
+        RETURNING id
+        "#,
+        klien_id,
+        payload.vendor_id,
+        kode_perangkat,
+        payload.dasar_penetapan,
+        payload.mulai_at,
+        user.id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        if e.as_database_error().is_some_and(|db_err| db_err.is_unique_violation()) {
+            return StatusCode::CONFLICT; // Klien atau perangkat sudah punya penugasan aktif
+        }
+        tracing::error!("Failed to create pemantauan elektronik: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::BAD_REQUEST)?; // Vendor tidak ada / nonaktif
+
+    Ok((StatusCode::CREATED, Json(get_pemantauan(&pool, id).await?)))
+}
+
+// --- READ ALL ---
+// URL: GET /api/klien/:klien_id/pemantauan-elektronik
+#[axum::debug_handler]
+pub async fn get_all_pemantauan_for_klien(
+    Extension(pool): Extension<PgPool>,
+    Path(klien_id): Path<i32>,
+) -> Result<Json<Vec<PemantauanElektronik>>, StatusCode> {
+    let list = sqlx::query_as!(
+        PemantauanElektronik,
+        r#"
+        SELECT p.id, p.klien_id, p.vendor_id, v.nama_vendor, p.kode_perangkat, p.dasar_penetapan,
+            p.mulai_at, p.selesai_at, p.created_at, p.updated_at, p.created_by, p.updated_by
+        FROM pemantauan_elektronik p
+        JOIN vendor_pemantauan v ON v.id = p.vendor_id
+        WHERE p.klien_id = $1
+        ORDER BY p.mulai_at DESC
+        "#,
+        klien_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch pemantauan elektronik: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// --- SELESAI (lepas perangkat) ---
+// URL: POST /api/pemantauan-elektronik/:id/selesai
+// Titik dari perangkat ini ditolak setelahnya; perangkat bisa ditugaskan ke klien lain.
+#[axum::debug_handler]
+pub async fn selesai_pemantauan_elektronik(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> Result<Json<PemantauanElektronik>, StatusCode> {
+    let pemantauan = get_pemantauan(&pool, id).await?;
+    ensure_klien_access(&pool, &user, pemantauan.klien_id).await?;
+
+    let result = sqlx::query!(
+        "UPDATE pemantauan_elektronik SET selesai_at = NOW(), updated_by = $1 WHERE id = $2 AND selesai_at IS NULL",
+        user.id,
+        id
+    )
+    .execute(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if result.rows_affected() == 0 {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    Ok(Json(get_pemantauan(&pool, id).await?))
+}
+
+// === ZONA ===
+
+// --- CREATE ---
+// URL: POST /api/klien/:klien_id/zona-pemantauan
+#[axum::debug_handler]
+pub async fn create_zona_pemantauan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(klien_id): Path<i32>,
+    Json(payload): Json<CreateZonaPemantauan>,
+) -> Result<(StatusCode, Json<ZonaPemantauan>), StatusCode> {
+    // Tepat satu bentuk: lingkaran lengkap atau poligon minimal 3 titik
+    let bentuk_valid = match (payload.latitude_pusat, payload.longitude_pusat, payload.radius_meter, &payload.poligon) {
+        (Some(lat), Some(lon), Some(radius), None) => koordinat_valid(lat, lon) && radius > 0.0,
+        (None, None, None, Some(p)) => p.len() >= 3 && p.iter().all(|&[lat, lon]| koordinat_valid(lat, lon)),
+        _ => false,
+    };
+    let jam_valid = match (payload.jam_mulai, payload.jam_selesai) {
+        (Some(mulai), Some(selesai)) => mulai != selesai,
+        (None, None) => true,
+        _ => false,
+    };
+    if !bentuk_valid || !jam_valid {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let zona = sqlx::query_as!(
+        ZonaPemantauan,
+        r#"
+        INSERT INTO zona_pemantauan
+            (klien_id, nama_zona, jenis_zona, latitude_pusat, longitude_pusat, radius_meter, poligon,
+             jam_mulai, jam_selesai, created_by, updated_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10)
+        RETURNING id, klien_id, nama_zona, jenis_zona AS "jenis_zona: _", latitude_pusat, longitude_pusat,
+            radius_meter, poligon AS "poligon: _", jam_mulai, jam_selesai, aktif,
+            created_at, updated_at, created_by, updated_by
+        "#,
+        klien_id,
+        payload.nama_zona,
+        payload.jenis_zona as _,
+        payload.latitude_pusat,
+        payload.longitude_pusat,
+        payload.radius_meter,
+        payload.poligon.map(SqlJson) as _,
+        payload.jam_mulai,
+        payload.jam_selesai,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create zona pemantauan: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((StatusCode::CREATED, Json(zona)))
+}
+
+// --- READ ALL ---
+// URL: GET /api/klien/:klien_id/zona-pemantauan
+#[axum::debug_handler]
+pub async fn get_all_zona_for_klien(
+    Extension(pool): Extension<PgPool>,
+    Path(klien_id): Path<i32>,
+) -> Result<Json<Vec<ZonaPemantauan>>, StatusCode> {
+    let list = sqlx::query_as!(
+        ZonaPemantauan,
+        r#"
+        SELECT id, klien_id, nama_zona, jenis_zona AS "jenis_zona: _", latitude_pusat, longitude_pusat,
+            radius_meter, poligon AS "poligon: _", jam_mulai, jam_selesai, aktif,
+            created_at, updated_at, created_by, updated_by
+        FROM zona_pemantauan WHERE klien_id = $1 AND deleted_at IS NULL
+        ORDER BY id
+        "#,
+        klien_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch zona pemantauan: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// --- UPDATE ---
+// URL: PUT /api/zona-pemantauan/:id
+// Menonaktifkan zona juga menutup pelanggaran yang masih terbuka.
+#[axum::debug_handler]
+pub async fn update_zona_pemantauan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateZonaPemantauan>,
+) -> Result<Json<ZonaPemantauan>, StatusCode> {
+    let lama = get_zona(&pool, id).await?;
+    ensure_klien_access(&pool, &user, lama.klien_id).await?;
+
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    sqlx::query!(
+        r#"
+        UPDATE zona_pemantauan SET
+            nama_zona = COALESCE($1, nama_zona),
+            aktif = COALESCE($2, aktif),
+            updated_by = $3
+        WHERE id = $4 AND deleted_at IS NULL
+        "#,
+        payload.nama_zona,
+        payload.aktif,
+        user.id,
+        id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if payload.aktif == Some(false) {
+        sqlx::query!(
+            "UPDATE pelanggaran_zona SET selesai_at = NOW() WHERE zona_id = $1 AND selesai_at IS NULL",
+            id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    }
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(get_zona(&pool, id).await?))
+}
+
+// --- DELETE (SOFT) ---
+// URL: DELETE /api/zona-pemantauan/:id
+#[axum::debug_handler]
+pub async fn delete_zona_pemantauan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, StatusCode> {
+    let zona = get_zona(&pool, id).await?;
+    ensure_klien_access(&pool, &user, zona.klien_id).await?;
+
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    sqlx::query!(
+        "UPDATE zona_pemantauan SET deleted_at = NOW(), aktif = FALSE, updated_by = $1 WHERE id = $2",
+        user.id,
+        id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    sqlx::query!(
+        "UPDATE pelanggaran_zona SET selesai_at = NOW() WHERE zona_id = $1 AND selesai_at IS NULL",
+        id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// === JEJAK & PELANGGARAN ===
+
+// --- JEJAK ---
+// URL: GET /api/klien/:klien_id/titik-lacak?mulai=&selesai=
+#[axum::debug_handler]
+pub async fn get_titik_lacak_for_klien(
+    Extension(pool): Extension<PgPool>,
+    Path(klien_id): Path<i32>,
+    Query(filter): Query<FilterTitikLacak>,
+) -> Result<Json<Vec<TitikLacak>>, StatusCode> {
+    let selesai = filter.selesai.unwrap_or_else(Utc::now);
+    let mulai = filter.mulai.unwrap_or(selesai - Duration::hours(24));
+    if mulai > selesai {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let list = sqlx::query_as!(
+        TitikLacak,
+        r#"
+        SELECT id, waktu_rekam, latitude, longitude, akurasi_meter
+        FROM titik_lacak
+        WHERE klien_id = $1 AND waktu_rekam BETWEEN $2 AND $3
+        ORDER BY waktu_rekam
+        LIMIT $4
+        "#,
+        klien_id,
+        mulai,
+        selesai,
+        MAKS_TITIK_JEJAK
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch titik lacak: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// --- PELANGGARAN ---
+// URL: GET /api/klien/:klien_id/pelanggaran-zona
+#[axum::debug_handler]
+pub async fn get_pelanggaran_zona_for_klien(
+    Extension(pool): Extension<PgPool>,
+    Path(klien_id): Path<i32>,
+) -> Result<Json<Vec<PelanggaranZona>>, StatusCode> {
+    let list = sqlx::query_as!(
+        PelanggaranZona,
+        r#"
+        SELECT p.id, p.zona_id, z.nama_zona, z.jenis_zona AS "jenis_zona: _", p.klien_id,
+            p.mulai_at, p.selesai_at, p.titik_pertama_id, p.jumlah_titik
+        FROM pelanggaran_zona p
+        JOIN zona_pemantauan z ON z.id = p.zona_id
+        WHERE p.klien_id = $1
+        ORDER BY p.mulai_at DESC
+        "#,
+        klien_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch pelanggaran zona: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// === ENDPOINT VENDOR (autentikasi API key vendor) ===
+
+// URL: POST /api/pemantauan/titik-lacak
+// Kiriman berkelompok (maks. 1000 titik). Titik yang tidak valid dilaporkan per indeks
+// tanpa menggagalkan titik lain; kiriman ulang titik yang sama dihitung sebagai duplikat.
+pub async fn kirim_titik_lacak(
+    Extension(pool): Extension<PgPool>,
+    Extension(vendor): Extension<AuthenticatedVendor>,
+    Json(payload): Json<KirimTitikLacak>,
+) -> Result<Json<HasilKirimTitikLacak>, StatusCode> {
+    let hasil = service::terima_titik_lacak(&pool, vendor.id, payload.titik).await?;
+    Ok(Json(hasil))
+}
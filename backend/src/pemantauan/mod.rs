@@ -0,0 +1,4 @@
+pub mod model;
+pub mod geometri;
+pub mod service;
+pub mod handlers;
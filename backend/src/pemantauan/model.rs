@@ -0,0 +1,169 @@
+// File baru: src/pemantauan/model.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, FromRow};
+use chrono::{DateTime, NaiveTime, Utc};
+use crate::types::JenisZonaEnum;
+
+// === VENDOR ===
+
+// Baris 'vendor_pemantauan' tanpa hash kunci
+#[derive(Debug, Serialize, FromRow)]
+pub struct VendorPemantauan {
+    pub id: i32,
+    pub nama_vendor: String,
+    pub aktif: bool,
+    pub terakhir_kirim_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateVendorPemantauan {
+    pub nama_vendor: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateVendorPemantauan {
+    pub nama_vendor: Option<String>,
+    pub aktif: Option<bool>,
+}
+
+// API key hanya ditampilkan saat registrasi/rotasi
+#[derive(Debug, Serialize)]
+pub struct VendorPemantauanDenganKunci {
+    #[serde(flatten)]
+    pub vendor: VendorPemantauan,
+    pub api_key: String,
+}
+
+// === PENUGASAN PERANGKAT ===
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PemantauanElektronik {
+    pub id: i32,
+    pub klien_id: i32,
+    pub vendor_id: i32,
+    pub nama_vendor: String,
+    pub kode_perangkat: String,
+    pub dasar_penetapan: Option<String>,
+    pub mulai_at: DateTime<Utc>,
+    pub selesai_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePemantauanElektronik {
+    pub vendor_id: i32,
+    pub kode_perangkat: String,
+    pub dasar_penetapan: Option<String>, // Nomor penetapan/putusan pengadilan
+    pub mulai_at: Option<DateTime<Utc>>,
+}
+
+// === ZONA ===
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ZonaPemantauan {
+    pub id: i32,
+    pub klien_id: i32,
+    pub nama_zona: String,
+    pub jenis_zona: JenisZonaEnum,
+    pub latitude_pusat: Option<f64>,
+    pub longitude_pusat: Option<f64>,
+    pub radius_meter: Option<f64>,
+    pub poligon: Option<Json<Vec<[f64; 2]>>>,
+    pub jam_mulai: Option<NaiveTime>,
+    pub jam_selesai: Option<NaiveTime>,
+    pub aktif: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+}
+
+// Lingkaran (pusat + radius) ATAU poligon [[lat, lon], ...], tidak keduanya
+#[derive(Debug, Deserialize)]
+pub struct CreateZonaPemantauan {
+    pub nama_zona: String,
+    pub jenis_zona: JenisZonaEnum,
+    pub latitude_pusat: Option<f64>,
+    pub longitude_pusat: Option<f64>,
+    pub radius_meter: Option<f64>,
+    pub poligon: Option<Vec<[f64; 2]>>,
+    pub jam_mulai: Option<NaiveTime>,
+    pub jam_selesai: Option<NaiveTime>,
+}
+
+// Bentuk zona tidak bisa diubah karena pelanggaran lama merujuk ke zona ini;
+// untuk batas baru, nonaktifkan zona lama dan buat zona baru.
+#[derive(Debug, Deserialize)]
+pub struct UpdateZonaPemantauan {
+    pub nama_zona: Option<String>,
+    pub aktif: Option<bool>,
+}
+
+// === TITIK LACAK ===
+
+#[derive(Debug, Deserialize)]
+pub struct TitikLacakMasuk {
+    pub kode_perangkat: String,
+    pub waktu_rekam: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub akurasi_meter: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KirimTitikLacak {
+    pub titik: Vec<TitikLacakMasuk>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TitikDitolak {
+    pub indeks: usize,
+    pub alasan: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HasilKirimTitikLacak {
+    pub diterima: usize,
+    pub duplikat: usize,
+    pub ditolak: Vec<TitikDitolak>,
+    pub pelanggaran_baru: usize,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct TitikLacak {
+    pub id: i64,
+    pub waktu_rekam: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub akurasi_meter: Option<f32>,
+}
+
+// Query string jejak; bawaan 24 jam terakhir
+#[derive(Debug, Deserialize)]
+pub struct FilterTitikLacak {
+    pub mulai: Option<DateTime<Utc>>,
+    pub selesai: Option<DateTime<Utc>>,
+}
+
+// === PELANGGARAN ===
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PelanggaranZona {
+    pub id: i64,
+    pub zona_id: i32,
+    pub nama_zona: String,
+    pub jenis_zona: JenisZonaEnum,
+    pub klien_id: i32,
+    pub mulai_at: DateTime<Utc>,
+    pub selesai_at: Option<DateTime<Utc>>,
+    pub titik_pertama_id: Option<i64>,
+    pub jumlah_titik: i32,
+}
@@ -0,0 +1,256 @@
+// File baru: src/pemantauan/service.rs
+//
+// Penerimaan titik lacak dari vendor dan deteksi pelanggaran zona. Titik
+// disimpan sekaligus (UNNEST) lalu hanya titik yang benar-benar baru yang
+// diperiksa terhadap zona, urut waktu rekam per klien.
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use sqlx::{PgConnection, PgPool};
+use std::collections::{BTreeMap, HashMap};
+use crate::peringatan::service as peringatan;
+use crate::types::{JenisPeringatanEnum, JenisZonaEnum};
+use super::geometri::koordinat_valid;
+use super::model::{HasilKirimTitikLacak, TitikDitolak, TitikLacakMasuk, ZonaPemantauan};
+
+pub const MAKS_TITIK_PER_KIRIMAN: usize = 1000;
+// Jam perangkat vendor boleh sedikit mendahului jam server
+const TOLERANSI_MASA_DEPAN_MENIT: i64 = 5;
+
+struct TitikBaru {
+    id: i64,
+    waktu_rekam: DateTime<Utc>,
+    latitude: f64,
+    longitude: f64,
+}
+
+// Pelanggaran yang sedang terbuka untuk satu zona selama pemrosesan kiriman
+struct PelanggaranTerbuka {
+    id: i64,
+    tambahan_titik: i32,
+}
+
+pub async fn terima_titik_lacak(
+    pool: &PgPool,
+    vendor_id: i32,
+    titik: Vec<TitikLacakMasuk>,
+) -> Result<HasilKirimTitikLacak, StatusCode> {
+    if titik.len() > MAKS_TITIK_PER_KIRIMAN {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    // 1. Petakan kode perangkat ke penugasan aktif milik vendor ini
+    let mut kode: Vec<String> = titik.iter().map(|t| t.kode_perangkat.clone()).collect();
+    kode.sort();
+    kode.dedup();
+    let penugasan: HashMap<String, (i32, i32, DateTime<Utc>)> = sqlx::query!(
+        r#"
+        SELECT id, klien_id, kode_perangkat, mulai_at FROM pemantauan_elektronik
+        WHERE vendor_id = $1 AND selesai_at IS NULL AND kode_perangkat = ANY($2)
+        "#,
+        vendor_id,
+        &kode
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .into_iter()
+    .map(|p| (p.kode_perangkat, (p.id, p.klien_id, p.mulai_at)))
+    .collect();
+
+    // 2. Validasi per titik; titik yang ditolak tidak menggagalkan kiriman
+    let batas_waktu = Utc::now() + Duration::minutes(TOLERANSI_MASA_DEPAN_MENIT);
+    let mut ditolak = Vec::new();
+    let (mut pemantauan_ids, mut klien_ids, mut waktu, mut lat, mut lon, mut akurasi) =
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    for (indeks, t) in titik.into_iter().enumerate() {
+        let alasan = match penugasan.get(&t.kode_perangkat) {
+            None => Some("Perangkat tidak terdaftar pada klien mana pun"),
+            Some(_) if !koordinat_valid(t.latitude, t.longitude) => Some("Koordinat tidak valid"),
+            Some(_) if t.waktu_rekam > batas_waktu => Some("Waktu rekam di masa depan"),
+            Some(&(_, _, mulai_at)) if t.waktu_rekam < mulai_at => Some("Waktu rekam sebelum pemantauan dimulai"),
+            Some(_) => None,
+        };
+        if let Some(alasan) = alasan {
+            ditolak.push(TitikDitolak { indeks, alasan: alasan.to_string() });
+            continue;
+        }
+        let (pemantauan_id, klien_id, _) = penugasan[&t.kode_perangkat];
+        pemantauan_ids.push(pemantauan_id);
+        klien_ids.push(klien_id);
+        waktu.push(t.waktu_rekam);
+        lat.push(t.latitude);
+        lon.push(t.longitude);
+        akurasi.push(t.akurasi_meter);
+    }
+    let jumlah_valid = pemantauan_ids.len();
+
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // 3. Simpan sekaligus; kiriman ulang titik yang sama diabaikan
+    let baru = sqlx::query!(
+        r#"
+        INSERT INTO titik_lacak (pemantauan_id, klien_id, waktu_rekam, latitude, longitude, akurasi_meter)
+        SELECT * FROM UNNEST($1::INT[], $2::INT[], $3::TIMESTAMPTZ[], $4::FLOAT8[], $5::FLOAT8[], $6::REAL[])
+        ON CONFLICT (klien_id, waktu_rekam) DO NOTHING
+        RETURNING id, klien_id, waktu_rekam, latitude, longitude
+        "#,
+        &pemantauan_ids,
+        &klien_ids,
+        &waktu,
+        &lat,
+        &lon,
+        &akurasi as &[Option<f32>]
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to insert titik lacak: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let diterima = baru.len();
+    let mut per_klien: BTreeMap<i32, Vec<TitikBaru>> = BTreeMap::new();
+    for t in baru {
+        per_klien.entry(t.klien_id).or_default().push(TitikBaru {
+            id: t.id,
+            waktu_rekam: t.waktu_rekam,
+            latitude: t.latitude,
+            longitude: t.longitude,
+        });
+    }
+
+    // 4. Periksa zona
+    let mut pelanggaran_baru = 0;
+    for (klien_id, mut daftar) in per_klien {
+        daftar.sort_by_key(|t| t.waktu_rekam);
+        pelanggaran_baru += periksa_zona(&mut tx, klien_id, &daftar)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to check zona for klien {}: {}", klien_id, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+    }
+
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(HasilKirimTitikLacak {
+        diterima,
+        duplikat: jumlah_valid - diterima,
+        ditolak,
+        pelanggaran_baru,
+    })
+}
+
+/// Menjalankan episode pelanggaran per zona atas titik-titik baru (urut waktu).
+/// Mengembalikan jumlah pelanggaran yang baru dibuka.
+async fn periksa_zona(
+    conn: &mut PgConnection,
+    klien_id: i32,
+    titik: &[TitikBaru],
+) -> Result<usize, sqlx::Error> {
+    let zona_list = sqlx::query_as!(
+        ZonaPemantauan,
+        r#"
+        SELECT id, klien_id, nama_zona, jenis_zona AS "jenis_zona: _", latitude_pusat, longitude_pusat,
+            radius_meter, poligon AS "poligon: _", jam_mulai, jam_selesai, aktif,
+            created_at, updated_at, created_by, updated_by
+        FROM zona_pemantauan
+        WHERE klien_id = $1 AND aktif AND deleted_at IS NULL
+        "#,
+        klien_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    if zona_list.is_empty() {
+        return Ok(0);
+    }
+
+    let mut terbuka: HashMap<i32, PelanggaranTerbuka> = sqlx::query!(
+        "SELECT id, zona_id FROM pelanggaran_zona WHERE klien_id = $1 AND selesai_at IS NULL",
+        klien_id
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|p| (p.zona_id, PelanggaranTerbuka { id: p.id, tambahan_titik: 0 }))
+    .collect();
+
+    let mut jumlah_baru = 0;
+    for zona in &zona_list {
+        for t in titik {
+            let melanggar = zona.dilanggar_oleh(t.latitude, t.longitude, t.waktu_rekam);
+            match (melanggar, terbuka.get_mut(&zona.id)) {
+                (true, Some(p)) => p.tambahan_titik += 1,
+                (true, None) => {
+                    let id = sqlx::query_scalar!(
+                        r#"
+                        INSERT INTO pelanggaran_zona (zona_id, klien_id, mulai_at, titik_pertama_id)
+                        VALUES ($1, $2, $3, $4)
+                        RETURNING id
+                        "#,
+                        zona.id,
+                        klien_id,
+                        t.waktu_rekam,
+                        t.id
+                    )
+                    .fetch_one(&mut *conn)
+                    .await?;
+                    peringatan::kirim_ke_pk(
+                        conn,
+                        klien_id,
+                        JenisPeringatanEnum::PelanggaranZona,
+                        &pesan_pelanggaran(zona, t.waktu_rekam),
+                        Some(id),
+                    )
+                    .await?;
+                    terbuka.insert(zona.id, PelanggaranTerbuka { id, tambahan_titik: 0 });
+                    jumlah_baru += 1;
+                }
+                (false, Some(_)) => {
+                    if let Some(p) = terbuka.remove(&zona.id) {
+                        sqlx::query!(
+                            r#"
+                            UPDATE pelanggaran_zona SET selesai_at = $2, jumlah_titik = jumlah_titik + $3
+                            WHERE id = $1
+                            "#,
+                            p.id,
+                            t.waktu_rekam,
+                            p.tambahan_titik
+                        )
+                        .execute(&mut *conn)
+                        .await?;
+                    }
+                }
+                (false, None) => {}
+            }
+        }
+    }
+
+    // Pelanggaran yang masih berlangsung: cukup perbarui jumlah titik
+    for p in terbuka.values().filter(|p| p.tambahan_titik > 0) {
+        sqlx::query!(
+            "UPDATE pelanggaran_zona SET jumlah_titik = jumlah_titik + $2 WHERE id = $1",
+            p.id,
+            p.tambahan_titik
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(jumlah_baru)
+}
+
+fn pesan_pelanggaran(zona: &ZonaPemantauan, waktu: DateTime<Utc>) -> String {
+    let wib = FixedOffset::east_opt(7 * 3600).expect("offset WIB valid");
+    let arah = match zona.jenis_zona {
+        JenisZonaEnum::Inklusi => "keluar dari zona inklusi",
+        JenisZonaEnum::Eksklusi => "memasuki zona eksklusi",
+    };
+    format!(
+        "Klien terdeteksi {} \"{}\" pada {} WIB.",
+        arah,
+        zona.nama_zona,
+        waktu.with_timezone(&wib).format("%d-%m-%Y %H:%M")
+    )
+}
@@ -0,0 +1,107 @@
+// File baru: src/penomoran/handlers.rs
+
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    Json,
+};
+use sqlx::PgPool;
+use crate::auth::authorization::{check_permission, get_bapas_ownership};
+use crate::auth::model::AuthenticatedUser;
+use crate::types::{JenisRegisterEnum, UserRoleEnum};
+use super::model::{FormatNomorRegister, FormatNomorRegisterEfektif, SetFormatNomorRegister};
+use super::service;
+
+const SEMUA_JENIS: [JenisRegisterEnum; 3] = [
+    JenisRegisterEnum::Litmas,
+    JenisRegisterEnum::Integrasi,
+    JenisRegisterEnum::ProsesHukum,
+];
+
+// --- READ ---
+// URL: GET /api/bapas/:bapas_id/format-nomor-register
+// Menampilkan template yang berlaku untuk setiap jenis register, termasuk contoh nomor.
+pub async fn get_format_nomor_register(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(bapas_id): Path<i32>,
+) -> Result<Json<Vec<FormatNomorRegisterEfektif>>, StatusCode> {
+    let ownership = get_bapas_ownership(&pool, bapas_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    // Pegawai boleh melihat format Bapasnya sendiri
+    let boleh_lihat = check_permission(&user, &ownership)
+        || (user.role == UserRoleEnum::Pegawai && user.bapas_id == Some(bapas_id));
+    if !boleh_lihat {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let mut conn = pool.acquire().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let kode = service::kode_bapas(&mut conn, bapas_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let hari_ini = service::hari_ini();
+
+    let mut list = Vec::with_capacity(SEMUA_JENIS.len());
+    for jenis in SEMUA_JENIS {
+        let (template, bawaan) = service::template_untuk_bapas(&mut conn, bapas_id, jenis)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to fetch format nomor register: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        let contoh = service::render_nomor(&template, 1, &kode, hari_ini);
+        list.push(FormatNomorRegisterEfektif { jenis_register: jenis, template, bawaan, contoh });
+    }
+
+    Ok(Json(list))
+}
+
+// --- UPSERT ---
+// URL: PUT /api/bapas/:bapas_id/format-nomor-register
+pub async fn set_format_nomor_register(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(bapas_id): Path<i32>,
+    Json(payload): Json<SetFormatNomorRegister>,
+) -> Result<Json<FormatNomorRegister>, StatusCode> {
+    let ownership = get_bapas_ownership(&pool, bapas_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if !check_permission(&user, &ownership) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let template = payload.template.trim();
+    if let Err(alasan) = service::validasi_template(template) {
+        tracing::warn!("Rejected format nomor register for bapas {}: {}", bapas_id, alasan);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let format = sqlx::query_as!(
+        FormatNomorRegister,
+        r#"
+        INSERT INTO format_nomor_register (bapas_id, jenis_register, template, created_by, updated_by)
+        VALUES ($1, $2, $3, $4, $4)
+        ON CONFLICT (bapas_id, jenis_register)
+        DO UPDATE SET template = EXCLUDED.template, updated_by = EXCLUDED.updated_by
+        RETURNING id, bapas_id, jenis_register AS "jenis_register: _", template,
+            created_at, updated_at, created_by, updated_by
+        "#,
+        bapas_id,
+        payload.jenis_register as _,
+        template,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to save format nomor register: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(format))
+}
@@ -0,0 +1,4 @@
+// File: src/penomoran/mod.rs
+pub mod model;
+pub mod service;
+pub mod handlers;
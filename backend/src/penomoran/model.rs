@@ -0,0 +1,35 @@
+// File baru: src/penomoran/model.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+use crate::types::JenisRegisterEnum;
+
+// Merepresentasikan satu baris dari tabel 'format_nomor_register'
+#[derive(Debug, Serialize, FromRow)]
+pub struct FormatNomorRegister {
+    pub id: i32,
+    pub bapas_id: i32,
+    pub jenis_register: JenisRegisterEnum,
+    pub template: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+}
+
+// Template yang berlaku untuk satu jenis register di sebuah Bapas
+#[derive(Debug, Serialize)]
+pub struct FormatNomorRegisterEfektif {
+    pub jenis_register: JenisRegisterEnum,
+    pub template: String,
+    pub bawaan: bool,
+    pub contoh: String,
+}
+
+// Data untuk mengatur template sebuah jenis register
+#[derive(Debug, Deserialize)]
+pub struct SetFormatNomorRegister {
+    pub jenis_register: JenisRegisterEnum,
+    pub template: String,
+}
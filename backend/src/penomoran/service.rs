@@ -0,0 +1,259 @@
+// File baru: src/penomoran/service.rs
+//
+// Layanan penomoran register. Nomor diambil di dalam transaksi yang sama
+// dengan INSERT data, sehingga urutan tetap tanpa celah: jika INSERT gagal,
+// penambahan penghitung ikut dibatalkan.
+
+use chrono::{Datelike, FixedOffset, NaiveDate, Utc};
+use sqlx::PgConnection;
+use crate::types::JenisRegisterEnum;
+
+pub const PLACEHOLDER: [&str; 5] = ["seq", "bapas_code", "roman_month", "month", "year"];
+
+pub fn template_bawaan(jenis: JenisRegisterEnum) -> &'static str {
+    match jenis {
+        JenisRegisterEnum::Litmas => "{seq}/LITMAS/{bapas_code}/{roman_month}/{year}",
+        JenisRegisterEnum::Integrasi => "{seq}/INTEGRASI/{bapas_code}/{roman_month}/{year}",
+        JenisRegisterEnum::ProsesHukum => "{seq}/PH/{bapas_code}/{roman_month}/{year}",
+    }
+}
+
+/// Tanggal hari ini menurut WIB, dipakai untuk menentukan tahun urutan.
+pub fn hari_ini() -> NaiveDate {
+    let wib = FixedOffset::east_opt(7 * 3600).expect("offset WIB valid");
+    Utc::now().with_timezone(&wib).date_naive()
+}
+
+fn bulan_romawi(bulan: u32) -> &'static str {
+    const ROMAWI: [&str; 12] = ["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X", "XI", "XII"];
+    ROMAWI[(bulan as usize).saturating_sub(1).min(11)]
+}
+
+/// Memecah `{nama}` atau `{nama:lebar}` di dalam template.
+fn placeholder_di(template: &str) -> Vec<(usize, usize, &str, Option<&str>)> {
+    let mut hasil = Vec::new();
+    let mut awal = 0;
+    while let Some(buka) = template[awal..].find('{').map(|i| i + awal) {
+        let Some(tutup) = template[buka..].find('}').map(|i| i + buka) else { break };
+        let isi = &template[buka + 1..tutup];
+        let (nama, format) = match isi.split_once(':') {
+            Some((nama, format)) => (nama, Some(format)),
+            None => (isi, None),
+        };
+        hasil.push((buka, tutup + 1, nama, format));
+        awal = tutup + 1;
+    }
+    hasil
+}
+
+/// Template harus memuat `{seq}`, `{year}`, dan `{bapas_code}`, dan hanya memakai
+/// placeholder yang dikenal. Urutan dimulai ulang setiap tahun per Bapas, sedangkan
+/// nomor register unik di semua Bapas, jadi tahun dan kode Bapas wajib ada.
+/// `{seq:4}` berarti nomor urut diisi nol di depan sampai 4 digit.
+pub fn validasi_template(template: &str) -> Result<(), String> {
+    let placeholder = placeholder_di(template);
+    for wajib in ["seq", "year", "bapas_code"] {
+        if !placeholder.iter().any(|(_, _, nama, _)| *nama == wajib) {
+            return Err(format!("Template harus memuat {{{}}}", wajib));
+        }
+    }
+    for (_, _, nama, format) in placeholder {
+        if !PLACEHOLDER.contains(&nama) {
+            return Err(format!("Placeholder {{{}}} tidak dikenal", nama));
+        }
+        if let Some(format) = format {
+            if nama != "seq" || !format.parse::<usize>().is_ok_and(|lebar| lebar <= 10) {
+                return Err(format!("Format {{{}:{}}} tidak valid", nama, format));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn render_nomor(template: &str, seq: i32, kode_bapas: &str, tanggal: NaiveDate) -> String {
+    let mut hasil = String::with_capacity(template.len() + 16);
+    let mut posisi = 0;
+    for (awal, akhir, nama, format) in placeholder_di(template) {
+        hasil.push_str(&template[posisi..awal]);
+        let lebar = format.and_then(|f| f.parse::<usize>().ok()).unwrap_or(0);
+        match nama {
+            "seq" => hasil.push_str(&format!("{:0lebar$}", seq, lebar = lebar)),
+            "bapas_code" => hasil.push_str(kode_bapas),
+            "roman_month" => hasil.push_str(bulan_romawi(tanggal.month())),
+            "month" => hasil.push_str(&format!("{:02}", tanggal.month())),
+            "year" => hasil.push_str(&tanggal.year().to_string()),
+            _ => hasil.push_str(&template[awal..akhir]),
+        }
+        posisi = akhir;
+    }
+    hasil.push_str(&template[posisi..]);
+    hasil
+}
+
+/// Template yang berlaku untuk Bapas, beserta penanda apakah memakai bawaan.
+pub async fn template_untuk_bapas(
+    conn: &mut PgConnection,
+    bapas_id: i32,
+    jenis: JenisRegisterEnum,
+) -> Result<(String, bool), sqlx::Error> {
+    let template = sqlx::query_scalar!(
+        "SELECT template FROM format_nomor_register WHERE bapas_id = $1 AND jenis_register = $2",
+        bapas_id,
+        jenis as _
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(match template {
+        Some(template) => (template, false),
+        None => (template_bawaan(jenis).to_string(), true),
+    })
+}
+
+pub async fn kode_bapas(conn: &mut PgConnection, bapas_id: i32) -> Result<String, sqlx::Error> {
+    let kode = sqlx::query_scalar!("SELECT kode_bapas FROM bapas WHERE id = $1", bapas_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    Ok(kode.unwrap_or_else(|| bapas_id.to_string()))
+}
+
+/// Nomor yang sudah dipakai data aktif, mis. karena pernah diisi manual.
+async fn nomor_terpakai(conn: &mut PgConnection, jenis: JenisRegisterEnum, nomor: &str) -> Result<bool, sqlx::Error> {
+    let terpakai = match jenis {
+        JenisRegisterEnum::Litmas => sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM penerimaan_dewasa WHERE no_register_litmas_dewasa = $1 AND deleted_at IS NULL) AS "ada!""#,
+            nomor
+        )
+        .fetch_one(&mut *conn)
+        .await?,
+        JenisRegisterEnum::Integrasi => sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM layanan_integrasi_dewasa WHERE nomor_register_integrasi_dewasa = $1 AND deleted_at IS NULL) AS "ada!""#,
+            nomor
+        )
+        .fetch_one(&mut *conn)
+        .await?,
+        JenisRegisterEnum::ProsesHukum => sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM proses_hukum_dewasa WHERE nomor_register_proses_hukum_dewasa = $1 AND deleted_at IS NULL) AS "ada!""#,
+            nomor
+        )
+        .fetch_one(&mut *conn)
+        .await?,
+    };
+    Ok(terpakai)
+}
+
+/// Mengambil nomor berikutnya untuk (Bapas, jenis register, tahun).
+/// Harus dipanggil di dalam transaksi yang juga menyimpan data pemakai nomor.
+/// Nomor yang sudah dipakai (diisi manual) dilewati; kalau tidak, INSERT akan
+/// selalu ditolak indeks unik dan penghitung ikut dibatalkan ke nilai yang sama.
+pub async fn ambil_nomor_berikutnya(
+    conn: &mut PgConnection,
+    bapas_id: i32,
+    jenis: JenisRegisterEnum,
+    tanggal: NaiveDate,
+) -> Result<String, sqlx::Error> {
+    let (template, _) = template_untuk_bapas(conn, bapas_id, jenis).await?;
+    let kode = kode_bapas(conn, bapas_id).await?;
+    loop {
+        let nomor = render_nomor(&template, naikkan_urutan(conn, bapas_id, jenis, tanggal).await?, &kode, tanggal);
+        if !nomor_terpakai(conn, jenis, &nomor).await? {
+            return Ok(nomor);
+        }
+    }
+}
+
+async fn naikkan_urutan(
+    conn: &mut PgConnection,
+    bapas_id: i32,
+    jenis: JenisRegisterEnum,
+    tanggal: NaiveDate,
+) -> Result<i32, sqlx::Error> {
+    // Baris penghitung terkunci sampai transaksi selesai; permintaan lain
+    // untuk urutan yang sama menunggu lalu melanjutkan dari nilai terbaru.
+    sqlx::query_scalar!(
+        r#"
+        INSERT INTO urutan_nomor_register (bapas_id, jenis_register, tahun, nomor_terakhir)
+        VALUES ($1, $2, $3, 1)
+        ON CONFLICT (bapas_id, jenis_register, tahun)
+        DO UPDATE SET nomor_terakhir = urutan_nomor_register.nomor_terakhir + 1
+        RETURNING nomor_terakhir
+        "#,
+        bapas_id,
+        jenis as _,
+        tanggal.year()
+    )
+    .fetch_one(&mut *conn)
+    .await
+}
+
+/// Mengisi nomor register untuk klien jika `nomor` kosong; nilai yang diisi
+/// manual dibiarkan apa adanya.
+pub async fn isi_nomor_register_klien(
+    conn: &mut PgConnection,
+    klien_id: i32,
+    jenis: JenisRegisterEnum,
+    nomor: Option<String>,
+) -> Result<Option<String>, sqlx::Error> {
+    if let Some(nomor) = nomor.filter(|n| !n.trim().is_empty()) {
+        return Ok(Some(nomor));
+    }
+
+    let bapas_id = sqlx::query_scalar!("SELECT bapas_id FROM klien WHERE id = $1", klien_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    ambil_nomor_berikutnya(conn, bapas_id, jenis, hari_ini()).await.map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tanggal(tahun: i32, bulan: u32, hari: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(tahun, bulan, hari).unwrap()
+    }
+
+    #[test]
+    fn template_bawaan_valid() {
+        for jenis in [JenisRegisterEnum::Litmas, JenisRegisterEnum::Integrasi, JenisRegisterEnum::ProsesHukum] {
+            assert_eq!(validasi_template(template_bawaan(jenis)), Ok(()));
+        }
+    }
+
+    #[test]
+    fn template_tanpa_placeholder_wajib_ditolak() {
+        assert!(validasi_template("{year}/{bapas_code}").is_err());
+        assert!(validasi_template("{seq}/{bapas_code}").is_err());
+        assert!(validasi_template("W{seq:4}-{year}").is_err());
+    }
+
+    #[test]
+    fn placeholder_tidak_dikenal_ditolak() {
+        assert!(validasi_template("{seq}/{bapas_code}/{year}/{hari}").is_err());
+    }
+
+    #[test]
+    fn format_hanya_untuk_seq_dan_lebar_wajar() {
+        assert_eq!(validasi_template("{seq:4}/{bapas_code}/{year}"), Ok(()));
+        assert!(validasi_template("{seq:x}/{bapas_code}/{year}").is_err());
+        assert!(validasi_template("{seq:11}/{bapas_code}/{year}").is_err());
+        assert!(validasi_template("{seq}/{bapas_code}/{year:2}").is_err());
+    }
+
+    #[test]
+    fn render_mengisi_semua_placeholder() {
+        let nomor = render_nomor("{seq:4}/LITMAS/{bapas_code}/{roman_month}/{month}/{year}", 7, "BPS.JKT", tanggal(2026, 9, 1));
+        assert_eq!(nomor, "0007/LITMAS/BPS.JKT/IX/09/2026");
+    }
+
+    #[test]
+    fn render_seq_tanpa_lebar_dan_melebihi_lebar() {
+        assert_eq!(render_nomor("{seq}-{year}", 12, "X", tanggal(2026, 1, 1)), "12-2026");
+        assert_eq!(render_nomor("{seq:2}-{year}", 12345, "X", tanggal(2026, 12, 31)), "12345-2026");
+    }
+
+    #[test]
+    fn render_membiarkan_kurung_tanpa_penutup() {
+        assert_eq!(render_nomor("{seq}/{year}/{", 1, "X", tanggal(2026, 12, 1)), "1/2026/{");
+    }
+}
@@ -0,0 +1,311 @@
+// File baru: src/perangkat/handlers.rs
+//
+// Pengikatan HP klien untuk wajib lapor mandiri. PK menerbitkan kode sekali
+// pakai, HP klien menukarnya dengan token perangkat, dan token itu wajib
+// disertakan pada setiap check-in mandiri.
+
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    Json,
+};
+use bcrypt::verify;
+use chrono::{Duration, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use sha256::digest;
+use sqlx::PgPool;
+use crate::auth::authorization::ensure_klien_access;
+use crate::auth::model::AuthenticatedUser;
+use super::model::{
+    CabutPerangkatKlien, DaftarPerangkatKlien, KodePendaftaranPerangkat, PerangkatKlien,
+    PerangkatKlienDenganToken,
+};
+
+const MASA_BERLAKU_KODE_JAM: i64 = 24;
+
+// Sama dengan login portal: setelah sekian kali kode/PIN salah, pendaftaran dikunci sementara
+const MAKS_GAGAL_DAFTAR: i32 = 5;
+const LAMA_KUNCI_MENIT: i32 = 15;
+
+// Kode angka supaya mudah diketik di HP
+fn buat_kode_pendaftaran() -> String {
+    let mut rng = rand::thread_rng();
+    (0..8).map(|_| char::from(b'0' + rng.gen_range(0..10))).collect()
+}
+
+fn buat_token_perangkat() -> String {
+    let acak: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(40)
+        .map(char::from)
+        .collect();
+    format!("dk_{}", acak) // Prefix berbeda dari API key user (ak_) dan kiosk (kk_)
+}
+
+/// Token perangkat yang valid (terdaftar untuk klien ini dan belum dicabut).
+pub async fn cari_perangkat_aktif(
+    pool: &PgPool,
+    klien_id: i32,
+    token: &str,
+) -> Result<Option<PerangkatKlien>, sqlx::Error> {
+    sqlx::query_as!(
+        PerangkatKlien,
+        r#"
+        SELECT id, klien_id, nama_perangkat, terakhir_dipakai_at, dicabut_at, dicabut_by,
+            alasan_pencabutan, created_at, updated_at
+        FROM perangkat_klien
+        WHERE token_hash = $1 AND klien_id = $2 AND dicabut_at IS NULL
+        "#,
+        digest(token),
+        klien_id
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+// --- TERBITKAN KODE PENDAFTARAN ---
+// URL: POST /api/klien/:klien_id/kode-pendaftaran-perangkat
+// Kode lama yang belum dipakai otomatis tidak berlaku.
+#[axum::debug_handler]
+pub async fn create_kode_pendaftaran_perangkat(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(klien_id): Path<i32>, // Diambil dari URL, sudah diautorisasi oleh middleware
+) -> Result<(StatusCode, Json<KodePendaftaranPerangkat>), StatusCode> {
+    let kode = buat_kode_pendaftaran();
+    let kedaluwarsa_at = Utc::now() + Duration::hours(MASA_BERLAKU_KODE_JAM);
+
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    sqlx::query!(
+        r#"
+        UPDATE kode_pendaftaran_perangkat SET kedaluwarsa_at = NOW()
+        WHERE klien_id = $1 AND dipakai_at IS NULL AND kedaluwarsa_at > NOW()
+        "#,
+        klien_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO kode_pendaftaran_perangkat (klien_id, kode_hash, kedaluwarsa_at, created_by)
+        VALUES ($1, $2, $3, $4)
+        "#,
+        klien_id,
+        digest(kode.clone()),
+        kedaluwarsa_at,
+        user.id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create kode pendaftaran perangkat: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok((
+        StatusCode::CREATED,
+        Json(KodePendaftaranPerangkat { kode_pendaftaran: kode, kedaluwarsa_at }),
+    ))
+}
+
+// --- READ ALL FOR A KLIEN ---
+// URL: GET /api/klien/:klien_id/perangkat-klien
+#[axum::debug_handler]
+pub async fn get_all_perangkat_for_klien(
+    Extension(pool): Extension<PgPool>,
+    Path(klien_id): Path<i32>,
+) -> Result<Json<Vec<PerangkatKlien>>, StatusCode> {
+    let list = sqlx::query_as!(
+        PerangkatKlien,
+        r#"
+        SELECT id, klien_id, nama_perangkat, terakhir_dipakai_at, dicabut_at, dicabut_by,
+            alasan_pencabutan, created_at, updated_at
+        FROM perangkat_klien
+        WHERE klien_id = $1
+        ORDER BY created_at DESC
+        "#,
+        klien_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch perangkat klien: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// --- CABUT PERANGKAT ---
+// URL: POST /api/perangkat-klien/:id/cabut
+#[axum::debug_handler]
+pub async fn cabut_perangkat_klien(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<CabutPerangkatKlien>,
+) -> Result<Json<PerangkatKlien>, StatusCode> {
+    let klien_id = sqlx::query_scalar!("SELECT klien_id FROM perangkat_klien WHERE id = $1", id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    ensure_klien_access(&pool, &user, klien_id).await?;
+
+    let perangkat = sqlx::query_as!(
+        PerangkatKlien,
+        r#"
+        UPDATE perangkat_klien
+        SET dicabut_at = NOW(), dicabut_by = $1, alasan_pencabutan = $2
+        WHERE id = $3 AND dicabut_at IS NULL
+        RETURNING id, klien_id, nama_perangkat, terakhir_dipakai_at, dicabut_at, dicabut_by,
+            alasan_pencabutan, created_at, updated_at
+        "#,
+        user.id,
+        payload.alasan_pencabutan,
+        id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::CONFLICT)?; // Sudah dicabut sebelumnya
+
+    Ok(Json(perangkat))
+}
+
+// Mengambil satu jatah percobaan sebelum kode/PIN diperiksa, supaya permintaan
+// paralel tidak bisa melewati batas. `false` jika pendaftaran sedang dikunci.
+async fn ambil_jatah_percobaan(pool: &PgPool, klien_id: i32) -> Result<bool, StatusCode> {
+    let percobaan_ke = sqlx::query_scalar!(
+        r#"
+        INSERT INTO percobaan_pendaftaran_perangkat AS p (klien_id, gagal_daftar) VALUES ($1, 1)
+        ON CONFLICT (klien_id) DO UPDATE SET gagal_daftar = p.gagal_daftar + 1
+        WHERE p.terkunci_sampai IS NULL OR p.terkunci_sampai <= NOW()
+        RETURNING gagal_daftar
+        "#,
+        klien_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to record percobaan pendaftaran perangkat: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(percobaan_ke.is_some_and(|ke| ke <= MAKS_GAGAL_DAFTAR))
+}
+
+// Hitungan gagal diulang dari nol begitu pendaftaran terkunci
+async fn catat_gagal_daftar(pool: &PgPool, klien_id: i32) -> Result<(), StatusCode> {
+    sqlx::query!(
+        r#"
+        UPDATE percobaan_pendaftaran_perangkat
+        SET gagal_daftar = CASE WHEN gagal_daftar >= $2 THEN 0 ELSE gagal_daftar END,
+            terkunci_sampai = CASE WHEN gagal_daftar >= $2
+                THEN NOW() + make_interval(mins => $3) ELSE terkunci_sampai END
+        WHERE klien_id = $1
+        "#,
+        klien_id,
+        MAKS_GAGAL_DAFTAR,
+        LAMA_KUNCI_MENIT
+    )
+    .execute(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(())
+}
+
+// --- DAFTARKAN PERANGKAT (HP KLIEN) ---
+// URL: POST /api/mandiri/perangkat (publik, dibuktikan dengan kode dari PK dan PIN klien)
+pub async fn daftar_perangkat_klien(
+    Extension(pool): Extension<PgPool>,
+    Json(payload): Json<DaftarPerangkatKlien>,
+) -> Result<(StatusCode, Json<PerangkatKlienDenganToken>), StatusCode> {
+    let klien = sqlx::query!(
+        "SELECT online_akses_klien, pin_klien_hash FROM klien WHERE id = $1 AND deleted_at IS NULL",
+        payload.klien_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::UNAUTHORIZED)?;
+    if !klien.online_akses_klien {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let pin_hash = klien.pin_klien_hash.ok_or(StatusCode::FORBIDDEN)?;
+
+    if !ambil_jatah_percobaan(&pool, payload.klien_id).await? {
+        return Err(StatusCode::TOO_MANY_REQUESTS);
+    }
+    if !verify(&payload.pin, &pin_hash).unwrap_or(false) {
+        catat_gagal_daftar(&pool, payload.klien_id).await?;
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // Menandai kode terpakai sekaligus memastikan kode hanya bisa dipakai sekali
+    let kode_id = sqlx::query_scalar!(
+        r#"
+        UPDATE kode_pendaftaran_perangkat SET dipakai_at = NOW()
+        WHERE klien_id = $1 AND kode_hash = $2 AND dipakai_at IS NULL AND kedaluwarsa_at > NOW()
+        RETURNING id
+        "#,
+        payload.klien_id,
+        digest(payload.kode_pendaftaran.trim())
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let Some(kode_id) = kode_id else {
+        drop(tx);
+        catat_gagal_daftar(&pool, payload.klien_id).await?;
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+
+    let token_perangkat = buat_token_perangkat();
+    let perangkat = sqlx::query_as!(
+        PerangkatKlien,
+        r#"
+        INSERT INTO perangkat_klien (klien_id, nama_perangkat, token_hash)
+        VALUES ($1, $2, $3)
+        RETURNING id, klien_id, nama_perangkat, terakhir_dipakai_at, dicabut_at, dicabut_by,
+            alasan_pencabutan, created_at, updated_at
+        "#,
+        payload.klien_id,
+        payload.nama_perangkat,
+        digest(token_perangkat.clone())
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create perangkat klien: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    sqlx::query!(
+        "UPDATE kode_pendaftaran_perangkat SET perangkat_klien_id = $1 WHERE id = $2",
+        perangkat.id,
+        kode_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    sqlx::query!(
+        "UPDATE percobaan_pendaftaran_perangkat SET gagal_daftar = 0, terkunci_sampai = NULL WHERE klien_id = $1",
+        payload.klien_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok((
+        StatusCode::CREATED,
+        Json(PerangkatKlienDenganToken { perangkat, token_perangkat }),
+    ))
+}
@@ -0,0 +1,57 @@
+// File baru: src/perangkat/model.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+use crate::klien::model_dewasa::CreateWajibLapor;
+
+// Merepresentasikan satu baris dari tabel 'perangkat_klien' (tanpa hash token)
+#[derive(Debug, Serialize, FromRow)]
+pub struct PerangkatKlien {
+    pub id: i32,
+    pub klien_id: i32,
+    pub nama_perangkat: String,
+    pub terakhir_dipakai_at: Option<DateTime<Utc>>,
+    pub dicabut_at: Option<DateTime<Utc>>,
+    pub dicabut_by: Option<i32>,
+    pub alasan_pencabutan: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Kode hanya ditampilkan sekali ke PK, lalu diberikan ke klien
+#[derive(Debug, Serialize)]
+pub struct KodePendaftaranPerangkat {
+    pub kode_pendaftaran: String,
+    pub kedaluwarsa_at: DateTime<Utc>,
+}
+
+// Body POST /api/mandiri/perangkat dari HP klien
+#[derive(Debug, Deserialize)]
+pub struct DaftarPerangkatKlien {
+    pub klien_id: i32,
+    pub kode_pendaftaran: String,
+    pub pin: String,
+    pub nama_perangkat: String,
+}
+
+// Token perangkat hanya dikirim sekali saat pendaftaran
+#[derive(Debug, Serialize)]
+pub struct PerangkatKlienDenganToken {
+    #[serde(flatten)]
+    pub perangkat: PerangkatKlien,
+    pub token_perangkat: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CabutPerangkatKlien {
+    pub alasan_pencabutan: Option<String>,
+}
+
+// Body POST /api/mandiri/klien/:klien_id/wajib-lapor-dewasa
+#[derive(Debug, Deserialize)]
+pub struct MandiriWajibLapor {
+    pub token_perangkat: String,
+    #[serde(flatten)]
+    pub lapor: CreateWajibLapor,
+}
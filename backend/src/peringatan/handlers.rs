@@ -0,0 +1,67 @@
+// File baru: src/peringatan/handlers.rs
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::StatusCode,
+    Json,
+};
+use sqlx::PgPool;
+use crate::auth::model::AuthenticatedUser;
+use super::model::{FilterPeringatan, Peringatan};
+
+// --- READ ALL (milik user yang login) ---
+// URL: GET /api/peringatan?belum_dibaca=true
+#[axum::debug_handler]
+pub async fn get_all_peringatan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(filter): Query<FilterPeringatan>,
+) -> Result<Json<Vec<Peringatan>>, StatusCode> {
+    let list = sqlx::query_as!(
+        Peringatan,
+        r#"
+        SELECT id, penerima_id, klien_id, jenis_peringatan AS "jenis_peringatan: _", pesan,
+            entitas_id, dibaca_at, created_at
+        FROM peringatan
+        WHERE penerima_id = $1 AND (NOT $2 OR dibaca_at IS NULL)
+        ORDER BY created_at DESC
+        "#,
+        user.id,
+        filter.belum_dibaca
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch peringatan: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// --- TANDAI DIBACA ---
+// URL: POST /api/peringatan/:id/baca
+#[axum::debug_handler]
+pub async fn baca_peringatan(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i64>,
+) -> Result<Json<Peringatan>, StatusCode> {
+    let peringatan = sqlx::query_as!(
+        Peringatan,
+        r#"
+        UPDATE peringatan SET dibaca_at = COALESCE(dibaca_at, NOW())
+        WHERE id = $1 AND penerima_id = $2
+        RETURNING id, penerima_id, klien_id, jenis_peringatan AS "jenis_peringatan: _", pesan,
+            entitas_id, dibaca_at, created_at
+        "#,
+        id,
+        user.id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(peringatan))
+}
@@ -0,0 +1,26 @@
+// File baru: src/peringatan/model.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+use crate::types::JenisPeringatanEnum;
+
+// Merepresentasikan satu baris dari tabel 'peringatan'
+#[derive(Debug, Serialize, FromRow)]
+pub struct Peringatan {
+    pub id: i64,
+    pub penerima_id: i32,
+    pub klien_id: Option<i32>,
+    pub jenis_peringatan: JenisPeringatanEnum,
+    pub pesan: String,
+    pub entitas_id: Option<i64>,
+    pub dibaca_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// Query string GET /api/peringatan
+#[derive(Debug, Deserialize)]
+pub struct FilterPeringatan {
+    #[serde(default)]
+    pub belum_dibaca: bool,
+}
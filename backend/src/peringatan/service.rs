@@ -0,0 +1,29 @@
+// File baru: src/peringatan/service.rs
+//
+// Peringatan dibuat oleh modul lain (mis. check-in mandiri) dan selalu
+// ditujukan ke PK klien saat peringatan dibuat.
+
+use sqlx::PgConnection;
+use crate::types::JenisPeringatanEnum;
+
+pub async fn kirim_ke_pk(
+    conn: &mut PgConnection,
+    klien_id: i32,
+    jenis: JenisPeringatanEnum,
+    pesan: &str,
+    entitas_id: Option<i64>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO peringatan (penerima_id, klien_id, jenis_peringatan, pesan, entitas_id)
+        SELECT pk_id, id, $2, $3, $4 FROM klien WHERE id = $1
+        "#,
+        klien_id,
+        jenis as JenisPeringatanEnum,
+        pesan,
+        entitas_id
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
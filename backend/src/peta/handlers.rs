@@ -0,0 +1,370 @@
+// File baru: src/peta/handlers.rs
+//
+// Endpoint GeoJSON untuk peta. Cakupan klien memakai filter yang sama dengan
+// GET /klien; AdminKanwil dan SuperAdmin (pemantauan tingkat wilayah) hanya
+// mendapat koordinat yang dibulatkan, sedangkan PK dan AdminBapas yang
+// menangani klien secara langsung mendapat posisi tepat.
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Duration;
+use sqlx::{types::Json as SqlJson, PgPool};
+use std::collections::HashMap;
+use crate::auth::authorization::ensure_bapas_access;
+use crate::auth::model::AuthenticatedUser;
+use crate::klien::handlers_core::{terapkan_filter_klien, GetAllKlienParams};
+use crate::pemantauan::geometri::{di_dalam_poligon, jarak_meter, koordinat_valid};
+use crate::penomoran::service::hari_ini;
+use crate::types::UserRoleEnum;
+use super::model::{
+    BarisKlienPeta, BarisWajibLaporPeta, FilterPetaBapas, FilterPetaKlien, FilterPetaWajibLapor, Fitur,
+    Geometri, KoleksiFitur, LokasiBapas, PropertiBapas, PropertiKlien, PropertiWajibLapor, SetLokasiBapas,
+};
+
+// 2 desimal ~ 1,1 km: cukup untuk sebaran tingkat kota/kecamatan
+pub const DESIMAL_KOORDINAT_KASAR: i32 = 2;
+const RENTANG_BAWAAN_HARI: i64 = 30;
+const MAKS_FITUR: i64 = 10_000;
+// Check-in kiosk seharusnya terjadi di kantor Bapas
+const BATAS_JARAK_KIOSK_METER: f64 = 1_000.0;
+
+/// Role yang hanya boleh melihat koordinat klien yang dibulatkan. Dipakai juga
+/// oleh ekspor dan change-feed agar posisi tepat tidak bocor lewat jalur lain.
+pub fn koordinat_kasar(user: &AuthenticatedUser) -> bool {
+    match user.role {
+        UserRoleEnum::AdminKanwil | UserRoleEnum::SuperAdmin => true,
+        UserRoleEnum::AdminBapas | UserRoleEnum::Pegawai => false,
+    }
+}
+
+pub fn bulatkan_koordinat(nilai: f64) -> f64 {
+    let faktor = 10f64.powi(DESIMAL_KOORDINAT_KASAR);
+    (nilai * faktor).round() / faktor
+}
+
+fn titik(user: &AuthenticatedUser, latitude: f64, longitude: f64) -> Geometri {
+    if koordinat_kasar(user) {
+        Geometri::Point([bulatkan_koordinat(longitude), bulatkan_koordinat(latitude)])
+    } else {
+        Geometri::Point([longitude, latitude])
+    }
+}
+
+impl Geometri {
+    /// Hanya Polygon/MultiPolygon dengan cincin tertutup minimal 4 titik yang valid.
+    fn wilayah_valid(&self) -> bool {
+        let cincin_valid = |cincin: &Vec<[f64; 2]>| {
+            cincin.len() >= 4
+                && cincin.first() == cincin.last()
+                && cincin.iter().all(|&[lon, lat]| koordinat_valid(lat, lon))
+        };
+        match self {
+            Geometri::Point(_) => false,
+            Geometri::Polygon(p) => !p.is_empty() && p.iter().all(cincin_valid),
+            Geometri::MultiPolygon(mp) => !mp.is_empty() && mp.iter().all(|p| !p.is_empty() && p.iter().all(cincin_valid)),
+        }
+    }
+
+    /// Titik di dalam salah satu poligon (cincin pertama) dan tidak di lubangnya.
+    fn berisi(&self, latitude: f64, longitude: f64) -> bool {
+        // Cincin GeoJSON berurutan [lon, lat], jadi argumen ikut ditukar
+        let dalam_poligon = |poligon: &Vec<Vec<[f64; 2]>>| {
+            let mut cincin = poligon.iter();
+            cincin.next().is_some_and(|luar| di_dalam_poligon(longitude, latitude, luar))
+                && !cincin.any(|lubang| di_dalam_poligon(longitude, latitude, lubang))
+        };
+        match self {
+            Geometri::Point(_) => false,
+            Geometri::Polygon(p) => dalam_poligon(p),
+            Geometri::MultiPolygon(mp) => mp.iter().any(dalam_poligon),
+        }
+    }
+}
+
+fn geojson<P: serde::Serialize>(koleksi: KoleksiFitur<P>) -> Response {
+    ([(header::CONTENT_TYPE, "application/geo+json")], Json(koleksi)).into_response()
+}
+
+struct LokasiKantor {
+    latitude: f64,
+    longitude: f64,
+    wilayah_layanan: Option<Geometri>,
+}
+
+async fn ambil_lokasi_kantor(pool: &PgPool) -> Result<HashMap<i32, LokasiKantor>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT bapas_id, latitude, longitude, wilayah_layanan AS "wilayah_layanan: SqlJson<Geometri>"
+        FROM lokasi_bapas
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| {
+            let lokasi = LokasiKantor {
+                latitude: r.latitude,
+                longitude: r.longitude,
+                wilayah_layanan: r.wilayah_layanan.map(|w| w.0),
+            };
+            (r.bapas_id, lokasi)
+        })
+        .collect())
+}
+
+// --- CHECK-IN ---
+// URL: GET /api/peta/wajib-lapor?tanggal_mulai=&tanggal_selesai=&metode=&anomali=&pk_id=&bapas_id=&kanwil_id=
+// Anomali: check-in kiosk jauh dari kantor Bapas, atau lokasi di luar wilayah layanan Bapas.
+#[axum::debug_handler]
+pub async fn get_peta_wajib_lapor(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(filter): Query<FilterPetaWajibLapor>,
+) -> Result<Response, StatusCode> {
+    let selesai = filter.tanggal_selesai.unwrap_or_else(hari_ini);
+    let mulai = filter.tanggal_mulai.unwrap_or(selesai - Duration::days(RENTANG_BAWAAN_HARI));
+    if mulai > selesai {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut query_builder = sqlx::QueryBuilder::new(
+        r#"
+        SELECT w.id, w.klien_id, k.nama_klien, k.tipe_klien, k.bapas_id, w.metode_lapor, w.waktu_lapor,
+            w.latitude, w.longitude, w.lewat_kiosk
+        FROM (
+            SELECT id, klien_id, metode_lapor_dewasa AS metode_lapor,
+                COALESCE(waktu_lapor_klien, created_at) AS waktu_lapor,
+                latitude_dewasa::FLOAT8 AS latitude, longitude_dewasa::FLOAT8 AS longitude,
+                perangkat_kiosk_id IS NOT NULL AS lewat_kiosk
+            FROM wajib_lapor_dewasa WHERE deleted_at IS NULL
+            UNION ALL
+            SELECT id, klien_id, metode_lapor_anak, created_at,
+                latitude_anak::FLOAT8, longitude_anak::FLOAT8, FALSE
+            FROM wajib_lapor_anak WHERE deleted_at IS NULL
+        ) w
+        JOIN klien k ON k.id = w.klien_id
+        WHERE k.deleted_at IS NULL AND w.latitude IS NOT NULL AND w.longitude IS NOT NULL
+        "#,
+    );
+    query_builder
+        .push(" AND (w.waktu_lapor AT TIME ZONE 'Asia/Jakarta')::DATE BETWEEN ")
+        .push_bind(mulai)
+        .push(" AND ")
+        .push_bind(selesai);
+    if let Some(metode) = filter.metode {
+        query_builder.push(" AND w.metode_lapor = ").push_bind(metode);
+    }
+    let params = GetAllKlienParams { pk_id: filter.pk_id, bapas_id: filter.bapas_id, kanwil_id: filter.kanwil_id };
+    terapkan_filter_klien(&mut query_builder, &user, &params, "k.");
+    query_builder.push(" ORDER BY w.waktu_lapor DESC LIMIT ").push_bind(MAKS_FITUR);
+
+    let rows = query_builder
+        .build_query_as::<BarisWajibLaporPeta>()
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch peta wajib lapor: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let kantor = ambil_lokasi_kantor(&pool).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let features = rows
+        .into_iter()
+        .filter_map(|r| {
+            let mut alasan_anomali = Vec::new();
+            if let Some(k) = kantor.get(&r.bapas_id) {
+                if r.lewat_kiosk && jarak_meter(r.latitude, r.longitude, k.latitude, k.longitude) > BATAS_JARAK_KIOSK_METER {
+                    alasan_anomali.push("Check-in kiosk jauh dari kantor Bapas");
+                }
+                if k.wilayah_layanan.as_ref().is_some_and(|w| !w.berisi(r.latitude, r.longitude)) {
+                    alasan_anomali.push("Di luar wilayah layanan Bapas");
+                }
+            }
+            let anomali = !alasan_anomali.is_empty();
+            if filter.anomali.is_some_and(|f| f != anomali) {
+                return None;
+            }
+            Some(Fitur::baru(
+                titik(&user, r.latitude, r.longitude),
+                PropertiWajibLapor {
+                    id: r.id,
+                    klien_id: r.klien_id,
+                    nama_klien: r.nama_klien,
+                    tipe_klien: r.tipe_klien,
+                    bapas_id: r.bapas_id,
+                    metode_lapor: r.metode_lapor,
+                    waktu_lapor: r.waktu_lapor,
+                    anomali,
+                    alasan_anomali,
+                },
+            ))
+        })
+        .collect();
+
+    Ok(geojson(KoleksiFitur::baru(features)))
+}
+
+// --- SEBARAN KLIEN ---
+// URL: GET /api/peta/klien?pk_id=&bapas_id=&kanwil_id=
+// Lokasi rumah = koordinat kunjungan rumah terakhir; klien tanpa kunjungan berkoordinat tidak tampil.
+#[axum::debug_handler]
+pub async fn get_peta_klien(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(filter): Query<FilterPetaKlien>,
+) -> Result<Response, StatusCode> {
+    let mut query_builder = sqlx::QueryBuilder::new(
+        r#"
+        SELECT k.id, k.nama_klien, k.tipe_klien, k.bapas_id, k.pk_id,
+            r.latitude::FLOAT8 AS latitude, r.longitude::FLOAT8 AS longitude, r.waktu_kunjungan
+        FROM klien k
+        JOIN LATERAL (
+            SELECT latitude, longitude, waktu_kunjungan FROM kunjungan_rumah
+            WHERE klien_id = k.id AND deleted_at IS NULL AND latitude IS NOT NULL AND longitude IS NOT NULL
+            ORDER BY waktu_kunjungan DESC
+            LIMIT 1
+        ) r ON TRUE
+        WHERE k.deleted_at IS NULL
+        "#,
+    );
+    let params = GetAllKlienParams { pk_id: filter.pk_id, bapas_id: filter.bapas_id, kanwil_id: filter.kanwil_id };
+    terapkan_filter_klien(&mut query_builder, &user, &params, "k.");
+    query_builder.push(" ORDER BY k.nama_klien LIMIT ").push_bind(MAKS_FITUR);
+
+    let rows = query_builder
+        .build_query_as::<BarisKlienPeta>()
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch peta klien: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let features = rows
+        .into_iter()
+        .map(|r| {
+            Fitur::baru(
+                titik(&user, r.latitude, r.longitude),
+                PropertiKlien {
+                    klien_id: r.id,
+                    nama_klien: r.nama_klien,
+                    tipe_klien: r.tipe_klien,
+                    bapas_id: r.bapas_id,
+                    pk_id: r.pk_id,
+                    lokasi_dari_kunjungan_at: r.waktu_kunjungan,
+                },
+            )
+        })
+        .collect();
+
+    Ok(geojson(KoleksiFitur::baru(features)))
+}
+
+// --- KANTOR BAPAS & WILAYAH LAYANAN ---
+// URL: GET /api/peta/bapas?kanwil_id=
+// Lokasi kantor bersifat publik, jadi tidak dibulatkan.
+#[axum::debug_handler]
+pub async fn get_peta_bapas(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(filter): Query<FilterPetaBapas>,
+) -> Result<Response, StatusCode> {
+    let (kanwil_id, bapas_id) = match user.role {
+        UserRoleEnum::SuperAdmin => (filter.kanwil_id, None),
+        UserRoleEnum::AdminKanwil => (user.kanwil_id, None),
+        UserRoleEnum::AdminBapas | UserRoleEnum::Pegawai => (None, user.bapas_id),
+    };
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT b.id, b.nama_bapas, b.kota_bapas, b.kanwil_id, l.latitude, l.longitude,
+            l.wilayah_layanan AS "wilayah_layanan: SqlJson<Geometri>"
+        FROM bapas b
+        JOIN lokasi_bapas l ON l.bapas_id = b.id
+        WHERE b.deleted_at IS NULL
+            AND ($1::INT IS NULL OR b.kanwil_id = $1)
+            AND ($2::INT IS NULL OR b.id = $2)
+        ORDER BY b.nama_bapas
+        "#,
+        kanwil_id,
+        bapas_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch peta bapas: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let mut features = Vec::new();
+    for r in rows {
+        let properti = |jenis| PropertiBapas {
+            bapas_id: r.id,
+            nama_bapas: r.nama_bapas.clone(),
+            kota_bapas: r.kota_bapas.clone(),
+            kanwil_id: r.kanwil_id,
+            jenis,
+        };
+        features.push(Fitur::baru(Geometri::Point([r.longitude, r.latitude]), properti("Kantor")));
+        if let Some(wilayah) = r.wilayah_layanan.clone() {
+            features.push(Fitur::baru(wilayah.0, properti("Wilayah Layanan")));
+        }
+    }
+
+    Ok(geojson(KoleksiFitur::baru(features)))
+}
+
+// --- SET LOKASI BAPAS ---
+// URL: PUT /api/bapas/:bapas_id/lokasi
+#[axum::debug_handler]
+pub async fn set_lokasi_bapas(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(bapas_id): Path<i32>,
+    Json(payload): Json<SetLokasiBapas>,
+) -> Result<Json<LokasiBapas>, StatusCode> {
+    ensure_bapas_access(&pool, &user, bapas_id, true).await?;
+    if !koordinat_valid(payload.latitude, payload.longitude)
+        || payload.wilayah_layanan.as_ref().is_some_and(|w| !w.wilayah_valid())
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let lokasi = sqlx::query!(
+        r#"
+        INSERT INTO lokasi_bapas (bapas_id, latitude, longitude, wilayah_layanan, created_by, updated_by)
+        VALUES ($1, $2, $3, $4, $5, $5)
+        ON CONFLICT (bapas_id) DO UPDATE SET
+            latitude = EXCLUDED.latitude,
+            longitude = EXCLUDED.longitude,
+            wilayah_layanan = EXCLUDED.wilayah_layanan,
+            updated_by = EXCLUDED.updated_by
+        RETURNING bapas_id, latitude, longitude, wilayah_layanan AS "wilayah_layanan: SqlJson<Geometri>", updated_at
+        "#,
+        bapas_id,
+        payload.latitude,
+        payload.longitude,
+        payload.wilayah_layanan.map(SqlJson) as _,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to set lokasi bapas: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(LokasiBapas {
+        bapas_id: lokasi.bapas_id,
+        latitude: lokasi.latitude,
+        longitude: lokasi.longitude,
+        wilayah_layanan: lokasi.wilayah_layanan.map(|w| w.0),
+        updated_at: lokasi.updated_at,
+    }))
+}
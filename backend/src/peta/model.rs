@@ -0,0 +1,149 @@
+// File baru: src/peta/model.rs
+//
+// Struktur GeoJSON (RFC 7946) secukupnya untuk endpoint peta. Urutan koordinat
+// mengikuti GeoJSON: [longitude, latitude].
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, NaiveDate, Utc};
+use crate::types::{MetodeLaporEnum, TipeKlienEnum};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "coordinates")]
+pub enum Geometri {
+    Point([f64; 2]),
+    Polygon(Vec<Vec<[f64; 2]>>),
+    MultiPolygon(Vec<Vec<Vec<[f64; 2]>>>),
+}
+
+#[derive(Debug, Serialize)]
+pub struct Fitur<P> {
+    #[serde(rename = "type")]
+    pub tipe: &'static str,
+    pub geometry: Geometri,
+    pub properties: P,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KoleksiFitur<P> {
+    #[serde(rename = "type")]
+    pub tipe: &'static str,
+    pub features: Vec<Fitur<P>>,
+}
+
+impl<P> KoleksiFitur<P> {
+    pub fn baru(features: Vec<Fitur<P>>) -> Self {
+        KoleksiFitur { tipe: "FeatureCollection", features }
+    }
+}
+
+impl<P> Fitur<P> {
+    pub fn baru(geometry: Geometri, properties: P) -> Self {
+        Fitur { tipe: "Feature", geometry, properties }
+    }
+}
+
+// === CHECK-IN (WAJIB LAPOR) ===
+
+// Query string; bawaan 30 hari terakhir. Filter klien sama dengan GET /klien.
+#[derive(Debug, Deserialize)]
+pub struct FilterPetaWajibLapor {
+    pub tanggal_mulai: Option<NaiveDate>,
+    pub tanggal_selesai: Option<NaiveDate>,
+    pub metode: Option<MetodeLaporEnum>,
+    pub anomali: Option<bool>,
+    pub pk_id: Option<i32>,
+    pub bapas_id: Option<i32>,
+    pub kanwil_id: Option<i32>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct BarisWajibLaporPeta {
+    pub id: i64,
+    pub klien_id: i32,
+    pub nama_klien: String,
+    pub tipe_klien: TipeKlienEnum,
+    pub bapas_id: i32,
+    pub metode_lapor: MetodeLaporEnum,
+    pub waktu_lapor: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub lewat_kiosk: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PropertiWajibLapor {
+    pub id: i64,
+    pub klien_id: i32,
+    pub nama_klien: String,
+    pub tipe_klien: TipeKlienEnum,
+    pub bapas_id: i32,
+    pub metode_lapor: MetodeLaporEnum,
+    pub waktu_lapor: DateTime<Utc>,
+    pub anomali: bool,
+    pub alasan_anomali: Vec<&'static str>,
+}
+
+// === SEBARAN KLIEN ===
+
+#[derive(Debug, Deserialize)]
+pub struct FilterPetaKlien {
+    pub pk_id: Option<i32>,
+    pub bapas_id: Option<i32>,
+    pub kanwil_id: Option<i32>,
+}
+
+// Lokasi rumah diambil dari kunjungan rumah terakhir yang berkoordinat
+#[derive(Debug, sqlx::FromRow)]
+pub struct BarisKlienPeta {
+    pub id: i32,
+    pub nama_klien: String,
+    pub tipe_klien: TipeKlienEnum,
+    pub bapas_id: i32,
+    pub pk_id: i32,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub waktu_kunjungan: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PropertiKlien {
+    pub klien_id: i32,
+    pub nama_klien: String,
+    pub tipe_klien: TipeKlienEnum,
+    pub bapas_id: i32,
+    pub pk_id: i32,
+    pub lokasi_dari_kunjungan_at: DateTime<Utc>,
+}
+
+// === KANTOR BAPAS ===
+
+#[derive(Debug, Deserialize)]
+pub struct FilterPetaBapas {
+    pub kanwil_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PropertiBapas {
+    pub bapas_id: i32,
+    pub nama_bapas: String,
+    pub kota_bapas: String,
+    pub kanwil_id: i32,
+    pub jenis: &'static str, // "Kantor" atau "Wilayah Layanan"
+}
+
+// Body PUT /bapas/:bapas_id/lokasi
+#[derive(Debug, Deserialize)]
+pub struct SetLokasiBapas {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub wilayah_layanan: Option<Geometri>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LokasiBapas {
+    pub bapas_id: i32,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub wilayah_layanan: Option<Geometri>,
+    pub updated_at: DateTime<Utc>,
+}
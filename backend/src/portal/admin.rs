@@ -0,0 +1,352 @@
+// File baru: src/portal/admin.rs
+//
+// Sisi staf untuk portal klien: akun portal, jadwal wajib lapor, dan
+// pengumuman Bapas.
+
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    Json,
+};
+use rand::seq::SliceRandom;
+use sqlx::PgPool;
+use crate::auth::authorization::{ensure_bapas_access, ensure_klien_access};
+use crate::auth::model::AuthenticatedUser;
+use super::jadwal::{susun_jadwal_lapor, INTERVAL_BAWAAN_HARI};
+use super::model::{
+    AkunPortalKlien, CreatePengumumanBapas, JadwalLaporKlien, JadwalWajibLapor, PengumumanBapas,
+    SetJadwalWajibLapor, UpdatePengumumanBapas,
+};
+
+// Tanpa 0/O dan 1/I/L supaya kode mudah dibacakan ke klien
+const ALFABET_KODE: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+const PANJANG_KODE: usize = 8;
+
+fn buat_kode_klien() -> String {
+    let mut rng = rand::thread_rng();
+    (0..PANJANG_KODE)
+        .map(|_| *ALFABET_KODE.choose(&mut rng).unwrap_or(&b'X') as char)
+        .collect()
+}
+
+async fn get_pengumuman(pool: &PgPool, id: i32) -> Result<PengumumanBapas, StatusCode> {
+    sqlx::query_as!(
+        PengumumanBapas,
+        "SELECT * FROM pengumuman_bapas WHERE id = $1 AND deleted_at IS NULL",
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)
+}
+
+// === AKUN PORTAL ===
+
+// --- BUAT / ATUR ULANG KODE ---
+// URL: POST /api/klien/:klien_id/akun-portal
+// Memanggil ulang menerbitkan kode baru dan membuka kunci akun.
+#[axum::debug_handler]
+pub async fn create_akun_portal_klien(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(klien_id): Path<i32>, // Diambil dari URL, sudah diautorisasi oleh middleware
+) -> Result<(StatusCode, Json<AkunPortalKlien>), StatusCode> {
+    let akun = sqlx::query_as!(
+        AkunPortalKlien,
+        r#"
+        INSERT INTO akun_portal_klien (klien_id, kode_klien, created_by, updated_by)
+        VALUES ($1, $2, $3, $3)
+        ON CONFLICT (klien_id) DO UPDATE
+        SET kode_klien = EXCLUDED.kode_klien, gagal_login = 0, terkunci_sampai = NULL,
+            updated_by = EXCLUDED.updated_by
+        RETURNING *
+        "#,
+        klien_id,
+        buat_kode_klien(),
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create akun portal klien: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((StatusCode::CREATED, Json(akun)))
+}
+
+// --- READ ---
+// URL: GET /api/klien/:klien_id/akun-portal
+#[axum::debug_handler]
+pub async fn get_akun_portal_klien(
+    Extension(pool): Extension<PgPool>,
+    Path(klien_id): Path<i32>,
+) -> Result<Json<AkunPortalKlien>, StatusCode> {
+    let akun = sqlx::query_as!(
+        AkunPortalKlien,
+        "SELECT * FROM akun_portal_klien WHERE klien_id = $1",
+        klien_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(akun))
+}
+
+// --- HAPUS ---
+// URL: DELETE /api/klien/:klien_id/akun-portal
+// Token yang sudah terbit ikut tidak berlaku (middleware memeriksa akun).
+#[axum::debug_handler]
+pub async fn delete_akun_portal_klien(
+    Extension(pool): Extension<PgPool>,
+    Path(klien_id): Path<i32>,
+) -> StatusCode {
+    match sqlx::query!("DELETE FROM akun_portal_klien WHERE klien_id = $1", klien_id)
+        .execute(&pool)
+        .await
+    {
+        Ok(result) if result.rows_affected() == 0 => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::NO_CONTENT,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+// --- JADWAL LAPOR KLIEN (tampilan staf) ---
+// URL: GET /api/klien/:klien_id/jadwal-lapor
+#[axum::debug_handler]
+pub async fn get_jadwal_lapor_klien(
+    Extension(pool): Extension<PgPool>,
+    Path(klien_id): Path<i32>,
+) -> Result<Json<JadwalLaporKlien>, StatusCode> {
+    let jadwal = susun_jadwal_lapor(&pool, klien_id).await.map_err(|e| {
+        tracing::error!("Failed to build jadwal lapor: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(jadwal))
+}
+
+// === JADWAL WAJIB LAPOR PER LAYANAN ===
+
+async fn klien_dari_layanan(pool: &PgPool, layanan_id: i32) -> Result<i32, StatusCode> {
+    sqlx::query_scalar!(
+        "SELECT klien_id FROM layanan_integrasi_dewasa WHERE id = $1 AND deleted_at IS NULL",
+        layanan_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)
+}
+
+// --- READ ---
+// URL: GET /api/layanan-integrasi-dewasa/:id/jadwal-wajib-lapor
+// Layanan tanpa jadwal tersimpan memakai interval bawaan.
+#[axum::debug_handler]
+pub async fn get_jadwal_wajib_lapor(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(layanan_id): Path<i32>,
+) -> Result<Json<SetJadwalWajibLapor>, StatusCode> {
+    let klien_id = klien_dari_layanan(&pool, layanan_id).await?;
+    ensure_klien_access(&pool, &user, klien_id).await?;
+
+    let interval_hari = sqlx::query_scalar!(
+        "SELECT interval_hari FROM jadwal_wajib_lapor WHERE layanan_integrasi_dewasa_id = $1",
+        layanan_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .unwrap_or(INTERVAL_BAWAAN_HARI);
+
+    Ok(Json(SetJadwalWajibLapor { interval_hari }))
+}
+
+// --- SET ---
+// URL: PUT /api/layanan-integrasi-dewasa/:id/jadwal-wajib-lapor
+#[axum::debug_handler]
+pub async fn set_jadwal_wajib_lapor(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(layanan_id): Path<i32>,
+    Json(payload): Json<SetJadwalWajibLapor>,
+) -> Result<Json<JadwalWajibLapor>, StatusCode> {
+    let klien_id = klien_dari_layanan(&pool, layanan_id).await?;
+    ensure_klien_access(&pool, &user, klien_id).await?;
+    if payload.interval_hari <= 0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let jadwal = sqlx::query_as!(
+        JadwalWajibLapor,
+        r#"
+        INSERT INTO jadwal_wajib_lapor (layanan_integrasi_dewasa_id, interval_hari, created_by, updated_by)
+        VALUES ($1, $2, $3, $3)
+        ON CONFLICT (layanan_integrasi_dewasa_id) DO UPDATE
+        SET interval_hari = EXCLUDED.interval_hari, updated_by = EXCLUDED.updated_by
+        RETURNING *
+        "#,
+        layanan_id,
+        payload.interval_hari,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to set jadwal wajib lapor: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(jadwal))
+}
+
+// === PENGUMUMAN BAPAS ===
+
+// --- CREATE ---
+// URL: POST /api/bapas/:bapas_id/pengumuman
+#[axum::debug_handler]
+pub async fn create_pengumuman_bapas(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(bapas_id): Path<i32>,
+    Json(payload): Json<CreatePengumumanBapas>,
+) -> Result<(StatusCode, Json<PengumumanBapas>), StatusCode> {
+    ensure_bapas_access(&pool, &user, bapas_id, true).await?;
+    if let (Some(mulai), Some(sampai)) = (payload.berlaku_mulai, payload.berlaku_sampai) {
+        if sampai < mulai {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let pengumuman = sqlx::query_as!(
+        PengumumanBapas,
+        r#"
+        INSERT INTO pengumuman_bapas (bapas_id, judul, isi, berlaku_mulai, berlaku_sampai, created_by, updated_by)
+        VALUES ($1, $2, $3, COALESCE($4, CURRENT_DATE), $5, $6, $6)
+        RETURNING *
+        "#,
+        bapas_id,
+        payload.judul,
+        payload.isi,
+        payload.berlaku_mulai,
+        payload.berlaku_sampai,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create pengumuman: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((StatusCode::CREATED, Json(pengumuman)))
+}
+
+// --- READ ALL ---
+// URL: GET /api/bapas/:bapas_id/pengumuman
+// Staf melihat semua pengumuman, termasuk yang sudah/belum berlaku.
+#[axum::debug_handler]
+pub async fn get_all_pengumuman_bapas(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(bapas_id): Path<i32>,
+) -> Result<Json<Vec<PengumumanBapas>>, StatusCode> {
+    ensure_bapas_access(&pool, &user, bapas_id, false).await?;
+
+    let list = sqlx::query_as!(
+        PengumumanBapas,
+        r#"
+        SELECT * FROM pengumuman_bapas
+        WHERE bapas_id = $1 AND deleted_at IS NULL
+        ORDER BY berlaku_mulai DESC, id DESC
+        "#,
+        bapas_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch pengumuman: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// --- UPDATE ---
+// URL: PUT /api/pengumuman/:id
+#[axum::debug_handler]
+pub async fn update_pengumuman_bapas(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdatePengumumanBapas>,
+) -> Result<Json<PengumumanBapas>, StatusCode> {
+    let lama = get_pengumuman(&pool, id).await?;
+    ensure_bapas_access(&pool, &user, lama.bapas_id, true).await?;
+    let mulai = payload.berlaku_mulai.unwrap_or(lama.berlaku_mulai);
+    if payload.berlaku_sampai.or(lama.berlaku_sampai).is_some_and(|sampai| sampai < mulai) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let pengumuman = sqlx::query_as!(
+        PengumumanBapas,
+        r#"
+        UPDATE pengumuman_bapas
+        SET judul = COALESCE($1, judul),
+            isi = COALESCE($2, isi),
+            berlaku_mulai = COALESCE($3, berlaku_mulai),
+            berlaku_sampai = COALESCE($4, berlaku_sampai),
+            updated_by = $5
+        WHERE id = $6 AND deleted_at IS NULL
+        RETURNING *
+        "#,
+        payload.judul,
+        payload.isi,
+        payload.berlaku_mulai,
+        payload.berlaku_sampai,
+        user.id,
+        id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update pengumuman: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(pengumuman))
+}
+
+// --- DELETE ---
+// URL: DELETE /api/pengumuman/:id
+#[axum::debug_handler]
+pub async fn delete_pengumuman_bapas(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> StatusCode {
+    let lama = match get_pengumuman(&pool, id).await {
+        Ok(p) => p,
+        Err(status) => return status,
+    };
+    if let Err(status) = ensure_bapas_access(&pool, &user, lama.bapas_id, true).await {
+        return status;
+    }
+
+    match sqlx::query!(
+        "UPDATE pengumuman_bapas SET deleted_at = NOW(), updated_by = $1 WHERE id = $2 AND deleted_at IS NULL",
+        user.id,
+        id
+    )
+    .execute(&pool)
+    .await
+    {
+        Ok(_) => StatusCode::NO_CONTENT,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
@@ -0,0 +1,198 @@
+// File baru: src/portal/handlers.rs
+//
+// Endpoint yang dipakai klien sendiri (portal mandiri). Semua data dibatasi
+// pada klien yang login; klien_id tidak pernah diambil dari URL.
+
+use axum::{extract::Extension, http::StatusCode, Json};
+use bcrypt::verify;
+use sqlx::PgPool;
+use crate::auth::model::{AuthenticatedKlien, LoginResponse};
+use crate::penomoran::service::hari_ini;
+use super::jadwal::susun_jadwal_lapor;
+use super::model::{
+    JadwalLaporKlien, LoginKlienRequest, PengumumanBapas, ProfilKlien, RiwayatLaporKlien,
+};
+use super::token;
+
+// Setelah sekian kali PIN salah berturut-turut, akun dikunci sementara
+const MAKS_GAGAL_LOGIN: i32 = 5;
+const LAMA_KUNCI_MENIT: i32 = 15;
+
+// --- LOGIN KLIEN ---
+// URL: POST /api/portal/login
+pub async fn login_klien(
+    Extension(pool): Extension<PgPool>,
+    Json(payload): Json<LoginKlienRequest>,
+) -> Result<Json<LoginResponse>, StatusCode> {
+    let akun = sqlx::query!(
+        r#"
+        SELECT a.klien_id, a.terkunci_sampai, k.bapas_id, k.online_akses_klien, k.pin_klien_hash
+        FROM akun_portal_klien a
+        JOIN klien k ON k.id = a.klien_id AND k.deleted_at IS NULL
+        WHERE a.kode_klien = UPPER($1)
+        "#,
+        payload.kode_klien.trim()
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Database error during portal login: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    if akun.terkunci_sampai.is_some_and(|t| t > chrono::Utc::now()) {
+        return Err(StatusCode::TOO_MANY_REQUESTS);
+    }
+    if !akun.online_akses_klien {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let pin_hash = akun.pin_klien_hash.ok_or(StatusCode::FORBIDDEN)?;
+
+    if !verify(&payload.pin, &pin_hash).unwrap_or(false) {
+        // Hitungan gagal diulang dari nol begitu akun terkunci
+        sqlx::query!(
+            r#"
+            UPDATE akun_portal_klien
+            SET gagal_login = CASE WHEN gagal_login + 1 >= $2 THEN 0 ELSE gagal_login + 1 END,
+                terkunci_sampai = CASE WHEN gagal_login + 1 >= $2
+                    THEN NOW() + make_interval(mins => $3) ELSE terkunci_sampai END
+            WHERE klien_id = $1
+            "#,
+            akun.klien_id,
+            MAKS_GAGAL_LOGIN,
+            LAMA_KUNCI_MENIT
+        )
+        .execute(&pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE akun_portal_klien
+        SET gagal_login = 0, terkunci_sampai = NULL, login_terakhir_at = NOW()
+        WHERE klien_id = $1
+        "#,
+        akun.klien_id
+    )
+    .execute(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let token = token::terbitkan(akun.klien_id, akun.bapas_id)?;
+    Ok(Json(LoginResponse { token }))
+}
+
+// --- PROFIL ---
+// URL: GET /api/portal/profil
+#[axum::debug_handler]
+pub async fn get_profil_klien(
+    Extension(pool): Extension<PgPool>,
+    Extension(klien): Extension<AuthenticatedKlien>,
+) -> Result<Json<ProfilKlien>, StatusCode> {
+    let profil = sqlx::query_as!(
+        ProfilKlien,
+        r#"
+        SELECT k.id, a.kode_klien, k.nama_klien, k.alamat_klien, k.tanggal_lahir_klien,
+            b.nama_bapas, u.nama_user AS nama_pk,
+            l.masa_bimbingan_awal_dewasa AS "masa_bimbingan_awal_dewasa?",
+            l.masa_bimbingan_akhir_dewasa AS "masa_bimbingan_akhir_dewasa?"
+        FROM klien k
+        JOIN akun_portal_klien a ON a.klien_id = k.id
+        JOIN bapas b ON b.id = k.bapas_id
+        JOIN users u ON u.id = k.pk_id
+        LEFT JOIN LATERAL (
+            SELECT masa_bimbingan_awal_dewasa, masa_bimbingan_akhir_dewasa
+            FROM layanan_integrasi_dewasa
+            WHERE klien_id = k.id AND deleted_at IS NULL AND NOT COALESCE(pengakhiran_dewasa, FALSE)
+            ORDER BY masa_bimbingan_awal_dewasa DESC NULLS LAST
+            LIMIT 1
+        ) l ON TRUE
+        WHERE k.id = $1
+        "#,
+        klien.klien_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch profil klien: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(profil))
+}
+
+// --- JADWAL WAJIB LAPOR ---
+// URL: GET /api/portal/jadwal-lapor
+#[axum::debug_handler]
+pub async fn get_jadwal_lapor_saya(
+    Extension(pool): Extension<PgPool>,
+    Extension(klien): Extension<AuthenticatedKlien>,
+) -> Result<Json<JadwalLaporKlien>, StatusCode> {
+    let jadwal = susun_jadwal_lapor(&pool, klien.klien_id).await.map_err(|e| {
+        tracing::error!("Failed to build jadwal lapor: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(jadwal))
+}
+
+// --- RIWAYAT WAJIB LAPOR ---
+// URL: GET /api/portal/riwayat-lapor
+#[axum::debug_handler]
+pub async fn get_riwayat_lapor_saya(
+    Extension(pool): Extension<PgPool>,
+    Extension(klien): Extension<AuthenticatedKlien>,
+) -> Result<Json<Vec<RiwayatLaporKlien>>, StatusCode> {
+    let list = sqlx::query_as!(
+        RiwayatLaporKlien,
+        r#"
+        SELECT id, COALESCE(waktu_lapor_klien, created_at) AS "waktu_lapor!",
+            metode_lapor_dewasa AS "metode_lapor_dewasa: _"
+        FROM wajib_lapor_dewasa
+        WHERE klien_id = $1 AND deleted_at IS NULL
+        ORDER BY 2 DESC
+        "#,
+        klien.klien_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch riwayat lapor: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// --- PENGUMUMAN BAPAS ---
+// URL: GET /api/portal/pengumuman
+// Hanya pengumuman Bapas klien yang sedang berlaku hari ini.
+#[axum::debug_handler]
+pub async fn get_pengumuman_saya(
+    Extension(pool): Extension<PgPool>,
+    Extension(klien): Extension<AuthenticatedKlien>,
+) -> Result<Json<Vec<PengumumanBapas>>, StatusCode> {
+    let list = sqlx::query_as!(
+        PengumumanBapas,
+        r#"
+        SELECT * FROM pengumuman_bapas
+        WHERE bapas_id = $1 AND deleted_at IS NULL
+            AND berlaku_mulai <= $2 AND (berlaku_sampai IS NULL OR berlaku_sampai >= $2)
+        ORDER BY berlaku_mulai DESC, id DESC
+        "#,
+        klien.bapas_id,
+        hari_ini()
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch pengumuman: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
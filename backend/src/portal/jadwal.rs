@@ -0,0 +1,120 @@
+// File baru: src/portal/jadwal.rs
+//
+// Periode wajib lapor dihitung dari awal masa bimbingan dengan panjang
+// `interval_hari`; periode terakhir dipotong di akhir masa bimbingan.
+
+use chrono::{Duration, NaiveDate};
+use sqlx::PgPool;
+use crate::penomoran::service::hari_ini;
+use super::model::{JadwalLaporKlien, JendelaLapor};
+
+/// Interval bawaan jika layanan belum punya jadwal sendiri
+pub const INTERVAL_BAWAAN_HARI: i32 = 30;
+
+// Jumlah periode yang ditampilkan (periode berjalan + berikutnya)
+const JUMLAH_JENDELA: usize = 4;
+
+/// Mengembalikan paling banyak `jumlah` periode (mulai, selesai), dimulai dari
+/// periode yang memuat `acuan` (atau periode pertama jika masa bimbingan belum mulai).
+pub fn jendela_lapor(
+    awal: NaiveDate,
+    akhir: Option<NaiveDate>,
+    interval_hari: i32,
+    acuan: NaiveDate,
+    jumlah: usize,
+) -> Vec<(NaiveDate, NaiveDate)> {
+    let interval = interval_hari.max(1) as i64;
+    let mut ke = if acuan > awal { (acuan - awal).num_days() / interval } else { 0 };
+    let mut hasil = Vec::with_capacity(jumlah);
+    while hasil.len() < jumlah {
+        let mulai = awal + Duration::days(ke * interval);
+        if akhir.is_some_and(|akhir| mulai > akhir) {
+            break;
+        }
+        let mut selesai = mulai + Duration::days(interval - 1);
+        if let Some(akhir) = akhir {
+            selesai = selesai.min(akhir);
+        }
+        hasil.push((mulai, selesai));
+        ke += 1;
+    }
+    hasil
+}
+
+/// Jadwal wajib lapor klien berdasarkan layanan integrasi yang sedang berjalan.
+/// Dipakai portal klien dan tampilan staf.
+pub async fn susun_jadwal_lapor(pool: &PgPool, klien_id: i32) -> Result<JadwalLaporKlien, sqlx::Error> {
+    let hari_ini = hari_ini();
+    let layanan = sqlx::query!(
+        r#"
+        SELECT l.masa_bimbingan_awal_dewasa AS "awal!", l.masa_bimbingan_akhir_dewasa,
+            COALESCE(j.interval_hari, $2) AS "interval_hari!"
+        FROM layanan_integrasi_dewasa l
+        LEFT JOIN jadwal_wajib_lapor j ON j.layanan_integrasi_dewasa_id = l.id
+        WHERE l.klien_id = $1 AND l.deleted_at IS NULL
+            AND NOT COALESCE(l.pengakhiran_dewasa, FALSE)
+            AND l.masa_bimbingan_awal_dewasa IS NOT NULL
+            AND (l.masa_bimbingan_akhir_dewasa IS NULL OR l.masa_bimbingan_akhir_dewasa >= $3)
+        ORDER BY l.masa_bimbingan_awal_dewasa DESC
+        LIMIT 1
+        "#,
+        klien_id,
+        INTERVAL_BAWAAN_HARI,
+        hari_ini
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(layanan) = layanan else {
+        return Ok(JadwalLaporKlien {
+            interval_hari: INTERVAL_BAWAAN_HARI,
+            masa_bimbingan_awal_dewasa: None,
+            masa_bimbingan_akhir_dewasa: None,
+            tanggal_jatuh_tempo_berikutnya: None,
+            jendela: Vec::new(),
+        });
+    };
+
+    let periode = jendela_lapor(
+        layanan.awal,
+        layanan.masa_bimbingan_akhir_dewasa,
+        layanan.interval_hari,
+        hari_ini,
+        JUMLAH_JENDELA,
+    );
+    let tanggal_lapor: Vec<NaiveDate> = match (periode.first(), periode.last()) {
+        (Some((mulai, _)), Some((_, selesai))) => {
+            sqlx::query_scalar!(
+                r#"
+                SELECT DISTINCT (COALESCE(waktu_lapor_klien, created_at) AT TIME ZONE 'Asia/Jakarta')::DATE AS "tanggal!"
+                FROM wajib_lapor_dewasa
+                WHERE klien_id = $1 AND deleted_at IS NULL
+                    AND (COALESCE(waktu_lapor_klien, created_at) AT TIME ZONE 'Asia/Jakarta')::DATE BETWEEN $2 AND $3
+                "#,
+                klien_id,
+                mulai,
+                selesai
+            )
+            .fetch_all(pool)
+            .await?
+        }
+        _ => Vec::new(),
+    };
+
+    let jendela: Vec<JendelaLapor> = periode
+        .into_iter()
+        .map(|(mulai, selesai)| JendelaLapor {
+            mulai,
+            selesai,
+            sudah_lapor: tanggal_lapor.iter().any(|t| *t >= mulai && *t <= selesai),
+        })
+        .collect();
+
+    Ok(JadwalLaporKlien {
+        interval_hari: layanan.interval_hari,
+        masa_bimbingan_awal_dewasa: Some(layanan.awal),
+        masa_bimbingan_akhir_dewasa: layanan.masa_bimbingan_akhir_dewasa,
+        tanggal_jatuh_tempo_berikutnya: jendela.iter().find(|j| !j.sudah_lapor).map(|j| j.selesai),
+        jendela,
+    })
+}
@@ -0,0 +1,5 @@
+pub mod model;
+pub mod token;
+pub mod jadwal;
+pub mod handlers;
+pub mod admin;
@@ -0,0 +1,119 @@
+// File baru: src/portal/model.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, NaiveDate, Utc};
+use crate::types::MetodeLaporEnum;
+
+// Isi token portal klien
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KlaimKlien {
+    pub sub: i32, // klien_id
+    pub bapas_id: i32,
+    pub exp: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoginKlienRequest {
+    pub kode_klien: String,
+    pub pin: String,
+}
+
+// Akun portal dilihat dari sisi staf
+#[derive(Debug, Serialize, FromRow)]
+pub struct AkunPortalKlien {
+    pub klien_id: i32,
+    pub kode_klien: String,
+    pub gagal_login: i32,
+    pub terkunci_sampai: Option<DateTime<Utc>>,
+    pub login_terakhir_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ProfilKlien {
+    pub id: i32,
+    pub kode_klien: String,
+    pub nama_klien: String,
+    pub alamat_klien: Option<String>,
+    pub tanggal_lahir_klien: Option<NaiveDate>,
+    pub nama_bapas: String,
+    pub nama_pk: String,
+    pub masa_bimbingan_awal_dewasa: Option<NaiveDate>,
+    pub masa_bimbingan_akhir_dewasa: Option<NaiveDate>,
+}
+
+// Satu periode wajib lapor; klien cukup lapor sekali di dalam periode
+#[derive(Debug, Serialize)]
+pub struct JendelaLapor {
+    pub mulai: NaiveDate,
+    pub selesai: NaiveDate,
+    pub sudah_lapor: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JadwalLaporKlien {
+    pub interval_hari: i32,
+    pub masa_bimbingan_awal_dewasa: Option<NaiveDate>,
+    pub masa_bimbingan_akhir_dewasa: Option<NaiveDate>,
+    pub tanggal_jatuh_tempo_berikutnya: Option<NaiveDate>,
+    pub jendela: Vec<JendelaLapor>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct RiwayatLaporKlien {
+    pub id: i64,
+    pub waktu_lapor: DateTime<Utc>,
+    pub metode_lapor_dewasa: MetodeLaporEnum,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct JadwalWajibLapor {
+    pub layanan_integrasi_dewasa_id: i32,
+    pub interval_hari: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+}
+
+// Juga dipakai sebagai respons GET (interval efektif, termasuk bawaan)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetJadwalWajibLapor {
+    pub interval_hari: i32,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PengumumanBapas {
+    pub id: i32,
+    pub bapas_id: i32,
+    pub judul: String,
+    pub isi: String,
+    pub berlaku_mulai: NaiveDate,
+    pub berlaku_sampai: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePengumumanBapas {
+    pub judul: String,
+    pub isi: String,
+    pub berlaku_mulai: Option<NaiveDate>,
+    pub berlaku_sampai: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePengumumanBapas {
+    pub judul: Option<String>,
+    pub isi: Option<String>,
+    pub berlaku_mulai: Option<NaiveDate>,
+    pub berlaku_sampai: Option<NaiveDate>,
+}
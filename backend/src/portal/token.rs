@@ -0,0 +1,36 @@
+// File baru: src/portal/token.rs
+//
+// Token portal klien memakai kunci sendiri (PORTAL_KLIEN_SECRET), jadi tidak
+// bisa dipakai di endpoint staf, dan token staf tidak berlaku di portal.
+
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use std::env;
+use super::model::KlaimKlien;
+
+// Sesi portal lebih pendek dari sesi staf
+const MASA_BERLAKU_JAM: i64 = 12;
+
+fn kunci() -> Result<Vec<u8>, StatusCode> {
+    env::var("PORTAL_KLIEN_SECRET").map(String::into_bytes).map_err(|_| {
+        tracing::error!("PORTAL_KLIEN_SECRET must be set");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+pub fn terbitkan(klien_id: i32, bapas_id: i32) -> Result<String, StatusCode> {
+    let klaim = KlaimKlien {
+        sub: klien_id,
+        bapas_id,
+        exp: (Utc::now() + Duration::hours(MASA_BERLAKU_JAM)).timestamp() as usize,
+    };
+    encode(&Header::default(), &klaim, &EncodingKey::from_secret(&kunci()?))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+pub fn baca(token: &str) -> Result<KlaimKlien, StatusCode> {
+    decode::<KlaimKlien>(token, &DecodingKey::from_secret(&kunci()?), &Validation::default())
+        .map(|data| data.claims)
+        .map_err(|_| StatusCode::UNAUTHORIZED)
+}
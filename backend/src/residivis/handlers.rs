@@ -0,0 +1,199 @@
+// File baru: src/residivis/handlers.rs
+//
+// Analitik residivisme dari view v_residivis_klien (lihat migrasi
+// analitik_residivis untuk kriterianya). Cakupan klien mengikuti GET /klien.
+
+use axum::{
+    extract::{Extension, Query},
+    http::StatusCode,
+    Json,
+};
+use sqlx::{PgPool, QueryBuilder};
+use crate::auth::model::AuthenticatedUser;
+use crate::klien::handlers_core::{terapkan_filter_klien, GetAllKlienParams};
+use crate::types::UserRoleEnum;
+use super::model::{
+    BarisSelisih, BarisTingkat, FilterResidivis, HasilPenyelarasan, RingkasanResidivis, SelisihResidivis,
+    TingkatResidivis,
+};
+use super::service;
+
+fn persentase(bagian: i64, total: i64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (bagian as f64 * 100.0 / total as f64 * 100.0).round() / 100.0
+}
+
+fn params_klien(filter: &FilterResidivis) -> GetAllKlienParams {
+    GetAllKlienParams { pk_id: filter.pk_id, bapas_id: filter.bapas_id, kanwil_id: filter.kanwil_id }
+}
+
+// --- RINGKASAN ---
+// URL: GET /api/residivis?pk_id=&bapas_id=&kanwil_id=
+#[axum::debug_handler]
+pub async fn get_ringkasan_residivis(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(filter): Query<FilterResidivis>,
+) -> Result<Json<RingkasanResidivis>, StatusCode> {
+    let params = params_klien(&filter);
+
+    let mut query_builder = QueryBuilder::new(
+        r#"
+        SELECT COUNT(*), COUNT(*) FILTER (WHERE r.residivis_terhitung),
+            COUNT(*) FILTER (WHERE r.residivis_terhitung <> r.pengulangan_klien
+                OR (r.identitas_cocok_klien_id IS NOT NULL AND NOT r.pengulangan_klien))
+        FROM v_residivis_klien r
+        WHERE TRUE
+        "#,
+    );
+    terapkan_filter_klien(&mut query_builder, &user, &params, "r.");
+    let (jumlah_klien, jumlah_residivis, jumlah_selisih) = query_builder
+        .build_query_as::<(i64, i64, i64)>()
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch ringkasan residivis: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    // Satu pemindaian untuk keempat dimensi
+    let mut query_builder = QueryBuilder::new(
+        r#"
+        SELECT d.dimensi, COALESCE(d.label, 'Tidak Diketahui') AS label, COUNT(*) AS jumlah_klien,
+            COUNT(*) FILTER (WHERE r.residivis_terhitung) AS jumlah_residivis,
+            COUNT(*) FILTER (WHERE r.pengulangan_klien) AS jumlah_flag_manual
+        FROM v_residivis_klien r
+        JOIN bapas b ON b.id = r.bapas_id
+        CROSS JOIN LATERAL (VALUES
+            ('kategori_tindak_pidana', r.kategori_tindak_pidana::TEXT),
+            ('bapas', b.nama_bapas::TEXT),
+            ('kelompok_umur', r.kelompok_umur::TEXT),
+            ('jenis_bimbingan', r.jenis_bimbingan::TEXT)
+        ) AS d(dimensi, label)
+        WHERE TRUE
+        "#,
+    );
+    terapkan_filter_klien(&mut query_builder, &user, &params, "r.");
+    query_builder.push(" GROUP BY 1, 2 ORDER BY 1, 3 DESC, 2");
+    let baris = query_builder
+        .build_query_as::<BarisTingkat>()
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch tingkat residivis: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let mut ringkasan = RingkasanResidivis {
+        jumlah_klien,
+        jumlah_residivis,
+        persentase: persentase(jumlah_residivis, jumlah_klien),
+        jumlah_selisih,
+        ..Default::default()
+    };
+    for b in baris {
+        let tujuan = match b.dimensi.as_str() {
+            "kategori_tindak_pidana" => &mut ringkasan.per_kategori_tindak_pidana,
+            "bapas" => &mut ringkasan.per_bapas,
+            "kelompok_umur" => &mut ringkasan.per_kelompok_umur,
+            _ => &mut ringkasan.per_jenis_bimbingan,
+        };
+        tujuan.push(TingkatResidivis {
+            persentase: persentase(b.jumlah_residivis, b.jumlah_klien),
+            label: b.label,
+            jumlah_klien: b.jumlah_klien,
+            jumlah_residivis: b.jumlah_residivis,
+            jumlah_flag_manual: b.jumlah_flag_manual,
+        });
+    }
+
+    Ok(Json(ringkasan))
+}
+
+// --- SELISIH FLAG MANUAL VS HASIL HITUNG ---
+// URL: GET /api/residivis/selisih?pk_id=&bapas_id=&kanwil_id=
+// Termasuk klien tanpa flag yang identitasnya cocok dengan klien lain; kecocokan
+// itu tidak pasti orang yang sama sehingga hanya diperiksa, tidak diselaraskan.
+#[axum::debug_handler]
+pub async fn get_selisih_residivis(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(filter): Query<FilterResidivis>,
+) -> Result<Json<Vec<SelisihResidivis>>, StatusCode> {
+    let mut query_builder = QueryBuilder::new(
+        r#"
+        SELECT r.klien_id, k.nama_klien, r.bapas_id, r.pk_id, r.pengulangan_klien, r.residivis_terhitung,
+            r.jumlah_riwayat_hukum, r.penerimaan_setelah_layanan_berakhir, r.identitas_cocok_klien_id
+        FROM v_residivis_klien r
+        JOIN klien k ON k.id = r.klien_id
+        WHERE r.residivis_terhitung <> r.pengulangan_klien
+            OR (r.identitas_cocok_klien_id IS NOT NULL AND NOT r.pengulangan_klien)
+        "#,
+    );
+    terapkan_filter_klien(&mut query_builder, &user, &params_klien(&filter), "r.");
+    query_builder.push(" ORDER BY r.bapas_id, k.nama_klien");
+    let baris = query_builder
+        .build_query_as::<BarisSelisih>()
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch selisih residivis: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let selisih = baris
+        .into_iter()
+        .map(|b| {
+            let mut alasan = Vec::new();
+            if b.jumlah_riwayat_hukum > 1 {
+                alasan.push("Lebih dari satu riwayat hukum");
+            }
+            if b.penerimaan_setelah_layanan_berakhir {
+                alasan.push("Penerimaan baru setelah layanan integrasi berakhir");
+            }
+            if b.identitas_cocok_klien_id.is_some() {
+                alasan.push("Identitas cocok dengan klien lain yang terdaftar lebih dulu; periksa apakah orang yang sama");
+            }
+            if b.pengulangan_klien && !b.residivis_terhitung {
+                alasan.push("Ditandai manual tanpa bukti di data; periksa riwayat hukum klien");
+            }
+            SelisihResidivis {
+                klien_id: b.klien_id,
+                nama_klien: b.nama_klien,
+                bapas_id: b.bapas_id,
+                pk_id: b.pk_id,
+                pengulangan_klien: b.pengulangan_klien,
+                residivis_terhitung: b.residivis_terhitung,
+                identitas_cocok_klien_id: b.identitas_cocok_klien_id,
+                alasan,
+            }
+        })
+        .collect();
+
+    Ok(Json(selisih))
+}
+
+// --- SELARASKAN FLAG ---
+// URL: POST /api/residivis/selaraskan?bapas_id=&kanwil_id=
+// Hanya dijalankan admin secara eksplisit; setiap flag yang dinyalakan tercatat
+// di penyelarasan_residivis_klien.
+#[axum::debug_handler]
+pub async fn selaraskan_residivis(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(filter): Query<FilterResidivis>,
+) -> Result<Json<HasilPenyelarasan>, StatusCode> {
+    if user.role == UserRoleEnum::Pegawai {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let params = params_klien(&filter);
+    let klien_id = service::selaraskan_pengulangan(&pool, &user, &params)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to sync pengulangan_klien: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok(Json(HasilPenyelarasan { jumlah_diperbarui: klien_id.len(), klien_id }))
+}
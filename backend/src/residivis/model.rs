@@ -0,0 +1,76 @@
+// File baru: src/residivis/model.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// Query string; filter klien sama dengan GET /klien
+#[derive(Debug, Deserialize)]
+pub struct FilterResidivis {
+    pub pk_id: Option<i32>,
+    pub bapas_id: Option<i32>,
+    pub kanwil_id: Option<i32>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct BarisTingkat {
+    pub dimensi: String,
+    pub label: String,
+    pub jumlah_klien: i64,
+    pub jumlah_residivis: i64,
+    pub jumlah_flag_manual: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TingkatResidivis {
+    pub label: String,
+    pub jumlah_klien: i64,
+    pub jumlah_residivis: i64,
+    // Persentase residivis terhitung, 0-100 dengan dua desimal
+    pub persentase: f64,
+    // Klien yang ditandai pengulangan secara manual, sebagai pembanding
+    pub jumlah_flag_manual: i64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct RingkasanResidivis {
+    pub jumlah_klien: i64,
+    pub jumlah_residivis: i64,
+    pub persentase: f64,
+    pub jumlah_selisih: i64,
+    pub per_kategori_tindak_pidana: Vec<TingkatResidivis>,
+    pub per_bapas: Vec<TingkatResidivis>,
+    pub per_kelompok_umur: Vec<TingkatResidivis>,
+    pub per_jenis_bimbingan: Vec<TingkatResidivis>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct BarisSelisih {
+    pub klien_id: i32,
+    pub nama_klien: String,
+    pub bapas_id: i32,
+    pub pk_id: i32,
+    pub pengulangan_klien: bool,
+    pub residivis_terhitung: bool,
+    pub jumlah_riwayat_hukum: i64,
+    pub penerimaan_setelah_layanan_berakhir: bool,
+    pub identitas_cocok_klien_id: Option<i32>,
+}
+
+// Klien yang flag manualnya berbeda dengan hasil hitung
+#[derive(Debug, Serialize)]
+pub struct SelisihResidivis {
+    pub klien_id: i32,
+    pub nama_klien: String,
+    pub bapas_id: i32,
+    pub pk_id: i32,
+    pub pengulangan_klien: bool,
+    pub residivis_terhitung: bool,
+    pub identitas_cocok_klien_id: Option<i32>,
+    pub alasan: Vec<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HasilPenyelarasan {
+    pub jumlah_diperbarui: usize,
+    pub klien_id: Vec<i32>,
+}
@@ -0,0 +1,35 @@
+// File baru: src/residivis/service.rs
+
+use sqlx::{PgPool, QueryBuilder};
+use crate::auth::model::AuthenticatedUser;
+use crate::klien::handlers_core::{terapkan_filter_klien, GetAllKlienParams};
+
+/// Menyalakan `pengulangan_klien` untuk klien dalam cakupan yang terhitung residivis
+/// tetapi belum ditandai, lalu mencatatnya di penyelarasan_residivis_klien.
+/// Arah sebaliknya (flag manual tanpa bukti di data) tidak diubah karena bisa
+/// berasal dari riwayat di luar sistem; kasus itu hanya dilaporkan sebagai selisih.
+pub async fn selaraskan_pengulangan(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    params: &GetAllKlienParams,
+) -> Result<Vec<i32>, sqlx::Error> {
+    let mut query_builder = QueryBuilder::new("WITH diubah AS (UPDATE klien k SET pengulangan_klien = TRUE, updated_by = ");
+    query_builder
+        .push_bind(user.id)
+        .push(
+            " FROM v_residivis_klien r WHERE r.klien_id = k.id AND r.residivis_terhitung AND NOT k.pengulangan_klien",
+        );
+    terapkan_filter_klien(&mut query_builder, user, params, "r.");
+    query_builder
+        .push(
+            r#"
+            RETURNING k.id, r.jumlah_riwayat_hukum, r.penerimaan_setelah_layanan_berakhir)
+            INSERT INTO penyelarasan_residivis_klien
+                (klien_id, jumlah_riwayat_hukum, penerimaan_setelah_layanan_berakhir, created_by)
+            SELECT id, jumlah_riwayat_hukum, penerimaan_setelah_layanan_berakhir,
+            "#,
+        )
+        .push_bind(user.id)
+        .push(" FROM diubah RETURNING klien_id");
+    query_builder.build_query_scalar::<i32>().fetch_all(pool).await
+}
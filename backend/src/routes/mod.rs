@@ -1,6 +1,6 @@
 // in src/routes/mod.rs
 use axum::{middleware, routing::{get, post, put, delete}, Router};
-use crate::{ users, auth, bapas, kanwil, klien};
+use crate::{ users, auth, bapas, kanwil, klien, penomoran, dokumen, litmas, tpp, bimbingan, kunjungan, sinkronisasi, kartu, kiosk, portal, perangkat, peringatan, antrian, pemantauan, peta, statistik, laporan, residivis, laporan_kustom, ekspor};
 use crate::auth::middleware::{
     self as auth_middleware, // Gunakan alias untuk middleware utama
     authorize_klien_access,
@@ -12,6 +12,269 @@ use crate::auth::middleware::auth_api_key;
 
 //use crate::{bapas, users, auth, klien};
 pub fn create_api_router() -> Router {
+    // Rute klien yang ditambahkan belakangan dikumpulkan di router terpisah,
+    // supaya `route_layer` di bawah hanya berlaku untuk rute-rute ini.
+    let klien_lanjutan_router = Router::new()
+        // --- REMISI DEWASA ---
+        .route(
+            "/klien/:klien_id/remisi-dewasa",
+            get(klien::handlers_dewasa::get_all_remisi_for_klien)
+                .post(klien::handlers_dewasa::create_remisi_dewasa),
+        )
+        // --- VERIFIKASI RANTAI HASH WAJIB LAPOR ---
+        .route(
+            "/klien/:klien_id/wajib-lapor-dewasa/verifikasi",
+            get(klien::handlers_dewasa::verifikasi_rantai_wajib_lapor),
+        )
+        // --- BIMBINGAN PER KLIEN ---
+        .route("/klien/:klien_id/sesi-bimbingan", get(bimbingan::handlers::get_all_sesi_bimbingan_for_klien))
+        .route("/klien/:klien_id/riwayat-pembimbingan", get(bimbingan::handlers::get_riwayat_pembimbingan))
+        // --- KUNJUNGAN RUMAH PER KLIEN ---
+        .route(
+            "/klien/:klien_id/kunjungan-rumah",
+            get(kunjungan::handlers::get_all_kunjungan_for_klien)
+                .post(kunjungan::handlers::create_kunjungan_rumah),
+        )
+        .route("/klien/:klien_id/catatan-lapangan", get(kunjungan::handlers::get_all_catatan_lapangan_for_klien))
+        // --- KARTU KLIEN (QR untuk kiosk) ---
+        .route(
+            "/klien/:klien_id/kartu-klien",
+            get(kartu::handlers::get_all_kartu_for_klien).post(kartu::handlers::create_kartu_klien),
+        )
+        // --- PORTAL KLIEN (sisi staf) ---
+        .route(
+            "/klien/:klien_id/akun-portal",
+            get(portal::admin::get_akun_portal_klien)
+                .post(portal::admin::create_akun_portal_klien)
+                .delete(portal::admin::delete_akun_portal_klien),
+        )
+        .route("/klien/:klien_id/jadwal-lapor", get(portal::admin::get_jadwal_lapor_klien))
+        // --- PERANGKAT KLIEN (check-in mandiri) ---
+        .route(
+            "/klien/:klien_id/kode-pendaftaran-perangkat",
+            post(perangkat::handlers::create_kode_pendaftaran_perangkat),
+        )
+        .route("/klien/:klien_id/perangkat-klien", get(perangkat::handlers::get_all_perangkat_for_klien))
+        // --- ANTRIAN (janji temu klien) ---
+        .route(
+            "/klien/:klien_id/antrian",
+            get(antrian::handlers::get_all_antrian_for_klien).post(antrian::handlers::create_antrian_for_klien),
+        )
+        // --- PEMANTAUAN ELEKTRONIK ---
+        .route(
+            "/klien/:klien_id/pemantauan-elektronik",
+            get(pemantauan::handlers::get_all_pemantauan_for_klien)
+                .post(pemantauan::handlers::create_pemantauan_elektronik),
+        )
+        .route(
+            "/klien/:klien_id/zona-pemantauan",
+            get(pemantauan::handlers::get_all_zona_for_klien).post(pemantauan::handlers::create_zona_pemantauan),
+        )
+        .route("/klien/:klien_id/titik-lacak", get(pemantauan::handlers::get_titik_lacak_for_klien))
+        .route("/klien/:klien_id/pelanggaran-zona", get(pemantauan::handlers::get_pelanggaran_zona_for_klien))
+        .route_layer(middleware::from_fn(authorize_klien_access))
+        // Rute by-id memeriksa kepemilikan klien di dalam handler
+        .route(
+            "/remisi-dewasa/:id",
+            get(klien::handlers_dewasa::get_remisi_by_id)
+                .put(klien::handlers_dewasa::update_remisi_dewasa)
+                .delete(klien::handlers_dewasa::delete_remisi_dewasa),
+        )
+
+        // --- FORMAT NOMOR REGISTER (otorisasi per Bapas di dalam handler) ---
+        .route(
+            "/bapas/:bapas_id/format-nomor-register",
+            get(penomoran::handlers::get_format_nomor_register)
+                .put(penomoran::handlers::set_format_nomor_register),
+        )
+
+        // --- DOKUMEN (Litmas, surat tugas, surat pengakhiran) ---
+        .route(
+            "/dokumen/template",
+            get(dokumen::handlers::get_all_template_dokumen)
+                .post(dokumen::handlers::create_template_dokumen),
+        )
+        .route(
+            "/dokumen/template/:id",
+            put(dokumen::handlers::update_template_dokumen)
+                .delete(dokumen::handlers::delete_template_dokumen),
+        )
+        .route(
+            "/penerimaan-dewasa/:id/dokumen",
+            get(dokumen::handlers::generate_dokumen_penerimaan),
+        )
+
+        // --- ALUR LITMAS (penugasan, review, jatuh tempo) ---
+        .route(
+            "/penerimaan-dewasa/:id/alur-litmas",
+            get(litmas::handlers::get_alur_litmas),
+        )
+        .route(
+            "/penerimaan-dewasa/:id/alur-litmas/transisi",
+            post(litmas::handlers::transisi_alur_litmas),
+        )
+        .route(
+            "/bapas/:bapas_id/litmas-terlambat",
+            get(litmas::handlers::get_litmas_terlambat),
+        )
+
+        // --- SIDANG TPP ---
+        .route(
+            "/bapas/:bapas_id/sidang-tpp",
+            get(tpp::handlers::get_all_sidang_tpp).post(tpp::handlers::create_sidang_tpp),
+        )
+        .route(
+            "/sidang-tpp/:id",
+            get(tpp::handlers::get_sidang_tpp_by_id)
+                .put(tpp::handlers::update_sidang_tpp)
+                .delete(tpp::handlers::delete_sidang_tpp),
+        )
+        .route("/sidang-tpp/:id/anggota", put(tpp::handlers::set_anggota_sidang_tpp))
+        .route("/sidang-tpp/:id/agenda", post(tpp::handlers::create_agenda_sidang_tpp))
+        .route("/sidang-tpp/:id/notulen", get(tpp::handlers::get_notulen_sidang_tpp))
+        .route("/sidang-tpp/:id/notulen/versi", get(tpp::handlers::get_versi_notulen_sidang_tpp))
+        .route(
+            "/sidang-tpp/:id/notulen/kunci",
+            post(tpp::handlers::kunci_notulen_sidang_tpp).delete(tpp::handlers::buka_kunci_notulen_sidang_tpp),
+        )
+        .route(
+            "/agenda-sidang-tpp/:id",
+            put(tpp::handlers::update_agenda_sidang_tpp).delete(tpp::handlers::delete_agenda_sidang_tpp),
+        )
+        .route("/agenda-sidang-tpp/:id/suara", put(tpp::handlers::set_suara_agenda_tpp))
+
+        // --- SESI BIMBINGAN (akses diperiksa di dalam handler) ---
+        .route("/sesi-bimbingan", post(bimbingan::handlers::create_sesi_bimbingan))
+        .route(
+            "/sesi-bimbingan/:id",
+            get(bimbingan::handlers::get_sesi_bimbingan_by_id)
+                .put(bimbingan::handlers::update_sesi_bimbingan)
+                .delete(bimbingan::handlers::delete_sesi_bimbingan),
+        )
+        .route(
+            "/sesi-bimbingan/:id/peserta/:klien_id",
+            put(bimbingan::handlers::update_kehadiran_peserta),
+        )
+
+        // --- KUNJUNGAN RUMAH (akses diperiksa di dalam handler) ---
+        .route("/kunjungan-rumah", get(kunjungan::handlers::get_all_kunjungan_rumah))
+        .route(
+            "/kunjungan-rumah/:id",
+            get(kunjungan::handlers::get_kunjungan_rumah_by_id)
+                .put(kunjungan::handlers::update_kunjungan_rumah)
+                .delete(kunjungan::handlers::delete_kunjungan_rumah),
+        )
+        .route(
+            "/kunjungan-rumah/:id/tindak-lanjut",
+            post(kunjungan::handlers::create_tindak_lanjut_kunjungan),
+        )
+        .route(
+            "/tindak-lanjut-kunjungan/:id",
+            put(kunjungan::handlers::update_tindak_lanjut_kunjungan),
+        )
+
+        // --- SINKRONISASI OFFLINE (otorisasi per operasi di dalam handler) ---
+        .route("/sinkronisasi", post(sinkronisasi::handlers::sinkronisasi))
+
+        // --- KARTU KLIEN ---
+        .route("/kartu-klien/:id/cetak", get(kartu::handlers::cetak_kartu_klien))
+        .route("/kartu-klien/:id/cabut", post(kartu::handlers::cabut_kartu_klien))
+
+        // --- REGISTRI PERANGKAT KIOSK (khusus admin) ---
+        .route(
+            "/bapas/:bapas_id/perangkat-kiosk",
+            get(kiosk::handlers::get_all_perangkat_kiosk).post(kiosk::handlers::create_perangkat_kiosk),
+        )
+        .route(
+            "/perangkat-kiosk/:id",
+            put(kiosk::handlers::update_perangkat_kiosk).delete(kiosk::handlers::delete_perangkat_kiosk),
+        )
+        .route("/perangkat-kiosk/:id/rotasi-kunci", post(kiosk::handlers::rotasi_kunci_perangkat_kiosk))
+
+        // --- JADWAL WAJIB LAPOR & PENGUMUMAN (otorisasi di dalam handler) ---
+        .route(
+            "/layanan-integrasi-dewasa/:id/jadwal-wajib-lapor",
+            get(portal::admin::get_jadwal_wajib_lapor).put(portal::admin::set_jadwal_wajib_lapor),
+        )
+        .route(
+            "/bapas/:bapas_id/pengumuman",
+            get(portal::admin::get_all_pengumuman_bapas).post(portal::admin::create_pengumuman_bapas),
+        )
+        .route(
+            "/pengumuman/:id",
+            put(portal::admin::update_pengumuman_bapas).delete(portal::admin::delete_pengumuman_bapas),
+        )
+
+        // --- PERANGKAT KLIEN & PERINGATAN PK ---
+        .route("/perangkat-klien/:id/cabut", post(perangkat::handlers::cabut_perangkat_klien))
+        .route("/peringatan", get(peringatan::handlers::get_all_peringatan))
+        .route("/peringatan/:id/baca", post(peringatan::handlers::baca_peringatan))
+
+        // --- SLOT LAYANAN & ANTRIAN BAPAS ---
+        .route(
+            "/bapas/:bapas_id/slot-layanan",
+            get(antrian::handlers::get_all_slot_layanan).post(antrian::handlers::create_slot_layanan),
+        )
+        .route(
+            "/slot-layanan/:id",
+            put(antrian::handlers::update_slot_layanan).delete(antrian::handlers::delete_slot_layanan),
+        )
+        .route("/bapas/:bapas_id/antrian", get(antrian::handlers::get_antrian_harian))
+        .route("/antrian/:id/transisi", post(antrian::handlers::transisi_antrian))
+
+        // --- PEMANTAUAN ELEKTRONIK ---
+        .route(
+            "/vendor-pemantauan",
+            get(pemantauan::handlers::get_all_vendor_pemantauan).post(pemantauan::handlers::create_vendor_pemantauan),
+        )
+        .route("/vendor-pemantauan/:id", put(pemantauan::handlers::update_vendor_pemantauan))
+        .route("/vendor-pemantauan/:id/rotasi-kunci", post(pemantauan::handlers::rotasi_kunci_vendor_pemantauan))
+        .route("/pemantauan-elektronik/:id/selesai", post(pemantauan::handlers::selesai_pemantauan_elektronik))
+        .route(
+            "/zona-pemantauan/:id",
+            put(pemantauan::handlers::update_zona_pemantauan).delete(pemantauan::handlers::delete_zona_pemantauan),
+        )
+
+        // --- PETA (GeoJSON) ---
+        .route("/peta/wajib-lapor", get(peta::handlers::get_peta_wajib_lapor))
+        .route("/peta/klien", get(peta::handlers::get_peta_klien))
+        .route("/peta/bapas", get(peta::handlers::get_peta_bapas))
+        .route("/bapas/:bapas_id/lokasi", put(peta::handlers::set_lokasi_bapas))
+
+        // --- STATISTIK DASHBOARD ---
+        .route("/statistik", get(statistik::handlers::get_statistik_dashboard))
+        .route("/statistik/beban-kasus", get(statistik::handlers::get_beban_kasus))
+        .route("/statistik/segarkan", post(statistik::handlers::segarkan_statistik))
+
+        // --- ANALITIK RESIDIVIS ---
+        .route("/residivis", get(residivis::handlers::get_ringkasan_residivis))
+        .route("/residivis/selisih", get(residivis::handlers::get_selisih_residivis))
+        .route("/residivis/selaraskan", post(residivis::handlers::selaraskan_residivis))
+
+        // --- LAPORAN BULANAN ---
+        .route(
+            "/laporan-bulanan",
+            get(laporan::handlers::get_all_laporan_bulanan).post(laporan::handlers::create_laporan_bulanan),
+        )
+        .route("/laporan-bulanan/pratinjau", get(laporan::handlers::get_pratinjau_laporan_bulanan))
+        .route("/laporan-bulanan/:id", get(laporan::handlers::get_laporan_bulanan_by_id))
+        .route("/laporan-bulanan/:id/unduh", get(laporan::handlers::unduh_laporan_bulanan))
+
+        // --- LAPORAN KUSTOM (ad-hoc) ---
+        .route("/laporan-kustom/field", get(laporan_kustom::handlers::get_field_laporan_kustom))
+        .route("/laporan-kustom/jalankan", post(laporan_kustom::handlers::jalankan_laporan_ad_hoc))
+        .route(
+            "/laporan-kustom",
+            get(laporan_kustom::handlers::get_all_laporan_kustom).post(laporan_kustom::handlers::create_laporan_kustom),
+        )
+        .route(
+            "/laporan-kustom/:id",
+            get(laporan_kustom::handlers::get_laporan_kustom_by_id)
+                .put(laporan_kustom::handlers::update_laporan_kustom)
+                .delete(laporan_kustom::handlers::delete_laporan_kustom),
+        )
+        .route("/laporan-kustom/:id/jalankan", get(laporan_kustom::handlers::jalankan_laporan_kustom));
+
     // These routes are PROTECTED and require a valid JWT.
     // We apply our `auth` middleware function to this router.
     let protected_router = Router::new()
@@ -109,9 +372,6 @@ pub fn create_api_router() -> Router {
         .route("/petugas/klien/:klien_id/wajib-lapor-dewasa", post(klien::handlers_dewasa::petugas_wajib_lapor_dewasa))
         .route_layer(middleware::from_fn(authorize_petugas_lapor_access)) // Lindungi lapor petugas
 
-        .route("/kiosk/klien/:klien_id/wajib-lapor-dewasa", post(klien::handlers_dewasa::kiosk_wajib_lapor_dewasa))
-        .route_layer(middleware::from_fn(authorize_klien_access)) // Kiosk pakai aturan standar
-
         .route("/wajib-lapor-dewasa/:id", delete(klien::handlers_dewasa::delete_wajib_lapor_dewasa))
         .route_layer(middleware::from_fn(authorize_wajib_lapor_delete_access)) // Lindungi delete
 
@@ -120,12 +380,40 @@ pub fn create_api_router() -> Router {
         .route("/me/api-key", get(users::handlers::get_my_api_key_status).delete(users::handlers::delete_my_api_key))
         .route("/me/api-key", post(users::handlers::generate_my_api_key)) // Pisahkan POST karena butuh body
 
+        .merge(klien_lanjutan_router)
+
         .layer(middleware::from_fn(auth_middleware::auth)); // Gunakan alias
 
 
 
+    // Rute khusus perangkat kiosk: autentikasi dengan API key perangkat, bukan JWT user.
+    let kiosk_router = Router::new()
+        .route("/perangkat", get(kiosk::handlers::get_perangkat_saya))
+        .route("/heartbeat", post(kiosk::handlers::heartbeat_perangkat_kiosk))
+        .route("/wajib-lapor-dewasa", post(klien::handlers_dewasa::kiosk_wajib_lapor_dewasa))
+        .route("/antrian", post(antrian::handlers::kiosk_ambil_antrian))
+        .layer(middleware::from_fn(auth_middleware::auth_perangkat_kiosk));
+
+    // Portal mandiri klien: token klien, bukan JWT staf. Login tetap publik.
+    let portal_router = Router::new()
+        .route("/profil", get(portal::handlers::get_profil_klien))
+        .route("/jadwal-lapor", get(portal::handlers::get_jadwal_lapor_saya))
+        .route("/riwayat-lapor", get(portal::handlers::get_riwayat_lapor_saya))
+        .route("/pengumuman", get(portal::handlers::get_pengumuman_saya))
+        .route("/slot-layanan", get(antrian::handlers::get_slot_layanan_saya))
+        .route("/antrian", get(antrian::handlers::get_antrian_saya).post(antrian::handlers::pesan_antrian_saya))
+        .route("/antrian/:id/batal", post(antrian::handlers::batal_antrian_saya))
+        .layer(middleware::from_fn(auth_middleware::auth_klien));
+
+    // Rute vendor pemantauan elektronik: API key vendor, bukan JWT user.
+    let pemantauan_router = Router::new()
+        .route("/titik-lacak", post(pemantauan::handlers::kirim_titik_lacak))
+        .layer(middleware::from_fn(auth_middleware::auth_vendor_pemantauan));
+
   let export_router = Router::new()
-        .route("/klien.csv", get(klien::handlers_core::export_klien_csv))
+        .route("/", get(ekspor::handlers::get_katalog_ekspor))
+        .route("/perubahan", get(ekspor::handlers::get_perubahan))
+        .route("/:entitas", get(ekspor::handlers::ekspor_entitas))
         .layer(middleware::from_fn(auth_api_key));
 
 
@@ -138,7 +426,12 @@ pub fn create_api_router() -> Router {
     Router::new()
         // Public routes (like login) do NOT go inside the protected router.
         .route("/auth/login", post(auth::handlers::login))
+        .route("/portal/login", post(portal::handlers::login_klien))
+        .route("/mandiri/perangkat", post(perangkat::handlers::daftar_perangkat_klien))
         .route("/mandiri/klien/:klien_id/wajib-lapor-dewasa", post(klien::handlers_dewasa::mandiri_wajib_lapor_dewasa))
         .nest("/", protected_router)
         .nest("/export", export_router) // Daftarkan rute ekspor di bawah /api/export
+        .nest("/kiosk", kiosk_router) // Rute perangkat kiosk di bawah /api/kiosk
+        .nest("/portal", portal_router) // Portal klien di bawah /api/portal
+        .nest("/pemantauan", pemantauan_router) // Kiriman titik lacak vendor di bawah /api/pemantauan
 }
\ No newline at end of file
@@ -0,0 +1,469 @@
+// File baru: src/sinkronisasi/handlers.rs
+//
+// Sinkronisasi batch untuk petugas yang bekerja tanpa sinyal. Setiap operasi
+// membawa client_uuid dari perangkat; operasi yang sudah diterapkan dicatat
+// di `operasi_sinkronisasi` dalam transaksi yang sama dengan datanya, sehingga
+// batch yang dikirim ulang tidak menggandakan data.
+
+use axum::{extract::Extension, http::StatusCode, Json};
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use std::collections::{BTreeMap, BTreeSet};
+use crate::auth::authorization::{check_permission, check_petugas_lapor_permission, get_klien_ownership};
+use crate::auth::model::AuthenticatedUser;
+use crate::ekspor::perubahan::{dekode_kursor, enkode_kursor};
+use crate::klien::handlers_dewasa::simpan_wajib_lapor_petugas;
+use crate::klien::model_dewasa::WajibLaporDewasa;
+use crate::kunjungan::handlers::simpan_kunjungan_rumah;
+use crate::kunjungan::model::{CatatanLapangan, KunjunganRumah};
+use crate::types::JenisOperasiSinkronEnum;
+use super::model::{
+    DataOperasi, DihapusServer, HasilOperasi, HasilSinkronisasi, KlienSinkron, OperasiSinkronisasi, OperasiTercatat,
+    PerubahanServer, PermintaanSinkronisasi, SinkronCatatanLapangan, StatusOperasiSinkron,
+    MAKS_OPERASI_PER_BATCH,
+};
+
+// Toleransi jam perangkat yang sedikit lebih cepat dari server
+const TOLERANSI_WAKTU_KLIEN_MENIT: i64 = 5;
+
+fn uuid_valid(uuid: &str) -> bool {
+    uuid.len() == 36
+        && uuid.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+fn hasil(
+    client_uuid: &str,
+    status: StatusOperasiSinkron,
+    jenis_operasi: Option<JenisOperasiSinkronEnum>,
+    entitas_id: Option<i64>,
+    pesan: Option<&str>,
+) -> HasilOperasi {
+    HasilOperasi {
+        client_uuid: Some(client_uuid.to_string()),
+        status,
+        jenis_operasi,
+        entitas_id,
+        pesan: pesan.map(str::to_string),
+    }
+}
+
+async fn cari_operasi(pool: &PgPool, client_uuid: &str) -> Result<Option<OperasiTercatat>, StatusCode> {
+    sqlx::query_as!(
+        OperasiTercatat,
+        r#"SELECT user_id, jenis_operasi AS "jenis_operasi: _", entitas_id FROM operasi_sinkronisasi WHERE client_uuid = $1"#,
+        client_uuid
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn hasil_tercatat(client_uuid: &str, tercatat: OperasiTercatat, user: &AuthenticatedUser) -> HasilOperasi {
+    if tercatat.user_id == user.id {
+        hasil(client_uuid, StatusOperasiSinkron::Duplikat, Some(tercatat.jenis_operasi), Some(tercatat.entitas_id), None)
+    } else {
+        hasil(client_uuid, StatusOperasiSinkron::Konflik, None, None, Some("client_uuid sudah dipakai pengguna lain"))
+    }
+}
+
+// Menentukan kunjungan yang dirujuk catatan. Err berisi hasil Konflik/Ditolak.
+async fn kunjungan_untuk_catatan(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    client_uuid: &str,
+    data: &SinkronCatatanLapangan,
+) -> Result<Result<Option<i32>, HasilOperasi>, StatusCode> {
+    let jenis = Some(JenisOperasiSinkronEnum::CatatanLapangan);
+    let kunjungan_id = match (&data.kunjungan_client_uuid, data.kunjungan_rumah_id) {
+        (Some(uuid_kunjungan), _) => match cari_operasi(pool, uuid_kunjungan).await? {
+            Some(op) if op.user_id == user.id && op.jenis_operasi == JenisOperasiSinkronEnum::KunjunganRumah => {
+                op.entitas_id as i32
+            }
+            _ => {
+                return Ok(Err(hasil(
+                    client_uuid,
+                    StatusOperasiSinkron::Konflik,
+                    jenis,
+                    None,
+                    Some("kunjungan rumah yang dirujuk belum tersinkron"),
+                )))
+            }
+        },
+        (None, Some(id)) => id,
+        (None, None) => return Ok(Ok(None)),
+    };
+
+    let kunjungan = sqlx::query!(
+        "SELECT klien_id, deleted_at FROM kunjungan_rumah WHERE id = $1",
+        kunjungan_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(match kunjungan {
+        Some(k) if k.klien_id != data.klien_id => Err(hasil(
+            client_uuid,
+            StatusOperasiSinkron::Ditolak,
+            jenis,
+            None,
+            Some("kunjungan rumah bukan milik klien ini"),
+        )),
+        Some(k) if k.deleted_at.is_none() => Ok(Some(kunjungan_id)),
+        _ => Err(hasil(
+            client_uuid,
+            StatusOperasiSinkron::Konflik,
+            jenis,
+            None,
+            Some("kunjungan rumah sudah dihapus"),
+        )),
+    })
+}
+
+async fn terapkan_operasi(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    mentah: serde_json::Value,
+) -> Result<HasilOperasi, StatusCode> {
+    let client_uuid = mentah
+        .get("client_uuid")
+        .and_then(|v| v.as_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    if !uuid_valid(&client_uuid) {
+        return Ok(HasilOperasi {
+            client_uuid: mentah.get("client_uuid").and_then(|v| v.as_str()).map(str::to_string),
+            status: StatusOperasiSinkron::Ditolak,
+            jenis_operasi: None,
+            entitas_id: None,
+            pesan: Some("client_uuid tidak valid".to_string()),
+        });
+    }
+
+    // 1. Idempotensi: operasi yang sudah pernah diterapkan tidak diulang
+    if let Some(tercatat) = cari_operasi(pool, &client_uuid).await? {
+        return Ok(hasil_tercatat(&client_uuid, tercatat, user));
+    }
+
+    // 2. Validasi bentuk dan waktu operasi
+    let operasi: OperasiSinkronisasi = match serde_json::from_value(mentah) {
+        Ok(op) => op,
+        Err(e) => {
+            return Ok(hasil(&client_uuid, StatusOperasiSinkron::Ditolak, None, None, Some(&e.to_string())));
+        }
+    };
+    let jenis = Some(operasi.data.jenis());
+    if operasi.waktu_klien > Utc::now() + Duration::minutes(TOLERANSI_WAKTU_KLIEN_MENIT) {
+        return Ok(hasil(&client_uuid, StatusOperasiSinkron::Ditolak, jenis, None, Some("waktu_klien di masa depan")));
+    }
+
+    // 3. Otorisasi per klien, dengan aturan yang sama seperti endpoint online
+    let klien_id = operasi.data.klien_id();
+    let Some(ownership) = get_klien_ownership(pool, klien_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    else {
+        return Ok(hasil(
+            &client_uuid,
+            StatusOperasiSinkron::Konflik,
+            jenis,
+            None,
+            Some("klien tidak ditemukan atau sudah dihapus"),
+        ));
+    };
+    let diizinkan = match &operasi.data {
+        DataOperasi::WajibLapor(_) => check_petugas_lapor_permission(user, &ownership),
+        _ => check_permission(user, &ownership),
+    };
+    if !diizinkan {
+        return Ok(hasil(&client_uuid, StatusOperasiSinkron::Ditolak, jenis, None, Some("tidak berhak atas klien ini")));
+    }
+
+    let kunjungan_catatan = match &operasi.data {
+        DataOperasi::CatatanLapangan(data) => match kunjungan_untuk_catatan(pool, user, &client_uuid, data).await? {
+            Ok(id) => id,
+            Err(hasil_gagal) => return Ok(hasil_gagal),
+        },
+        _ => None,
+    };
+
+    // 4. Terapkan dan catat dalam satu transaksi
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let entitas_id: i64 = match &operasi.data {
+        DataOperasi::WajibLapor(data) => {
+            simpan_wajib_lapor_petugas(&mut tx, klien_id, user.id, &data.lapor, Some(operasi.waktu_klien))
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to sync wajib lapor: {}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?
+        }
+        DataOperasi::KunjunganRumah(data) => {
+            match simpan_kunjungan_rumah(&mut tx, klien_id, user.id, &data.kunjungan).await {
+                Ok(kunjungan) => kunjungan.id as i64,
+                Err(StatusCode::BAD_REQUEST) => {
+                    return Ok(hasil(
+                        &client_uuid,
+                        StatusOperasiSinkron::Ditolak,
+                        jenis,
+                        None,
+                        Some("penerimaan bukan milik klien ini"),
+                    ));
+                }
+                Err(status) => return Err(status),
+            }
+        }
+        DataOperasi::CatatanLapangan(data) => {
+            let id = sqlx::query_scalar!(
+                r#"
+                INSERT INTO catatan_lapangan (klien_id, kunjungan_rumah_id, isi, waktu_catat, created_by, updated_by)
+                VALUES ($1, $2, $3, $4, $5, $5)
+                RETURNING id
+                "#,
+                klien_id,
+                kunjungan_catatan,
+                data.isi,
+                operasi.waktu_klien,
+                user.id
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to sync catatan lapangan: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+            id as i64
+        }
+    };
+
+    let tercatat = sqlx::query!(
+        r#"
+        INSERT INTO operasi_sinkronisasi (client_uuid, user_id, jenis_operasi, entitas_id, waktu_klien)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (client_uuid) DO NOTHING
+        "#,
+        client_uuid,
+        user.id,
+        operasi.data.jenis() as _,
+        entitas_id,
+        operasi.waktu_klien
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if tercatat.rows_affected() == 0 {
+        // Permintaan lain dengan UUID yang sama menang lebih dulu
+        tx.rollback().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let tercatat = cari_operasi(pool, &client_uuid).await?.ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+        return Ok(hasil_tercatat(&client_uuid, tercatat, user));
+    }
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(hasil(&client_uuid, StatusOperasiSinkron::Diterapkan, jenis, Some(entitas_id), None))
+}
+
+// Tabel di log_perubahan yang ikut dikirim ke perangkat lapangan
+const TABEL_SINKRON: [&str; 4] = ["klien", "wajib_lapor_dewasa", "kunjungan_rumah", "catatan_lapangan"];
+
+// ID yang disentuh log tetapi tidak lagi aktif dalam cakupan petugas
+fn terhapus(disentuh: Option<&BTreeSet<i64>>, masih_ada: impl Iterator<Item = i64>) -> Vec<i64> {
+    let Some(disentuh) = disentuh else {
+        return Vec::new();
+    };
+    let masih_ada: BTreeSet<i64> = masih_ada.collect();
+    disentuh.difference(&masih_ada).copied().collect()
+}
+
+// Tanpa kursor: seluruh data aktif milik petugas. Dengan kursor: keadaan terbaru
+// setiap baris yang tercatat di log_perubahan sesudah kursor, dengan aturan
+// xmin yang sama seperti change-feed ekspor, sehingga transaksi yang commit
+// belakangan tidak terlewat. Baris yang sudah dihapus atau keluar dari cakupan
+// petugas dikirim sebagai ID di `dihapus`.
+async fn ambil_perubahan(
+    pool: &PgPool,
+    user: &AuthenticatedUser,
+    kursor: Option<(i64, i64)>,
+) -> Result<(PerubahanServer, String), sqlx::Error> {
+    // Semua bacaan memakai satu snapshot supaya data dan kursor sejalan
+    let mut tx = pool.begin().await?;
+    sqlx::query!("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+        .execute(&mut *tx)
+        .await?;
+
+    let (kursor_berikutnya, disentuh) = match kursor {
+        None => {
+            // Transaksi yang lebih tua dari xmin sudah tercermin di snapshot ini;
+            // sisanya mungkin terkirim dua kali, dan perangkat menimpa per id
+            let xmin = sqlx::query_scalar!(
+                r#"SELECT pg_snapshot_xmin(pg_current_snapshot())::TEXT::BIGINT AS "xmin!""#
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            (enkode_kursor(xmin, 0), None)
+        }
+        Some((xid, id)) => {
+            let tabel: Vec<String> = TABEL_SINKRON.iter().map(|t| t.to_string()).collect();
+            let entri = sqlx::query!(
+                r#"
+                SELECT l.id, l.xid, l.tabel, l.baris_id FROM log_perubahan l
+                WHERE (l.xid, l.id) > ($1::BIGINT, $2::BIGINT)
+                    AND l.xid < pg_snapshot_xmin(pg_current_snapshot())::TEXT::BIGINT
+                    AND l.tabel = ANY($3)
+                    AND (l.pk_id = $4
+                        OR (l.tabel = 'kunjungan_rumah' AND EXISTS (
+                            SELECT 1 FROM kunjungan_rumah kr WHERE kr.id = l.baris_id AND kr.petugas_id = $4))
+                        OR (l.tabel = 'catatan_lapangan' AND EXISTS (
+                            SELECT 1 FROM catatan_lapangan c WHERE c.id = l.baris_id AND c.created_by = $4)))
+                ORDER BY l.xid, l.id
+                "#,
+                xid,
+                id,
+                &tabel,
+                user.id
+            )
+            .fetch_all(&mut *tx)
+            .await?;
+
+            let kursor_berikutnya = entri
+                .last()
+                .map(|e| enkode_kursor(e.xid, e.id))
+                .unwrap_or_else(|| enkode_kursor(xid, id));
+            let mut disentuh: BTreeMap<String, BTreeSet<i64>> = BTreeMap::new();
+            for e in entri {
+                disentuh.entry(e.tabel).or_default().insert(e.baris_id);
+            }
+            (kursor_berikutnya, Some(disentuh))
+        }
+    };
+    // Tanpa kursor semua baris dikirim; dengan kursor hanya yang disentuh log
+    let id_tabel = |tabel: &str| -> Option<Vec<i64>> {
+        disentuh
+            .as_ref()
+            .map(|d| d.get(tabel).map(|ids| ids.iter().copied().collect()).unwrap_or_default())
+    };
+    let (id_klien, id_wajib_lapor, id_kunjungan, id_catatan) = (
+        id_tabel("klien"),
+        id_tabel("wajib_lapor_dewasa"),
+        id_tabel("kunjungan_rumah"),
+        id_tabel("catatan_lapangan"),
+    );
+
+    let klien = sqlx::query_as!(
+        KlienSinkron,
+        r#"
+        SELECT id, nama_klien, alamat_klien, bapas_id, pk_id, updated_at
+        FROM klien
+        WHERE pk_id = $1 AND deleted_at IS NULL AND ($2::BIGINT[] IS NULL OR id = ANY($2))
+        ORDER BY id
+        "#,
+        user.id,
+        id_klien.as_deref()
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let wajib_lapor = sqlx::query_as!(
+        WajibLaporDewasa,
+        r#"
+        SELECT w.id, w.klien_id, w.photo_path_dewasa, w.latitude_dewasa, w.longitude_dewasa,
+            w.metode_lapor_dewasa AS "metode_lapor_dewasa: _", w.created_by, w.deleted_at, w.created_at,
+            w.waktu_lapor_klien, w.perangkat_kiosk_id, w.perangkat_klien_id,
+            w.photo_hash_dewasa, w.nomor_urut, w.hash_rantai
+        FROM wajib_lapor_dewasa w
+        JOIN klien k ON k.id = w.klien_id
+        WHERE k.pk_id = $1 AND k.deleted_at IS NULL AND w.deleted_at IS NULL
+            AND ($2::BIGINT[] IS NULL OR w.id = ANY($2))
+        ORDER BY w.id
+        "#,
+        user.id,
+        id_wajib_lapor.as_deref()
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let kunjungan_rumah = sqlx::query_as!(
+        KunjunganRumah,
+        r#"
+        SELECT kr.id, kr.klien_id, kr.penerimaan_dewasa_id, kr.petugas_id, kr.waktu_kunjungan,
+            kr.tujuan_kunjungan AS "tujuan_kunjungan: _", kr.alamat_kunjungan, kr.latitude, kr.longitude,
+            kr.photo_paths, kr.temuan_kondisi_rumah, kr.temuan_hubungan_keluarga, kr.temuan_lingkungan,
+            kr.temuan_penjamin, kr.temuan_pekerjaan, kr.kesimpulan, kr.created_at, kr.updated_at,
+            kr.created_by, kr.updated_by, kr.deleted_at
+        FROM kunjungan_rumah kr
+        JOIN klien k ON k.id = kr.klien_id
+        WHERE (k.pk_id = $1 OR kr.petugas_id = $1) AND k.deleted_at IS NULL AND kr.deleted_at IS NULL
+            AND ($2::BIGINT[] IS NULL OR kr.id = ANY($2))
+        ORDER BY kr.id
+        "#,
+        user.id,
+        id_kunjungan.as_deref()
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let catatan_lapangan = sqlx::query_as!(
+        CatatanLapangan,
+        r#"
+        SELECT c.id, c.klien_id, c.kunjungan_rumah_id, c.isi, c.waktu_catat, c.created_at, c.updated_at,
+            c.created_by, c.updated_by, c.deleted_at
+        FROM catatan_lapangan c
+        JOIN klien k ON k.id = c.klien_id
+        WHERE (k.pk_id = $1 OR c.created_by = $1) AND k.deleted_at IS NULL AND c.deleted_at IS NULL
+            AND ($2::BIGINT[] IS NULL OR c.id = ANY($2))
+        ORDER BY c.id
+        "#,
+        user.id,
+        id_catatan.as_deref()
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    let dari_log = |tabel: &str| disentuh.as_ref().and_then(|d| d.get(tabel));
+    let dihapus = DihapusServer {
+        klien: terhapus(dari_log("klien"), klien.iter().map(|k| k.id.into())),
+        wajib_lapor: terhapus(dari_log("wajib_lapor_dewasa"), wajib_lapor.iter().map(|w| w.id)),
+        kunjungan_rumah: terhapus(dari_log("kunjungan_rumah"), kunjungan_rumah.iter().map(|k| k.id.into())),
+        catatan_lapangan: terhapus(dari_log("catatan_lapangan"), catatan_lapangan.iter().map(|c| c.id.into())),
+    };
+
+    Ok((PerubahanServer { klien, wajib_lapor, kunjungan_rumah, catatan_lapangan, dihapus }, kursor_berikutnya))
+}
+
+// --- SINKRONISASI BATCH ---
+// URL: POST /api/sinkronisasi
+// Operasi diterapkan berurutan; hasil dilaporkan per operasi.
+#[axum::debug_handler]
+pub async fn sinkronisasi(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(payload): Json<PermintaanSinkronisasi>,
+) -> Result<Json<HasilSinkronisasi>, StatusCode> {
+    if payload.operasi.len() > MAKS_OPERASI_PER_BATCH {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+    // Kursor diperiksa sebelum operasi apa pun diterapkan
+    let kursor = match payload.kursor.as_deref().filter(|k| !k.is_empty()) {
+        Some(kursor) => Some(dekode_kursor(kursor).ok_or(StatusCode::BAD_REQUEST)?),
+        None => None,
+    };
+
+    let mut hasil = Vec::with_capacity(payload.operasi.len());
+    for mentah in payload.operasi {
+        hasil.push(terapkan_operasi(&pool, &user, mentah).await?);
+    }
+
+    // Operasi batch ini sendiri ikut terkirim kembali sebagai perubahan
+    let (perubahan, kursor_berikutnya) = ambil_perubahan(&pool, &user, kursor)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch sync change set: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(HasilSinkronisasi { hasil, perubahan, kursor_berikutnya }))
+}
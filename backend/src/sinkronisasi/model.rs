@@ -0,0 +1,152 @@
+// File baru: src/sinkronisasi/model.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+use crate::klien::model_dewasa::{CreateWajibLapor, WajibLaporDewasa};
+use crate::kunjungan::model::{CatatanLapangan, CreateKunjunganRumah, KunjunganRumah};
+use crate::types::JenisOperasiSinkronEnum;
+
+/// Batas jumlah operasi dalam satu permintaan sinkronisasi.
+pub const MAKS_OPERASI_PER_BATCH: usize = 200;
+
+// Body POST /api/sinkronisasi. `operasi` dibiarkan sebagai JSON mentah agar
+// satu operasi yang rusak tidak menggagalkan seluruh antrean perangkat.
+#[derive(Debug, Deserialize)]
+pub struct PermintaanSinkronisasi {
+    // Kursor opak dari kursor_berikutnya; kosong untuk sinkronisasi pertama
+    pub kursor: Option<String>,
+    #[serde(default)]
+    pub operasi: Vec<serde_json::Value>,
+}
+
+// Satu operasi antrean perangkat. `client_uuid` dibaca langsung dari JSON
+// mentah supaya tetap bisa dilaporkan walaupun operasi gagal diurai.
+#[derive(Debug, Deserialize)]
+pub struct OperasiSinkronisasi {
+    pub waktu_klien: DateTime<Utc>,
+    #[serde(flatten)]
+    pub data: DataOperasi,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "jenis_operasi", content = "data")]
+pub enum DataOperasi {
+    #[serde(rename = "Wajib Lapor")]
+    WajibLapor(SinkronWajibLapor),
+    #[serde(rename = "Kunjungan Rumah")]
+    KunjunganRumah(Box<SinkronKunjunganRumah>),
+    #[serde(rename = "Catatan Lapangan")]
+    CatatanLapangan(SinkronCatatanLapangan),
+}
+
+impl DataOperasi {
+    pub fn jenis(&self) -> JenisOperasiSinkronEnum {
+        match self {
+            DataOperasi::WajibLapor(_) => JenisOperasiSinkronEnum::WajibLapor,
+            DataOperasi::KunjunganRumah(_) => JenisOperasiSinkronEnum::KunjunganRumah,
+            DataOperasi::CatatanLapangan(_) => JenisOperasiSinkronEnum::CatatanLapangan,
+        }
+    }
+
+    pub fn klien_id(&self) -> i32 {
+        match self {
+            DataOperasi::WajibLapor(d) => d.klien_id,
+            DataOperasi::KunjunganRumah(d) => d.klien_id,
+            DataOperasi::CatatanLapangan(d) => d.klien_id,
+        }
+    }
+}
+
+// Check-in petugas, sama dengan POST /api/petugas/klien/:klien_id/wajib-lapor-dewasa
+#[derive(Debug, Deserialize)]
+pub struct SinkronWajibLapor {
+    pub klien_id: i32,
+    #[serde(flatten)]
+    pub lapor: CreateWajibLapor,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SinkronKunjunganRumah {
+    pub klien_id: i32,
+    #[serde(flatten)]
+    pub kunjungan: CreateKunjunganRumah,
+}
+
+// Kunjungan bisa dirujuk lewat ID server, atau lewat client_uuid kunjungan
+// yang juga dibuat offline (boleh dalam batch yang sama, asal urutannya lebih dulu).
+#[derive(Debug, Deserialize)]
+pub struct SinkronCatatanLapangan {
+    pub klien_id: i32,
+    pub kunjungan_rumah_id: Option<i32>,
+    pub kunjungan_client_uuid: Option<String>,
+    pub isi: String,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum StatusOperasiSinkron {
+    Diterapkan,
+    // client_uuid sudah pernah diterapkan; entitas_id menunjuk data yang sama
+    Duplikat,
+    // Data server sudah berubah (klien/kunjungan dihapus, UUID dipakai pengguna lain)
+    Konflik,
+    // Operasi tidak valid atau tidak diizinkan; perangkat sebaiknya tidak mengirim ulang
+    Ditolak,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HasilOperasi {
+    pub client_uuid: Option<String>,
+    pub status: StatusOperasiSinkron,
+    pub jenis_operasi: Option<JenisOperasiSinkronEnum>,
+    pub entitas_id: Option<i64>,
+    pub pesan: Option<String>,
+}
+
+// Baris log operasi yang sudah diterapkan
+#[derive(Debug, FromRow)]
+pub struct OperasiTercatat {
+    pub user_id: i32,
+    pub jenis_operasi: JenisOperasiSinkronEnum,
+    pub entitas_id: i64,
+}
+
+// Data klien seperlunya untuk perangkat lapangan
+#[derive(Debug, Serialize, FromRow)]
+pub struct KlienSinkron {
+    pub id: i32,
+    pub nama_klien: String,
+    pub alamat_klien: Option<String>,
+    pub bapas_id: i32,
+    pub pk_id: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ID yang harus dihapus dari perangkat: dihapus di server, atau sudah di luar
+// cakupan petugas (mis. klien dipindah ke PK lain)
+#[derive(Debug, Default, Serialize)]
+pub struct DihapusServer {
+    pub klien: Vec<i64>,
+    pub wajib_lapor: Vec<i64>,
+    pub kunjungan_rumah: Vec<i64>,
+    pub catatan_lapangan: Vec<i64>,
+}
+
+// Perubahan di server sejak kursor perangkat: keadaan terbaru baris yang
+// berubah, ditambah ID yang harus dihapus.
+#[derive(Debug, Serialize)]
+pub struct PerubahanServer {
+    pub klien: Vec<KlienSinkron>,
+    pub wajib_lapor: Vec<WajibLaporDewasa>,
+    pub kunjungan_rumah: Vec<KunjunganRumah>,
+    pub catatan_lapangan: Vec<CatatanLapangan>,
+    pub dihapus: DihapusServer,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HasilSinkronisasi {
+    pub hasil: Vec<HasilOperasi>,
+    pub perubahan: PerubahanServer,
+    // Dikirim kembali sebagai `kursor` pada sinkronisasi berikutnya
+    pub kursor_berikutnya: String,
+}
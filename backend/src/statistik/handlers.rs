@@ -0,0 +1,256 @@
+// File baru: src/statistik/handlers.rs
+//
+// Agregat untuk dashboard, dibaca dari materialized view analitik (lihat
+// penyegaran.rs) sehingga angkanya setua penyegaran terakhir (`data_per`).
+// Cakupan klien memakai filter yang sama dengan GET /klien, jadi Pegawai hanya
+// melihat klien bimbingannya sendiri, AdminBapas Bapasnya, dan AdminKanwil Kanwilnya.
+
+use axum::{
+    extract::{Extension, Query},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Datelike, Months};
+use sqlx::{PgPool, QueryBuilder};
+use std::collections::BTreeMap;
+use crate::auth::model::AuthenticatedUser;
+use crate::klien::handlers_core::{terapkan_filter_klien, GetAllKlienParams};
+use crate::penomoran::service::hari_ini;
+use crate::types::UserRoleEnum;
+use super::model::{
+    BarisJumlah, BarisLaporBulanan, BebanKasus, BebanKasusPk, FilterBebanKasus, FilterStatistik, JumlahPerLabel,
+    KelompokStatistik, LaporBulanan, StatistikDashboard, StatistikGrup, StatusPenyegaran,
+};
+use super::penyegaran::{self, VIEW_KLIEN_PER_PK, VIEW_LAYANAN_PER_STATUS, VIEW_WAJIB_LAPOR_HARIAN};
+
+// Label untuk nilai kosong pada kolom opsional
+const TIDAK_DIKETAHUI: &str = "Tidak Diketahui";
+
+fn kolom_grup(kelompok: KelompokStatistik) -> &'static str {
+    match kelompok {
+        KelompokStatistik::Bapas => "m.bapas_id",
+        KelompokStatistik::Kanwil => "m.kanwil_id",
+    }
+}
+
+// --- RINGKASAN DASHBOARD ---
+// URL: GET /api/statistik?kelompok=bapas|kanwil&pk_id=&bapas_id=&kanwil_id=&tanggal_mulai=&tanggal_selesai=
+#[axum::debug_handler]
+pub async fn get_statistik_dashboard(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(filter): Query<FilterStatistik>,
+) -> Result<Json<StatistikDashboard>, StatusCode> {
+    let tanggal_selesai = filter.tanggal_selesai.unwrap_or_else(hari_ini);
+    // Bawaan: awal bulan, 11 bulan sebelum bulan tanggal_selesai (total 12 bulan)
+    let tanggal_mulai = match filter.tanggal_mulai {
+        Some(tanggal) => tanggal,
+        None => tanggal_selesai
+            .with_day(1)
+            .and_then(|t| t.checked_sub_months(Months::new(11)))
+            .ok_or(StatusCode::BAD_REQUEST)?,
+    };
+    if tanggal_mulai > tanggal_selesai {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let grup_kolom = kolom_grup(filter.kelompok);
+    let params = GetAllKlienParams { pk_id: filter.pk_id, bapas_id: filter.bapas_id, kanwil_id: filter.kanwil_id };
+
+    // 1. Demografi klien: satu pemindaian, satu baris per (grup, dimensi, label)
+    let mut query_builder = QueryBuilder::new(format!(
+        r#"
+        SELECT {grup} AS grup_id, d.dimensi || '|' || COALESCE(d.label, '{kosong}') AS label,
+            SUM(m.jumlah_klien)::BIGINT AS jumlah
+        FROM {view} m
+        CROSS JOIN LATERAL (VALUES
+            ('tipe_klien', m.tipe_klien::TEXT),
+            ('jenis_kelamin', m.jenis_kelamin::TEXT),
+            ('pendidikan', m.pendidikan::TEXT),
+            ('pekerjaan', m.pekerjaan::TEXT),
+            ('kewarganegaraan', m.kewarganegaraan::TEXT)
+        ) AS d(dimensi, label)
+        WHERE TRUE
+        "#,
+        grup = grup_kolom,
+        kosong = TIDAK_DIKETAHUI,
+        view = VIEW_KLIEN_PER_PK
+    ));
+    terapkan_filter_klien(&mut query_builder, &user, &params, "m.");
+    query_builder.push(" GROUP BY 1, 2 ORDER BY 1, 3 DESC, 2");
+    let demografi = query_builder
+        .build_query_as::<BarisJumlah>()
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch statistik demografi: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    // 2. Layanan integrasi aktif per jenis bimbingan (belum diakhiri dan masa bimbingan belum lewat)
+    let mut query_builder = QueryBuilder::new(format!(
+        r#"
+        SELECT {grup} AS grup_id, COALESCE(m.jenis_bimbingan, '{kosong}') AS label, SUM(m.jumlah)::BIGINT AS jumlah
+        FROM {view} m
+        WHERE m.status_layanan = 'Aktif'
+        "#,
+        grup = grup_kolom,
+        kosong = TIDAK_DIKETAHUI,
+        view = VIEW_LAYANAN_PER_STATUS
+    ));
+    terapkan_filter_klien(&mut query_builder, &user, &params, "m.");
+    query_builder.push(" GROUP BY 1, 2 ORDER BY 1, 3 DESC, 2");
+    let layanan = query_builder
+        .build_query_as::<BarisJumlah>()
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch statistik layanan: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    // 3. Wajib lapor per bulan (WIB) dan metode, dari rekap harian
+    let mut query_builder = QueryBuilder::new(format!(
+        r#"
+        SELECT {grup} AS grup_id, TO_CHAR(m.tanggal, 'YYYY-MM') AS bulan,
+            m.metode_lapor::TEXT AS metode_lapor, SUM(m.jumlah)::BIGINT AS jumlah
+        FROM {view} m
+        "#,
+        grup = grup_kolom,
+        view = VIEW_WAJIB_LAPOR_HARIAN
+    ));
+    query_builder
+        .push(" WHERE m.tanggal BETWEEN ")
+        .push_bind(tanggal_mulai)
+        .push(" AND ")
+        .push_bind(tanggal_selesai);
+    terapkan_filter_klien(&mut query_builder, &user, &params, "m.");
+    query_builder.push(" GROUP BY 1, 2, 3 ORDER BY 1, 2, 3");
+    let lapor = query_builder
+        .build_query_as::<BarisLaporBulanan>()
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch statistik wajib lapor: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    // 4. Susun per grup
+    let mut grup: BTreeMap<Option<i32>, StatistikGrup> = BTreeMap::new();
+    for baris in demografi {
+        let g = grup.entry(baris.grup_id).or_default();
+        let (dimensi, label) = baris.label.split_once('|').unwrap_or(("", &baris.label));
+        let tujuan = match dimensi {
+            "tipe_klien" => {
+                g.total_klien += baris.jumlah;
+                &mut g.tipe_klien
+            }
+            "jenis_kelamin" => &mut g.jenis_kelamin,
+            "pendidikan" => &mut g.pendidikan,
+            "pekerjaan" => &mut g.pekerjaan,
+            _ => &mut g.kewarganegaraan,
+        };
+        tujuan.push(JumlahPerLabel { label: label.to_string(), jumlah: baris.jumlah });
+    }
+    for baris in layanan {
+        grup.entry(baris.grup_id)
+            .or_default()
+            .layanan_aktif
+            .push(JumlahPerLabel { label: baris.label, jumlah: baris.jumlah });
+    }
+    for baris in lapor {
+        grup.entry(baris.grup_id).or_default().wajib_lapor_bulanan.push(LaporBulanan {
+            bulan: baris.bulan,
+            metode_lapor: baris.metode_lapor,
+            jumlah: baris.jumlah,
+        });
+    }
+
+    // 5. Nama Bapas/Kanwil
+    let ids: Vec<i32> = grup.keys().flatten().copied().collect();
+    let nama: BTreeMap<i32, String> = match filter.kelompok {
+        KelompokStatistik::Bapas => sqlx::query!("SELECT id, nama_bapas AS nama FROM bapas WHERE id = ANY($1)", &ids)
+            .fetch_all(&pool)
+            .await
+            .map(|rows| rows.into_iter().map(|r| (r.id, r.nama)).collect()),
+        KelompokStatistik::Kanwil => sqlx::query!("SELECT id, nama_kanwil AS nama FROM kanwil WHERE id = ANY($1)", &ids)
+            .fetch_all(&pool)
+            .await
+            .map(|rows| rows.into_iter().map(|r| (r.id, r.nama)).collect()),
+    }
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let grup = grup
+        .into_iter()
+        .map(|(id, mut g)| {
+            g.id = id;
+            g.nama = id.and_then(|id| nama.get(&id).cloned()).unwrap_or_else(|| TIDAK_DIKETAHUI.to_string());
+            g
+        })
+        .collect();
+
+    let data_per = penyegaran::data_per(&pool, &[VIEW_KLIEN_PER_PK, VIEW_LAYANAN_PER_STATUS, VIEW_WAJIB_LAPOR_HARIAN])
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(StatistikDashboard { kelompok: filter.kelompok, tanggal_mulai, tanggal_selesai, data_per, grup }))
+}
+
+// --- BEBAN KASUS PER PK ---
+// URL: GET /api/statistik/beban-kasus?pk_id=&bapas_id=&kanwil_id=
+#[axum::debug_handler]
+pub async fn get_beban_kasus(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(filter): Query<FilterBebanKasus>,
+) -> Result<Json<BebanKasus>, StatusCode> {
+    let mut query_builder = QueryBuilder::new(format!(
+        r#"
+        SELECT m.pk_id, u.nama_user, m.bapas_id,
+            SUM(m.jumlah_klien)::BIGINT AS jumlah_klien, SUM(m.jumlah_klien_aktif)::BIGINT AS jumlah_klien_aktif
+        FROM {view} m
+        JOIN users u ON u.id = m.pk_id
+        WHERE TRUE
+        "#,
+        view = VIEW_KLIEN_PER_PK
+    ));
+    let params = GetAllKlienParams { pk_id: filter.pk_id, bapas_id: filter.bapas_id, kanwil_id: filter.kanwil_id };
+    terapkan_filter_klien(&mut query_builder, &user, &params, "m.");
+    query_builder.push(" GROUP BY m.pk_id, u.nama_user, m.bapas_id ORDER BY jumlah_klien_aktif DESC, u.nama_user");
+    let pk = query_builder
+        .build_query_as::<BebanKasusPk>()
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to fetch beban kasus: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let data_per = penyegaran::data_per(&pool, &[VIEW_KLIEN_PER_PK])
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(BebanKasus { data_per, pk }))
+}
+
+// --- SEGARKAN SEKARANG ---
+// URL: POST /api/statistik/segarkan (SuperAdmin), mis. setelah impor data massal
+#[axum::debug_handler]
+pub async fn segarkan_statistik(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<Json<Vec<StatusPenyegaran>>, StatusCode> {
+    if user.role != UserRoleEnum::SuperAdmin {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let selesai = penyegaran::segarkan_semua(&pool).await.map_err(|e| {
+        tracing::error!("Failed to refresh analytics views: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    if !selesai {
+        // Penyegaran lain sedang berjalan
+        return Err(StatusCode::CONFLICT);
+    }
+    let status = penyegaran::status_penyegaran(&pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(status))
+}
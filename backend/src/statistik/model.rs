@@ -0,0 +1,111 @@
+// File baru: src/statistik/model.rs
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, NaiveDate, Utc};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KelompokStatistik {
+    #[default]
+    Bapas,
+    Kanwil,
+}
+
+// Query string GET /api/statistik. Filter klien sama dengan GET /klien;
+// rentang wajib lapor bawaan 12 bulan terakhir.
+#[derive(Debug, Deserialize)]
+pub struct FilterStatistik {
+    #[serde(default)]
+    pub kelompok: KelompokStatistik,
+    pub pk_id: Option<i32>,
+    pub bapas_id: Option<i32>,
+    pub kanwil_id: Option<i32>,
+    pub tanggal_mulai: Option<NaiveDate>,
+    pub tanggal_selesai: Option<NaiveDate>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct BarisJumlah {
+    pub grup_id: Option<i32>,
+    pub label: String,
+    pub jumlah: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct BarisLaporBulanan {
+    pub grup_id: Option<i32>,
+    pub bulan: String, // "YYYY-MM" (WIB)
+    pub metode_lapor: String,
+    pub jumlah: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JumlahPerLabel {
+    pub label: String,
+    pub jumlah: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LaporBulanan {
+    pub bulan: String,
+    pub metode_lapor: String,
+    pub jumlah: i64,
+}
+
+// Statistik untuk satu Bapas/Kanwil
+#[derive(Debug, Default, Serialize)]
+pub struct StatistikGrup {
+    pub id: Option<i32>,
+    pub nama: String,
+    pub total_klien: i64,
+    pub tipe_klien: Vec<JumlahPerLabel>,
+    pub jenis_kelamin: Vec<JumlahPerLabel>,
+    pub pendidikan: Vec<JumlahPerLabel>,
+    pub pekerjaan: Vec<JumlahPerLabel>,
+    pub kewarganegaraan: Vec<JumlahPerLabel>,
+    pub layanan_aktif: Vec<JumlahPerLabel>,
+    pub wajib_lapor_bulanan: Vec<LaporBulanan>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatistikDashboard {
+    pub kelompok: KelompokStatistik,
+    pub tanggal_mulai: NaiveDate,
+    pub tanggal_selesai: NaiveDate,
+    // Waktu penyegaran ringkasan analitik yang menjadi sumber angka
+    pub data_per: Option<DateTime<Utc>>,
+    pub grup: Vec<StatistikGrup>,
+}
+
+// === BEBAN KASUS PK ===
+
+#[derive(Debug, Deserialize)]
+pub struct FilterBebanKasus {
+    pub pk_id: Option<i32>,
+    pub bapas_id: Option<i32>,
+    pub kanwil_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct BebanKasusPk {
+    pub pk_id: i32,
+    pub nama_user: String,
+    pub bapas_id: i32,
+    pub jumlah_klien: i64,
+    pub jumlah_klien_aktif: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BebanKasus {
+    pub data_per: Option<DateTime<Utc>>,
+    pub pk: Vec<BebanKasusPk>,
+}
+
+// === PENYEGARAN ANALITIK ===
+
+#[derive(Debug, Serialize)]
+pub struct StatusPenyegaran {
+    pub nama_view: String,
+    pub disegarkan_at: DateTime<Utc>,
+    pub durasi_ms: i32,
+}
@@ -0,0 +1,115 @@
+// File baru: src/statistik/penyegaran.rs
+//
+// Penjadwal penyegaran materialized view analitik. Berjalan di dalam proses
+// aplikasi; jika ada beberapa instance, advisory lock memastikan hanya satu
+// yang menyegarkan pada satu waktu. REFRESH ... CONCURRENTLY membuat dashboard
+// tetap bisa membaca data lama selama penyegaran berlangsung.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::env;
+use std::time::{Duration, Instant};
+use tokio::time::MissedTickBehavior;
+use super::model::StatusPenyegaran;
+
+pub const VIEW_WAJIB_LAPOR_HARIAN: &str = "mv_wajib_lapor_harian";
+pub const VIEW_LAYANAN_PER_STATUS: &str = "mv_layanan_per_status";
+pub const VIEW_KLIEN_PER_PK: &str = "mv_klien_per_pk";
+
+const SEMUA_VIEW: [&str; 3] = [VIEW_WAJIB_LAPOR_HARIAN, VIEW_LAYANAN_PER_STATUS, VIEW_KLIEN_PER_PK];
+
+// Kunci pg_advisory_lock khusus penyegaran analitik ("AKSARA" dalam ASCII)
+const KUNCI_ADVISORY: i64 = 0x414B_5341_5241;
+
+const INTERVAL_BAWAAN_MENIT: u64 = 15;
+
+/// Menyegarkan semua view. `Ok(false)` jika instance lain sedang menyegarkan.
+pub async fn segarkan_semua(pool: &PgPool) -> Result<bool, sqlx::Error> {
+    // Advisory lock terikat ke sesi, jadi kunci, penyegaran, dan pelepasan
+    // harus memakai koneksi yang sama
+    let mut conn = pool.acquire().await?;
+    let dapat_kunci = sqlx::query_scalar!(r#"SELECT pg_try_advisory_lock($1) AS "dapat!""#, KUNCI_ADVISORY)
+        .fetch_one(&mut *conn)
+        .await?;
+    if !dapat_kunci {
+        return Ok(false);
+    }
+
+    let mut hasil = Ok(());
+    for nama in SEMUA_VIEW {
+        let mulai = Instant::now();
+        // Nama view berasal dari konstanta di atas, bukan dari input pengguna
+        if let Err(e) = sqlx::query(&format!("REFRESH MATERIALIZED VIEW CONCURRENTLY {}", nama))
+            .execute(&mut *conn)
+            .await
+        {
+            hasil = Err(e);
+            break;
+        }
+        let durasi_ms = i32::try_from(mulai.elapsed().as_millis()).unwrap_or(i32::MAX);
+        if let Err(e) = sqlx::query!(
+            r#"
+            INSERT INTO penyegaran_analitik (nama_view, disegarkan_at, durasi_ms)
+            VALUES ($1, NOW(), $2)
+            ON CONFLICT (nama_view) DO UPDATE SET disegarkan_at = EXCLUDED.disegarkan_at, durasi_ms = EXCLUDED.durasi_ms
+            "#,
+            nama,
+            durasi_ms
+        )
+        .execute(&mut *conn)
+        .await
+        {
+            hasil = Err(e);
+            break;
+        }
+    }
+
+    sqlx::query!("SELECT pg_advisory_unlock($1)", KUNCI_ADVISORY)
+        .fetch_one(&mut *conn)
+        .await?;
+    hasil.map(|_| true)
+}
+
+/// Waktu data tertua di antara view yang dipakai sebuah respons.
+pub async fn data_per(pool: &PgPool, view: &[&str]) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    let view: Vec<String> = view.iter().map(|v| v.to_string()).collect();
+    sqlx::query_scalar!(
+        "SELECT MIN(disegarkan_at) FROM penyegaran_analitik WHERE nama_view = ANY($1)",
+        &view
+    )
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn status_penyegaran(pool: &PgPool) -> Result<Vec<StatusPenyegaran>, sqlx::Error> {
+    sqlx::query_as!(
+        StatusPenyegaran,
+        "SELECT nama_view, disegarkan_at, durasi_ms FROM penyegaran_analitik ORDER BY nama_view"
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Menjalankan penyegaran saat start lalu setiap `INTERVAL_SEGARKAN_ANALITIK_MENIT`
+/// menit (bawaan 15).
+pub fn jalankan_penjadwal(pool: PgPool) {
+    let menit = env::var("INTERVAL_SEGARKAN_ANALITIK_MENIT")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|m| *m > 0)
+        .unwrap_or(INTERVAL_BAWAAN_MENIT);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(menit * 60));
+        // Penyegaran yang lama tidak perlu dikejar dengan beberapa penyegaran beruntun
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match segarkan_semua(&pool).await {
+                Ok(true) => tracing::info!("Analytics views refreshed"),
+                Ok(false) => tracing::info!("Analytics refresh skipped: another instance holds the lock"),
+                Err(e) => tracing::error!("Failed to refresh analytics views: {}", e),
+            }
+        }
+    });
+}
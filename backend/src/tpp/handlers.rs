@@ -0,0 +1,863 @@
+// File baru: src/tpp/handlers.rs
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use sqlx::{types::Json as SqlJson, PgConnection, PgPool};
+use crate::auth::authorization::{check_permission, get_bapas_ownership};
+use crate::auth::model::AuthenticatedUser;
+use crate::dokumen::{docx, model::FormatDokumen, pdf};
+use crate::types::{KeputusanTppEnum, PeranTppEnum, SuaraTppEnum, UserRoleEnum};
+use super::model::{
+    AgendaSidangTpp, AgendaSidangTppDetail, AnggotaSidangTpp, CreateAgendaSidangTpp, CreateSidangTpp,
+    SetAnggotaSidangTpp, SetSuaraAgendaTpp, SidangTpp, SidangTppDetail, SuaraAgendaTpp,
+    UpdateAgendaSidangTpp, UpdateSidangTpp, VersiNotulenSidangTpp,
+};
+use super::notulen;
+
+/// Hak pengguna atas sidang di sebuah Bapas.
+struct AksesSidang {
+    /// Admin yang berwenang atas Bapas
+    kelola: bool,
+    /// Admin, atau pegawai Bapas yang sama
+    lihat: bool,
+}
+
+async fn akses_bapas(pool: &PgPool, user: &AuthenticatedUser, bapas_id: i32) -> Result<AksesSidang, StatusCode> {
+    let ownership = get_bapas_ownership(pool, bapas_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let admin = user.role != UserRoleEnum::Pegawai && check_permission(user, &ownership);
+    Ok(AksesSidang {
+        kelola: admin,
+        lihat: admin || user.bapas_id == Some(bapas_id),
+    })
+}
+
+async fn get_sidang(pool: &PgPool, id: i32) -> Result<SidangTpp, StatusCode> {
+    sqlx::query_as!(
+        SidangTpp,
+        "SELECT * FROM sidang_tpp WHERE id = $1 AND deleted_at IS NULL",
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn get_agenda(pool: &PgPool, id: i32) -> Result<AgendaSidangTpp, StatusCode> {
+    sqlx::query_as!(
+        AgendaSidangTpp,
+        r#"
+        SELECT id, sidang_tpp_id, klien_id, penerimaan_dewasa_id, urutan, uraian, rekomendasi,
+            keputusan_tpp AS "keputusan_tpp: _", layanan_integrasi_dewasa_id,
+            created_at, updated_at, created_by, updated_by
+        FROM agenda_sidang_tpp WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn peran_dalam_sidang(pool: &PgPool, sidang_id: i32, user_id: i32) -> Result<Option<PeranTppEnum>, StatusCode> {
+    sqlx::query_scalar!(
+        r#"SELECT peran_tpp AS "peran_tpp: PeranTppEnum" FROM anggota_sidang_tpp WHERE sidang_tpp_id = $1 AND user_id = $2"#,
+        sidang_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+// Agenda dan keputusan boleh dicatat admin Bapas, Ketua, atau Sekretaris sidang
+async fn ensure_pencatat(pool: &PgPool, user: &AuthenticatedUser, sidang: &SidangTpp) -> Result<(), StatusCode> {
+    if akses_bapas(pool, user, sidang.bapas_id).await?.kelola {
+        return Ok(());
+    }
+    match peran_dalam_sidang(pool, sidang.id, user.id).await? {
+        Some(PeranTppEnum::Ketua | PeranTppEnum::Sekretaris) => Ok(()),
+        _ => Err(StatusCode::FORBIDDEN),
+    }
+}
+
+// Selama notulen terkunci, anggota, agenda, suara, dan keputusan tidak boleh
+// berubah. Baris sidang ditahan (FOR SHARE) sampai transaksi pemanggil selesai
+// supaya perubahan tidak berbalapan dengan penguncian notulen.
+async fn ensure_notulen_terbuka(conn: &mut PgConnection, sidang_id: i32) -> Result<(), StatusCode> {
+    let terkunci = sqlx::query_scalar!(
+        r#"
+        SELECT notulen_dikunci_at IS NOT NULL AS "terkunci!" FROM sidang_tpp
+        WHERE id = $1 AND deleted_at IS NULL
+        FOR SHARE
+        "#,
+        sidang_id
+    )
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or(StatusCode::NOT_FOUND)?;
+    if terkunci {
+        return Err(StatusCode::CONFLICT);
+    }
+    Ok(())
+}
+
+async fn muat_detail(pool: &PgPool, sidang: SidangTpp) -> Result<SidangTppDetail, sqlx::Error> {
+    let anggota = sqlx::query_as!(
+        AnggotaSidangTpp,
+        r#"
+        SELECT a.user_id, u.nama_user, u.nip_user, a.peran_tpp AS "peran_tpp: _"
+        FROM anggota_sidang_tpp a JOIN users u ON u.id = a.user_id
+        WHERE a.sidang_tpp_id = $1
+        ORDER BY a.peran_tpp, u.nama_user
+        "#,
+        sidang.id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let agenda = sqlx::query!(
+        r#"
+        SELECT g.id, g.sidang_tpp_id, g.klien_id, g.penerimaan_dewasa_id, g.urutan, g.uraian, g.rekomendasi,
+            g.keputusan_tpp AS "keputusan_tpp: KeputusanTppEnum", g.layanan_integrasi_dewasa_id,
+            g.created_at, g.updated_at, g.created_by, g.updated_by, k.nama_klien
+        FROM agenda_sidang_tpp g JOIN klien k ON k.id = g.klien_id
+        WHERE g.sidang_tpp_id = $1
+        ORDER BY g.urutan, g.id
+        "#,
+        sidang.id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let semua_suara = sqlx::query_as!(
+        SuaraAgendaTpp,
+        r#"
+        SELECT s.agenda_sidang_tpp_id, s.user_id, u.nama_user, s.suara_tpp AS "suara_tpp: _", s.catatan
+        FROM suara_agenda_tpp s
+        JOIN agenda_sidang_tpp g ON g.id = s.agenda_sidang_tpp_id
+        JOIN users u ON u.id = s.user_id
+        WHERE g.sidang_tpp_id = $1
+        ORDER BY u.nama_user
+        "#,
+        sidang.id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut suara_per_agenda: HashMap<i32, Vec<SuaraAgendaTpp>> = HashMap::new();
+    for suara in semua_suara {
+        suara_per_agenda.entry(suara.agenda_sidang_tpp_id).or_default().push(suara);
+    }
+
+    let agenda = agenda
+        .into_iter()
+        .map(|row| {
+            let suara = suara_per_agenda.remove(&row.id).unwrap_or_default();
+            let hitung = |jenis: SuaraTppEnum| suara.iter().filter(|s| s.suara_tpp == jenis).count();
+            AgendaSidangTppDetail {
+                jumlah_setuju: hitung(SuaraTppEnum::Setuju),
+                jumlah_tidak_setuju: hitung(SuaraTppEnum::TidakSetuju),
+                jumlah_abstain: hitung(SuaraTppEnum::Abstain),
+                nama_klien: row.nama_klien,
+                agenda: AgendaSidangTpp {
+                    id: row.id,
+                    sidang_tpp_id: row.sidang_tpp_id,
+                    klien_id: row.klien_id,
+                    penerimaan_dewasa_id: row.penerimaan_dewasa_id,
+                    urutan: row.urutan,
+                    uraian: row.uraian,
+                    rekomendasi: row.rekomendasi,
+                    keputusan_tpp: row.keputusan_tpp,
+                    layanan_integrasi_dewasa_id: row.layanan_integrasi_dewasa_id,
+                    created_at: row.created_at,
+                    updated_at: row.updated_at,
+                    created_by: row.created_by,
+                    updated_by: row.updated_by,
+                },
+                suara,
+            }
+        })
+        .collect();
+
+    Ok(SidangTppDetail { sidang, anggota, agenda })
+}
+
+// === SIDANG TPP ===
+
+// --- CREATE ---
+// URL: POST /api/bapas/:bapas_id/sidang-tpp
+#[axum::debug_handler]
+pub async fn create_sidang_tpp(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(bapas_id): Path<i32>,
+    Json(payload): Json<CreateSidangTpp>,
+) -> Result<(StatusCode, Json<SidangTpp>), StatusCode> {
+    if !akses_bapas(&pool, &user, bapas_id).await?.kelola {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let sidang = sqlx::query_as!(
+        SidangTpp,
+        r#"
+        INSERT INTO sidang_tpp (bapas_id, tanggal_sidang, tempat_sidang, keterangan, created_by, updated_by)
+        VALUES ($1, $2, $3, $4, $5, $5)
+        RETURNING *
+        "#,
+        bapas_id,
+        payload.tanggal_sidang,
+        payload.tempat_sidang,
+        payload.keterangan,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create sidang tpp: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((StatusCode::CREATED, Json(sidang)))
+}
+
+// --- READ ALL FOR A BAPAS ---
+// URL: GET /api/bapas/:bapas_id/sidang-tpp
+#[axum::debug_handler]
+pub async fn get_all_sidang_tpp(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(bapas_id): Path<i32>,
+) -> Result<Json<Vec<SidangTpp>>, StatusCode> {
+    if !akses_bapas(&pool, &user, bapas_id).await?.lihat {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let list = sqlx::query_as!(
+        SidangTpp,
+        "SELECT * FROM sidang_tpp WHERE bapas_id = $1 AND deleted_at IS NULL ORDER BY tanggal_sidang DESC, id DESC",
+        bapas_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch sidang tpp: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(list))
+}
+
+// --- READ ONE ---
+// URL: GET /api/sidang-tpp/:id
+#[axum::debug_handler]
+pub async fn get_sidang_tpp_by_id(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> Result<Json<SidangTppDetail>, StatusCode> {
+    let sidang = get_sidang(&pool, id).await?;
+    if !akses_bapas(&pool, &user, sidang.bapas_id).await?.lihat {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let detail = muat_detail(&pool, sidang).await.map_err(|e| {
+        tracing::error!("Failed to load sidang tpp {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(detail))
+}
+
+// --- UPDATE ---
+// URL: PUT /api/sidang-tpp/:id
+#[axum::debug_handler]
+pub async fn update_sidang_tpp(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateSidangTpp>,
+) -> Result<Json<SidangTpp>, StatusCode> {
+    let sidang = get_sidang(&pool, id).await?;
+    if !akses_bapas(&pool, &user, sidang.bapas_id).await?.kelola {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    ensure_notulen_terbuka(&mut tx, id).await?;
+    let updated = sqlx::query_as!(
+        SidangTpp,
+        r#"
+        UPDATE sidang_tpp SET
+            tanggal_sidang = COALESCE($1, tanggal_sidang),
+            tempat_sidang = COALESCE($2, tempat_sidang),
+            keterangan = COALESCE($3, keterangan),
+            updated_by = $4
+        WHERE id = $5 AND deleted_at IS NULL
+        RETURNING *
+        "#,
+        payload.tanggal_sidang,
+        payload.tempat_sidang,
+        payload.keterangan,
+        user.id,
+        id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update sidang tpp {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::NOT_FOUND)?;
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(updated))
+}
+
+// --- DELETE (SOFT) ---
+// URL: DELETE /api/sidang-tpp/:id
+#[axum::debug_handler]
+pub async fn delete_sidang_tpp(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> StatusCode {
+    let sidang = match get_sidang(&pool, id).await {
+        Ok(sidang) => sidang,
+        Err(status) => return status,
+    };
+    match akses_bapas(&pool, &user, sidang.bapas_id).await {
+        Ok(akses) if akses.kelola => {}
+        Ok(_) => return StatusCode::FORBIDDEN,
+        Err(status) => return status,
+    }
+
+    // Sidang dengan notulen terkunci harus dibuka dulu sebelum dihapus
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    if let Err(status) = ensure_notulen_terbuka(&mut tx, id).await {
+        return status;
+    }
+    let result = sqlx::query!(
+        "UPDATE sidang_tpp SET deleted_at = NOW(), updated_by = $1 WHERE id = $2",
+        user.id,
+        id
+    )
+    .execute(&mut *tx)
+    .await;
+
+    let status = match result {
+        Ok(res) if res.rows_affected() > 0 => StatusCode::NO_CONTENT,
+        Ok(_) => StatusCode::NOT_FOUND,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    if status == StatusCode::NO_CONTENT && tx.commit().await.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+    status
+}
+
+// --- SET ANGGOTA ---
+// URL: PUT /api/sidang-tpp/:id/anggota
+// Mengganti seluruh daftar anggota. Anggota harus pegawai aktif Bapas yang sama
+// dan paling banyak satu Ketua.
+#[axum::debug_handler]
+pub async fn set_anggota_sidang_tpp(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<Vec<SetAnggotaSidangTpp>>,
+) -> Result<Json<Vec<AnggotaSidangTpp>>, StatusCode> {
+    let sidang = get_sidang(&pool, id).await?;
+    if !akses_bapas(&pool, &user, sidang.bapas_id).await?.kelola {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    if payload.iter().filter(|a| a.peran_tpp == PeranTppEnum::Ketua).count() > 1 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let user_ids: Vec<i32> = payload.iter().map(|a| a.user_id).collect();
+    let jumlah_valid = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(DISTINCT id) AS "jumlah!" FROM users
+        WHERE id = ANY($1) AND bapas_id = $2 AND deleted_at IS NULL AND status_aktif_user = 'Aktif'
+        "#,
+        &user_ids,
+        sidang.bapas_id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if jumlah_valid as usize != payload.len() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    ensure_notulen_terbuka(&mut tx, id).await?;
+    sqlx::query!("DELETE FROM anggota_sidang_tpp WHERE sidang_tpp_id = $1", id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    for anggota in &payload {
+        sqlx::query!(
+            "INSERT INTO anggota_sidang_tpp (sidang_tpp_id, user_id, peran_tpp) VALUES ($1, $2, $3)",
+            id,
+            anggota.user_id,
+            anggota.peran_tpp as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to insert anggota sidang tpp: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    }
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let detail = muat_detail(&pool, sidang).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(detail.anggota))
+}
+
+// --- NOTULEN ---
+// URL: GET /api/sidang-tpp/:id/notulen?format=pdf[&versi=2]
+// Tanpa `versi`, notulen disusun dari data saat ini; dengan `versi`, dari isi
+// yang disimpan saat versi itu dikunci.
+#[derive(Debug, Deserialize)]
+pub struct NotulenParams {
+    pub format: FormatDokumen,
+    pub versi: Option<i32>,
+}
+
+#[axum::debug_handler]
+pub async fn get_notulen_sidang_tpp(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Query(params): Query<NotulenParams>,
+) -> Result<Response, StatusCode> {
+    let sidang = get_sidang(&pool, id).await?;
+    if !akses_bapas(&pool, &user, sidang.bapas_id).await?.lihat {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let nama_bapas = sqlx::query_scalar!("SELECT nama_bapas FROM bapas WHERE id = $1", sidang.bapas_id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let detail = match params.versi {
+        Some(versi) => {
+            sqlx::query_scalar!(
+                r#"SELECT isi AS "isi: SqlJson<SidangTppDetail>" FROM versi_notulen_sidang_tpp WHERE sidang_tpp_id = $1 AND versi = $2"#,
+                id,
+                versi
+            )
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to load notulen sidang tpp {} versi {}: {}", id, versi, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+            .ok_or(StatusCode::NOT_FOUND)?
+            .0
+        }
+        None => muat_detail(&pool, sidang).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+    };
+    let baris = notulen::susun(&detail, &nama_bapas);
+
+    let (content_type, ekstensi, isi) = match params.format {
+        FormatDokumen::Docx => {
+            let isi = docx::render(&baris).map_err(|e| {
+                tracing::error!("Failed to build notulen docx: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+            ("application/vnd.openxmlformats-officedocument.wordprocessingml.document", "docx", isi)
+        }
+        FormatDokumen::Pdf => ("application/pdf", "pdf", pdf::render(&baris)),
+    };
+
+    let headers = [
+        (header::CONTENT_TYPE, content_type.to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"notulen_sidang_tpp_{}.{}\"", id, ekstensi),
+        ),
+    ];
+    Ok((headers, isi).into_response())
+}
+
+// --- KUNCI NOTULEN ---
+// URL: POST /api/sidang-tpp/:id/notulen/kunci
+// Membekukan anggota, agenda, suara, dan keputusan sehingga notulen yang
+// diunduh selalu sesuai dengan suara yang tersimpan. Versi notulen naik setiap
+// kali dikunci dan isinya disimpan di versi_notulen_sidang_tpp.
+#[axum::debug_handler]
+pub async fn kunci_notulen_sidang_tpp(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> Result<Json<SidangTpp>, StatusCode> {
+    let sidang = get_sidang(&pool, id).await?;
+    ensure_pencatat(&pool, &user, &sidang).await?;
+
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let dikunci = sqlx::query_as!(
+        SidangTpp,
+        r#"
+        UPDATE sidang_tpp SET
+            notulen_versi = notulen_versi + 1,
+            notulen_dikunci_at = NOW(),
+            notulen_dikunci_by = $1,
+            updated_by = $1
+        WHERE id = $2 AND deleted_at IS NULL AND notulen_dikunci_at IS NULL
+        RETURNING *
+        "#,
+        user.id,
+        id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to lock notulen sidang tpp {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::CONFLICT)?; // Sudah dikunci
+
+    // Perubahan lain menahan baris sidang (ensure_notulen_terbuka), jadi setelah
+    // UPDATE di atas isi anggota, agenda, dan suara tidak bisa berubah lagi
+    let detail = muat_detail(&pool, dikunci).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    sqlx::query!(
+        r#"
+        INSERT INTO versi_notulen_sidang_tpp (sidang_tpp_id, versi, isi, dikunci_at, dikunci_by)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        id,
+        detail.sidang.notulen_versi,
+        SqlJson(&detail) as _,
+        detail.sidang.notulen_dikunci_at,
+        user.id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to store notulen sidang tpp {} version: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(detail.sidang))
+}
+
+// --- RIWAYAT VERSI NOTULEN ---
+// URL: GET /api/sidang-tpp/:id/notulen/versi
+#[axum::debug_handler]
+pub async fn get_versi_notulen_sidang_tpp(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<VersiNotulenSidangTpp>>, StatusCode> {
+    let sidang = get_sidang(&pool, id).await?;
+    if !akses_bapas(&pool, &user, sidang.bapas_id).await?.lihat {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let daftar = sqlx::query_as!(
+        VersiNotulenSidangTpp,
+        "SELECT versi, dikunci_at, dikunci_by FROM versi_notulen_sidang_tpp WHERE sidang_tpp_id = $1 ORDER BY versi",
+        id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch notulen versions for sidang tpp {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(daftar))
+}
+
+// --- BUKA KUNCI NOTULEN ---
+// URL: DELETE /api/sidang-tpp/:id/notulen/kunci
+// Hanya admin Bapas. Perubahan sesudahnya harus dikunci ulang sebagai versi baru.
+#[axum::debug_handler]
+pub async fn buka_kunci_notulen_sidang_tpp(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> Result<Json<SidangTpp>, StatusCode> {
+    let sidang = get_sidang(&pool, id).await?;
+    if !akses_bapas(&pool, &user, sidang.bapas_id).await?.kelola {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let dibuka = sqlx::query_as!(
+        SidangTpp,
+        r#"
+        UPDATE sidang_tpp SET notulen_dikunci_at = NULL, notulen_dikunci_by = NULL, updated_by = $1
+        WHERE id = $2 AND deleted_at IS NULL AND notulen_dikunci_at IS NOT NULL
+        RETURNING *
+        "#,
+        user.id,
+        id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to unlock notulen sidang tpp {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::CONFLICT)?; // Belum dikunci
+
+    Ok(Json(dibuka))
+}
+
+// === AGENDA SIDANG TPP ===
+
+// --- CREATE ---
+// URL: POST /api/sidang-tpp/:id/agenda
+#[axum::debug_handler]
+pub async fn create_agenda_sidang_tpp(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(sidang_id): Path<i32>,
+    Json(payload): Json<CreateAgendaSidangTpp>,
+) -> Result<(StatusCode, Json<AgendaSidangTpp>), StatusCode> {
+    let sidang = get_sidang(&pool, sidang_id).await?;
+    ensure_pencatat(&pool, &user, &sidang).await?;
+
+    // Klien harus milik Bapas penyelenggara; penerimaan (jika ada) milik klien tersebut
+    let valid = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM klien k
+            WHERE k.id = $1 AND k.bapas_id = $2 AND k.deleted_at IS NULL
+              AND ($3::INT IS NULL OR EXISTS (
+                  SELECT 1 FROM penerimaan_dewasa p
+                  WHERE p.id = $3 AND p.klien_id = k.id AND p.deleted_at IS NULL))
+        ) AS "valid!"
+        "#,
+        payload.klien_id,
+        sidang.bapas_id,
+        payload.penerimaan_dewasa_id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !valid {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    ensure_notulen_terbuka(&mut tx, sidang_id).await?;
+    let agenda = sqlx::query_as!(
+        AgendaSidangTpp,
+        r#"
+        INSERT INTO agenda_sidang_tpp (
+            sidang_tpp_id, klien_id, penerimaan_dewasa_id, urutan, uraian, rekomendasi, created_by, updated_by
+        )
+        VALUES (
+            $1, $2, $3,
+            COALESCE($4, (SELECT COALESCE(MAX(urutan), 0) + 1 FROM agenda_sidang_tpp WHERE sidang_tpp_id = $1)),
+            $5, $6, $7, $7
+        )
+        RETURNING id, sidang_tpp_id, klien_id, penerimaan_dewasa_id, urutan AS "urutan!", uraian, rekomendasi,
+            keputusan_tpp AS "keputusan_tpp: _", layanan_integrasi_dewasa_id,
+            created_at, updated_at, created_by, updated_by
+        "#,
+        sidang_id,
+        payload.klien_id,
+        payload.penerimaan_dewasa_id,
+        payload.urutan,
+        payload.uraian,
+        payload.rekomendasi,
+        user.id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to create agenda sidang tpp: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok((StatusCode::CREATED, Json(agenda)))
+}
+
+// --- UPDATE ---
+// URL: PUT /api/agenda-sidang-tpp/:id
+// Rekomendasi yang disetujui ditautkan ke layanan integrasi yang dihasilkannya.
+// Setelah notulen dikunci hanya tautan layanan integrasi yang masih boleh diisi.
+#[axum::debug_handler]
+pub async fn update_agenda_sidang_tpp(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateAgendaSidangTpp>,
+) -> Result<Json<AgendaSidangTpp>, StatusCode> {
+    let agenda = get_agenda(&pool, id).await?;
+    let sidang = get_sidang(&pool, agenda.sidang_tpp_id).await?;
+    ensure_pencatat(&pool, &user, &sidang).await?;
+
+    let keputusan = payload.keputusan_tpp.or(agenda.keputusan_tpp);
+    let layanan_id = payload.layanan_integrasi_dewasa_id.or(agenda.layanan_integrasi_dewasa_id);
+    if layanan_id.is_some() && keputusan != Some(KeputusanTppEnum::Disetujui) {
+        return Err(StatusCode::CONFLICT);
+    }
+    if let Some(layanan_id) = payload.layanan_integrasi_dewasa_id {
+        let milik_klien = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM layanan_integrasi_dewasa WHERE id = $1 AND klien_id = $2 AND deleted_at IS NULL
+            ) AS "ada!"
+            "#,
+            layanan_id,
+            agenda.klien_id
+        )
+        .fetch_one(&pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        if !milik_klien {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let ubah_isi = payload.urutan.is_some()
+        || payload.uraian.is_some()
+        || payload.rekomendasi.is_some()
+        || payload.keputusan_tpp.is_some();
+    if ubah_isi {
+        ensure_notulen_terbuka(&mut tx, sidang.id).await?;
+    }
+    let updated = sqlx::query_as!(
+        AgendaSidangTpp,
+        r#"
+        UPDATE agenda_sidang_tpp SET
+            urutan = COALESCE($1, urutan),
+            uraian = COALESCE($2, uraian),
+            rekomendasi = COALESCE($3, rekomendasi),
+            keputusan_tpp = COALESCE($4, keputusan_tpp),
+            layanan_integrasi_dewasa_id = COALESCE($5, layanan_integrasi_dewasa_id),
+            updated_by = $6
+        WHERE id = $7
+        RETURNING id, sidang_tpp_id, klien_id, penerimaan_dewasa_id, urutan, uraian, rekomendasi,
+            keputusan_tpp AS "keputusan_tpp: _", layanan_integrasi_dewasa_id,
+            created_at, updated_at, created_by, updated_by
+        "#,
+        payload.urutan,
+        payload.uraian,
+        payload.rekomendasi,
+        payload.keputusan_tpp as _,
+        payload.layanan_integrasi_dewasa_id,
+        user.id,
+        id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to update agenda sidang tpp {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::NOT_FOUND)?;
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(updated))
+}
+
+// --- DELETE ---
+// URL: DELETE /api/agenda-sidang-tpp/:id
+#[axum::debug_handler]
+pub async fn delete_agenda_sidang_tpp(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+) -> StatusCode {
+    let agenda = match get_agenda(&pool, id).await {
+        Ok(agenda) => agenda,
+        Err(status) => return status,
+    };
+    let sidang = match get_sidang(&pool, agenda.sidang_tpp_id).await {
+        Ok(sidang) => sidang,
+        Err(status) => return status,
+    };
+    if let Err(status) = ensure_pencatat(&pool, &user, &sidang).await {
+        return status;
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    if let Err(status) = ensure_notulen_terbuka(&mut tx, sidang.id).await {
+        return status;
+    }
+    let status = match sqlx::query!("DELETE FROM agenda_sidang_tpp WHERE id = $1", id).execute(&mut *tx).await {
+        Ok(res) if res.rows_affected() > 0 => StatusCode::NO_CONTENT,
+        Ok(_) => StatusCode::NOT_FOUND,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    if status == StatusCode::NO_CONTENT && tx.commit().await.is_err() {
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+    status
+}
+
+// --- SUARA ---
+// URL: PUT /api/agenda-sidang-tpp/:id/suara
+// Hanya anggota sidang yang dapat memberikan suara, dan hanya atas namanya sendiri,
+// selama notulen belum dikunci.
+#[axum::debug_handler]
+pub async fn set_suara_agenda_tpp(
+    Extension(pool): Extension<PgPool>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<i32>,
+    Json(payload): Json<SetSuaraAgendaTpp>,
+) -> Result<Json<SuaraAgendaTpp>, StatusCode> {
+    let agenda = get_agenda(&pool, id).await?;
+    let sidang = get_sidang(&pool, agenda.sidang_tpp_id).await?;
+    if peran_dalam_sidang(&pool, sidang.id, user.id).await?.is_none() {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let mut tx = pool.begin().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    ensure_notulen_terbuka(&mut tx, sidang.id).await?;
+    let suara = sqlx::query_as!(
+        SuaraAgendaTpp,
+        r#"
+        WITH simpan AS (
+            INSERT INTO suara_agenda_tpp (agenda_sidang_tpp_id, user_id, suara_tpp, catatan)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (agenda_sidang_tpp_id, user_id)
+            DO UPDATE SET suara_tpp = EXCLUDED.suara_tpp, catatan = EXCLUDED.catatan
+            RETURNING agenda_sidang_tpp_id, user_id, suara_tpp, catatan
+        )
+        SELECT s.agenda_sidang_tpp_id AS "agenda_sidang_tpp_id!", s.user_id AS "user_id!",
+            u.nama_user AS "nama_user!", s.suara_tpp AS "suara_tpp!: _", s.catatan
+        FROM simpan s JOIN users u ON u.id = s.user_id
+        "#,
+        id,
+        user.id,
+        payload.suara_tpp as _,
+        payload.catatan
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to save suara agenda tpp: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    tx.commit().await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(suara))
+}
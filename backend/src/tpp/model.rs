@@ -0,0 +1,134 @@
+// File baru: src/tpp/model.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, NaiveDate, Utc};
+use crate::types::{KeputusanTppEnum, PeranTppEnum, SuaraTppEnum};
+
+// Merepresentasikan satu baris dari tabel 'sidang_tpp'
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct SidangTpp {
+    pub id: i32,
+    pub bapas_id: i32,
+    pub tanggal_sidang: NaiveDate,
+    pub tempat_sidang: Option<String>,
+    pub keterangan: Option<String>,
+    pub notulen_versi: i32,
+    pub notulen_dikunci_at: Option<DateTime<Utc>>,
+    pub notulen_dikunci_by: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSidangTpp {
+    pub tanggal_sidang: NaiveDate,
+    pub tempat_sidang: Option<String>,
+    pub keterangan: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSidangTpp {
+    pub tanggal_sidang: Option<NaiveDate>,
+    pub tempat_sidang: Option<String>,
+    pub keterangan: Option<String>,
+}
+
+// Anggota sidang beserta nama untuk ditampilkan
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct AnggotaSidangTpp {
+    pub user_id: i32,
+    pub nama_user: String,
+    pub nip_user: String,
+    pub peran_tpp: PeranTppEnum,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetAnggotaSidangTpp {
+    pub user_id: i32,
+    pub peran_tpp: PeranTppEnum,
+}
+
+// Merepresentasikan satu baris dari tabel 'agenda_sidang_tpp'
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct AgendaSidangTpp {
+    pub id: i32,
+    pub sidang_tpp_id: i32,
+    pub klien_id: i32,
+    pub penerimaan_dewasa_id: Option<i32>,
+    pub urutan: i32,
+    pub uraian: Option<String>,
+    pub rekomendasi: Option<String>,
+    pub keputusan_tpp: Option<KeputusanTppEnum>,
+    pub layanan_integrasi_dewasa_id: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by: Option<i32>,
+    pub updated_by: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateAgendaSidangTpp {
+    pub klien_id: i32,
+    pub penerimaan_dewasa_id: Option<i32>,
+    pub urutan: Option<i32>,
+    pub uraian: Option<String>,
+    pub rekomendasi: Option<String>,
+}
+
+// Keputusan dan tautan layanan integrasi juga diubah lewat endpoint ini
+#[derive(Debug, Deserialize)]
+pub struct UpdateAgendaSidangTpp {
+    pub urutan: Option<i32>,
+    pub uraian: Option<String>,
+    pub rekomendasi: Option<String>,
+    pub keputusan_tpp: Option<KeputusanTppEnum>,
+    pub layanan_integrasi_dewasa_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct SuaraAgendaTpp {
+    pub agenda_sidang_tpp_id: i32,
+    pub user_id: i32,
+    pub nama_user: String,
+    pub suara_tpp: SuaraTppEnum,
+    pub catatan: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetSuaraAgendaTpp {
+    pub suara_tpp: SuaraTppEnum,
+    pub catatan: Option<String>,
+}
+
+// Agenda beserta nama klien, suara anggota, dan rekapnya
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AgendaSidangTppDetail {
+    #[serde(flatten)]
+    pub agenda: AgendaSidangTpp,
+    pub nama_klien: String,
+    pub suara: Vec<SuaraAgendaTpp>,
+    pub jumlah_setuju: usize,
+    pub jumlah_tidak_setuju: usize,
+    pub jumlah_abstain: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SidangTppDetail {
+    #[serde(flatten)]
+    pub sidang: SidangTpp,
+    pub anggota: Vec<AnggotaSidangTpp>,
+    pub agenda: Vec<AgendaSidangTppDetail>,
+}
+
+// Satu versi notulen yang pernah dikunci
+#[derive(Debug, Serialize, FromRow)]
+pub struct VersiNotulenSidangTpp {
+    pub versi: i32,
+    pub dikunci_at: DateTime<Utc>,
+    pub dikunci_by: Option<i32>,
+}
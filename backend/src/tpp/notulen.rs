@@ -0,0 +1,105 @@
+// File baru: src/tpp/notulen.rs
+//
+// Menyusun notulen sidang TPP sebagai baris dokumen, lalu dirender
+// dengan penulis DOCX/PDF yang sama dengan generator dokumen Litmas.
+
+use chrono::FixedOffset;
+use crate::dokumen::merge::tanggal_indonesia;
+use crate::dokumen::model::Baris;
+use crate::types::{KeputusanTppEnum, PeranTppEnum};
+use super::model::SidangTppDetail;
+
+fn wib() -> FixedOffset {
+    FixedOffset::east_opt(7 * 3600).expect("offset WIB valid")
+}
+
+fn label_peran(peran: PeranTppEnum) -> &'static str {
+    match peran {
+        PeranTppEnum::Ketua => "Ketua",
+        PeranTppEnum::Sekretaris => "Sekretaris",
+        PeranTppEnum::Anggota => "Anggota",
+    }
+}
+
+fn label_keputusan(keputusan: Option<KeputusanTppEnum>) -> &'static str {
+    match keputusan {
+        Some(KeputusanTppEnum::Disetujui) => "Disetujui",
+        Some(KeputusanTppEnum::Ditolak) => "Ditolak",
+        Some(KeputusanTppEnum::Ditunda) => "Ditunda",
+        None => "Belum diputuskan",
+    }
+}
+
+pub fn susun(sidang: &SidangTppDetail, nama_bapas: &str) -> Vec<Baris> {
+    let mut baris = vec![
+        Baris::Judul(nama_bapas.to_string()),
+        Baris::Judul("NOTULEN SIDANG TIM PENGAMAT PEMASYARAKATAN".to_string()),
+        Baris::Kosong,
+        Baris::Paragraf(format!("Tanggal: {}", tanggal_indonesia(sidang.sidang.tanggal_sidang))),
+        Baris::Paragraf(format!("Tempat: {}", sidang.sidang.tempat_sidang.as_deref().unwrap_or("-"))),
+    ];
+    // Notulen yang belum dikunci hanya draf; isinya masih bisa berubah
+    baris.push(Baris::Paragraf(match sidang.sidang.notulen_dikunci_at {
+        Some(dikunci_at) => format!(
+            "Versi notulen: {} (dikunci {})",
+            sidang.sidang.notulen_versi,
+            tanggal_indonesia(dikunci_at.with_timezone(&wib()).date_naive())
+        ),
+        None => "DRAF - notulen belum dikunci".to_string(),
+    }));
+    if let Some(keterangan) = sidang.sidang.keterangan.as_deref() {
+        baris.push(Baris::Paragraf(keterangan.to_string()));
+    }
+
+    baris.push(Baris::Kosong);
+    baris.push(Baris::SubJudul("I. ANGGOTA YANG HADIR".to_string()));
+    for (i, anggota) in sidang.anggota.iter().enumerate() {
+        baris.push(Baris::Paragraf(format!(
+            "{}. {} (NIP {}) - {}",
+            i + 1,
+            anggota.nama_user,
+            anggota.nip_user,
+            label_peran(anggota.peran_tpp)
+        )));
+    }
+
+    baris.push(Baris::Kosong);
+    baris.push(Baris::SubJudul("II. PEMBAHASAN".to_string()));
+    for item in &sidang.agenda {
+        baris.push(Baris::Kosong);
+        baris.push(Baris::Paragraf(format!("{}. Klien: {}", item.agenda.urutan, item.nama_klien)));
+        if let Some(uraian) = item.agenda.uraian.as_deref() {
+            baris.push(Baris::Paragraf(format!("Uraian: {}", uraian)));
+        }
+        baris.push(Baris::Paragraf(format!(
+            "Rekomendasi: {}",
+            item.agenda.rekomendasi.as_deref().unwrap_or("-")
+        )));
+        baris.push(Baris::Paragraf(format!(
+            "Suara: {} setuju, {} tidak setuju, {} abstain",
+            item.jumlah_setuju, item.jumlah_tidak_setuju, item.jumlah_abstain
+        )));
+        for suara in item.suara.iter().filter(|s| s.catatan.is_some()) {
+            baris.push(Baris::Paragraf(format!(
+                "Catatan {}: {}",
+                suara.nama_user,
+                suara.catatan.as_deref().unwrap_or_default()
+            )));
+        }
+        baris.push(Baris::Paragraf(format!("Keputusan: {}", label_keputusan(item.agenda.keputusan_tpp))));
+    }
+
+    baris.push(Baris::Kosong);
+    baris.push(Baris::Kosong);
+    for peran in [PeranTppEnum::Ketua, PeranTppEnum::Sekretaris] {
+        if let Some(anggota) = sidang.anggota.iter().find(|a| a.peran_tpp == peran) {
+            baris.push(Baris::Paragraf(format!("{} TPP,", label_peran(peran))));
+            baris.push(Baris::Kosong);
+            baris.push(Baris::Kosong);
+            baris.push(Baris::Paragraf(anggota.nama_user.clone()));
+            baris.push(Baris::Paragraf(format!("NIP. {}", anggota.nip_user)));
+            baris.push(Baris::Kosong);
+        }
+    }
+    baris
+}
@@ -1,5 +1,30 @@
 // AUTO-GENERATED FILE FROM DB ENUMS
 
+#[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "bentuk_bimbingan_enum")]
+pub enum BentukBimbinganEnum {
+    #[serde(rename = "Individu")]
+    #[sqlx(rename = "Individu")]
+    Individu,
+    #[serde(rename = "Kelompok")]
+    #[sqlx(rename = "Kelompok")]
+    Kelompok,
+}
+
+#[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "jenis_dokumen_enum")]
+pub enum JenisDokumenEnum {
+    #[serde(rename = "Litmas")]
+    #[sqlx(rename = "Litmas")]
+    Litmas,
+    #[serde(rename = "Surat Tugas")]
+    #[sqlx(rename = "Surat Tugas")]
+    SuratTugas,
+    #[serde(rename = "Surat Pengakhiran")]
+    #[sqlx(rename = "Surat Pengakhiran")]
+    SuratPengakhiran,
+}
+
 #[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
 #[sqlx(type_name = "jenis_kelamin_enum")]
 pub enum JenisKelaminEnum {
@@ -11,6 +36,20 @@ pub enum JenisKelaminEnum {
     Perempuan,
 }
 
+#[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "jenis_operasi_sinkron_enum")]
+pub enum JenisOperasiSinkronEnum {
+    #[serde(rename = "Wajib Lapor")]
+    #[sqlx(rename = "Wajib Lapor")]
+    WajibLapor,
+    #[serde(rename = "Kunjungan Rumah")]
+    #[sqlx(rename = "Kunjungan Rumah")]
+    KunjunganRumah,
+    #[serde(rename = "Catatan Lapangan")]
+    #[sqlx(rename = "Catatan Lapangan")]
+    CatatanLapangan,
+}
+
 #[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
 #[sqlx(type_name = "jenis_pekerjaan_enum")]
 pub enum JenisPekerjaanEnum {
@@ -283,6 +322,70 @@ pub enum JenisPekerjaanEnum {
     Lainnya,
 }
 
+#[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "jenis_peringatan_enum")]
+pub enum JenisPeringatanEnum {
+    #[serde(rename = "Perangkat Baru")]
+    #[sqlx(rename = "Perangkat Baru")]
+    PerangkatBaru,
+    #[serde(rename = "Pelanggaran Zona")]
+    #[sqlx(rename = "Pelanggaran Zona")]
+    PelanggaranZona,
+}
+
+#[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "jenis_register_enum")]
+pub enum JenisRegisterEnum {
+    #[serde(rename = "Litmas")]
+    #[sqlx(rename = "Litmas")]
+    Litmas,
+    #[serde(rename = "Integrasi")]
+    #[sqlx(rename = "Integrasi")]
+    Integrasi,
+    #[serde(rename = "Proses Hukum")]
+    #[sqlx(rename = "Proses Hukum")]
+    ProsesHukum,
+}
+
+#[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "jenis_remisi_enum")]
+pub enum JenisRemisiEnum {
+    #[serde(rename = "Umum")]
+    #[sqlx(rename = "Umum")]
+    Umum,
+    #[serde(rename = "Khusus")]
+    #[sqlx(rename = "Khusus")]
+    Khusus,
+    #[serde(rename = "Dasawarsa")]
+    #[sqlx(rename = "Dasawarsa")]
+    Dasawarsa,
+}
+
+#[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "jenis_zona_enum")]
+pub enum JenisZonaEnum {
+    #[serde(rename = "Inklusi")]
+    #[sqlx(rename = "Inklusi")]
+    Inklusi,
+    #[serde(rename = "Eksklusi")]
+    #[sqlx(rename = "Eksklusi")]
+    Eksklusi,
+}
+
+#[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "keputusan_tpp_enum")]
+pub enum KeputusanTppEnum {
+    #[serde(rename = "Disetujui")]
+    #[sqlx(rename = "Disetujui")]
+    Disetujui,
+    #[serde(rename = "Ditolak")]
+    #[sqlx(rename = "Ditolak")]
+    Ditolak,
+    #[serde(rename = "Ditunda")]
+    #[sqlx(rename = "Ditunda")]
+    Ditunda,
+}
+
 #[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
 #[sqlx(type_name = "kewarganegaraan_enum")]
 pub enum KewarganegaraanEnum {
@@ -349,6 +452,105 @@ pub enum NamaInstansiEnum {
     Lainnya,
 }
 
+#[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "peran_tpp_enum")]
+pub enum PeranTppEnum {
+    #[serde(rename = "Ketua")]
+    #[sqlx(rename = "Ketua")]
+    Ketua,
+    #[serde(rename = "Sekretaris")]
+    #[sqlx(rename = "Sekretaris")]
+    Sekretaris,
+    #[serde(rename = "Anggota")]
+    #[sqlx(rename = "Anggota")]
+    Anggota,
+}
+
+#[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "program_bimbingan_enum")]
+pub enum ProgramBimbinganEnum {
+    #[serde(rename = "Kepribadian")]
+    #[sqlx(rename = "Kepribadian")]
+    Kepribadian,
+    #[serde(rename = "Kemandirian")]
+    #[sqlx(rename = "Kemandirian")]
+    Kemandirian,
+}
+
+#[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "status_antrian_enum")]
+pub enum StatusAntrianEnum {
+    #[serde(rename = "Dipesan")]
+    #[sqlx(rename = "Dipesan")]
+    Dipesan,
+    #[serde(rename = "Menunggu")]
+    #[sqlx(rename = "Menunggu")]
+    Menunggu,
+    #[serde(rename = "Dipanggil")]
+    #[sqlx(rename = "Dipanggil")]
+    Dipanggil,
+    #[serde(rename = "Dilayani")]
+    #[sqlx(rename = "Dilayani")]
+    Dilayani,
+    #[serde(rename = "Tidak Hadir")]
+    #[sqlx(rename = "Tidak Hadir")]
+    TidakHadir,
+    #[serde(rename = "Dibatalkan")]
+    #[sqlx(rename = "Dibatalkan")]
+    Dibatalkan,
+}
+
+#[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "status_kehadiran_enum")]
+pub enum StatusKehadiranEnum {
+    #[serde(rename = "Hadir")]
+    #[sqlx(rename = "Hadir")]
+    Hadir,
+    #[serde(rename = "Izin")]
+    #[sqlx(rename = "Izin")]
+    Izin,
+    #[serde(rename = "Tidak Hadir")]
+    #[sqlx(rename = "Tidak Hadir")]
+    TidakHadir,
+}
+
+#[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "status_litmas_enum")]
+pub enum StatusLitmasEnum {
+    #[serde(rename = "Diterima")]
+    #[sqlx(rename = "Diterima")]
+    Diterima,
+    #[serde(rename = "Ditugaskan")]
+    #[sqlx(rename = "Ditugaskan")]
+    Ditugaskan,
+    #[serde(rename = "Draft")]
+    #[sqlx(rename = "Draft")]
+    Draft,
+    #[serde(rename = "Direview")]
+    #[sqlx(rename = "Direview")]
+    Direview,
+    #[serde(rename = "Disetujui")]
+    #[sqlx(rename = "Disetujui")]
+    Disetujui,
+    #[serde(rename = "Dikirim")]
+    #[sqlx(rename = "Dikirim")]
+    Dikirim,
+}
+
+#[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "suara_tpp_enum")]
+pub enum SuaraTppEnum {
+    #[serde(rename = "Setuju")]
+    #[sqlx(rename = "Setuju")]
+    Setuju,
+    #[serde(rename = "Tidak Setuju")]
+    #[sqlx(rename = "Tidak Setuju")]
+    TidakSetuju,
+    #[serde(rename = "Abstain")]
+    #[sqlx(rename = "Abstain")]
+    Abstain,
+}
+
 #[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
 #[sqlx(type_name = "tingkat_pendidikan_enum")]
 pub enum TingkatPendidikanEnum {
@@ -398,6 +600,26 @@ pub enum TipeKlienEnum {
     Anak,
 }
 
+#[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "tujuan_kunjungan_enum")]
+pub enum TujuanKunjunganEnum {
+    #[serde(rename = "Penelitian Kemasyarakatan")]
+    #[sqlx(rename = "Penelitian Kemasyarakatan")]
+    PenelitianKemasyarakatan,
+    #[serde(rename = "Pengawasan")]
+    #[sqlx(rename = "Pengawasan")]
+    Pengawasan,
+    #[serde(rename = "Pembimbingan")]
+    #[sqlx(rename = "Pembimbingan")]
+    Pembimbingan,
+    #[serde(rename = "Verifikasi Penjamin")]
+    #[sqlx(rename = "Verifikasi Penjamin")]
+    VerifikasiPenjamin,
+    #[serde(rename = "Lainnya")]
+    #[sqlx(rename = "Lainnya")]
+    Lainnya,
+}
+
 #[derive(Debug, sqlx::Type, serde::Serialize, serde::Deserialize,Copy, Clone, PartialEq, Eq)]
 #[sqlx(type_name = "user_role_enum")]
 pub enum UserRoleEnum {
@@ -30,7 +30,7 @@ pub async fn get_all_users(
             status_kepegawaian_user AS "status_kepegawaian_user: _",
             email_user, nomor_telepon_user,
             status_aktif_user AS "status_aktif_user: _",
-            role_user AS "role_user: _",
+            role_user AS "role_user: _", kasubsi_user,
             password_hash, api_key_hash,created_at, updated_at, created_by, updated_by, deleted_at
         FROM users 
         WHERE deleted_at IS NULL 
@@ -60,7 +60,7 @@ pub async fn get_user_by_id(
             status_kepegawaian_user AS "status_kepegawaian_user: _",
             email_user, nomor_telepon_user,
             status_aktif_user AS "status_aktif_user: _",
-            role_user AS "role_user: _",
+            role_user AS "role_user: _", kasubsi_user,
             password_hash, api_key_hash,created_at, updated_at, created_by, updated_by, deleted_at
         FROM users 
         WHERE id = $1 AND deleted_at IS NULL
@@ -105,11 +105,11 @@ let new_user = sqlx::query_as!(
         nip_user, nama_user, gelar_depan_user, gelar_belakang_user, pangkat_golongan_user,
         jabatan_user, bapas_id, kanwil_id, status_kepegawaian_user, email_user,
         nomor_telepon_user, status_aktif_user, role_user, password_hash, api_key_hash,
-        created_by, updated_by
+        created_by, updated_by, kasubsi_user
     )
     VALUES (
         $1, $2, $3, $4, $5, $6, $7, $8, $9, $10,
-        $11, $12, $13, $14, $15, $16, $17
+        $11, $12, $13, $14, $15, $16, $17, $18
     )
     RETURNING
         id, nip_user, nama_user, gelar_depan_user, gelar_belakang_user,
@@ -117,7 +117,7 @@ let new_user = sqlx::query_as!(
         status_kepegawaian_user AS "status_kepegawaian_user: _",
         email_user, nomor_telepon_user,
         status_aktif_user AS "status_aktif_user: _",
-        role_user AS "role_user: _",
+        role_user AS "role_user: _", kasubsi_user,
         password_hash, api_key_hash, created_at, updated_at, created_by, updated_by, deleted_at
     "#,
     payload.nip_user,
@@ -136,7 +136,8 @@ let new_user = sqlx::query_as!(
     password_hash,
     api_key_hash,              // ✅ now defined
     current_user.id,           // created_by
-    current_user.id            // updated_by
+    current_user.id,           // updated_by
+    payload.kasubsi_user
 )
 .fetch_one(&pool)
 .await
@@ -197,7 +198,7 @@ pub async fn update_user(
     Json(payload): Json<UpdateUser>,
 ) -> Result<Json<User>, StatusCode> {
     
-    let user_to_update = sqlx::query_as!(User, r#"SELECT id, nip_user, nama_user, gelar_depan_user, gelar_belakang_user, pangkat_golongan_user, jabatan_user, bapas_id, kanwil_id, status_kepegawaian_user AS "status_kepegawaian_user: _", email_user, nomor_telepon_user, status_aktif_user AS "status_aktif_user: _", role_user AS "role_user: _", password_hash, api_key_hash,created_at, updated_at, created_by, updated_by, deleted_at FROM users WHERE id = $1 AND deleted_at IS NULL"#, id)
+    let user_to_update = sqlx::query_as!(User, r#"SELECT id, nip_user, nama_user, gelar_depan_user, gelar_belakang_user, pangkat_golongan_user, jabatan_user, bapas_id, kanwil_id, status_kepegawaian_user AS "status_kepegawaian_user: _", email_user, nomor_telepon_user, status_aktif_user AS "status_aktif_user: _", role_user AS "role_user: _", kasubsi_user, password_hash, api_key_hash,created_at, updated_at, created_by, updated_by, deleted_at FROM users WHERE id = $1 AND deleted_at IS NULL"#, id)
         .fetch_optional(&pool)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
@@ -217,7 +218,8 @@ pub async fn update_user(
             }
         }
         UserRoleEnum::Pegawai => {
-            if user_to_update.id != current_user.id {
+            // Pegawai tidak bisa menjadikan dirinya reviewer Litmas
+            if user_to_update.id != current_user.id || payload.kasubsi_user.is_some() {
                 return Err(StatusCode::FORBIDDEN);
             }
         }
@@ -250,7 +252,8 @@ pub async fn update_user(
             role_user = COALESCE($13, role_user),
             password_hash = $14,
             api_key_hash = $15,
-            updated_by = $16
+            updated_by = $16,
+            kasubsi_user = COALESCE($18, kasubsi_user)
         WHERE id = $17
         RETURNING
             id, nip_user, nama_user, gelar_depan_user, gelar_belakang_user,
@@ -258,7 +261,7 @@ pub async fn update_user(
             status_kepegawaian_user AS "status_kepegawaian_user: _",
             email_user, nomor_telepon_user,
             status_aktif_user AS "status_aktif_user: _",
-            role_user AS "role_user: _",
+            role_user AS "role_user: _", kasubsi_user,
             password_hash, api_key_hash,created_at, updated_at, created_by, updated_by, deleted_at
         "#,
         payload.nip_user,
@@ -277,7 +280,8 @@ pub async fn update_user(
         password_hash,
         api_key_hash,
         current_user.id, // [FIX] Mengisi updated_by
-        id
+        id,
+        payload.kasubsi_user
     )
     .fetch_one(&pool)
     .await
@@ -23,6 +23,8 @@ pub struct User {
     pub nomor_telepon_user: Option<String>,
     pub status_aktif_user: UserStatusAktifEnum,
     pub role_user: UserRoleEnum,
+    // Boleh menjadi reviewer Litmas
+    pub kasubsi_user: bool,
     #[serde(skip_serializing)]
     pub api_key_hash: Option<String>,
     #[serde(skip_serializing)]
@@ -55,6 +57,8 @@ pub struct CreateUser {
     pub nomor_telepon_user: Option<String>,
     pub status_aktif_user: Option<UserStatusAktifEnum>, // Optional karena ada DEFAULT di DB
     pub role_user: UserRoleEnum,
+    #[serde(default)]
+    pub kasubsi_user: bool,
 }
 
 // Struct untuk MENERIMA data dari API untuk mengupdate user.
@@ -75,6 +79,8 @@ pub struct UpdateUser {
     pub nomor_telepon_user: Option<String>,
     pub status_aktif_user: Option<UserStatusAktifEnum>,
     pub role_user: Option<UserRoleEnum>,
+    // Hanya admin yang boleh mengubah
+    pub kasubsi_user: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]